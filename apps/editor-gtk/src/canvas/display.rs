@@ -16,6 +16,10 @@ use gtk4::prelude::*;
 use gtk4::{gdk::ModifierType, Box, Button, Label, Orientation};
 use libadwaita::StatusPage;
 use std::sync::{Arc, Mutex};
+use weightlifting_core::{
+    warmup_exercise, AppPaths, DisplayUnit, LoadCalculator, SessionLoads, TrainingMaxRegistry,
+    WarmupGenerator,
+};
 
 pub fn update_canvas_content(state: Arc<Mutex<AppState>>) {
     let app_state = state.lock().unwrap();
@@ -57,6 +61,28 @@ pub fn update_canvas_content(state: Arc<Mutex<AppState>>) {
             plan_header.add_controller(right_click);
             content_box.append(&plan_header);
 
+            // Warm-up ladder shown under the first segment of each lift: the generated
+            // sets when a training max gives a working load, else the stage percentages
+            let warmup_preview = plan
+                .warmup
+                .as_ref()
+                .and_then(|config| WarmupGenerator::new(config).ok())
+                .map(|generator| {
+                    let stages: Vec<String> = generator
+                        .stages()
+                        .iter()
+                        .map(|(pct, reps)| format!("{:.0}% × {}", pct * 100.0, reps))
+                        .collect();
+                    format!("Warm-up: {}", stages.join(" · "))
+                });
+            let maxes = AppPaths::new()
+                .ok()
+                .and_then(|paths| TrainingMaxRegistry::load(&paths).ok())
+                .map(|registry| registry.maxes())
+                .unwrap_or_default();
+            let calculator = LoadCalculator::new(plan, &maxes);
+            let unit = DisplayUnit::from(&plan.unit);
+
             // Display days and segments
             for (day_idx, day) in plan.schedule.iter().enumerate() {
                 let day_box = Box::builder()
@@ -131,6 +157,10 @@ pub fn update_canvas_content(state: Arc<Mutex<AppState>>) {
 
                 day_box.append(&day_header_box);
 
+                let day_loads = warmup_preview
+                    .as_ref()
+                    .and_then(|_| calculator.day_loads(1, day).ok());
+                let mut warmed_up: Vec<&str> = Vec::new();
                 for (seg_idx, segment) in day.segments.iter().enumerate() {
                     match segment {
                         weightlifting_core::Segment::Superset(superset) => {
@@ -269,6 +299,28 @@ pub fn update_canvas_content(state: Arc<Mutex<AppState>>) {
 
                             seg_row_box.append(&seg_label);
                             day_box.append(&seg_row_box);
+
+                            if let (Some(preview), Some(ex)) =
+                                (&warmup_preview, warmup_exercise(segment))
+                            {
+                                if !warmed_up.contains(&ex) {
+                                    warmed_up.push(ex);
+                                    let text = day_loads
+                                        .as_ref()
+                                        .and_then(|loads| warmup_sets_text(loads, seg_idx, unit))
+                                        .unwrap_or_else(|| preview.clone());
+                                    let warmup_label = Label::builder()
+                                        .label(text.as_str())
+                                        .css_classes(vec![
+                                            "dim-label".to_string(),
+                                            "caption".to_string(),
+                                        ])
+                                        .halign(gtk4::Align::Start)
+                                        .margin_start(72)
+                                        .build();
+                                    day_box.append(&warmup_label);
+                                }
+                            }
                         }
                     }
                 }
@@ -289,6 +341,27 @@ pub fn update_canvas_content(state: Arc<Mutex<AppState>>) {
     }
 }
 
+/// "Warm-up: 40 kg × 5 · 60 kg × 3" from the rounded, merged warm-up sets of a segment
+fn warmup_sets_text(loads: &SessionLoads, seg_idx: usize, unit: DisplayUnit) -> Option<String> {
+    let sets: Vec<String> = loads
+        .segments
+        .iter()
+        .find(|segment| segment.segment_idx == seg_idx)?
+        .sets
+        .iter()
+        .filter(|set| set.is_warmup)
+        .filter_map(|set| {
+            Some(format!(
+                "{:.1} {} × {}",
+                unit.convert(set.load_kg()?),
+                unit.label(),
+                set.reps?
+            ))
+        })
+        .collect();
+    (!sets.is_empty()).then(|| format!("Warm-up: {}", sets.join(" · ")))
+}
+
 fn create_superset_display(
     parent_box: &Box,
     superset: &weightlifting_core::SupersetSegment,
@@ -563,7 +563,57 @@ pub fn add_scheme_to_plan(
     backoff_percent: f64,
     _backoff_rest: u32,
 ) {
-    use weightlifting_core::{BaseSegment, RpeOrRange, SchemeSegment, SchemeSet};
+    use weightlifting_core::{RpeOrRange, SchemeSet};
+    let top_set = SchemeSet {
+        label: Some("Top set".to_string()),
+        sets: Some(1),
+        reps: Some(RepsOrRange::Range(RepsRange {
+            min: top_reps,
+            max: top_reps,
+            target: Some(top_reps),
+        })),
+        time_sec: None,
+        rpe: Some(RpeOrRange::Fixed(top_rpe)),
+        rest_sec: None,
+        anchor: None,
+        track_pr: None,
+    };
+    let backoff_set = SchemeSet {
+        label: Some(format!("Backoff @ {}%", (backoff_percent * 100.0) as u32)),
+        sets: Some(backoff_sets),
+        reps: Some(RepsOrRange::Range(RepsRange {
+            min: backoff_reps,
+            max: backoff_reps,
+            target: Some(backoff_reps),
+        })),
+        time_sec: None,
+        rpe: None,
+        rest_sec: None,
+        anchor: None,
+        track_pr: None,
+    };
+    push_scheme_segment(state, ex_code, ex_label, vec![top_set, backoff_set], None);
+}
+
+/// Add a scheme segment that expands from a template (e.g. a user-defined one from the
+/// scheme templates directory) instead of listing its sets
+pub fn add_template_scheme_to_plan(
+    state: Arc<Mutex<AppState>>,
+    ex_code: String,
+    ex_label: String,
+    template: weightlifting_core::SchemeTemplate,
+) {
+    push_scheme_segment(state, ex_code, ex_label, Vec::new(), Some(template));
+}
+
+fn push_scheme_segment(
+    state: Arc<Mutex<AppState>>,
+    ex_code: String,
+    ex_label: String,
+    sets: Vec<weightlifting_core::SchemeSet>,
+    template: Option<weightlifting_core::SchemeTemplate>,
+) {
+    use weightlifting_core::SchemeSegment;
     let mut app_state = state.lock().unwrap();
     let target_day = app_state.get_and_clear_target_day().unwrap_or(0);
     app_state.save_to_undo_history();
@@ -583,34 +633,6 @@ pub fn add_scheme_to_plan(
             };
             plan.schedule.push(day);
         }
-        let top_set = SchemeSet {
-            label: Some("Top set".to_string()),
-            sets: Some(1),
-            reps: Some(RepsOrRange::Range(RepsRange {
-                min: top_reps,
-                max: top_reps,
-                target: Some(top_reps),
-            })),
-            time_sec: None,
-            rpe: Some(RpeOrRange::Fixed(top_rpe)),
-            rest_sec: None,
-            anchor: None,
-            track_pr: None,
-        };
-        let backoff_set = SchemeSet {
-            label: Some(format!("Backoff @ {}%", (backoff_percent * 100.0) as u32)),
-            sets: Some(backoff_sets),
-            reps: Some(RepsOrRange::Range(RepsRange {
-                min: backoff_reps,
-                max: backoff_reps,
-                target: Some(backoff_reps),
-            })),
-            time_sec: None,
-            rpe: None,
-            rest_sec: None,
-            anchor: None,
-            track_pr: None,
-        };
         let scheme_segment = SchemeSegment {
             base: BaseSegment {
                 ex: ex_code,
@@ -623,9 +645,9 @@ pub fn add_scheme_to_plan(
                 technique: None,
                 equipment_policy: None,
             },
-            sets: vec![top_set, backoff_set],
+            sets,
             load_mode: None,
-            template: None,
+            template,
         };
         let target_day_index = if target_day >= plan.schedule.len() {
             0
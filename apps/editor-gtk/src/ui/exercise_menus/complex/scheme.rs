@@ -2,13 +2,20 @@
 
 use super::components::create_exercise_search_section_complex;
 use crate::state::AppState;
+use crate::ui::schemes::SchemeCard;
 use glib::clone;
 use gtk4::prelude::EditableExt;
 use gtk4::prelude::*;
-use gtk4::{Box as GtkBox, Dialog, DialogFlags, Expander, Orientation, ResponseType, SpinButton};
+use gtk4::{
+    Box as GtkBox, ComboBoxText, Dialog, DialogFlags, Expander, Orientation, ResponseType,
+    SpinButton,
+};
+use std::cell::RefCell;
+use std::rc::Rc;
 use std::sync::{Arc, Mutex};
+use weightlifting_core::AppPaths;
 
-use crate::operations::plan_ops::add_scheme_to_plan;
+use crate::operations::plan_ops::{add_scheme_to_plan, add_template_scheme_to_plan};
 
 pub fn show_add_scheme_dialog(state: Arc<Mutex<AppState>>) {
     use gtk4::Label as GtkLabel;
@@ -54,6 +61,53 @@ pub fn show_add_scheme_dialog(state: Arc<Mutex<AppState>>) {
     let label_label = GtkLabel::new(Some("Exercise Name:"));
     label_entry.set_text("Back Squat");
 
+    // Scheme picker: the built-in top set + backoffs, or a template from the user's
+    // scheme templates directory
+    let custom_templates = AppPaths::new()
+        .map(|paths| SchemeCard::custom_templates(&paths))
+        .unwrap_or_default();
+    let template_label = GtkLabel::new(Some("Scheme Template:"));
+    let template_combo = ComboBoxText::new();
+    template_combo.append_text("Top set + backoffs");
+    for template in &custom_templates {
+        template_combo.append_text(template.title.as_deref().unwrap_or(&template.name));
+    }
+    template_combo.set_active(Some(0));
+    template_combo.set_sensitive(!custom_templates.is_empty());
+
+    // Top set + backoff fields, hidden while a custom template is picked
+    let top_backoff_box = GtkBox::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(12)
+        .build();
+    // Parameter card for the picked custom template
+    let custom_box = GtkBox::builder().orientation(Orientation::Vertical).build();
+    let custom_card: Rc<RefCell<Option<SchemeCard>>> = Rc::default();
+
+    template_combo.connect_changed(
+        clone!(@strong ex_entry, @strong top_backoff_box, @strong custom_box, @strong custom_card => move |combo| {
+            while let Some(child) = custom_box.first_child() {
+                custom_box.remove(&child);
+            }
+            let picked = combo
+                .active()
+                .and_then(|index| (index as usize).checked_sub(1))
+                .and_then(|index| custom_templates.get(index));
+            match picked {
+                Some(template) => {
+                    let card = SchemeCard::for_custom_template(&ex_entry.text(), template.clone());
+                    custom_box.append(&card.widget);
+                    *custom_card.borrow_mut() = Some(card);
+                    top_backoff_box.set_visible(false);
+                }
+                None => {
+                    *custom_card.borrow_mut() = None;
+                    top_backoff_box.set_visible(true);
+                }
+            }
+        }),
+    );
+
     // Scheme sets configuration
     let scheme_label = GtkLabel::new(Some("Scheme Configuration"));
     scheme_label.set_css_classes(&["heading"]);
@@ -103,30 +157,44 @@ pub fn show_add_scheme_dialog(state: Arc<Mutex<AppState>>) {
     manual_expander.set_child(Some(&manual_content));
     content.append(&manual_expander);
 
-    content.append(&scheme_label);
-    content.append(&top_set_label);
-    content.append(&top_reps_label);
-    content.append(&top_reps_entry);
-    content.append(&top_rpe_label);
-    content.append(&top_rpe_entry);
-    content.append(&top_rest_label);
-    content.append(&top_rest_entry);
-    content.append(&backoff_label);
-    content.append(&backoff_sets_label);
-    content.append(&backoff_sets_entry);
-    content.append(&backoff_reps_label);
-    content.append(&backoff_reps_entry);
-    content.append(&backoff_percent_label);
-    content.append(&backoff_percent_entry);
-    content.append(&backoff_rest_label);
-    content.append(&backoff_rest_entry);
+    content.append(&template_label);
+    content.append(&template_combo);
+
+    top_backoff_box.append(&scheme_label);
+    top_backoff_box.append(&top_set_label);
+    top_backoff_box.append(&top_reps_label);
+    top_backoff_box.append(&top_reps_entry);
+    top_backoff_box.append(&top_rpe_label);
+    top_backoff_box.append(&top_rpe_entry);
+    top_backoff_box.append(&top_rest_label);
+    top_backoff_box.append(&top_rest_entry);
+    top_backoff_box.append(&backoff_label);
+    top_backoff_box.append(&backoff_sets_label);
+    top_backoff_box.append(&backoff_sets_entry);
+    top_backoff_box.append(&backoff_reps_label);
+    top_backoff_box.append(&backoff_reps_entry);
+    top_backoff_box.append(&backoff_percent_label);
+    top_backoff_box.append(&backoff_percent_entry);
+    top_backoff_box.append(&backoff_rest_label);
+    top_backoff_box.append(&backoff_rest_entry);
+    content.append(&top_backoff_box);
+    content.append(&custom_box);
 
     dialog.content_area().append(&content);
 
-    dialog.connect_response(clone!(@strong state, @strong ex_entry, @strong label_entry, @strong top_reps_entry, @strong top_rpe_entry, @strong top_rest_entry, @strong backoff_sets_entry, @strong backoff_reps_entry, @strong backoff_percent_entry, @strong backoff_rest_entry => move |dialog, response| {
+    dialog.connect_response(clone!(@strong state, @strong ex_entry, @strong label_entry, @strong custom_card, @strong top_reps_entry, @strong top_rpe_entry, @strong top_rest_entry, @strong backoff_sets_entry, @strong backoff_reps_entry, @strong backoff_percent_entry, @strong backoff_rest_entry => move |dialog, response| {
         if response == ResponseType::Accept {
             let ex_code = ex_entry.text().to_string();
             let ex_label = label_entry.text().to_string();
+            let template = custom_card
+                .borrow()
+                .as_ref()
+                .and_then(|card| card.current_template());
+            if let Some(template) = template {
+                add_template_scheme_to_plan(state.clone(), ex_code, ex_label, template);
+                dialog.close();
+                return;
+            }
             let top_reps = top_reps_entry.value() as u32;
             let top_rpe = top_rpe_entry.value();
             let top_rest = top_rest_entry.value() as u32;
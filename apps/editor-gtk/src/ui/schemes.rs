@@ -4,8 +4,12 @@ use gtk4::prelude::*;
 use gtk4::{Box, Button, Entry, Label, Orientation, ScrolledWindow, SpinButton, TextView};
 use libadwaita::prelude::*;
 use libadwaita::{ActionRow, PreferencesGroup, PreferencesPage};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
 use weightlifting_core::{
-    BackoffParams, IntensityParams, SchemeTemplate, TopBackoffParams, TopSetParams,
+    AppPaths, BackoffParams, CustomSchemeParams, CustomTemplate, ExpandedScheme, IntensityParams,
+    ParamKind, SchemeTemplate, SchemeTemplates, TopBackoffParams, TopSetParams,
 };
 
 /// Scheme card widget for displaying and editing scheme templates
@@ -13,6 +17,8 @@ use weightlifting_core::{
 pub struct SchemeCard {
     pub widget: Box,
     pub template: Option<SchemeTemplate>,
+    /// Parameter values edited on a custom template card
+    values: Rc<RefCell<BTreeMap<String, f64>>>,
 }
 
 #[allow(dead_code)]
@@ -31,6 +37,7 @@ impl SchemeCard {
         Self {
             widget,
             template: None,
+            values: Rc::default(),
         }
     }
 
@@ -74,6 +81,148 @@ impl SchemeCard {
         card
     }
 
+    /// Create a scheme card for a user-defined template with one input per parameter
+    pub fn for_custom_template(exercise: &str, template: CustomTemplate) -> Self {
+        let mut card = Self::new();
+
+        let header = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(12)
+            .build();
+
+        let title = Label::builder()
+            .label(format!(
+                "{}: {}",
+                template.title.as_deref().unwrap_or(&template.name),
+                exercise
+            ))
+            .css_classes(vec!["heading".to_string()])
+            .halign(gtk4::Align::Start)
+            .hexpand(true)
+            .build();
+
+        let preview_btn = Button::with_label("Preview Expansion");
+
+        header.append(&title);
+        header.append(&preview_btn);
+        card.widget.append(&header);
+
+        if let Some(description) = &template.description {
+            let description = Label::builder()
+                .label(description.as_str())
+                .css_classes(vec!["dim-label".to_string()])
+                .halign(gtk4::Align::Start)
+                .wrap(true)
+                .build();
+            card.widget.append(&description);
+        }
+
+        let params_section = card.create_custom_params_ui(&template);
+        card.widget.append(&params_section);
+
+        let preview_section = card.create_preview_section();
+        card.widget.append(&preview_section);
+
+        let widget = card.widget.clone();
+        let values = card.values.clone();
+        let exercise = exercise.to_string();
+        let preview_template = template.clone();
+        preview_btn.connect_clicked(move |_| {
+            let scheme = SchemeTemplate::Custom(CustomSchemeParams {
+                template: preview_template.clone(),
+                values: values.borrow().clone(),
+            });
+            Self::show_expansion_preview(&widget, scheme.expand(&exercise));
+        });
+
+        card.template = Some(SchemeTemplate::Custom(CustomSchemeParams {
+            template,
+            values: BTreeMap::new(),
+        }));
+        card
+    }
+
+    /// The card's template with the parameter values currently entered
+    pub fn current_template(&self) -> Option<SchemeTemplate> {
+        match &self.template {
+            Some(SchemeTemplate::Custom(params)) => {
+                Some(SchemeTemplate::Custom(CustomSchemeParams {
+                    template: params.template.clone(),
+                    values: self.values.borrow().clone(),
+                }))
+            }
+            other => other.clone(),
+        }
+    }
+
+    /// Valid templates from the user's templates directory; broken files are skipped
+    pub fn custom_templates(paths: &AppPaths) -> Vec<CustomTemplate> {
+        SchemeTemplates::discover(&paths.scheme_templates_dir())
+            .into_iter()
+            .filter_map(|(path, template)| match template {
+                Ok(template) => Some(template),
+                Err(e) => {
+                    println!("Skipping scheme template {}: {}", path.display(), e);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Create one spin button per template parameter, starting at its default
+    fn create_custom_params_ui(&self, template: &CustomTemplate) -> Box {
+        let params_box = Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(8)
+            .build();
+
+        let group_label = Label::builder()
+            .label("Template Parameters")
+            .css_classes(vec!["heading".to_string()])
+            .halign(gtk4::Align::Start)
+            .build();
+        params_box.append(&group_label);
+
+        for param in &template.params {
+            let row = Box::builder()
+                .orientation(Orientation::Horizontal)
+                .spacing(8)
+                .build();
+
+            let label = Label::new(Some(format!("{}:", param.name).as_str()));
+            label.set_width_chars(16);
+            label.set_xalign(0.0);
+
+            let (step, digits) = match param.kind {
+                ParamKind::Integer => (1.0, 0),
+                ParamKind::Number => (0.5, 2),
+            };
+            let min = param.min.unwrap_or(0.0);
+            let max = param.max.unwrap_or(1000.0).max(min + step);
+            let spin = SpinButton::with_range(min, max, step);
+            spin.set_digits(digits);
+            spin.set_value(param.default.unwrap_or(min));
+            if let Some(description) = &param.description {
+                spin.set_tooltip_text(Some(description.as_str()));
+            }
+
+            self.values
+                .borrow_mut()
+                .insert(param.name.clone(), spin.value());
+            let values = self.values.clone();
+            let name = param.name.clone();
+            spin.connect_value_changed(move |spin| {
+                values.borrow_mut().insert(name.clone(), spin.value());
+            });
+
+            row.append(&label);
+            row.append(&spin);
+            params_box.append(&row);
+        }
+
+        params_box
+    }
+
     /// Create UI for editing top-backoff parameters
     fn create_top_backoff_params_ui(&self) -> Box {
         let params_box = Box::builder()
@@ -324,6 +473,13 @@ impl SchemeCard {
 
     /// Update the expansion preview with computed scheme
     fn update_expansion_preview(widget: &Box, exercise: &str) {
+        // Create a sample top-backoff template and expand it
+        let template = Self::create_sample_top_backoff_template();
+        Self::show_expansion_preview(widget, template.expand(exercise));
+    }
+
+    /// Reveal the preview section and show an expansion or its error
+    fn show_expansion_preview(widget: &Box, expansion: Result<ExpandedScheme, String>) {
         // Find the preview section in the widget tree
         let preview_section = Self::find_preview_section(widget);
 
@@ -335,29 +491,8 @@ impl SchemeCard {
                 if let Some(text_view) = scrolled.first_child() {
                     if let Some(text_view) = text_view.downcast_ref::<TextView>() {
                         let buffer = text_view.buffer();
-
-                        // Create a sample top-backoff template and expand it
-                        let template = Self::create_sample_top_backoff_template();
-
-                        match template.expand(exercise) {
-                            Ok(expanded) => {
-                                let preview_text = format!(
-                                    "Exercise: {}\nTotal Sets: {}\nTotal Volume: {} reps\nEstimated Duration: {} seconds\n\nExpanded Sets:\n{}",
-                                    expanded.exercise,
-                                    expanded.sets.len(),
-                                    expanded.total_volume_reps,
-                                    expanded.estimated_duration,
-                                    expanded.sets.iter().enumerate().map(|(i, set)| {
-                                        format!("  {}: {} sets × {} reps", i + 1,
-                                               set.sets.unwrap_or(1),
-                                               match &set.reps {
-                                                   Some(reps) => format!("{:?}", reps),
-                                                   None => "unspecified".to_string()
-                                               })
-                                    }).collect::<Vec<_>>().join("\n")
-                                );
-                                buffer.set_text(&preview_text);
-                            }
+                        match expansion {
+                            Ok(expanded) => buffer.set_text(&Self::format_expansion(&expanded)),
                             Err(error) => {
                                 buffer.set_text(&format!("Expansion Error: {}", error));
                             }
@@ -368,6 +503,52 @@ impl SchemeCard {
         }
     }
 
+    fn format_expansion(expanded: &ExpandedScheme) -> String {
+        let rows = expanded
+            .sets
+            .iter()
+            .zip(&expanded.intensities)
+            .enumerate()
+            .map(|(i, (set, intensity))| {
+                let label = set
+                    .base
+                    .label
+                    .as_deref()
+                    .map(|label| format!("{} – ", label))
+                    .unwrap_or_default();
+                let reps = match &set.reps {
+                    Some(weightlifting_core::RepsOrRange::Range(reps)) => reps.max.to_string(),
+                    None => "unspecified".to_string(),
+                };
+                let intensity = match intensity {
+                    IntensityParams::Rpe { rpe } => format!("RPE {}", rpe),
+                    IntensityParams::Rir { rir } => format!("RIR {}", rir),
+                    IntensityParams::Percent1Rm { percent_1rm } => {
+                        format!("{:.1}% 1RM", percent_1rm * 100.0)
+                    }
+                    IntensityParams::Load { load } => format!("{} kg", load),
+                };
+                format!(
+                    "  {}: {}{} sets × {} reps @ {}",
+                    i + 1,
+                    label,
+                    set.sets.unwrap_or(1),
+                    reps,
+                    intensity
+                )
+            })
+            .collect::<Vec<_>>();
+
+        format!(
+            "Exercise: {}\nTotal Sets: {}\nTotal Volume: {} reps\nEstimated Duration: {} seconds\n\nExpanded Sets:\n{}",
+            expanded.exercise,
+            expanded.sets.len(),
+            expanded.total_volume_reps,
+            expanded.estimated_duration,
+            rows.join("\n")
+        )
+    }
+
     /// Find the preview section widget in the hierarchy
     fn find_preview_section(widget: &Box) -> Option<Box> {
         // Simple implementation - find the last child which should be the preview
@@ -1,21 +1,48 @@
-use chrono::NaiveDate;
-use clap::{Parser, Subcommand};
+use chrono::{Datelike, NaiveDate};
+use clap::{Args, Parser, Subcommand};
 use std::fs;
 use std::io::{self, Read};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use weightlifting_core::{location::LocationProfile, LoadBasis, LoadCalculator, SessionLoads};
+use weightlifting_core::{validate_plan_id, TrainingMaxRegistry, TrainingMaxSource, TrainingMaxes};
 use weightlifting_core::{
-    AppPaths, ExportStager, Plan, PlanVersion, VersionMetadata, VersionState, VersionedPlan,
+    AppPaths, ChangeType, Day, ExportStager, Plan, PlanDiff, PlanRef, PlanStore, PlanStoreError,
+    PlanVersion, Segment, Unit, VersionBump, VersionMetadata, VersionState, VersionedPlan,
 };
-use weightlifting_core::{BuiltinCharts, PRDisplayMode, VolumeMetric};
-use weightlifting_indexer::cache::MetricsCache;
+use weightlifting_core::{
+    BuiltinCharts, ChartDataset, ChartDefinition, ChartDefinitions, ChartGenerator, ChartRenderer,
+    CompareAlignment, CompareCharts, CompareLayout, CompareMeasure, CompareMetric, CompareSeries,
+    DataTransforms, DefinitionError, DisplayUnit, HtmlReport, IntensityMetric, RenderFormat,
+    ReportAdherence, SessionNote, UserPreferences,
+};
+use weightlifting_indexer::adherence::{AdherenceAnalyzer, AdherenceReport};
+use weightlifting_indexer::cache::{CacheError, MetricsCache, MetricsQuery};
+use weightlifting_indexer::charts::{
+    builtin_chart_spec, group_volume_kind, ChartFilters, BUILTIN_CHARTS,
+};
+use weightlifting_indexer::csv_parser::SessionCsvParser;
+use weightlifting_indexer::exercise_catalog::ExerciseCatalog;
+use weightlifting_indexer::metrics::{E1RMCalculator, VolumeGrouping};
+use weightlifting_indexer::plan_projection::{PlanProjection, PlanProjector};
+use weightlifting_indexer::progression::{ProgressionEngine, ProgressionTarget};
 use weightlifting_validate::PlanValidator;
 
+mod output;
+
+use output::{CliError, ExitCode, Output, OutputFormat};
+
 /// **Death to Windows!** - Weightlifting Desktop CLI (Linux native)
 #[derive(Parser)]
 #[command(name = "comp")]
 #[command(about = "Weightlifting plan management CLI")]
 #[command(version)]
+#[command(
+    after_help = "Exit codes: 0 success, 1 other failure, 2 invalid input or failed validation, 3 conflict, 4 not found"
+)]
 struct Cli {
+    /// Output format: text, or a JSON envelope {ok, data, errors} on stdout
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
     #[command(subcommand)]
     command: Commands,
 }
@@ -32,6 +59,119 @@ enum Commands {
         #[command(subcommand)]
         action: ChartAction,
     },
+    /// Write a self-contained HTML training report
+    Report(ReportArgs),
+    /// Training max registry commands
+    Maxes {
+        #[command(subcommand)]
+        action: MaxAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum MaxAction {
+    /// List training maxes with their source and date
+    List,
+    /// Set an exercise's training max
+    Set {
+        /// Exercise code
+        ex: String,
+        /// Training max in kg
+        kg: f64,
+    },
+    /// Remove an exercise's training max
+    Remove {
+        /// Exercise code
+        ex: String,
+    },
+    /// Add training maxes from cached e1RMs for exercises without one
+    Seed {
+        /// Fraction of the e1RM to use
+        #[arg(long, default_value_t = 0.9)]
+        fraction: f64,
+        /// Only use e1RMs from this date on (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
+    },
+}
+
+#[derive(Args)]
+struct ReportArgs {
+    /// Start date (YYYY-MM-DD)
+    #[arg(long)]
+    start_date: Option<String>,
+    /// End date (YYYY-MM-DD)
+    #[arg(long)]
+    end_date: Option<String>,
+    /// Plan ID for the plan summary and adherence table
+    #[arg(long)]
+    plan: Option<String>,
+    /// Plan version (defaults to the current draft)
+    #[arg(long)]
+    plan_version: Option<String>,
+    /// Report title
+    #[arg(long)]
+    title: Option<String>,
+    /// Weight display unit: kg or lb (defaults to the plan's unit, then preferences)
+    #[arg(long)]
+    unit: Option<String>,
+    /// Output HTML file
+    #[arg(long)]
+    output: PathBuf,
+}
+#[derive(Args)]
+struct CompareArgs {
+    /// Metric to compare: e1rm, tonnage or sets
+    #[arg(long, default_value = "e1rm")]
+    metric: String,
+    /// Exercise for both series (required for e1rm; volume defaults to all exercises)
+    #[arg(long)]
+    exercise: Option<String>,
+    /// Exercise for series B, to compare two lifts
+    #[arg(long)]
+    exercise_b: Option<String>,
+    /// Series A start date (YYYY-MM-DD)
+    #[arg(long)]
+    start_a: Option<String>,
+    /// Series A end date (YYYY-MM-DD)
+    #[arg(long)]
+    end_a: Option<String>,
+    /// Series B start date (defaults to series A's window)
+    #[arg(long)]
+    start_b: Option<String>,
+    /// Series B end date (defaults to series A's window)
+    #[arg(long)]
+    end_b: Option<String>,
+    /// Plan ID run during window A; its block length bounds plan-week alignment
+    #[arg(long)]
+    plan_a: Option<String>,
+    /// Plan ID run during window B
+    #[arg(long)]
+    plan_b: Option<String>,
+    /// Alignment: week or plan-week (defaults to plan-week when a plan is given)
+    #[arg(long)]
+    align: Option<String>,
+    /// Plotted value: value, change or change-pct (change since each series' first week)
+    #[arg(long, default_value = "value")]
+    show: String,
+    /// Layout: layered or side-by-side
+    #[arg(long, default_value = "layered")]
+    layout: String,
+    /// Label for series A
+    #[arg(long)]
+    label_a: Option<String>,
+    /// Label for series B
+    #[arg(long)]
+    label_b: Option<String>,
+    /// Chart title
+    #[arg(long)]
+    title: Option<String>,
+    /// Weight display unit: kg or lb (defaults to plan A's unit, then preferences)
+    #[arg(long)]
+    unit: Option<String>,
+    /// Output file (defaults to stdout)
+    #[arg(long)]
+    output: Option<PathBuf>,
 }
 
 #[derive(Subcommand)]
@@ -78,6 +218,214 @@ enum PlanAction {
         #[arg(long)]
         dry_run: bool,
     },
+    /// Report prescribed vs performed sets for a plan
+    Adherence {
+        /// Plan ID to compare against
+        #[arg(long)]
+        id: String,
+        /// Version to compare against (defaults to latest draft)
+        #[arg(long)]
+        version: Option<String>,
+        /// Path to session CSV file
+        #[arg(long)]
+        input: PathBuf,
+        /// Output format: json, csv or spec (Vega-Lite)
+        #[arg(long, default_value = "json")]
+        format: String,
+        /// Output file (defaults to stdout)
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Compute target loads for a plan's sessions from training maxes
+    Loads {
+        /// Plan ID
+        id: String,
+        /// Version to use (defaults to latest draft)
+        #[arg(long)]
+        version: Option<String>,
+        /// Week of the block (1-based)
+        #[arg(long, default_value_t = 1)]
+        week: u32,
+        /// Day number (defaults to every day in the week)
+        #[arg(long)]
+        day: Option<u32>,
+        /// Round loads for a location: home, gym, or a location profile JSON file
+        #[arg(long)]
+        location: Option<String>,
+        /// Training max in kg overriding the registry and e1RM cache, e.g. SQ.BB.BACK=180 (repeatable)
+        #[arg(long = "tm")]
+        training_max: Vec<String>,
+        /// Only use e1RMs from this date on as training maxes (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
+    },
+    /// Next-session targets from the plan's progression rule and logged sessions
+    Progress {
+        /// Plan ID
+        id: String,
+        /// Version to use (defaults to latest draft)
+        #[arg(long)]
+        version: Option<String>,
+        /// Session CSV file (defaults to the sets in the metrics cache)
+        #[arg(long)]
+        input: Option<PathBuf>,
+        /// Simulate this many weeks ahead instead of showing the next targets
+        #[arg(long)]
+        weeks: Option<u32>,
+        /// Training max in kg overriding the registry and e1RM cache, e.g. SQ.BB.BACK=180 (repeatable)
+        #[arg(long = "tm")]
+        training_max: Vec<String>,
+        /// Store advanced training maxes in the registry
+        #[arg(long)]
+        apply: bool,
+    },
+    /// List stored plans with their draft and promoted versions
+    List,
+    /// Show the promoted versions of a plan
+    History {
+        /// Plan ID
+        id: String,
+    },
+    /// Diff two versions of a plan, or a version and the draft
+    Diff {
+        /// Plan ID
+        id: String,
+        /// Version to diff from, or "draft" (defaults to the latest version)
+        #[arg(long)]
+        from: Option<String>,
+        /// Version to diff to, or "draft" (defaults to the draft)
+        #[arg(long)]
+        to: Option<String>,
+    },
+    /// Promote the current draft to a new version
+    Promote {
+        /// Plan ID
+        id: String,
+        /// Release message
+        #[arg(long)]
+        message: Option<String>,
+        /// Author recorded on the version
+        #[arg(long)]
+        author: Option<String>,
+        /// Version part to increment: major, minor or patch
+        #[arg(long, default_value = "patch")]
+        bump: String,
+    },
+    /// Add or remove a tag on a promoted version
+    Tag {
+        /// Plan ID
+        id: String,
+        /// Tag name, e.g. stable
+        tag: String,
+        /// Version to tag (defaults to the latest version)
+        #[arg(long)]
+        version: Option<String>,
+        /// Remove the tag instead of adding it
+        #[arg(long)]
+        remove: bool,
+    },
+    /// Delete a plan's draft, one version, or the whole plan
+    Delete {
+        /// Plan ID
+        id: String,
+        /// Version to delete, or "draft" (defaults to the draft and every version)
+        #[arg(long)]
+        version: Option<String>,
+    },
+    /// Create an empty draft plan
+    New {
+        /// Plan name
+        name: String,
+        /// Plan ID (defaults to one derived from the name)
+        #[arg(long)]
+        id: Option<String>,
+        /// Load unit: kg, lb or bw
+        #[arg(long, default_value = "kg")]
+        unit: String,
+        /// Plan author
+        #[arg(long)]
+        author: Option<String>,
+        /// Replace an existing draft with the same ID
+        #[arg(long)]
+        force: bool,
+    },
+    /// Append a training day to a draft
+    AddDay {
+        /// Plan ID
+        id: String,
+        /// Day label, e.g. "Lower A"
+        #[arg(long)]
+        label: String,
+        /// Day number (defaults to one after the last day)
+        #[arg(long)]
+        day: Option<u32>,
+        /// Session time cap in minutes
+        #[arg(long)]
+        time_cap: Option<u32>,
+        /// Session goal
+        #[arg(long)]
+        goal: Option<String>,
+    },
+    /// Append a segment to a day of a draft
+    AddSegment(Box<AddSegmentArgs>),
+    /// Set the value at a JSON pointer in a draft, e.g. /schedule/0/segments/1/sets 4
+    Set {
+        /// Plan ID
+        id: String,
+        /// JSON pointer to set; use - as the last token to append to an array
+        pointer: String,
+        /// Value as JSON; anything that does not parse is set as a string
+        value: String,
+    },
+    /// Remove the value at a JSON pointer in a draft
+    Rm {
+        /// Plan ID
+        id: String,
+        /// JSON pointer to remove, e.g. /schedule/1
+        pointer: String,
+    },
+}
+
+#[derive(Args)]
+struct AddSegmentArgs {
+    /// Plan ID
+    id: String,
+    /// Day number to add to (defaults to the last day)
+    #[arg(long)]
+    day: Option<u32>,
+    /// Segment type: straight, rpe, percentage, amrap, ...
+    #[arg(long = "type", default_value = "straight")]
+    segment_type: String,
+    /// Exercise code, e.g. SQ.BB.BACK
+    #[arg(long)]
+    ex: Option<String>,
+    /// Exercise name, added to the plan dictionary
+    #[arg(long)]
+    name: Option<String>,
+    /// Number of sets
+    #[arg(long)]
+    sets: Option<u32>,
+    /// Reps, either a count (5) or a range (8-12)
+    #[arg(long)]
+    reps: Option<String>,
+    /// Target RPE
+    #[arg(long)]
+    rpe: Option<f64>,
+    /// Target reps in reserve
+    #[arg(long)]
+    rir: Option<f64>,
+    /// Percentage of 1RM (percentage segments)
+    #[arg(long)]
+    pct: Option<f64>,
+    /// Rest between sets in seconds
+    #[arg(long)]
+    rest: Option<u32>,
+    /// Segment label
+    #[arg(long)]
+    label: Option<String>,
+    /// Segment fields as a JSON object; the flags above take precedence
+    #[arg(long)]
+    json: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -87,7 +435,7 @@ enum ChartAction {
         /// Chart type to generate
         #[arg(long)]
         chart_type: String,
-        /// Exercise filter (for e1rm and pr charts)
+        /// Exercise filter (for e1rm, pr and planned intensity charts)
         #[arg(long)]
         exercise: Option<String>,
         /// Start date filter (YYYY-MM-DD)
@@ -96,6 +444,18 @@ enum ChartAction {
         /// End date filter (YYYY-MM-DD)
         #[arg(long)]
         end_date: Option<String>,
+        /// Plan ID to project (for planned-* charts)
+        #[arg(long)]
+        plan: Option<String>,
+        /// Plan version (defaults to the current draft)
+        #[arg(long)]
+        plan_version: Option<String>,
+        /// Number of weeks to project (defaults to the plan's block length)
+        #[arg(long)]
+        weeks: Option<u32>,
+        /// Weight display unit: kg or lb (defaults to the plan's unit, then preferences)
+        #[arg(long)]
+        unit: Option<String>,
         /// Output file (defaults to stdout)
         #[arg(long)]
         output: Option<PathBuf>,
@@ -107,11 +467,15 @@ enum ChartAction {
         spec: PathBuf,
         /// Output format
         #[arg(long)]
-        format: String, // svg, png, pdf
+        format: String, // svg, png
         /// Output file
         #[arg(long)]
         output: PathBuf,
     },
+    /// List built-in chart types and user chart definitions
+    List,
+    /// Compare two lifts, date windows or plan blocks week by week
+    Compare(Box<CompareArgs>),
     /// Export chart data as CSV
     ExportCsv {
         /// Chart type to export data for
@@ -126,6 +490,9 @@ enum ChartAction {
         /// End date filter (YYYY-MM-DD)
         #[arg(long)]
         end_date: Option<String>,
+        /// Weight display unit: kg or lb (defaults to preferences)
+        #[arg(long)]
+        unit: Option<String>,
         /// Output file
         #[arg(long)]
         output: PathBuf,
@@ -135,30 +502,24 @@ enum ChartAction {
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
+    let out = Output::new(cli.format);
 
     let paths = match AppPaths::new() {
         Ok(paths) => paths,
-        Err(e) => {
-            eprintln!("Failed to initialize app paths: {}", e);
-            std::process::exit(4);
-        }
+        Err(e) => out.fail(&CliError::not_found(format!(
+            "Failed to initialize app paths: {}",
+            e
+        ))),
     };
 
-    match cli.command {
-        Commands::Plans { action } => match handle_plan_command(action, &paths).await {
-            Ok(()) => {}
-            Err(e) => {
-                eprintln!("Error: {}", e);
-                std::process::exit(2);
-            }
-        },
-        Commands::Chart { action } => match handle_chart_command(action, &paths).await {
-            Ok(()) => {}
-            Err(e) => {
-                eprintln!("Error: {}", e);
-                std::process::exit(2);
-            }
-        },
+    let result = match cli.command {
+        Commands::Plans { action } => handle_plan_command(action, &paths, &out).await,
+        Commands::Chart { action } => handle_chart_command(action, &paths, &out).await,
+        Commands::Report(args) => handle_report_command(args, &paths, &out),
+        Commands::Maxes { action } => handle_max_command(action, &paths, &out),
+    };
+    if let Err(e) = result {
+        out.fail(e.as_ref());
     }
 
     Ok(())
@@ -167,6 +528,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 async fn handle_plan_command(
     action: PlanAction,
     paths: &AppPaths,
+    out: &Output,
 ) -> Result<(), Box<dyn std::error::Error>> {
     match action {
         PlanAction::Validate { r#in, file } => {
@@ -177,15 +539,22 @@ async fn handle_plan_command(
             } else if let Some(file_path) = file {
                 fs::read_to_string(file_path)?
             } else {
-                return Err("Must specify either --in flag or --file path".into());
+                return Err(
+                    CliError::invalid("Must specify either --in flag or --file path").into(),
+                );
             };
 
             let plan: Plan = serde_json::from_str(&plan_json)?;
             let validator = PlanValidator::new()?;
             let result = validator.validate(&plan);
+            let data = serde_json::to_value(&result)?;
 
-            // Output JSON result to stdout
-            println!("{}", serde_json::to_string_pretty(&result)?);
+            // Text mode keeps the full result on stdout even when validation fails
+            if result.errors.is_empty() || !out.is_json() {
+                out.emit(&data, || {
+                    serde_json::to_string_pretty(&data).unwrap_or_default()
+                })?;
+            }
 
             // Human-readable summary to stderr
             eprintln!(
@@ -194,9 +563,14 @@ async fn handle_plan_command(
                 result.warnings.len()
             );
 
-            // Exit with appropriate code
             if !result.errors.is_empty() {
-                std::process::exit(2);
+                return Err(CliError::new(
+                    ExitCode::Invalid,
+                    format!("Plan failed validation with {} errors", result.errors.len()),
+                )
+                .with_errors(result.errors)
+                .with_data(data)
+                .into());
             }
         }
         PlanAction::Save { r#in, file } => {
@@ -207,13 +581,16 @@ async fn handle_plan_command(
             } else if let Some(file_path) = file {
                 fs::read_to_string(file_path)?
             } else {
-                return Err("Must specify either --in flag or --file path".into());
+                return Err(
+                    CliError::invalid("Must specify either --in flag or --file path").into(),
+                );
             };
 
             let plan: Plan = serde_json::from_str(&plan_json)?;
 
             // Generate plan ID if not present - using plan name as base
             let plan_id = generate_plan_id(&plan.name);
+            validate_plan_id(&plan_id)?;
 
             // Save as draft
             let draft_path = paths.draft_path(&plan_id);
@@ -227,22 +604,15 @@ async fn handle_plan_command(
                 "path": draft_path.to_string_lossy()
             });
 
-            println!("{}", serde_json::to_string_pretty(&response)?);
+            out.emit(&response, || {
+                serde_json::to_string_pretty(&response).unwrap_or_default()
+            })?;
         }
         PlanAction::Get { id, version } => {
-            let plan_path = if let Some(v) = version {
-                paths.active_plan_path(&id, &v)
-            } else {
-                paths.draft_path(&id)
-            };
-
-            if !plan_path.exists() {
-                eprintln!("Plan not found: {}", id);
-                std::process::exit(4);
-            }
-
-            let plan_json = fs::read_to_string(plan_path)?;
-            println!("{}", plan_json);
+            let plan_ref = parse_plan_ref(version.as_deref())?;
+            let plan_json = PlanStore::new(paths).read(&id, &plan_ref)?;
+            let plan: serde_json::Value = serde_json::from_str(&plan_json)?;
+            out.emit(&plan, || plan_json.trim_end().to_string())?;
         }
         PlanAction::Export {
             id,
@@ -251,25 +621,13 @@ async fn handle_plan_command(
             dry_run,
         } => {
             // Load plan
-            let plan_path = if let Some(ref v) = version {
-                paths.active_plan_path(&id, v)
-            } else {
-                paths.draft_path(&id)
-            };
-
-            if !plan_path.exists() {
-                eprintln!("Plan not found: {}", id);
-                std::process::exit(4);
-            }
-
-            let plan_json = fs::read_to_string(&plan_path)?;
-            let plan: Plan = serde_json::from_str(&plan_json)?;
+            let plan_ref = parse_plan_ref(version.as_deref())?;
+            let plan = PlanStore::new(paths).load(&id, &plan_ref)?;
 
             // Create versioned plan wrapper
-            let plan_version = if let Some(ref v) = version {
-                parse_version(v)?
-            } else {
-                PlanVersion::new(1, 0, 0)
+            let plan_version = match plan_ref {
+                PlanRef::Version(version) => version,
+                PlanRef::Draft => PlanVersion::new(1, 0, 0),
             };
 
             let versioned_plan = VersionedPlan {
@@ -293,22 +651,42 @@ async fn handle_plan_command(
             // Check for conflicts
             let conflicts = stager.get_conflicts();
             if !conflicts.is_empty() {
-                eprintln!("Export conflicts detected:");
-                for conflict in conflicts {
-                    eprintln!(
-                        "  • {:?} ({:?}): {}",
-                        conflict.conflict_type, conflict.severity, conflict.description
-                    );
-                }
+                let mut details: Vec<String> = conflicts
+                    .iter()
+                    .map(|conflict| {
+                        format!(
+                            "{:?} ({:?}): {}",
+                            conflict.conflict_type, conflict.severity, conflict.description
+                        )
+                    })
+                    .collect();
 
                 // Check for existing files at mount point
                 let export_path = mount.join("plans").join(&id);
                 if export_path.exists() {
-                    eprintln!("  • File exists at mount point: {}", export_path.display());
+                    details.push(format!(
+                        "File exists at mount point: {}",
+                        export_path.display()
+                    ));
                 }
 
                 if !stager.can_export() {
-                    std::process::exit(3); // Exit code 3 for conflicts
+                    let errors = details
+                        .iter()
+                        .map(|detail| CliError::error_info(ExitCode::Conflict, detail, &id))
+                        .collect();
+                    return Err(CliError::conflict(format!(
+                        "Export of {} blocked by {} conflicts",
+                        id,
+                        conflicts.len()
+                    ))
+                    .with_errors(errors)
+                    .into());
+                }
+
+                eprintln!("Export conflicts detected:");
+                for detail in details {
+                    eprintln!("  • {}", detail);
                 }
             }
 
@@ -319,7 +697,9 @@ async fn handle_plan_command(
             );
 
             // Output manifest JSON to stdout
-            println!("{}", serde_json::to_string_pretty(&manifest)?);
+            out.emit(&manifest, || {
+                serde_json::to_string_pretty(&manifest).unwrap_or_default()
+            })?;
 
             if !dry_run {
                 // Perform actual export
@@ -344,11 +724,619 @@ async fn handle_plan_command(
                 eprintln!("Dry run - no files written");
             }
         }
+        PlanAction::Adherence {
+            id,
+            version,
+            input,
+            format,
+            output,
+        } => {
+            let plan = load_plan(paths, &id, version.as_deref())?;
+            let sessions = SessionCsvParser::new().parse_csv_file(&input)?;
+            let report = AdherenceAnalyzer::analyze(&plan, &sessions)?;
+
+            let rendered = match format.as_str() {
+                "json" => serde_json::to_value(&report)?,
+                "csv" => serde_json::Value::String(adherence_csv(&report)?),
+                "spec" => {
+                    let chart_data = adherence_chart_data(&report);
+                    BuiltinCharts::plan_adherence(&chart_data, &report.plan_name, None).to_json()
+                }
+                _ => {
+                    return Err(CliError::invalid(format!(
+                        "Unknown adherence format: {}. Available: json, csv, spec",
+                        format
+                    ))
+                    .into());
+                }
+            };
+
+            write_or_emit(out, &rendered, output.as_deref())?;
+            if let Some(output_path) = &output {
+                eprintln!("Adherence report written to: {}", output_path.display());
+            }
+
+            eprintln!(
+                "Adherence summary: {} sessions, {:.0}% of prescribed sets completed, {} missed, {} extra",
+                report.summary.sessions,
+                report.summary.completion_pct,
+                report.summary.missed_sets,
+                report.summary.extra_sets
+            );
+        }
+        PlanAction::Loads {
+            id,
+            version,
+            week,
+            day,
+            location,
+            training_max,
+            since,
+        } => {
+            let plan = load_plan(paths, &id, version.as_deref())?;
+            let since = since
+                .map(|s| NaiveDate::parse_from_str(&s, "%Y-%m-%d"))
+                .transpose()?;
+            let maxes = training_maxes(paths, since, &training_max)?;
+
+            let location = match location.as_deref() {
+                None => None,
+                Some("home") => Some(LocationProfile::home_gym()),
+                Some("gym") => Some(LocationProfile::commercial_gym()),
+                Some(path) => Some(serde_json::from_str(&fs::read_to_string(path)?)?),
+            };
+            let mut calculator = LoadCalculator::new(&plan, &maxes);
+            if let Some(location) = &location {
+                calculator = calculator.location(location);
+            }
+            let sessions = match day {
+                Some(day) => vec![calculator.session(week, day)?],
+                None => calculator.week(week)?,
+            };
+
+            if maxes.is_empty() {
+                eprintln!("No training maxes: ingest session logs or pass --tm EX=kg");
+            }
+            out.emit(&sessions, || loads_text(&sessions))?;
+        }
+        PlanAction::Progress {
+            id,
+            version,
+            input,
+            weeks,
+            training_max,
+            apply,
+        } => {
+            let plan = load_plan(paths, &id, version.as_deref())?;
+            let sessions = match &input {
+                Some(input) => SessionCsvParser::new().parse_csv_file(input)?,
+                None => MetricsCache::new(paths)?.query_sets(&MetricsQuery::new())?,
+            };
+            let maxes = training_maxes(paths, None, &training_max)?;
+            let engine = ProgressionEngine::new(&plan, &maxes)?;
+            let targets = engine.next_targets(&sessions);
+
+            match weeks {
+                Some(weeks) => {
+                    let simulated = engine.simulate(&sessions, weeks);
+                    out.emit(&simulated, || {
+                        simulated
+                            .iter()
+                            .map(|week| {
+                                let mut lines = vec![format!("Week {}", week.week)];
+                                lines.extend(week.targets.iter().map(progression_target_line));
+                                lines.join("\n")
+                            })
+                            .collect::<Vec<_>>()
+                            .join("\n")
+                    })?;
+                }
+                None => out.emit(&targets, || {
+                    targets
+                        .iter()
+                        .map(progression_target_line)
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })?,
+            }
+
+            if apply {
+                let mut registry = TrainingMaxRegistry::load(paths)?;
+                let today = chrono::Local::now().date_naive();
+                let mut updated = 0;
+                for target in &targets {
+                    let Some(kg) = target.training_max_kg else {
+                        continue;
+                    };
+                    if maxes.get(&target.ex) != Some(&kg) {
+                        registry.set(&target.ex, kg, TrainingMaxSource::Progression, today)?;
+                        updated += 1;
+                    }
+                }
+                registry.save(paths)?;
+                eprintln!("Updated {} training max(es)", updated);
+            }
+        }
+        PlanAction::List => {
+            let plans = PlanStore::new(paths).list()?;
+            if plans.is_empty() {
+                eprintln!("No plans found");
+            }
+            out.emit(&plans, || {
+                plans
+                    .iter()
+                    .map(|plan| {
+                        format!(
+                            "{:<24} {:<32} {:<8} {}",
+                            plan.id,
+                            plan.name.as_deref().unwrap_or("(unreadable)"),
+                            plan.latest()
+                                .map(|v| v.to_string())
+                                .unwrap_or_else(|| "-".to_string()),
+                            if plan.has_draft { "draft" } else { "" }
+                        )
+                        .trim_end()
+                        .to_string()
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })?;
+        }
+        PlanAction::History { id } => {
+            let store = PlanStore::new(paths);
+            let history = store.history(&id)?;
+            if history.is_empty() {
+                // A plan with only a draft has no history yet
+                store.load(&id, &PlanRef::Draft)?;
+                eprintln!("Plan {} has no promoted versions", id);
+            }
+            out.emit(&history, || {
+                history
+                    .iter()
+                    .map(|entry| {
+                        format!(
+                            "{:<8} {}  {}{}{}",
+                            entry.version.to_string(),
+                            entry.metadata.created_at,
+                            entry.metadata.message.as_deref().unwrap_or("(no message)"),
+                            entry
+                                .metadata
+                                .author
+                                .as_ref()
+                                .map(|author| format!(" - {}", author))
+                                .unwrap_or_default(),
+                            if entry.metadata.tags.is_empty() {
+                                String::new()
+                            } else {
+                                format!(" [{}]", entry.metadata.tags.join(", "))
+                            }
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })?;
+        }
+        PlanAction::Diff { id, from, to } => {
+            let store = PlanStore::new(paths);
+            let from = match from {
+                Some(from) => from.parse::<PlanRef>()?,
+                None => match store.history(&id)?.pop() {
+                    Some(latest) => PlanRef::Version(latest.version),
+                    None => {
+                        return Err(CliError::not_found(format!(
+                            "Plan {} has no promoted versions",
+                            id
+                        ))
+                        .into())
+                    }
+                },
+            };
+            let to = match to {
+                Some(to) => to.parse::<PlanRef>()?,
+                None => PlanRef::Draft,
+            };
+
+            let diff = store.diff(&id, &from, &to)?;
+            out.emit(&diff, || diff_text(&id, &from, &to, &diff))?;
+        }
+        PlanAction::Promote {
+            id,
+            message,
+            author,
+            bump,
+        } => {
+            let bump: VersionBump = bump.parse()?;
+            let entry = PlanStore::new(paths).promote(&id, bump, message, author)?;
+
+            let response = serde_json::json!({
+                "plan_id": id,
+                "version": entry.version.to_string(),
+                "status": entry.state,
+                "parent_version": entry.metadata.parent_version.map(|v| v.to_string()),
+                "path": paths.active_plan_path(&id, &entry.version.to_string()).to_string_lossy()
+            });
+            out.emit(&response, || {
+                serde_json::to_string_pretty(&response).unwrap_or_default()
+            })?;
+        }
+        PlanAction::Tag {
+            id,
+            tag,
+            version,
+            remove,
+        } => {
+            let store = PlanStore::new(paths);
+            let version = match version {
+                Some(version) => parse_version(&version)?,
+                None => match store.history(&id)?.pop() {
+                    Some(latest) => latest.version,
+                    None => {
+                        return Err(CliError::not_found(format!(
+                            "Plan {} has no promoted versions",
+                            id
+                        ))
+                        .into())
+                    }
+                },
+            };
+            let entry = store.tag(&id, &version, &tag, remove)?;
+            out.emit(&entry, || {
+                format!(
+                    "{} {}: [{}]",
+                    id,
+                    entry.version,
+                    entry.metadata.tags.join(", ")
+                )
+            })?;
+        }
+        PlanAction::Delete { id, version } => {
+            let target = version.map(|v| v.parse::<PlanRef>()).transpose()?;
+            PlanStore::new(paths).delete(&id, target.as_ref())?;
+            let deleted = target
+                .map(|target| target.to_string())
+                .unwrap_or_else(|| "all".to_string());
+            let response = serde_json::json!({ "plan_id": id, "deleted": deleted });
+            out.emit(&response, || {
+                if deleted == "all" {
+                    format!("Deleted plan {}", id)
+                } else {
+                    format!("Deleted {} of plan {}", deleted, id)
+                }
+            })?;
+        }
+        PlanAction::New {
+            name,
+            id,
+            unit,
+            author,
+            force,
+        } => {
+            let plan_id = id.unwrap_or_else(|| generate_plan_id(&name));
+            validate_plan_id(&plan_id)?;
+            let draft_path = paths.draft_path(&plan_id);
+            if draft_path.exists() && !force {
+                return Err(CliError::conflict(format!(
+                    "Draft {} already exists; use --force to replace it",
+                    plan_id
+                ))
+                .into());
+            }
+
+            let mut plan = Plan::new(name);
+            plan.unit = serde_json::from_value::<Unit>(serde_json::json!(unit))
+                .map_err(|_| format!("Unknown unit: {}. Available: kg, lb, bw", unit))?;
+            plan.author = author;
+            plan.source_url = None;
+            plan.license_note = None;
+
+            save_edited_draft(paths, out, &plan_id, &plan, "")?;
+        }
+        PlanAction::AddDay {
+            id,
+            label,
+            day,
+            time_cap,
+            goal,
+        } => {
+            let mut plan = load_plan(paths, &id, None)?;
+            let number =
+                day.unwrap_or_else(|| plan.schedule.iter().map(|d| d.day).max().unwrap_or(0) + 1);
+            plan.add_day(Day {
+                day: number,
+                label,
+                time_cap_min: time_cap,
+                goal,
+                equipment_policy: None,
+                segments: Vec::new(),
+            })?;
+            let pointer = format!("/schedule/{}", plan.schedule.len() - 1);
+            save_edited_draft(paths, out, &id, &plan, &pointer)?;
+        }
+        PlanAction::AddSegment(args) => {
+            let mut plan = load_plan(paths, &args.id, None)?;
+            let day_index = day_index(&plan, args.day)?;
+            let segment: Segment = serde_json::from_value(segment_json(&args)?)
+                .map_err(|e| format!("Invalid {} segment: {}", args.segment_type, e))?;
+            if let (Some(ex), Some(name)) = (&args.ex, &args.name) {
+                plan.add_dictionary_entry(ex, name)?;
+            }
+            plan.add_segment(day_index, segment)?;
+            let pointer = format!(
+                "/schedule/{}/segments/{}",
+                day_index,
+                plan.schedule[day_index].segments.len() - 1
+            );
+            save_edited_draft(paths, out, &args.id, &plan, &pointer)?;
+        }
+        PlanAction::Set { id, pointer, value } => {
+            let mut plan = load_plan(paths, &id, None)?;
+            let value = serde_json::from_str(&value).unwrap_or(serde_json::Value::String(value));
+            plan.set_pointer(&pointer, value)?;
+            save_edited_draft(paths, out, &id, &plan, &pointer)?;
+        }
+        PlanAction::Rm { id, pointer } => {
+            let mut plan = load_plan(paths, &id, None)?;
+            plan.remove_pointer(&pointer)?;
+            save_edited_draft(paths, out, &id, &plan, &pointer)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate an edited plan and save it as the draft. Nothing is written when
+/// validation fails; warnings are reported but do not block the edit.
+fn save_edited_draft(
+    paths: &AppPaths,
+    out: &Output,
+    id: &str,
+    plan: &Plan,
+    pointer: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let result = PlanValidator::new()?.validate(plan);
+    if !result.errors.is_empty() {
+        return Err(CliError::new(
+            ExitCode::Invalid,
+            format!(
+                "Edit rejected: plan would fail validation with {} errors",
+                result.errors.len()
+            ),
+        )
+        .with_errors(result.errors)
+        .into());
     }
+    for warning in &result.warnings {
+        eprintln!(
+            "Warning [{}] {} at {}",
+            warning.code, warning.message, warning.path
+        );
+    }
+
+    validate_plan_id(id)?;
+    let draft_path = paths.draft_path(id);
+    fs::create_dir_all(draft_path.parent().unwrap())?;
+    fs::write(&draft_path, serde_json::to_string_pretty(plan)?)?;
 
+    let response = serde_json::json!({
+        "plan_id": id,
+        "pointer": pointer,
+        "path": draft_path.to_string_lossy(),
+        "warnings": result.warnings,
+    });
+    out.emit(&response, || {
+        if pointer.is_empty() {
+            format!("Created draft {} at {}", id, draft_path.display())
+        } else {
+            format!("Updated draft {} at {}", id, pointer)
+        }
+    })?;
     Ok(())
 }
 
+/// Schedule index of the day numbered `day`, or of the last day
+fn day_index(plan: &Plan, day: Option<u32>) -> Result<usize, CliError> {
+    match day {
+        Some(number) => plan
+            .schedule
+            .iter()
+            .position(|d| d.day == number)
+            .ok_or_else(|| CliError::not_found(format!("Day {} not found", number))),
+        None => plan.schedule.len().checked_sub(1).ok_or_else(|| {
+            CliError::new(
+                ExitCode::Invalid,
+                "Plan has no days yet; add one with `comp plans add-day`",
+            )
+        }),
+    }
+}
+
+/// Segment JSON from `--json` overlaid with the individual flags
+fn segment_json(args: &AddSegmentArgs) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    let mut segment = match &args.json {
+        Some(json) => match serde_json::from_str(json)? {
+            serde_json::Value::Object(map) => map,
+            _ => return Err(CliError::invalid("--json must be a JSON object").into()),
+        },
+        None => serde_json::Map::new(),
+    };
+    segment.insert("type".into(), args.segment_type.clone().into());
+
+    let reps = args
+        .reps
+        .as_deref()
+        .map(parse_reps)
+        .transpose()
+        .map_err(CliError::invalid)?;
+    if args.segment_type == "percentage" {
+        if let Some(pct) = args.pct {
+            let reps = match reps {
+                Some((min, max)) if min == max => min,
+                Some(_) => {
+                    return Err(
+                        CliError::invalid("Percentage segments take a single rep count").into(),
+                    )
+                }
+                None => return Err(CliError::invalid("--reps is required with --pct").into()),
+            };
+            segment.insert(
+                "prescriptions".into(),
+                serde_json::json!([{ "sets": args.sets.unwrap_or(1), "reps": reps, "pct_1rm": pct }]),
+            );
+        }
+    } else {
+        if let Some(sets) = args.sets {
+            segment.insert("sets".into(), sets.into());
+        }
+        if let Some((min, max)) = reps {
+            segment.insert("reps".into(), serde_json::json!({ "min": min, "max": max }));
+        }
+    }
+
+    let fields = [
+        ("ex", args.ex.clone().map(Into::into)),
+        ("rpe", args.rpe.map(Into::into)),
+        ("rir", args.rir.map(Into::into)),
+        ("rest_sec", args.rest.map(Into::into)),
+        ("label", args.label.clone().map(Into::into)),
+    ];
+    for (field, value) in fields {
+        if let Some(value) = value {
+            segment.insert(field.into(), value);
+        }
+    }
+
+    Ok(serde_json::Value::Object(segment))
+}
+
+/// Parse a rep count ("5") or range ("8-12") into min and max
+fn parse_reps(reps: &str) -> Result<(u32, u32), String> {
+    let invalid = || format!("Invalid reps: {}. Use a count (5) or a range (8-12)", reps);
+    let (min, max) = match reps.split_once('-') {
+        Some((min, max)) => (min.trim(), max.trim()),
+        None => (reps.trim(), reps.trim()),
+    };
+    let min: u32 = min.parse().map_err(|_| invalid())?;
+    let max: u32 = max.parse().map_err(|_| invalid())?;
+    if min > max {
+        return Err(invalid());
+    }
+    Ok((min, max))
+}
+
+/// Human-readable diff: a summary line, then one line per change
+fn diff_text(id: &str, from: &PlanRef, to: &PlanRef, diff: &PlanDiff) -> String {
+    let metrics = &diff.metrics;
+    let mut text = format!(
+        "{} {} -> {}: {} changes (+{} ~{} -{})",
+        id,
+        from,
+        to,
+        metrics.total_changes,
+        metrics.additions,
+        metrics.modifications,
+        metrics.deletions
+    );
+    for change in &diff.changes {
+        let (marker, detail) = match change.change_type {
+            ChangeType::Added => ("+", compact_json(change.new_value.as_ref())),
+            ChangeType::Removed => ("-", compact_json(change.old_value.as_ref())),
+            ChangeType::Modified => (
+                "~",
+                format!(
+                    "{} -> {}",
+                    compact_json(change.old_value.as_ref()),
+                    compact_json(change.new_value.as_ref())
+                ),
+            ),
+        };
+        text.push_str(&format!("\n{} {}  {}", marker, change.path, detail));
+    }
+    text
+}
+
+/// Single-line JSON, shortened for terminal output
+fn compact_json(value: Option<&serde_json::Value>) -> String {
+    const MAX_CHARS: usize = 60;
+    let json = value.map(|v| v.to_string()).unwrap_or_default();
+    if json.chars().count() > MAX_CHARS {
+        format!("{}...", json.chars().take(MAX_CHARS).collect::<String>())
+    } else {
+        json
+    }
+}
+
+/// Per-session (date, day_label, completed, missed, extra) totals for the adherence chart
+fn adherence_chart_data(report: &AdherenceReport) -> Vec<(NaiveDate, String, u32, u32, u32)> {
+    let mut totals: std::collections::BTreeMap<(NaiveDate, String, String), (u32, u32, u32)> =
+        std::collections::BTreeMap::new();
+    for row in &report.segments {
+        let entry = totals
+            .entry((row.date, row.session_id.clone(), row.day_label.clone()))
+            .or_default();
+        entry.0 += row.performed_sets - row.extra_sets;
+        entry.1 += row.missed_sets;
+        entry.2 += row.extra_sets;
+    }
+
+    totals
+        .into_iter()
+        .map(|((date, _, day_label), (completed, missed, extra))| {
+            (date, day_label, completed, missed, extra)
+        })
+        .collect()
+}
+
+fn adherence_csv(report: &AdherenceReport) -> Result<String, Box<dyn std::error::Error>> {
+    let mut wtr = csv::Writer::from_writer(Vec::new());
+
+    // Write header
+    wtr.write_record([
+        "session_id",
+        "date",
+        "week",
+        "day_label",
+        "segment_id",
+        "ex",
+        "alt_group",
+        "prescribed_sets",
+        "performed_sets",
+        "missed_sets",
+        "extra_sets",
+        "swapped_sets",
+        "swapped_to",
+        "reps_under",
+        "reps_over",
+        "reps_in_range",
+        "mean_rpe_drift",
+    ])?;
+
+    // Write data
+    for row in &report.segments {
+        wtr.write_record([
+            row.session_id.as_str(),
+            &row.date.format("%Y-%m-%d").to_string(),
+            &row.week.to_string(),
+            row.day_label.as_str(),
+            &row.segment_id.to_string(),
+            row.ex.as_str(),
+            row.alt_group.as_deref().unwrap_or(""),
+            &row.prescribed_sets.to_string(),
+            &row.performed_sets.to_string(),
+            &row.missed_sets.to_string(),
+            &row.extra_sets.to_string(),
+            &row.swapped_sets.to_string(),
+            &row.swapped_to.join(";"),
+            &row.reps_under.to_string(),
+            &row.reps_over.to_string(),
+            &row.reps_in_range.to_string(),
+            &row.mean_rpe_drift
+                .map_or("".to_string(), |d| format!("{:.2}", d)),
+        ])?;
+    }
+
+    Ok(String::from_utf8(wtr.into_inner()?)?)
+}
+
 fn generate_plan_id(name: &str) -> String {
     // Convert name to valid ID (alphanumeric + underscores)
     name.chars()
@@ -365,27 +1353,17 @@ fn generate_plan_id(name: &str) -> String {
 }
 
 fn parse_version(version_str: &str) -> Result<PlanVersion, Box<dyn std::error::Error>> {
-    let parts: Vec<&str> = version_str.split('.').collect();
-    if parts.len() != 3 {
-        return Err(format!(
-            "Invalid version format: {}. Expected major.minor.patch",
-            version_str
-        )
-        .into());
-    }
-
-    let major = parts[0].parse::<u32>()?;
-    let minor = parts[1].parse::<u32>()?;
-    let patch = parts[2].parse::<u32>()?;
-
-    Ok(PlanVersion::new(major, minor, patch))
+    Ok(version_str
+        .parse::<PlanVersion>()
+        .map_err(CliError::invalid)?)
 }
 
 async fn handle_chart_command(
     action: ChartAction,
     paths: &AppPaths,
+    out: &Output,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let cache = MetricsCache::new(paths);
+    let cache = MetricsCache::new(paths)?;
 
     match action {
         ChartAction::EmitSpec {
@@ -393,123 +1371,534 @@ async fn handle_chart_command(
             exercise,
             start_date,
             end_date,
+            plan,
+            plan_version,
+            weeks,
+            unit,
             output,
         } => {
             let date_range = parse_date_range(start_date.as_deref(), end_date.as_deref())?;
+            let plan = match plan.as_deref() {
+                Some(id) => Some(load_plan(paths, id, plan_version.as_deref())?),
+                None => None,
+            };
+            let unit = resolve_unit(paths, unit.as_deref(), plan.as_ref())?;
+            let projection = || -> Result<PlanProjection, Box<dyn std::error::Error>> {
+                let plan = plan
+                    .as_ref()
+                    .ok_or_else(|| CliError::invalid("Planned charts require --plan <id>"))?;
+                project_plan(paths, plan, weeks)
+            };
+
+            let filters = ChartFilters {
+                exercise: exercise.as_deref(),
+                date_range,
+                unit,
+                title: None,
+            };
 
             let spec = match chart_type.as_str() {
-                "e1rm" => {
-                    let e1rm_data = cache.load_e1rm_data()?;
-                    let filtered_data =
-                        filter_e1rm_data(&e1rm_data, exercise.as_deref(), date_range);
-                    BuiltinCharts::e1rm_over_time(&filtered_data, exercise.as_deref(), None)
+                "planned-volume" => {
+                    let projection = projection()?;
+                    let planned_data: Vec<_> = projection
+                        .volume
+                        .iter()
+                        .map(|v| (v.week, v.group.clone(), v.weighted_sets))
+                        .collect();
+                    BuiltinCharts::planned_weekly_sets(&planned_data, &projection.plan_name, None)
                 }
-                "volume" => {
-                    let volume_data = cache.load_volume_data()?;
-                    let filtered_data = filter_volume_data(&volume_data, date_range);
-                    BuiltinCharts::weekly_volume_by_bodypart(
-                        &filtered_data,
-                        None,
-                        VolumeMetric::TonnageKg,
-                        None,
-                    )
-                }
-                "pr" => {
-                    let pr_data = cache.load_pr_data()?;
-                    let filtered_data = filter_pr_data(&pr_data, exercise.as_deref(), date_range);
-                    BuiltinCharts::pr_board(
-                        &filtered_data,
+                "planned-rpe" | "planned-pct1rm" => {
+                    let metric = if chart_type == "planned-rpe" {
+                        IntensityMetric::Rpe
+                    } else {
+                        IntensityMetric::PercentOneRm
+                    };
+                    let projection = projection()?;
+                    let planned_data: Vec<_> = projection
+                        .intensity
+                        .iter()
+                        .map(|i| (i.week, i.exercise.clone(), i.mean_rpe, i.mean_pct_1rm))
+                        .collect();
+                    BuiltinCharts::planned_intensity(
+                        &planned_data,
                         exercise.as_deref(),
-                        PRDisplayMode::Bar,
+                        metric,
+                        &projection.plan_name,
                         None,
                     )
                 }
-                "heatmap" => {
-                    let e1rm_data = cache.load_e1rm_data()?;
-                    let session_dates: Vec<NaiveDate> = e1rm_data.iter().map(|d| d.date).collect();
-                    let (start, end) =
-                        date_range.unwrap_or_else(|| {
-                            let min_date =
-                                session_dates.iter().min().copied().unwrap_or_else(|| {
-                                    NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()
-                                });
-                            let max_date =
-                                session_dates.iter().max().copied().unwrap_or_else(|| {
-                                    NaiveDate::from_ymd_opt(2025, 12, 31).unwrap()
-                                });
-                            (min_date, max_date)
-                        });
-                    BuiltinCharts::session_frequency_heatmap(&session_dates, start, end, None)
-                }
-                _ => {
-                    return Err(format!(
-                        "Unknown chart type: {}. Available: e1rm, volume, pr, heatmap",
-                        chart_type
-                    )
-                    .into());
+                "planned-duration" => {
+                    let projection = projection()?;
+                    let planned_data: Vec<_> = projection
+                        .duration
+                        .iter()
+                        .map(|d| (d.week, d.day, d.day_label.clone(), d.minutes))
+                        .collect();
+                    BuiltinCharts::planned_duration(&planned_data, &projection.plan_name, None)
                 }
+                _ => match builtin_chart_spec(&cache, paths, &chart_type, &filters)? {
+                    Some(spec) => spec,
+                    None => {
+                        let definition = match ChartDefinitions::find(
+                            &paths.charts_dir(),
+                            &chart_type,
+                        ) {
+                            Err(DefinitionError::NotFound(_)) => {
+                                return Err(CliError::invalid(format!(
+                                        "Unknown chart type: {}. Available: {} (or a definition from `comp chart list`)",
+                                        chart_type,
+                                        all_builtin_charts()
+                                            .map(|(name, _)| *name)
+                                            .collect::<Vec<_>>()
+                                            .join(", ")
+                                    ))
+                                    .into());
+                            }
+                            definition => definition?,
+                        };
+                        let rows = query_definition_rows(
+                            &cache,
+                            paths,
+                            &definition,
+                            exercise.as_deref(),
+                            date_range,
+                            unit,
+                        )?;
+                        definition.build_spec(rows)
+                    }
+                },
             };
 
-            let json_spec = spec.to_json_string()?;
+            write_or_emit(out, &spec.to_json(), output.as_deref())?;
+        }
+        ChartAction::Compare(args) => handle_compare_command(&cache, *args, paths, out)?,
+        ChartAction::List => {
+            let charts_dir = paths.charts_dir();
+            let builtin: Vec<_> = all_builtin_charts()
+                .map(|(name, description)| {
+                    serde_json::json!({ "name": name, "description": description })
+                })
+                .collect();
+            let user: Vec<_> = ChartDefinitions::discover(&charts_dir)
+                .into_iter()
+                .map(|(path, definition)| match definition {
+                    Ok(def) => serde_json::json!({
+                        "name": def.name,
+                        "description": def.description
+                            .or(def.title)
+                            .unwrap_or_else(|| format!("{:?} chart", def.query.dataset)),
+                        "path": path,
+                    }),
+                    Err(e) => serde_json::json!({
+                        "name": path.file_name().unwrap_or_default().to_string_lossy(),
+                        "error": e.to_string(),
+                        "path": path,
+                    }),
+                })
+                .collect();
 
-            if let Some(output_path) = output {
-                fs::write(output_path, json_spec)?;
-            } else {
-                println!("{}", json_spec);
-            }
+            let data = serde_json::json!({ "builtin": builtin, "user": user });
+            out.emit(&data, || {
+                let mut text = String::from("Built-in charts:");
+                for (name, description) in all_builtin_charts() {
+                    text.push_str(&format!("\n  {:<18} {}", name, description));
+                }
+                text.push_str(&format!("\n\nUser charts ({}):", charts_dir.display()));
+                if user.is_empty() {
+                    text.push_str("\n  (none)");
+                }
+                for chart in &user {
+                    let name = chart["name"].as_str().unwrap_or_default();
+                    match chart["error"].as_str() {
+                        Some(error) => {
+                            text.push_str(&format!("\n  {:<18} INVALID: {}", name, error))
+                        }
+                        None => text.push_str(&format!(
+                            "\n  {:<18} {}",
+                            name,
+                            chart["description"].as_str().unwrap_or_default()
+                        )),
+                    }
+                }
+                text
+            })?;
         }
         ChartAction::Render {
-            spec: _spec,
-            format: _format,
-            output: _output,
+            spec,
+            format,
+            output,
         } => {
-            eprintln!(
-                "Chart rendering not yet implemented - requires headless browser integration"
-            );
-            std::process::exit(1);
+            let format: RenderFormat = format.parse()?;
+            let spec_json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&spec)?)?;
+            let rendered = ChartRenderer::render(&spec_json, format)?;
+            fs::write(&output, rendered)?;
+            emit_written(out, "Chart rendered", &output)?;
         }
         ChartAction::ExportCsv {
             chart_type,
             exercise,
             start_date,
             end_date,
+            unit,
             output,
         } => {
             let date_range = parse_date_range(start_date.as_deref(), end_date.as_deref())?;
+            let query = MetricsQuery::new()
+                .exercise(exercise.as_deref())
+                .date_range(date_range);
+            let all_exercises = MetricsQuery::new().date_range(date_range);
+            let unit = resolve_unit(paths, unit.as_deref(), None)?;
 
             match chart_type.as_str() {
                 "e1rm" => {
-                    let e1rm_data = cache.load_e1rm_data()?;
-                    let filtered_data =
-                        filter_e1rm_data(&e1rm_data, exercise.as_deref(), date_range);
-                    export_e1rm_csv(&filtered_data, &output)?;
+                    let filtered_data = query_e1rm_data(&cache, &query)?;
+                    export_e1rm_csv(&filtered_data, unit, &output)?;
                 }
                 "volume" => {
-                    let volume_data = cache.load_volume_data()?;
-                    let filtered_data = filter_volume_data(&volume_data, date_range);
-                    export_volume_csv(&filtered_data, &output)?;
+                    let filtered_data = query_volume_data(&cache, &all_exercises)?;
+                    export_volume_csv(&filtered_data, unit, &output)?;
+                }
+                "muscle-volume" | "pattern-volume" => {
+                    let (grouping, _) = group_volume_kind(&chart_type);
+                    let filtered_data = query_group_volume_data(&cache, grouping, &all_exercises)?;
+                    export_group_volume_csv(&filtered_data, unit, &output)?;
+                }
+                "acwr" | "fitness-fatigue" => {
+                    let filtered_data = cache.query_daily_load_data(&all_exercises)?;
+                    export_daily_load_csv(&filtered_data, &output)?;
+                }
+                "weekly-load" | "monotony" | "strain" => {
+                    let filtered_data = query_weekly_load_data(&cache, &all_exercises)?;
+                    export_weekly_load_csv(&filtered_data, &output)?;
                 }
                 "pr" => {
-                    let pr_data = cache.load_pr_data()?;
-                    let filtered_data = filter_pr_data(&pr_data, exercise.as_deref(), date_range);
-                    export_pr_csv(&filtered_data, &output)?;
+                    let filtered_data = query_pr_data(&cache, &query)?;
+                    export_pr_csv(&filtered_data, unit, &output)?;
                 }
                 _ => {
-                    return Err(format!(
-                        "Unknown chart type for CSV export: {}. Available: e1rm, volume, pr",
+                    return Err(CliError::invalid(format!(
+                        "Unknown chart type for CSV export: {}. Available: e1rm, volume, muscle-volume, pattern-volume, acwr, fitness-fatigue, weekly-load, monotony, strain, pr",
                         chart_type
-                    )
+                    ))
                     .into());
                 }
             }
 
-            eprintln!("CSV data exported to: {}", output.display());
+            emit_written(out, "CSV data exported", &output)?;
         }
     }
 
     Ok(())
 }
 
+/// Build both comparison series, print their changes and emit the comparison spec
+fn handle_compare_command(
+    cache: &MetricsCache,
+    args: CompareArgs,
+    paths: &AppPaths,
+    out: &Output,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let metric: CompareMetric = args.metric.parse()?;
+    let measure = match args.show.as_str() {
+        "value" => CompareMeasure::Value,
+        "change" => CompareMeasure::Change,
+        "change-pct" => CompareMeasure::ChangePct,
+        other => {
+            return Err(CliError::invalid(format!(
+                "Unknown value to show: {}. Available: value, change, change-pct",
+                other
+            ))
+            .into())
+        }
+    };
+    let layout = match args.layout.as_str() {
+        "layered" => CompareLayout::Layered,
+        "side-by-side" => CompareLayout::SideBySide,
+        other => {
+            return Err(CliError::invalid(format!(
+                "Unknown layout: {}. Available: layered, side-by-side",
+                other
+            ))
+            .into())
+        }
+    };
+
+    let plan_a = match args.plan_a.as_deref() {
+        Some(id) => Some(load_plan(paths, id, None)?),
+        None => None,
+    };
+    let plan_b = match args.plan_b.as_deref() {
+        Some(id) => Some(load_plan(paths, id, None)?),
+        None => None,
+    };
+    let alignment = match args.align.as_deref() {
+        Some("week") => CompareAlignment::WeekIndex,
+        Some("plan-week") => CompareAlignment::PlanWeek,
+        Some(other) => {
+            return Err(CliError::invalid(format!(
+                "Unknown alignment: {}. Available: week, plan-week",
+                other
+            ))
+            .into())
+        }
+        None if plan_a.is_some() || plan_b.is_some() => CompareAlignment::PlanWeek,
+        None => CompareAlignment::WeekIndex,
+    };
+    let unit = resolve_unit(paths, args.unit.as_deref(), plan_a.as_ref())?;
+
+    let range_a = parse_date_range(args.start_a.as_deref(), args.end_a.as_deref())?;
+    let range_b = match (&args.start_b, &args.end_b) {
+        (None, None) => range_a,
+        _ => parse_date_range(args.start_b.as_deref(), args.end_b.as_deref())?,
+    };
+    let exercise_b = args.exercise_b.as_deref().or(args.exercise.as_deref());
+
+    // Default labels name whatever differs between the two sides
+    let default_label =
+        |side: &str, exercise: Option<&str>, range: Option<(NaiveDate, NaiveDate)>| {
+            let mut parts = Vec::new();
+            if args.exercise.as_deref() != exercise_b {
+                parts.push(exercise.unwrap_or("All exercises").to_string());
+            }
+            if range_a != range_b {
+                if let Some((start, end)) = range {
+                    parts.push(format!("{} to {}", start, end));
+                }
+            }
+            if parts.is_empty() {
+                side.to_string()
+            } else {
+                parts.join(" ")
+            }
+        };
+    let label_a = args
+        .label_a
+        .clone()
+        .or_else(|| plan_a.as_ref().map(|p| p.name.clone()))
+        .unwrap_or_else(|| default_label("A", args.exercise.as_deref(), range_a));
+    let label_b = args
+        .label_b
+        .clone()
+        .or_else(|| plan_b.as_ref().map(|p| p.name.clone()))
+        .unwrap_or_else(|| default_label("B", exercise_b, range_b));
+
+    let series = [
+        compare_series(
+            cache,
+            metric,
+            label_a,
+            args.exercise.as_deref(),
+            range_a,
+            plan_a.as_ref(),
+        )?,
+        compare_series(cache, metric, label_b, exercise_b, range_b, plan_b.as_ref())?,
+    ];
+
+    let points = CompareCharts::align(&series, metric, alignment, unit);
+    for summary in CompareCharts::summarize(&points) {
+        eprintln!(
+            "{}: {:.1} -> {:.1} over {} weeks ({:+.1}{})",
+            summary.series,
+            summary.first,
+            summary.last,
+            summary.weeks,
+            summary.change,
+            summary
+                .change_pct
+                .map(|pct| format!(", {:+.1}%", pct))
+                .unwrap_or_default()
+        );
+    }
+
+    let spec = CompareCharts::compare(
+        &series, metric, alignment, measure, layout, unit, args.title,
+    );
+    write_or_emit(out, &spec.to_json(), args.output.as_deref())?;
+
+    Ok(())
+}
+
+/// Write a command result to `output`, or print it (as the envelope's `data` in JSON mode).
+/// String results are written as-is, anything else as pretty JSON.
+fn write_or_emit(
+    out: &Output,
+    value: &serde_json::Value,
+    output: Option<&Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let text = match value {
+        serde_json::Value::String(text) => text.clone(),
+        other => serde_json::to_string_pretty(other)?,
+    };
+    match output {
+        Some(path) => {
+            fs::write(path, text)?;
+            if out.is_json() {
+                out.emit(&serde_json::json!({ "path": path }), String::new)?;
+            }
+        }
+        None => out.emit(value, || text.trim_end().to_string())?,
+    }
+    Ok(())
+}
+
+/// Report a file a command wrote: a note on stderr, or `{"path"}` in JSON mode
+fn emit_written(out: &Output, what: &str, path: &Path) -> Result<(), serde_json::Error> {
+    if out.is_json() {
+        out.emit(&serde_json::json!({ "path": path }), String::new)
+    } else {
+        eprintln!("{} to: {}", what, path.display());
+        Ok(())
+    }
+}
+
+/// One comparison series from the metrics store; values stay in kg until alignment
+fn compare_series(
+    cache: &MetricsCache,
+    metric: CompareMetric,
+    label: String,
+    exercise: Option<&str>,
+    date_range: Option<(NaiveDate, NaiveDate)>,
+    plan: Option<&Plan>,
+) -> Result<CompareSeries, Box<dyn std::error::Error>> {
+    // Volume rows are keyed by the Monday of their week, so include the start's whole week
+    let query_range = match metric {
+        CompareMetric::E1rm => date_range,
+        CompareMetric::Tonnage | CompareMetric::Sets => date_range.map(|(start, end)| {
+            let monday = start.weekday().num_days_from_monday() as i64;
+            (start - chrono::Duration::days(monday), end)
+        }),
+    };
+    let query = MetricsQuery::new()
+        .exercise(exercise)
+        .date_range(query_range);
+
+    let values: Vec<(NaiveDate, f64)> = match metric {
+        CompareMetric::E1rm => {
+            if exercise.is_none() {
+                return Err(CliError::invalid("Comparing e1rm requires --exercise").into());
+            }
+            query_e1rm_data(cache, &query)?
+                .into_iter()
+                .map(|(_, date, e1rm)| (date, e1rm))
+                .collect()
+        }
+        CompareMetric::Tonnage | CompareMetric::Sets => query_volume_data(cache, &query)?
+            .into_iter()
+            .map(|(_, week_start, sets, _, tonnage)| {
+                let value = if metric == CompareMetric::Sets {
+                    sets as f64
+                } else {
+                    tonnage
+                };
+                (week_start, value)
+            })
+            .collect(),
+    };
+
+    let start = date_range
+        .map(|(start, _)| start)
+        .or_else(|| values.iter().map(|(date, _)| *date).min())
+        .unwrap_or_else(|| chrono::Local::now().date_naive());
+
+    Ok(CompareSeries {
+        label,
+        start,
+        block_weeks: plan.map(Plan::block_weeks),
+        values,
+    })
+}
+
+/// Assemble the offline HTML report from the metrics store (and a plan, if given)
+fn handle_report_command(
+    args: ReportArgs,
+    paths: &AppPaths,
+    out: &Output,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let cache = MetricsCache::new(paths)?;
+    let date_range = parse_date_range(args.start_date.as_deref(), args.end_date.as_deref())?;
+    let query = MetricsQuery::new().date_range(date_range);
+
+    let e1rm_data = query_e1rm_data(&cache, &query)?;
+    let volume_data = query_volume_data(&cache, &query)?;
+    let pr_data = query_pr_data(&cache, &query)?;
+    let session_dates = query_session_dates(&cache, &query)?;
+    let sets = cache.query_sets(&query)?;
+    let plan = match args.plan.as_deref() {
+        Some(id) => Some(load_plan(paths, id, args.plan_version.as_deref())?),
+        None => None,
+    };
+    let unit = resolve_unit(paths, args.unit.as_deref(), plan.as_ref())?;
+
+    let today = chrono::Local::now().date_naive();
+    let (start, end) = date_range.unwrap_or_else(|| {
+        (
+            session_dates.first().copied().unwrap_or(today),
+            session_dates.last().copied().unwrap_or(today),
+        )
+    });
+
+    let charts = ChartGenerator::generate_all_charts(
+        &e1rm_data,
+        &volume_data,
+        &pr_data,
+        &session_dates,
+        (start, end),
+        unit,
+    );
+
+    let mut notes: Vec<SessionNote> = Vec::new();
+    for set in &sets {
+        let Some(text) = set
+            .notes
+            .as_deref()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+        else {
+            continue;
+        };
+        // Notes are stored per set, so a session note repeats on every set it was logged with
+        if notes
+            .last()
+            .is_some_and(|n| n.date == set.date && n.text == text)
+        {
+            continue;
+        }
+        notes.push(SessionNote {
+            date: set.date,
+            day_label: set.day_label.clone(),
+            exercise: Some(set.ex_code.clone()),
+            text: text.to_string(),
+        });
+    }
+
+    let title = args.title.unwrap_or_else(|| {
+        format!(
+            "Training Report {} - {}",
+            start.format("%Y-%m-%d"),
+            end.format("%Y-%m-%d")
+        )
+    });
+    let mut report = HtmlReport::new(title, (start, end))
+        .charts(charts)
+        .notes(notes);
+
+    if let Some(plan) = plan {
+        let adherence = AdherenceAnalyzer::analyze(&plan, &sets)?;
+        report = report
+            .adherence(ReportAdherence {
+                plan_name: adherence.plan_name.clone(),
+                completion_pct: adherence.summary.completion_pct,
+                sessions: adherence_chart_data(&adherence),
+            })
+            .plan(plan);
+    }
+
+    fs::write(&args.output, report.to_html())?;
+    emit_written(out, "Report written", &args.output)?;
+
+    Ok(())
+}
+
 fn parse_date_range(
     start: Option<&str>,
     end: Option<&str>,
@@ -534,75 +1923,446 @@ fn parse_date_range(
     }
 }
 
-fn filter_e1rm_data(
-    data: &[weightlifting_indexer::metrics::E1RMDataPoint],
+/// Plan projection charts handled by `chart emit-spec`, on top of the indexer's `BUILTIN_CHARTS`
+const PLANNED_CHARTS: &[(&str, &str)] = &[
+    (
+        "planned-volume",
+        "Planned weekly sets by muscle group (--plan)",
+    ),
+    (
+        "planned-rpe",
+        "Planned RPE per exercise across the block (--plan)",
+    ),
+    (
+        "planned-pct1rm",
+        "Planned %1RM per exercise across the block (--plan)",
+    ),
+    (
+        "planned-duration",
+        "Planned session length per day (--plan)",
+    ),
+];
+
+/// Every chart type `chart emit-spec` builds without a user definition
+fn all_builtin_charts() -> impl Iterator<Item = &'static (&'static str, &'static str)> {
+    BUILTIN_CHARTS.iter().chain(PLANNED_CHARTS)
+}
+
+/// Dataset rows for a user chart definition; CLI filters override the definition's own
+fn query_definition_rows(
+    cache: &MetricsCache,
+    paths: &AppPaths,
+    definition: &ChartDefinition,
     exercise: Option<&str>,
     date_range: Option<(NaiveDate, NaiveDate)>,
-) -> Vec<(String, NaiveDate, f64)> {
-    data.iter()
-        .filter(|d| exercise.is_none_or(|ex| d.exercise == ex))
-        .filter(|d| date_range.is_none_or(|(start, end)| d.date >= start && d.date <= end))
-        .map(|d| (d.exercise.clone(), d.date, d.e1rm_kg))
-        .collect()
+    unit: DisplayUnit,
+) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {
+    let date_range = date_range.or_else(|| {
+        definition
+            .query
+            .date_range(chrono::Local::now().date_naive())
+    });
+    let query = MetricsQuery::new()
+        .exercise(exercise.or(definition.query.exercise.as_deref()))
+        .date_range(date_range);
+
+    Ok(match definition.query.dataset {
+        ChartDataset::E1rm => {
+            DataTransforms::e1rm_to_chart_data(&query_e1rm_data(cache, &query)?, None)
+        }
+        ChartDataset::Volume => {
+            let body_part_map =
+                load_body_part_map(paths).unwrap_or_else(DataTransforms::get_default_body_part_map);
+            DataTransforms::volume_to_chart_data(&query_volume_data(cache, &query)?, &body_part_map)
+        }
+        ChartDataset::MuscleVolume | ChartDataset::PatternVolume => {
+            let grouping = if definition.query.dataset == ChartDataset::PatternVolume {
+                VolumeGrouping::MovementPattern
+            } else {
+                VolumeGrouping::MuscleGroup
+            };
+            DataTransforms::group_volume_to_chart_data(&query_group_volume_data(
+                cache, grouping, &query,
+            )?)
+        }
+        ChartDataset::DailyLoad => {
+            let load_data: Vec<_> = cache
+                .query_daily_load_data(&query)?
+                .iter()
+                .map(|d| (d.date, d.load, d.acwr, d.fitness, d.fatigue, d.performance))
+                .collect();
+            DataTransforms::daily_load_to_chart_data(&load_data)
+        }
+        ChartDataset::WeeklyLoad => {
+            DataTransforms::weekly_load_to_chart_data(&query_weekly_load_data(cache, &query)?)
+        }
+        ChartDataset::Prs => {
+            DataTransforms::pr_to_chart_data(&query_pr_data(cache, &query)?, None, unit)
+        }
+    })
 }
 
-fn filter_volume_data(
-    data: &[weightlifting_indexer::metrics::VolumeDataPoint],
-    date_range: Option<(NaiveDate, NaiveDate)>,
-) -> Vec<(String, NaiveDate, u32, u32, f64)> {
-    data.iter()
-        .filter(|d| {
-            date_range.is_none_or(|(start, end)| d.week_start >= start && d.week_start <= end)
-        })
+/// (exercise, week_start, sets, reps, tonnage_kg)
+type VolumeRow = (String, NaiveDate, u32, u32, f64);
+/// (group, week_start, weighted_sets, weighted_reps, weighted_tonnage_kg)
+type GroupVolumeRow = (String, NaiveDate, f64, f64, f64);
+/// (week_start, load, monotony, strain)
+type WeeklyLoadRow = (NaiveDate, f64, Option<f64>, Option<f64>);
+/// (exercise, pr_type, date, value, reps)
+type PRRow = (String, String, NaiveDate, f64, Option<u32>);
+
+fn query_e1rm_data(
+    cache: &MetricsCache,
+    query: &MetricsQuery,
+) -> Result<Vec<(String, NaiveDate, f64)>, CacheError> {
+    Ok(cache
+        .query_e1rm_data(query)?
+        .into_iter()
+        .map(|d| (d.exercise, d.date, d.e1rm_kg))
+        .collect())
+}
+
+fn query_volume_data(
+    cache: &MetricsCache,
+    query: &MetricsQuery,
+) -> Result<Vec<VolumeRow>, CacheError> {
+    Ok(cache
+        .query_volume_data(query)?
+        .into_iter()
         .map(|d| {
             (
-                d.category.clone(),
+                d.category,
                 d.week_start,
                 d.total_sets,
                 d.total_reps,
                 d.total_tonnage_kg,
             )
         })
-        .collect()
+        .collect())
 }
 
-fn filter_pr_data(
-    data: &[weightlifting_indexer::metrics::PRDataPoint],
-    exercise: Option<&str>,
-    date_range: Option<(NaiveDate, NaiveDate)>,
-) -> Vec<(String, String, NaiveDate, f64, Option<u32>)> {
-    data.iter()
-        .filter(|d| exercise.is_none_or(|ex| d.exercise == ex))
-        .filter(|d| date_range.is_none_or(|(start, end)| d.date >= start && d.date <= end))
+/// One date per logged session, from the raw sets in the store
+fn query_session_dates(
+    cache: &MetricsCache,
+    query: &MetricsQuery,
+) -> Result<Vec<NaiveDate>, CacheError> {
+    let sessions: std::collections::BTreeSet<(NaiveDate, String)> = cache
+        .query_sets(query)?
+        .into_iter()
+        .map(|set| (set.date, set.session_id))
+        .collect();
+    Ok(sessions.into_iter().map(|(date, _)| date).collect())
+}
+
+fn handle_max_command(
+    action: MaxAction,
+    paths: &AppPaths,
+    out: &Output,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut registry = TrainingMaxRegistry::load(paths)?;
+    let today = chrono::Local::now().date_naive();
+    match action {
+        MaxAction::List => {
+            out.emit(&registry, || {
+                registry
+                    .entries
+                    .iter()
+                    .map(|(ex, entry)| {
+                        format!(
+                            "{:<24} {:>7.1} kg  {:<11} {}",
+                            ex,
+                            entry.kg,
+                            serde_json::to_value(entry.source)
+                                .ok()
+                                .and_then(|v| v.as_str().map(str::to_string))
+                                .unwrap_or_default(),
+                            entry.updated
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })?;
+        }
+        MaxAction::Set { ex, kg } => {
+            registry.set(&ex, kg, TrainingMaxSource::Manual, today)?;
+            registry.save(paths)?;
+            eprintln!("Training max for {} set to {:.1} kg", ex, kg);
+        }
+        MaxAction::Remove { ex } => {
+            if registry.remove(&ex).is_none() {
+                return Err(CliError::not_found(format!("No training max for {}", ex)).into());
+            }
+            registry.save(paths)?;
+            eprintln!("Removed training max for {}", ex);
+        }
+        MaxAction::Seed { fraction, since } => {
+            if !(fraction > 0.0 && fraction <= 1.0) {
+                return Err(CliError::invalid(format!(
+                    "Fraction must be in (0, 1], got {}",
+                    fraction
+                ))
+                .into());
+            }
+            let since = since
+                .map(|s| NaiveDate::parse_from_str(&s, "%Y-%m-%d"))
+                .transpose()?;
+            let e1rms =
+                E1RMCalculator::training_maxes(&MetricsCache::new(paths)?.load_e1rm_data()?, since);
+            let added = registry.seed(&e1rms, fraction, today);
+            registry.save(paths)?;
+            out.emit(&added, || {
+                format!(
+                    "Seeded {} training max(es): {}",
+                    added.len(),
+                    added.join(", ")
+                )
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// Training maxes for load and progression commands: best cached e1RMs (since
+/// `since`), replaced by registry entries, replaced by `EX=kg` overrides
+fn training_maxes(
+    paths: &AppPaths,
+    since: Option<NaiveDate>,
+    overrides: &[String],
+) -> Result<TrainingMaxes, Box<dyn std::error::Error>> {
+    let mut maxes =
+        E1RMCalculator::training_maxes(&MetricsCache::new(paths)?.load_e1rm_data()?, since);
+    maxes.extend(TrainingMaxRegistry::load(paths)?.maxes());
+    for entry in overrides {
+        let (ex, kg) = entry.split_once('=').ok_or_else(|| {
+            CliError::invalid(format!("Invalid training max: {} (expected EX=kg)", entry))
+        })?;
+        maxes.insert(ex.to_string(), kg.parse()?);
+    }
+    Ok(maxes)
+}
+
+fn progression_target_line(target: &ProgressionTarget) -> String {
+    format!(
+        "  {:<18} W{} D{}  {}x{}  {}  {}{}",
+        target.ex,
+        target.week,
+        target.day,
+        target.sets,
+        target.reps,
+        target
+            .load_kg
+            .map(|kg| format!("{:.1} kg", kg))
+            .unwrap_or_else(|| "- (no training max)".to_string()),
+        serde_json::to_value(target.decision)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default(),
+        target
+            .training_max_kg
+            .map(|kg| format!("  TM {:.1} kg", kg))
+            .unwrap_or_default()
+    )
+}
+
+/// One line per prescription: sets x reps, what the load is relative to, and the
+/// target with its rounded load and plates per side
+fn loads_text(sessions: &[SessionLoads]) -> String {
+    let mut lines = Vec::new();
+    for session in sessions {
+        lines.push(format!(
+            "Week {} - Day {}: {}",
+            session.week, session.day, session.label
+        ));
+        for segment in &session.segments {
+            for set in &segment.sets {
+                let basis = match &set.basis {
+                    Some(LoadBasis::Percent1Rm { pct_1rm, of }) if *of == set.ex => {
+                        format!("{:.1}% 1RM", pct_1rm * 100.0)
+                    }
+                    Some(LoadBasis::Percent1Rm { pct_1rm, of }) => {
+                        format!("{:.1}% of {}", pct_1rm * 100.0, of)
+                    }
+                    Some(LoadBasis::Rpe { rpe, .. }) => format!("RPE {}", rpe),
+                    Some(LoadBasis::Anchor {
+                        of_set_index,
+                        multiplier,
+                    }) => format!("{:.0}% of set {}", multiplier * 100.0, of_set_index),
+                    Some(LoadBasis::Drop { percent_drop }) => {
+                        format!("drop {:.0}%", percent_drop * 100.0)
+                    }
+                    Some(LoadBasis::Fixed) => "fixed".to_string(),
+                    None => String::new(),
+                };
+                let load = match (set.target_kg, &set.rounded, &set.note) {
+                    (Some(target), Some(rounded), _) => {
+                        let plates = rounded
+                            .plate_solution
+                            .as_ref()
+                            .map(|solution| {
+                                let per_side: Vec<String> = solution
+                                    .plates_per_side
+                                    .iter()
+                                    .map(|(weight, count)| format!("{}x{}", count, weight))
+                                    .collect();
+                                format!(
+                                    "  [bar {} + {} per side]",
+                                    solution.bar_weight,
+                                    per_side.join(", ")
+                                )
+                            })
+                            .unwrap_or_default();
+                        format!(
+                            "{:.1} kg -> {:.1} kg{}",
+                            target, rounded.rounded_load, plates
+                        )
+                    }
+                    (Some(target), None, _) => format!("{:.1} kg", target),
+                    (None, _, Some(note)) => format!("- ({})", note),
+                    (None, _, None) => "-".to_string(),
+                };
+                lines.push(format!(
+                    "  {}.{} {}{}  {}x{}  {}  {}",
+                    segment.segment_idx,
+                    set.set_index,
+                    set.ex,
+                    set.label
+                        .as_deref()
+                        .map(|label| format!(" ({})", label))
+                        .unwrap_or_default(),
+                    set.sets,
+                    set.reps
+                        .map(|r| r.to_string())
+                        .unwrap_or_else(|| "-".to_string()),
+                    basis,
+                    load
+                ));
+            }
+        }
+    }
+    lines.join("\n")
+}
+
+/// Load a plan draft, or a specific active version (not found exits with code 4)
+fn load_plan(
+    paths: &AppPaths,
+    id: &str,
+    version: Option<&str>,
+) -> Result<Plan, Box<dyn std::error::Error>> {
+    Ok(PlanStore::new(paths).load(id, &parse_plan_ref(version)?)?)
+}
+
+/// `--version` value as a stored plan ref: "draft" or major.minor.patch, the draft when unset
+fn parse_plan_ref(version: Option<&str>) -> Result<PlanRef, PlanStoreError> {
+    version.map_or(Ok(PlanRef::Draft), str::parse)
+}
+
+/// Display unit from `--unit`, else the plan's unit, else the saved preference (kg by default)
+fn resolve_unit(
+    paths: &AppPaths,
+    flag: Option<&str>,
+    plan: Option<&Plan>,
+) -> Result<DisplayUnit, Box<dyn std::error::Error>> {
+    if let Some(flag) = flag {
+        return Ok(flag.parse()?);
+    }
+    if let Some(plan) = plan {
+        return Ok(DisplayUnit::from(&plan.unit));
+    }
+    Ok(UserPreferences::load(paths)
+        .display_unit
+        .unwrap_or_default())
+}
+
+/// Project a plan's prescribed volume, intensity and duration week by week
+fn project_plan(
+    paths: &AppPaths,
+    plan: &Plan,
+    weeks: Option<u32>,
+) -> Result<PlanProjection, Box<dyn std::error::Error>> {
+    let catalog = load_catalog(paths);
+    let projector = match &catalog {
+        Some(catalog) => PlanProjector::with_catalog(catalog),
+        None => PlanProjector::new(),
+    };
+    Ok(projector.project(plan, weeks)?)
+}
+
+/// The installed exercise catalog, if any
+fn load_catalog(paths: &AppPaths) -> Option<ExerciseCatalog> {
+    let db_path = paths.exercise_db_path();
+    if !db_path.exists() {
+        return None;
+    }
+
+    match ExerciseCatalog::open(&db_path) {
+        Ok(catalog) => Some(catalog),
+        Err(e) => {
+            eprintln!("Warning: Failed to load exercise catalog: {}", e);
+            None
+        }
+    }
+}
+
+/// Exercise -> body part map from the exercise catalog, if one is installed
+fn load_body_part_map(paths: &AppPaths) -> Option<std::collections::HashMap<String, String>> {
+    load_catalog(paths).map(|catalog| catalog.primary_body_part_map())
+}
+
+fn query_group_volume_data(
+    cache: &MetricsCache,
+    grouping: VolumeGrouping,
+    query: &MetricsQuery,
+) -> Result<Vec<GroupVolumeRow>, CacheError> {
+    Ok(cache
+        .query_group_volume_data(grouping, query)?
+        .into_iter()
         .map(|d| {
             (
-                d.exercise.clone(),
-                d.pr_type.clone(),
-                d.date,
-                d.value,
-                d.reps,
+                d.group,
+                d.week_start,
+                d.weighted_sets,
+                d.weighted_reps,
+                d.weighted_tonnage_kg,
             )
         })
-        .collect()
+        .collect())
+}
+
+fn query_weekly_load_data(
+    cache: &MetricsCache,
+    query: &MetricsQuery,
+) -> Result<Vec<WeeklyLoadRow>, CacheError> {
+    Ok(cache
+        .query_weekly_load_data(query)?
+        .into_iter()
+        .map(|d| (d.week_start, d.total_load, d.monotony, d.strain))
+        .collect())
+}
+
+fn query_pr_data(cache: &MetricsCache, query: &MetricsQuery) -> Result<Vec<PRRow>, CacheError> {
+    Ok(cache
+        .query_pr_data(query)?
+        .into_iter()
+        .map(|d| (d.exercise, d.pr_type.to_string(), d.date, d.value, d.reps))
+        .collect())
 }
 
 fn export_e1rm_csv(
     data: &[(String, NaiveDate, f64)],
+    unit: DisplayUnit,
     output: &PathBuf,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let mut wtr = csv::Writer::from_path(output)?;
 
     // Write header
-    wtr.write_record(["exercise", "date", "e1rm_kg", "e1rm_lb"])?;
+    wtr.write_record(["exercise", "date", &unit.field("e1rm")])?;
 
     // Write data
     for (exercise, date, e1rm_kg) in data {
-        let e1rm_lb = e1rm_kg * 2.20462;
         wtr.write_record([
             exercise.as_str(),
             &date.format("%Y-%m-%d").to_string(),
-            &e1rm_kg.to_string(),
-            &e1rm_lb.to_string(),
+            &unit.convert(*e1rm_kg).to_string(),
         ])?;
     }
 
@@ -612,6 +2372,7 @@ fn export_e1rm_csv(
 
 fn export_volume_csv(
     data: &[(String, NaiveDate, u32, u32, f64)],
+    unit: DisplayUnit,
     output: &PathBuf,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let mut wtr = csv::Writer::from_path(output)?;
@@ -622,20 +2383,17 @@ fn export_volume_csv(
         "week_start",
         "total_sets",
         "total_reps",
-        "tonnage_kg",
-        "tonnage_lb",
+        &unit.field("tonnage"),
     ])?;
 
     // Write data
     for (exercise, week_start, sets, reps, tonnage_kg) in data {
-        let tonnage_lb = tonnage_kg * 2.20462;
         wtr.write_record([
             exercise.as_str(),
             &week_start.format("%Y-%m-%d").to_string(),
             &sets.to_string(),
             &reps.to_string(),
-            &tonnage_kg.to_string(),
-            &tonnage_lb.to_string(),
+            &unit.convert(*tonnage_kg).to_string(),
         ])?;
     }
 
@@ -643,31 +2401,113 @@ fn export_volume_csv(
     Ok(())
 }
 
-fn export_pr_csv(
-    data: &[(String, String, NaiveDate, f64, Option<u32>)],
+fn export_group_volume_csv(
+    data: &[(String, NaiveDate, f64, f64, f64)],
+    unit: DisplayUnit,
+    output: &PathBuf,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut wtr = csv::Writer::from_path(output)?;
+
+    // Write header
+    wtr.write_record([
+        "group",
+        "week_start",
+        "weighted_sets",
+        "weighted_reps",
+        &unit.field("tonnage"),
+    ])?;
+
+    // Write data
+    for (group, week_start, sets, reps, tonnage_kg) in data {
+        wtr.write_record([
+            group.as_str(),
+            &week_start.format("%Y-%m-%d").to_string(),
+            &sets.to_string(),
+            &reps.to_string(),
+            &unit.convert(*tonnage_kg).to_string(),
+        ])?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+fn export_daily_load_csv(
+    data: &[weightlifting_indexer::training_load::DailyLoadDataPoint],
     output: &PathBuf,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let mut wtr = csv::Writer::from_path(output)?;
 
     // Write header
     wtr.write_record([
-        "exercise",
-        "pr_type",
         "date",
-        "weight_kg",
-        "weight_lb",
-        "reps",
+        "load",
+        "acute_load",
+        "chronic_load",
+        "acwr",
+        "fitness",
+        "fatigue",
+        "performance",
     ])?;
 
+    // Write data
+    for d in data {
+        wtr.write_record([
+            &d.date.format("%Y-%m-%d").to_string(),
+            &d.load.to_string(),
+            &d.acute_load.to_string(),
+            &d.chronic_load.to_string(),
+            &d.acwr.map_or("".to_string(), |r| r.to_string()),
+            &d.fitness.to_string(),
+            &d.fatigue.to_string(),
+            &d.performance.to_string(),
+        ])?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+fn export_weekly_load_csv(
+    data: &[(NaiveDate, f64, Option<f64>, Option<f64>)],
+    output: &PathBuf,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut wtr = csv::Writer::from_path(output)?;
+
+    // Write header
+    wtr.write_record(["week_start", "load", "monotony", "strain"])?;
+
+    // Write data
+    for (week_start, load, monotony, strain) in data {
+        wtr.write_record([
+            &week_start.format("%Y-%m-%d").to_string(),
+            &load.to_string(),
+            &monotony.map_or("".to_string(), |m| m.to_string()),
+            &strain.map_or("".to_string(), |s| s.to_string()),
+        ])?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+fn export_pr_csv(
+    data: &[(String, String, NaiveDate, f64, Option<u32>)],
+    unit: DisplayUnit,
+    output: &PathBuf,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut wtr = csv::Writer::from_path(output)?;
+
+    // Write header
+    wtr.write_record(["exercise", "pr_type", "date", &unit.field("weight"), "reps"])?;
+
     // Write data
     for (exercise, pr_type, date, weight_kg, reps) in data {
-        let weight_lb = weight_kg * 2.20462;
         wtr.write_record([
             exercise.as_str(),
             pr_type.as_str(),
             &date.format("%Y-%m-%d").to_string(),
-            &weight_kg.to_string(),
-            &weight_lb.to_string(),
+            &unit.convert(*weight_kg).to_string(),
             &reps.map_or("".to_string(), |r| r.to_string()),
         ])?;
     }
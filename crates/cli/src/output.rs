@@ -0,0 +1,230 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::error::Error;
+use weightlifting_core::{PlanEditError, PlanStoreError};
+use weightlifting_validate::ValidationErrorInfo;
+
+/// Non-zero process exit codes (success is 0). Scripts and the macOS app rely on these,
+/// so existing codes never change meaning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitCode {
+    /// Any other failure, e.g. a file that cannot be read or written
+    Failure = 1,
+    /// Validation failed or arguments were invalid
+    Invalid = 2,
+    /// Export conflicts, an existing version, or a draft with nothing to promote
+    Conflict = 3,
+    /// A plan, version or app directory could not be found
+    NotFound = 4,
+}
+
+impl ExitCode {
+    /// Error code reported in the JSON envelope for failures that are not plan
+    /// validation errors (which carry their own E1xx/W2xx codes)
+    fn error_code(self) -> &'static str {
+        match self {
+            ExitCode::Failure => "E901",
+            ExitCode::Invalid => "E902",
+            ExitCode::Conflict => "E903",
+            ExitCode::NotFound => "E904",
+        }
+    }
+
+    pub fn exit(self) -> ! {
+        std::process::exit(self as i32)
+    }
+}
+
+/// Output mode selected with the top-level `--format` flag
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable output; command results on stdout, progress on stderr
+    #[default]
+    Text,
+    /// A single JSON envelope on stdout: {"ok", "data", "errors"}
+    Json,
+}
+
+/// A failed command with its exit code and structured error details
+#[derive(Debug, Clone)]
+pub struct CliError {
+    pub exit: ExitCode,
+    pub message: String,
+    pub errors: Vec<ValidationErrorInfo>,
+    pub data: Option<Value>,
+}
+
+impl CliError {
+    pub fn new(exit: ExitCode, message: impl Into<String>) -> Self {
+        Self {
+            exit,
+            message: message.into(),
+            errors: Vec::new(),
+            data: None,
+        }
+    }
+
+    pub fn invalid(message: impl Into<String>) -> Self {
+        Self::new(ExitCode::Invalid, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ExitCode::NotFound, message)
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        Self::new(ExitCode::Conflict, message)
+    }
+
+    /// Detailed errors, replacing the single message in the envelope's `errors`
+    pub fn with_errors(mut self, errors: Vec<ValidationErrorInfo>) -> Self {
+        self.errors = errors;
+        self
+    }
+
+    /// Partial result still reported as the envelope's `data`
+    pub fn with_data(mut self, data: Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    /// An envelope error entry in the shape of a validation error
+    pub fn error_info(exit: ExitCode, message: &str, path: &str) -> ValidationErrorInfo {
+        ValidationErrorInfo {
+            code: exit.error_code().to_string(),
+            message: message.to_string(),
+            path: path.to_string(),
+            field: None,
+            hint: None,
+        }
+    }
+}
+
+impl std::fmt::Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for CliError {}
+
+impl From<&PlanStoreError> for CliError {
+    fn from(error: &PlanStoreError) -> Self {
+        let exit = match error {
+            PlanStoreError::PlanNotFound(_) | PlanStoreError::VersionNotFound(_, _) => {
+                ExitCode::NotFound
+            }
+            PlanStoreError::VersionExists(_, _) | PlanStoreError::NoChanges(_, _) => {
+                ExitCode::Conflict
+            }
+            PlanStoreError::InvalidId(_)
+            | PlanStoreError::InvalidRef(_)
+            | PlanStoreError::InvalidBump(_) => ExitCode::Invalid,
+            PlanStoreError::Io(_) | PlanStoreError::Json(_) | PlanStoreError::Diff(_) => {
+                ExitCode::Failure
+            }
+        };
+        Self::new(exit, error.to_string())
+    }
+}
+
+impl From<&PlanEditError> for CliError {
+    fn from(error: &PlanEditError) -> Self {
+        let exit = match error {
+            PlanEditError::DayOutOfBounds(_)
+            | PlanEditError::SegmentOutOfBounds(_)
+            | PlanEditError::PathNotFound(_) => ExitCode::NotFound,
+            PlanEditError::DuplicateDay(_) => ExitCode::Conflict,
+            _ => ExitCode::Invalid,
+        };
+        Self::new(exit, error.to_string())
+    }
+}
+
+/// The stable JSON envelope written in `--format json` mode
+#[derive(Debug, Serialize)]
+pub struct Envelope {
+    pub ok: bool,
+    pub data: Value,
+    pub errors: Vec<ValidationErrorInfo>,
+}
+
+/// Writes command results and failures in the selected format
+pub struct Output {
+    format: OutputFormat,
+}
+
+impl Output {
+    pub fn new(format: OutputFormat) -> Self {
+        Self { format }
+    }
+
+    pub fn is_json(&self) -> bool {
+        self.format == OutputFormat::Json
+    }
+
+    /// Print a command's result: the envelope around `data` in JSON mode, `text()` otherwise
+    pub fn emit<T: Serialize>(
+        &self,
+        data: &T,
+        text: impl FnOnce() -> String,
+    ) -> Result<(), serde_json::Error> {
+        if self.is_json() {
+            let envelope = Envelope {
+                ok: true,
+                data: serde_json::to_value(data)?,
+                errors: Vec::new(),
+            };
+            println!("{}", serde_json::to_string_pretty(&envelope)?);
+        } else {
+            println!("{}", text());
+        }
+        Ok(())
+    }
+
+    /// Report a failed command and exit with its code
+    pub fn fail(&self, error: &(dyn Error + 'static)) -> ! {
+        let error = if let Some(cli_error) = error.downcast_ref::<CliError>() {
+            cli_error.clone()
+        } else if let Some(store_error) = error.downcast_ref::<PlanStoreError>() {
+            CliError::from(store_error)
+        } else if let Some(edit_error) = error.downcast_ref::<PlanEditError>() {
+            CliError::from(edit_error)
+        } else if let Some(json_error) = error
+            .downcast_ref::<serde_json::Error>()
+            .filter(|e| !e.is_io())
+        {
+            CliError::invalid(json_error.to_string())
+        } else {
+            CliError::new(ExitCode::Failure, error.to_string())
+        };
+
+        if self.is_json() {
+            let errors = if error.errors.is_empty() {
+                vec![CliError::error_info(error.exit, &error.message, "")]
+            } else {
+                error.errors
+            };
+            let envelope = Envelope {
+                ok: false,
+                data: error.data.unwrap_or(Value::Null),
+                errors,
+            };
+            match serde_json::to_string_pretty(&envelope) {
+                Ok(json) => println!("{}", json),
+                Err(e) => eprintln!("Error: {}", e),
+            }
+        } else {
+            eprintln!("Error: {}", error.message);
+            for info in &error.errors {
+                if info.path.is_empty() {
+                    eprintln!("  [{}] {}", info.code, info.message);
+                } else {
+                    eprintln!("  [{}] {} at {}", info.code, info.message, info.path);
+                }
+            }
+        }
+
+        error.exit.exit()
+    }
+}
@@ -0,0 +1,122 @@
+//! Pins the documented exit codes: 1 other failure, 2 invalid input,
+//! 3 conflict, 4 not found. Scripts and the macOS app branch on these.
+
+use std::path::Path;
+use std::process::Command;
+
+const PLAN: &str = r#"{"name": "Exit Codes", "unit": "kg", "dictionary": {}, "groups": {},
+    "schedule": [{"day": 1, "label": "Lower", "segments": []}]}"#;
+
+/// Runs `comp` against app directories inside `home` and returns its exit code
+fn comp(home: &Path, args: &[&str]) -> i32 {
+    let output = Command::new(env!("CARGO_BIN_EXE_comp"))
+        .args(args)
+        .env("HOME", home)
+        .env("XDG_DATA_HOME", home.join("data"))
+        .env("XDG_STATE_HOME", home.join("state"))
+        .env("XDG_CACHE_HOME", home.join("cache"))
+        .env("XDG_CONFIG_HOME", home.join("config"))
+        .output()
+        .unwrap();
+    output.status.code().unwrap()
+}
+
+#[test]
+fn test_invalid_input_exits_2() {
+    let home = tempfile::tempdir().unwrap();
+    let home = home.path();
+    let bad_json = home.join("bad.json");
+    std::fs::write(&bad_json, "{ not json").unwrap();
+
+    assert_eq!(
+        comp(
+            home,
+            &["plans", "validate", "--file", bad_json.to_str().unwrap()]
+        ),
+        2
+    );
+    assert_eq!(comp(home, &["plans", "validate"]), 2);
+    assert_eq!(comp(home, &["plans", "save"]), 2);
+    assert_eq!(
+        comp(home, &["plans", "diff", "exit_codes", "--from", "latest"]),
+        2
+    );
+    assert_eq!(
+        comp(home, &["plans", "promote", "exit_codes", "--bump", "huge"]),
+        2
+    );
+    assert_eq!(
+        comp(
+            home,
+            &["plans", "get", "--id", "exit_codes", "--version", "../../x"]
+        ),
+        2
+    );
+    assert_eq!(
+        comp(
+            home,
+            &["chart", "emit-spec", "--chart-type", "no-such-chart"]
+        ),
+        2
+    );
+}
+
+#[test]
+fn test_unknown_adherence_format_exits_2() {
+    let home = tempfile::tempdir().unwrap();
+    let home = home.path();
+    let plan = home.join("plan.json");
+    std::fs::write(&plan, PLAN).unwrap();
+    let sessions = home.join("sessions.csv");
+    std::fs::write(&sessions, "").unwrap();
+
+    assert_eq!(
+        comp(home, &["plans", "save", "--file", plan.to_str().unwrap()]),
+        0
+    );
+    assert_eq!(
+        comp(
+            home,
+            &[
+                "plans",
+                "adherence",
+                "--id",
+                "exit_codes",
+                "--input",
+                sessions.to_str().unwrap(),
+                "--format",
+                "xml",
+            ]
+        ),
+        2
+    );
+}
+
+#[test]
+fn test_missing_file_exits_1() {
+    let home = tempfile::tempdir().unwrap();
+    let home = home.path();
+    let missing = home.join("missing.json");
+
+    assert_eq!(
+        comp(
+            home,
+            &["plans", "validate", "--file", missing.to_str().unwrap()]
+        ),
+        1
+    );
+}
+
+#[test]
+fn test_missing_plan_exits_4() {
+    let home = tempfile::tempdir().unwrap();
+
+    assert_eq!(comp(home.path(), &["plans", "get", "--id", "missing"]), 4);
+    assert_eq!(
+        comp(
+            home.path(),
+            &["plans", "get", "--id", "missing", "--version", "1.0.0"]
+        ),
+        4
+    );
+}
@@ -1,5 +1,8 @@
 use super::data_transforms::DataTransforms;
-use super::vega_specs::{AxisEncoding, ChartTemplates, ColorEncoding, VegaLiteSpec};
+use super::vega_specs::{
+    AxisEncoding, ChartTemplates, ColorEncoding, ParamSpec, TransformSpec, VegaLiteSpec,
+};
+use crate::units::DisplayUnit;
 use chrono::NaiveDate;
 use serde_json::{json, Value};
 use std::collections::HashMap;
@@ -8,13 +11,17 @@ use std::collections::HashMap;
 pub struct BuiltinCharts;
 
 impl BuiltinCharts {
-    /// 1. E1RM over time (line chart with exercise picker)
+    /// 1. E1RM over time: session line, 4-week rolling mean of weekly bests and PR points
     pub fn e1rm_over_time(
         e1rm_data: &[(String, NaiveDate, f64)], // (exercise, date, e1rm_kg)
         exercise: Option<&str>,
+        unit: DisplayUnit,
         title: Option<String>,
     ) -> VegaLiteSpec {
         let chart_data = DataTransforms::e1rm_to_chart_data(e1rm_data, exercise);
+        let e1rm = unit.field("e1rm");
+        let best = unit.field("best_e1rm");
+        let rolling_e1rm = unit.field("rolling_e1rm");
 
         let chart_title = title.unwrap_or_else(|| {
             if let Some(ex) = exercise {
@@ -24,24 +31,85 @@ impl BuiltinCharts {
             }
         });
 
-        let mut spec = ChartTemplates::line_chart()
+        // If showing multiple exercises, color every layer by exercise
+        let colored = |spec: VegaLiteSpec, color: &str| {
+            if exercise.is_none() {
+                spec.color_field("exercise", "nominal")
+            } else {
+                spec.color_value(color)
+            }
+        };
+
+        let sessions = colored(
+            ChartTemplates::line_chart()
+                .x_axis("date", "temporal", Some("Date".to_string()))
+                .y_axis(
+                    &e1rm,
+                    "quantitative",
+                    Some(format!("Estimated 1RM ({})", unit.label())),
+                )
+                .param(ParamSpec::zoom_x("zoom")),
+            "steelblue",
+        );
+
+        let rolling = colored(
+            ChartTemplates::line_chart()
+                .mark_style(Some(0.4), Some(4.0))
+                .x_axis("week", "temporal", None)
+                .y_axis(&rolling_e1rm, "quantitative", None)
+                .transform(TransformSpec::aggregate(
+                    "max",
+                    Some(&e1rm),
+                    &best,
+                    &["exercise", "week"],
+                ))
+                .transform(TransformSpec::rolling_mean(
+                    &best,
+                    &rolling_e1rm,
+                    [Some(-3), Some(0)],
+                    &["exercise"],
+                    "week",
+                )),
+            "steelblue",
+        );
+
+        let prs = colored(
+            ChartTemplates::scatter_plot()
+                .x_axis("date", "temporal", None)
+                .y_axis(&e1rm, "quantitative", None)
+                .transform(TransformSpec::filter_equal("is_pr", json!(true))),
+            "darkorange",
+        );
+
+        VegaLiteSpec::new()
             .title(chart_title)
             .dimensions(800, 400)
+            .layer(sessions)
+            .layer(rolling)
+            .layer(prs)
+            .data_values(chart_data)
+    }
+
+    /// 1b. E1RM small multiples, one panel per exercise
+    pub fn e1rm_by_exercise(
+        e1rm_data: &[(String, NaiveDate, f64)], // (exercise, date, e1rm_kg)
+        columns: u32,
+        unit: DisplayUnit,
+        title: Option<String>,
+    ) -> VegaLiteSpec {
+        let chart_data = DataTransforms::e1rm_to_chart_data(e1rm_data, None);
+
+        ChartTemplates::line_chart()
+            .title(title.unwrap_or_else(|| "E1RM Progress by Exercise".to_string()))
+            .dimensions(300, 200)
             .x_axis("date", "temporal", Some("Date".to_string()))
             .y_axis(
-                "e1rm_kg",
+                &unit.field("e1rm"),
                 "quantitative",
-                Some("Estimated 1RM (kg)".to_string()),
-            );
-
-        // If showing multiple exercises, color by exercise
-        if exercise.is_none() {
-            spec = spec.color_field("exercise", "nominal");
-        } else {
-            spec = spec.color_value("steelblue");
-        }
-
-        spec.data_values(chart_data)
+                Some(format!("Estimated 1RM ({})", unit.label())),
+            )
+            .facet("exercise", "nominal", Some(columns))
+            .data_values(chart_data)
     }
 
     /// 2. Weekly volume by body-part (stacked bar chart)
@@ -49,6 +117,7 @@ impl BuiltinCharts {
         volume_data: &[(String, NaiveDate, u32, u32, f64)], // (exercise, week_start, sets, reps, tonnage)
         body_part_map: Option<&HashMap<String, String>>,
         metric: VolumeMetric,
+        unit: DisplayUnit,
         title: Option<String>,
     ) -> VegaLiteSpec {
         let default_body_parts = DataTransforms::get_default_body_part_map();
@@ -56,9 +125,9 @@ impl BuiltinCharts {
         let chart_data = DataTransforms::volume_to_chart_data(volume_data, body_parts);
 
         let (y_field, y_title) = match metric {
-            VolumeMetric::Sets => ("sets", "Total Sets"),
-            VolumeMetric::Reps => ("reps", "Total Reps"),
-            VolumeMetric::TonnageKg => ("tonnage_kg", "Tonnage (kg)"),
+            VolumeMetric::Sets => ("sets".to_string(), "Total Sets".to_string()),
+            VolumeMetric::Reps => ("reps".to_string(), "Total Reps".to_string()),
+            VolumeMetric::Tonnage => (unit.field("tonnage"), format!("Tonnage ({})", unit.label())),
         };
 
         let chart_title = title.unwrap_or_else(|| format!("Weekly {} by Body Part", y_title));
@@ -67,19 +136,201 @@ impl BuiltinCharts {
             .title(chart_title)
             .dimensions(900, 500)
             .x_axis("week", "ordinal", Some("Week".to_string()))
-            .y_axis(y_field, "quantitative", Some(y_title.to_string()))
+            .y_axis(&y_field, "quantitative", Some(y_title))
             .color_field("body_part", "nominal")
             .data_values(chart_data)
     }
 
+    /// 2b. Weekly weighted volume by muscle group or movement pattern (stacked bar chart)
+    pub fn weekly_volume_by_group(
+        group_data: &[(String, NaiveDate, f64, f64, f64)], // (group, week_start, sets, reps, tonnage)
+        group_label: &str,                                 // "Muscle Group", "Movement Pattern"
+        metric: VolumeMetric,
+        unit: DisplayUnit,
+        title: Option<String>,
+    ) -> VegaLiteSpec {
+        let chart_data = DataTransforms::group_volume_to_chart_data(group_data);
+
+        let (y_field, y_title) = match metric {
+            VolumeMetric::Sets => ("sets".to_string(), "Weighted Sets".to_string()),
+            VolumeMetric::Reps => ("reps".to_string(), "Weighted Reps".to_string()),
+            VolumeMetric::Tonnage => (unit.field("tonnage"), format!("Tonnage ({})", unit.label())),
+        };
+
+        let chart_title = title.unwrap_or_else(|| format!("Weekly {} by {}", y_title, group_label));
+
+        let mut spec = ChartTemplates::stacked_bar_chart()
+            .title(chart_title)
+            .dimensions(900, 500)
+            .x_axis("week", "ordinal", Some("Week".to_string()))
+            .y_axis(&y_field, "quantitative", Some(y_title))
+            .color_field("group", "nominal");
+
+        if let Some(color) = spec.encoding.color.as_mut() {
+            color.legend = Some(super::vega_specs::LegendSpec {
+                title: Some(group_label.to_string()),
+                orient: Some("right".to_string()),
+            });
+        }
+
+        spec.data_values(chart_data)
+    }
+
+    /// 2c. Acute:chronic workload ratio over time (line chart)
+    pub fn acwr_over_time(
+        load_data: &[(NaiveDate, f64, Option<f64>)], // (date, load, acwr)
+        title: Option<String>,
+    ) -> VegaLiteSpec {
+        let chart_data = DataTransforms::acwr_to_chart_data(load_data);
+
+        ChartTemplates::line_chart()
+            .title(title.unwrap_or_else(|| "Acute:Chronic Workload Ratio".to_string()))
+            .dimensions(800, 400)
+            .x_axis("date", "temporal", Some("Date".to_string()))
+            .y_axis("acwr", "quantitative", Some("ACWR".to_string()))
+            .color_value("darkorange")
+            .data_values(chart_data)
+    }
+
+    /// 2d. Banister fitness-fatigue model (multi-series line chart)
+    pub fn fitness_fatigue(
+        model_data: &[(NaiveDate, f64, f64, f64)], // (date, fitness, fatigue, performance)
+        title: Option<String>,
+    ) -> VegaLiteSpec {
+        let chart_data = DataTransforms::fitness_fatigue_to_chart_data(model_data);
+
+        ChartTemplates::line_chart()
+            .title(title.unwrap_or_else(|| "Fitness-Fatigue Model".to_string()))
+            .dimensions(800, 400)
+            .x_axis("date", "temporal", Some("Date".to_string()))
+            .y_axis("value", "quantitative", Some("Training Load".to_string()))
+            .color_field("series", "nominal")
+            .data_values(chart_data)
+    }
+
+    /// 2e. Weekly load, monotony or strain (bar chart)
+    pub fn weekly_training_load(
+        weekly_data: &[(NaiveDate, f64, Option<f64>, Option<f64>)], // (week_start, load, monotony, strain)
+        metric: LoadMetric,
+        title: Option<String>,
+    ) -> VegaLiteSpec {
+        let chart_data = DataTransforms::weekly_load_to_chart_data(weekly_data);
+
+        let (y_field, y_title) = match metric {
+            LoadMetric::Load => ("load", "Session RPE Load"),
+            LoadMetric::Monotony => ("monotony", "Monotony"),
+            LoadMetric::Strain => ("strain", "Strain"),
+        };
+
+        ChartTemplates::bar_chart()
+            .title(title.unwrap_or_else(|| format!("Weekly {}", y_title)))
+            .dimensions(800, 400)
+            .x_axis("week", "ordinal", Some("Week".to_string()))
+            .y_axis(y_field, "quantitative", Some(y_title.to_string()))
+            .color_value("steelblue")
+            .data_values(chart_data)
+    }
+
+    /// 2f. Plan adherence: completed, missed and extra sets per session (stacked bar chart)
+    pub fn plan_adherence(
+        adherence_data: &[(NaiveDate, String, u32, u32, u32)], // (date, day_label, completed, missed, extra)
+        plan_name: &str,
+        title: Option<String>,
+    ) -> VegaLiteSpec {
+        let chart_data = DataTransforms::adherence_to_chart_data(adherence_data);
+
+        let mut spec = ChartTemplates::stacked_bar_chart()
+            .title(title.unwrap_or_else(|| format!("Plan Adherence - {}", plan_name)))
+            .dimensions(900, 500)
+            .x_axis("session", "ordinal", Some("Session".to_string()))
+            .y_axis("sets", "quantitative", Some("Sets".to_string()))
+            .color_field("status", "nominal");
+
+        if let Some(color) = spec.encoding.color.as_mut() {
+            color.scale = Some(super::vega_specs::ScaleSpec {
+                domain: Some(vec![json!("Completed"), json!("Missed"), json!("Extra")]),
+                range: Some(vec![json!("#40c463"), json!("#e45756"), json!("#4c78a8")]),
+                scale_type: None,
+            });
+        }
+
+        spec.data_values(chart_data)
+    }
+
+    /// 2g. Prescribed weekly sets per muscle group across a plan's block (stacked bar chart)
+    pub fn planned_weekly_sets(
+        planned_data: &[(u32, String, f64)], // (week, group, weighted_sets)
+        plan_name: &str,
+        title: Option<String>,
+    ) -> VegaLiteSpec {
+        let chart_data = DataTransforms::planned_volume_to_chart_data(planned_data);
+
+        ChartTemplates::stacked_bar_chart()
+            .title(title.unwrap_or_else(|| format!("Planned Weekly Sets - {}", plan_name)))
+            .dimensions(900, 500)
+            .x_axis("week", "ordinal", Some("Week".to_string()))
+            .y_axis("sets", "quantitative", Some("Planned Sets".to_string()))
+            .color_field("group", "nominal")
+            .data_values(chart_data)
+    }
+
+    /// 2h. Prescribed intensity per exercise across a plan's block (multi-series line chart)
+    pub fn planned_intensity(
+        planned_data: &[(u32, String, Option<f64>, Option<f64>)], // (week, exercise, mean_rpe, mean_pct_1rm)
+        exercise_filter: Option<&str>,
+        metric: IntensityMetric,
+        plan_name: &str,
+        title: Option<String>,
+    ) -> VegaLiteSpec {
+        let (y_field, y_title) = match metric {
+            IntensityMetric::Rpe => ("rpe", "RPE"),
+            IntensityMetric::PercentOneRm => ("pct_1rm", "%1RM"),
+        };
+
+        // Exercises without a target in this metric would only contribute empty series
+        let chart_data: Vec<Value> =
+            DataTransforms::planned_intensity_to_chart_data(planned_data, exercise_filter)
+                .into_iter()
+                .filter(|row| !row[y_field].is_null())
+                .collect();
+
+        ChartTemplates::line_chart()
+            .title(title.unwrap_or_else(|| format!("Planned {} - {}", y_title, plan_name)))
+            .dimensions(800, 400)
+            .x_axis("week", "ordinal", Some("Week".to_string()))
+            .y_axis(y_field, "quantitative", Some(y_title.to_string()))
+            .color_field("exercise", "nominal")
+            .data_values(chart_data)
+    }
+
+    /// 2i. Estimated session length per plan day across the block (multi-series line chart)
+    pub fn planned_duration(
+        planned_data: &[(u32, u32, String, f64)], // (week, day, day_label, minutes)
+        plan_name: &str,
+        title: Option<String>,
+    ) -> VegaLiteSpec {
+        let chart_data = DataTransforms::planned_duration_to_chart_data(planned_data);
+
+        ChartTemplates::line_chart()
+            .title(title.unwrap_or_else(|| format!("Planned Session Duration - {}", plan_name)))
+            .dimensions(800, 400)
+            .x_axis("week", "ordinal", Some("Week".to_string()))
+            .y_axis("minutes", "quantitative", Some("Minutes".to_string()))
+            .color_field("day", "nominal")
+            .data_values(chart_data)
+    }
+
     /// 3. PR Board (table/bar showing best sets/1RMs with dates)
     pub fn pr_board(
         pr_data: &[(String, String, NaiveDate, f64, Option<u32>)], // (exercise, pr_type, date, value, reps)
         exercise_filter: Option<&str>,
         display_mode: PRDisplayMode,
+        unit: DisplayUnit,
         title: Option<String>,
     ) -> VegaLiteSpec {
-        let chart_data = DataTransforms::pr_to_chart_data(pr_data, exercise_filter);
+        let chart_data = DataTransforms::pr_to_chart_data(pr_data, exercise_filter, unit);
+        let (value_field, value_title) =
+            (unit.field("value"), format!("Weight ({})", unit.label()));
 
         let chart_title = title.unwrap_or_else(|| {
             if let Some(ex) = exercise_filter {
@@ -98,7 +349,7 @@ impl BuiltinCharts {
                     .dimensions(800, 600)
                     .mark("point")
                     .x_axis("date", "temporal", Some("Date Achieved".to_string()))
-                    .y_axis("value_kg", "quantitative", Some("Weight (kg)".to_string()))
+                    .y_axis(&value_field, "quantitative", Some(value_title))
                     .color_field("pr_type", "nominal")
                     .data_values(chart_data)
             }
@@ -106,7 +357,7 @@ impl BuiltinCharts {
                 .title(chart_title)
                 .dimensions(800, 500)
                 .x_axis("exercise", "nominal", Some("Exercise".to_string()))
-                .y_axis("value_kg", "quantitative", Some("Weight (kg)".to_string()))
+                .y_axis(&value_field, "quantitative", Some(value_title))
                 .color_field("pr_type", "nominal")
                 .data_values(chart_data),
         }
@@ -209,7 +460,23 @@ impl BuiltinCharts {
 pub enum VolumeMetric {
     Sets,
     Reps,
-    TonnageKg,
+    /// Tonnage in the chart's display unit
+    Tonnage,
+}
+
+/// Training-load metrics for weekly chart display
+#[derive(Debug, Clone, Copy)]
+pub enum LoadMetric {
+    Load,
+    Monotony,
+    Strain,
+}
+
+/// Planned intensity metrics for chart display
+#[derive(Debug, Clone, Copy)]
+pub enum IntensityMetric {
+    Rpe,
+    PercentOneRm,
 }
 
 /// PR board display modes
@@ -228,6 +495,7 @@ impl ChartGenerator {
         cache_data: &[Value], // JSON data from cache
         exercise_filter: Option<&str>,
         date_range: Option<(NaiveDate, NaiveDate)>,
+        unit: DisplayUnit,
     ) -> Result<VegaLiteSpec, Box<dyn std::error::Error>> {
         // Parse cached data
         let mut e1rm_data = Vec::new();
@@ -250,6 +518,7 @@ impl ChartGenerator {
         Ok(BuiltinCharts::e1rm_over_time(
             &e1rm_data,
             exercise_filter,
+            unit,
             None,
         ))
     }
@@ -261,13 +530,14 @@ impl ChartGenerator {
         pr_data: &[(String, String, NaiveDate, f64, Option<u32>)],
         session_dates: &[NaiveDate],
         date_range: (NaiveDate, NaiveDate),
+        unit: DisplayUnit,
     ) -> Vec<(String, VegaLiteSpec)> {
         let mut charts = Vec::new();
 
         // E1RM chart (top exercises only for overview)
         let top_exercises = DataTransforms::get_unique_exercises(e1rm_data);
         if !top_exercises.is_empty() {
-            let chart = BuiltinCharts::e1rm_over_time(e1rm_data, None, None);
+            let chart = BuiltinCharts::e1rm_over_time(e1rm_data, None, unit, None);
             charts.push(("e1rm_overview".to_string(), chart));
         }
 
@@ -275,13 +545,14 @@ impl ChartGenerator {
         let volume_chart = BuiltinCharts::weekly_volume_by_bodypart(
             volume_data,
             None,
-            VolumeMetric::TonnageKg,
+            VolumeMetric::Tonnage,
+            unit,
             None,
         );
         charts.push(("weekly_volume".to_string(), volume_chart));
 
         // PR board
-        let pr_chart = BuiltinCharts::pr_board(pr_data, None, PRDisplayMode::Bar, None);
+        let pr_chart = BuiltinCharts::pr_board(pr_data, None, PRDisplayMode::Bar, unit, None);
         charts.push(("pr_board".to_string(), pr_chart));
 
         // Session frequency heatmap
@@ -317,13 +588,71 @@ mod tests {
             ),
         ];
 
-        let spec = BuiltinCharts::e1rm_over_time(&data, Some("BP.BB.FLAT"), None);
+        let spec = BuiltinCharts::e1rm_over_time(&data, Some("BP.BB.FLAT"), DisplayUnit::Kg, None);
         let json = spec.to_json();
 
         assert!(json["title"].as_str().unwrap().contains("BP.BB.FLAT"));
-        assert_eq!(json["mark"], "line");
-        assert_eq!(json["encoding"]["x"]["field"], "date");
-        assert_eq!(json["encoding"]["y"]["field"], "e1rm_kg");
+        let layers = json["layer"].as_array().unwrap();
+        assert_eq!(layers.len(), 3);
+        assert_eq!(layers[0]["mark"], "line");
+        assert_eq!(layers[0]["encoding"]["x"]["field"], "date");
+        assert_eq!(layers[0]["encoding"]["y"]["field"], "e1rm_kg");
+        assert_eq!(layers[1]["encoding"]["y"]["field"], "rolling_e1rm_kg");
+        assert_eq!(layers[1]["transform"][1]["frame"], json!([-3, 0]));
+        assert_eq!(layers[2]["transform"][0]["filter"]["field"], "is_pr");
+    }
+
+    #[test]
+    fn test_e1rm_small_multiples() {
+        let data = vec![(
+            "SQ.BB.BACK".to_string(),
+            NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+            150.0,
+        )];
+
+        let json = BuiltinCharts::e1rm_by_exercise(&data, 2, DisplayUnit::Kg, None).to_json();
+        assert_eq!(json["facet"]["field"], "exercise");
+        assert_eq!(json["columns"], 2);
+        assert_eq!(json["spec"]["encoding"]["y"]["field"], "e1rm_kg");
+    }
+
+    #[test]
+    fn test_unit_aware_charts() {
+        let date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let e1rm = vec![("SQ.BB.BACK".to_string(), date, 100.0)];
+
+        let json = BuiltinCharts::e1rm_over_time(&e1rm, None, DisplayUnit::Lb, None).to_json();
+        let layers = json["layer"].as_array().unwrap();
+        assert_eq!(layers[0]["encoding"]["y"]["field"], "e1rm_lb");
+        assert_eq!(
+            layers[0]["encoding"]["y"]["axis"]["title"],
+            "Estimated 1RM (lb)"
+        );
+        assert_eq!(layers[1]["encoding"]["y"]["field"], "rolling_e1rm_lb");
+
+        let volume = vec![("SQ.BB.BACK".to_string(), date, 3, 15, 1000.0)];
+        let json = BuiltinCharts::weekly_volume_by_bodypart(
+            &volume,
+            None,
+            VolumeMetric::Tonnage,
+            DisplayUnit::Lb,
+            None,
+        )
+        .to_json();
+        assert_eq!(json["encoding"]["y"]["field"], "tonnage_lb");
+        assert_eq!(json["title"], "Weekly Tonnage (lb) by Body Part");
+
+        let prs = vec![(
+            "SQ.BB.BACK".to_string(),
+            "e1rm".to_string(),
+            date,
+            100.0,
+            Some(5),
+        )];
+        let json = BuiltinCharts::pr_board(&prs, None, PRDisplayMode::Bar, DisplayUnit::Lb, None)
+            .to_json();
+        assert_eq!(json["encoding"]["y"]["field"], "value_lb");
+        assert_eq!(json["data"]["values"][0]["display_text"], "220.5lb x5");
     }
 
     #[test]
@@ -345,7 +674,13 @@ mod tests {
             ),
         ];
 
-        let spec = BuiltinCharts::weekly_volume_by_bodypart(&data, None, VolumeMetric::Sets, None);
+        let spec = BuiltinCharts::weekly_volume_by_bodypart(
+            &data,
+            None,
+            VolumeMetric::Sets,
+            DisplayUnit::Kg,
+            None,
+        );
         let json = spec.to_json();
 
         assert_eq!(json["mark"], "bar");
@@ -353,6 +688,121 @@ mod tests {
         assert!(json["title"].as_str().unwrap().contains("Sets"));
     }
 
+    #[test]
+    fn test_group_volume_chart_generation() {
+        let data = vec![(
+            "Triceps".to_string(),
+            NaiveDate::from_ymd_opt(2025, 1, 6).unwrap(),
+            1.5,
+            7.5,
+            600.0,
+        )];
+
+        let spec = BuiltinCharts::weekly_volume_by_group(
+            &data,
+            "Muscle Group",
+            VolumeMetric::Sets,
+            DisplayUnit::Kg,
+            None,
+        );
+        let json = spec.to_json();
+
+        assert_eq!(json["mark"], "bar");
+        assert_eq!(json["encoding"]["y"]["field"], "sets");
+        assert_eq!(json["encoding"]["color"]["field"], "group");
+        assert_eq!(json["encoding"]["color"]["legend"]["title"], "Muscle Group");
+        assert_eq!(json["title"], "Weekly Weighted Sets by Muscle Group");
+    }
+
+    #[test]
+    fn test_training_load_charts() {
+        let day = NaiveDate::from_ymd_opt(2025, 2, 3).unwrap();
+
+        let acwr = BuiltinCharts::acwr_over_time(
+            &[
+                (day, 400.0, None),
+                (day.succ_opt().unwrap(), 0.0, Some(1.234)),
+            ],
+            None,
+        )
+        .to_json();
+        assert_eq!(acwr["encoding"]["y"]["field"], "acwr");
+        assert_eq!(acwr["data"]["values"].as_array().unwrap().len(), 1);
+        assert_eq!(acwr["data"]["values"][0]["acwr"], 1.23);
+
+        let model = BuiltinCharts::fitness_fatigue(&[(day, 10.0, 20.0, -30.0)], None).to_json();
+        assert_eq!(model["encoding"]["color"]["field"], "series");
+        assert_eq!(model["data"]["values"].as_array().unwrap().len(), 3);
+
+        let strain = BuiltinCharts::weekly_training_load(
+            &[(day, 1000.0, Some(1.5), Some(1500.0))],
+            LoadMetric::Strain,
+            None,
+        )
+        .to_json();
+        assert_eq!(strain["mark"], "bar");
+        assert_eq!(strain["encoding"]["y"]["field"], "strain");
+        assert_eq!(strain["title"], "Weekly Strain");
+    }
+
+    #[test]
+    fn test_plan_adherence_chart() {
+        let data = vec![(
+            NaiveDate::from_ymd_opt(2025, 8, 4).unwrap(),
+            "Lower".to_string(),
+            4,
+            1,
+            0,
+        )];
+
+        let json = BuiltinCharts::plan_adherence(&data, "PHUL", None).to_json();
+
+        assert_eq!(json["title"], "Plan Adherence - PHUL");
+        assert_eq!(json["encoding"]["color"]["field"], "status");
+        let values = json["data"]["values"].as_array().unwrap();
+        assert_eq!(values.len(), 3);
+        assert_eq!(values[0]["session"], "2025-08-04 Lower");
+        assert_eq!(values[1]["status"], "Missed");
+        assert_eq!(values[1]["sets"], 1);
+    }
+
+    #[test]
+    fn test_plan_projection_charts() {
+        let intensity = vec![
+            (1, "SQ.BB.BACK".to_string(), Some(7.0), None),
+            (2, "SQ.BB.BACK".to_string(), Some(8.0), None),
+            (1, "DL.BB".to_string(), None, Some(0.8166)),
+        ];
+
+        let rpe =
+            BuiltinCharts::planned_intensity(&intensity, None, IntensityMetric::Rpe, "Block", None)
+                .to_json();
+        assert_eq!(rpe["title"], "Planned RPE - Block");
+        assert_eq!(rpe["data"]["values"].as_array().unwrap().len(), 2);
+
+        let pct = BuiltinCharts::planned_intensity(
+            &intensity,
+            Some("DL.BB"),
+            IntensityMetric::PercentOneRm,
+            "Block",
+            None,
+        )
+        .to_json();
+        let values = pct["data"]["values"].as_array().unwrap();
+        assert_eq!(values.len(), 1);
+        assert_eq!(values[0]["pct_1rm"], 81.7);
+
+        let volume = vec![(1, "Legs".to_string(), 3.5), (2, "Legs".to_string(), 4.0)];
+        let json = BuiltinCharts::planned_weekly_sets(&volume, "Block", None).to_json();
+        assert_eq!(json["encoding"]["x"]["field"], "week");
+        assert_eq!(json["data"]["values"][0]["sets"], 3.5);
+
+        let duration = vec![(1, 1, "Lower".to_string(), 52.4)];
+        let json = BuiltinCharts::planned_duration(&duration, "Block", None).to_json();
+        assert_eq!(json["data"]["values"][0]["day"], "Day 1 - Lower");
+        assert_eq!(json["data"]["values"][0]["minutes"], 52.0);
+    }
+
     #[test]
     fn test_heatmap_generation() {
         let sessions = vec![
@@ -0,0 +1,407 @@
+use super::vega_specs::{ChartTemplates, VegaLiteSpec};
+use crate::units::DisplayUnit;
+use chrono::{Datelike, Duration, NaiveDate};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// One side of a comparison: a lift, date window or plan block
+#[derive(Debug, Clone)]
+pub struct CompareSeries {
+    pub label: String,
+    /// First day of the window; week 1 is counted from here
+    pub start: NaiveDate,
+    /// Block length in weeks when the window follows a plan
+    pub block_weeks: Option<u32>,
+    pub values: Vec<(NaiveDate, f64)>, // (date, value in kg or count)
+}
+
+/// Metric being compared; decides how values within a week are combined
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareMetric {
+    /// Best e1RM of the week (kg)
+    E1rm,
+    /// Weekly tonnage (kg), one value per week start
+    Tonnage,
+    /// Weekly working sets, one value per week start
+    Sets,
+}
+
+impl CompareMetric {
+    fn is_weight(&self) -> bool {
+        matches!(self, CompareMetric::E1rm | CompareMetric::Tonnage)
+    }
+
+    fn title(&self, unit: DisplayUnit) -> String {
+        match self {
+            CompareMetric::E1rm => format!("Best e1RM ({})", unit.label()),
+            CompareMetric::Tonnage => format!("Tonnage ({})", unit.label()),
+            CompareMetric::Sets => "Sets".to_string(),
+        }
+    }
+}
+
+impl FromStr for CompareMetric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "e1rm" => Ok(CompareMetric::E1rm),
+            "tonnage" | "volume" => Ok(CompareMetric::Tonnage),
+            "sets" => Ok(CompareMetric::Sets),
+            other => Err(format!(
+                "Unknown compare metric: {}. Available: e1rm, tonnage, sets",
+                other
+            )),
+        }
+    }
+}
+
+/// How the windows are lined up on the x axis
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareAlignment {
+    /// Week 1 is the calendar (Monday) week containing the window start
+    WeekIndex,
+    /// Week 1 starts on the window start day, as a plan block does; weeks past
+    /// the series' `block_weeks` are dropped
+    PlanWeek,
+}
+
+/// Which column of the aligned data is plotted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareMeasure {
+    /// Weekly value
+    Value,
+    /// Change since the series' first week
+    Change,
+    /// Percentage change since the series' first week
+    ChangePct,
+}
+
+impl CompareMeasure {
+    fn field(&self) -> &'static str {
+        match self {
+            CompareMeasure::Value => "value",
+            CompareMeasure::Change => "change",
+            CompareMeasure::ChangePct => "change_pct",
+        }
+    }
+}
+
+/// Layered lines on one axis, or one panel per series
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareLayout {
+    Layered,
+    SideBySide,
+}
+
+/// A week of one series after alignment
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComparePoint {
+    pub series: String,
+    pub week: u32,
+    pub value: f64,
+    /// Change since the series' first week
+    pub change: f64,
+    pub change_pct: Option<f64>,
+    /// Difference from the first (baseline) series in the same week
+    pub delta: Option<f64>,
+    pub delta_pct: Option<f64>,
+}
+
+/// First-to-last-week change of one series
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompareSummary {
+    pub series: String,
+    pub weeks: u32,
+    pub first: f64,
+    pub last: f64,
+    pub change: f64,
+    pub change_pct: Option<f64>,
+}
+
+/// Comparison charts: two or more windows aligned week by week, with deltas
+/// against the first series and change since each series' first week
+pub struct CompareCharts;
+
+impl CompareCharts {
+    /// Bucket each series into weeks and compute changes and baseline deltas.
+    /// Weight values are converted into `unit`.
+    pub fn align(
+        series: &[CompareSeries],
+        metric: CompareMetric,
+        alignment: CompareAlignment,
+        unit: DisplayUnit,
+    ) -> Vec<ComparePoint> {
+        let weekly: Vec<BTreeMap<u32, f64>> = series
+            .iter()
+            .map(|s| Self::weekly_values(s, metric, alignment, unit))
+            .collect();
+        let baseline = weekly.first();
+
+        let mut points = Vec::new();
+        for (s, weeks) in series.iter().zip(&weekly) {
+            let Some(&first) = weeks.values().next() else {
+                continue;
+            };
+            for (&week, &value) in weeks {
+                let base = baseline.and_then(|b| b.get(&week)).copied();
+                points.push(ComparePoint {
+                    series: s.label.clone(),
+                    week,
+                    value,
+                    change: value - first,
+                    change_pct: pct_change(first, value),
+                    delta: base.map(|b| value - b),
+                    delta_pct: base.and_then(|b| pct_change(b, value)),
+                });
+            }
+        }
+        points
+    }
+
+    /// First-to-last-week change per series, in series order
+    pub fn summarize(points: &[ComparePoint]) -> Vec<CompareSummary> {
+        let mut summaries: Vec<CompareSummary> = Vec::new();
+        for point in points {
+            match summaries.iter_mut().find(|s| s.series == point.series) {
+                Some(summary) => {
+                    summary.weeks += 1;
+                    summary.last = point.value;
+                    summary.change = point.value - summary.first;
+                    summary.change_pct = pct_change(summary.first, point.value);
+                }
+                None => summaries.push(CompareSummary {
+                    series: point.series.clone(),
+                    weeks: 1,
+                    first: point.value,
+                    last: point.value,
+                    change: 0.0,
+                    change_pct: pct_change(point.value, point.value),
+                }),
+            }
+        }
+        summaries
+    }
+
+    /// Chart data rows for aligned points
+    pub fn to_chart_data(points: &[ComparePoint]) -> Vec<Value> {
+        points
+            .iter()
+            .map(|p| {
+                json!({
+                    "series": p.series,
+                    "week": p.week,
+                    "value": round1(p.value),
+                    "change": round1(p.change),
+                    "change_pct": p.change_pct.map(round1),
+                    "delta": p.delta.map(round1),
+                    "delta_pct": p.delta_pct.map(round1),
+                })
+            })
+            .collect()
+    }
+
+    /// Comparison chart of aligned series
+    pub fn compare(
+        series: &[CompareSeries],
+        metric: CompareMetric,
+        alignment: CompareAlignment,
+        measure: CompareMeasure,
+        layout: CompareLayout,
+        unit: DisplayUnit,
+        title: Option<String>,
+    ) -> VegaLiteSpec {
+        let points = Self::align(series, metric, alignment, unit);
+        let labels: Vec<&str> = series.iter().map(|s| s.label.as_str()).collect();
+
+        let metric_title = metric.title(unit);
+        let y_title = match measure {
+            CompareMeasure::Value => metric_title.clone(),
+            CompareMeasure::Change => format!("Change in {}", metric_title),
+            CompareMeasure::ChangePct => format!("Change in {} (%)", metric_title),
+        };
+        let x_title = match alignment {
+            CompareAlignment::WeekIndex => "Week",
+            CompareAlignment::PlanWeek => "Plan week",
+        };
+        let chart_title =
+            title.unwrap_or_else(|| format!("{}: {}", metric_title, labels.join(" vs ")));
+
+        let base = |template: VegaLiteSpec| {
+            template
+                .x_axis("week", "ordinal", Some(x_title.to_string()))
+                .y_axis(measure.field(), "quantitative", Some(y_title.clone()))
+                .color_field("series", "nominal")
+        };
+
+        let spec = match layout {
+            CompareLayout::Layered => VegaLiteSpec::new()
+                .dimensions(700, 400)
+                .layer(base(ChartTemplates::line_chart()))
+                .layer(base(ChartTemplates::scatter_plot())),
+            CompareLayout::SideBySide => base(ChartTemplates::bar_chart())
+                .dimensions(300, 300)
+                .facet("series", "nominal", Some(labels.len().max(1) as u32)),
+        };
+
+        spec.title(chart_title)
+            .data_values(Self::to_chart_data(&points))
+    }
+
+    fn weekly_values(
+        series: &CompareSeries,
+        metric: CompareMetric,
+        alignment: CompareAlignment,
+        unit: DisplayUnit,
+    ) -> BTreeMap<u32, f64> {
+        // Weekly metrics arrive keyed by Monday, so they always count calendar weeks
+        let origin = match (alignment, metric) {
+            (CompareAlignment::PlanWeek, CompareMetric::E1rm) => series.start,
+            _ => week_start(series.start),
+        };
+
+        let mut weeks: BTreeMap<u32, f64> = BTreeMap::new();
+        for &(date, value) in &series.values {
+            if date < origin {
+                continue;
+            }
+            let week = ((date - origin).num_days() / 7) as u32 + 1;
+            if alignment == CompareAlignment::PlanWeek
+                && series.block_weeks.is_some_and(|max| week > max)
+            {
+                continue;
+            }
+            let value = if metric.is_weight() {
+                unit.convert(value)
+            } else {
+                value
+            };
+            weeks
+                .entry(week)
+                .and_modify(|v| match metric {
+                    CompareMetric::E1rm => *v = v.max(value),
+                    CompareMetric::Tonnage | CompareMetric::Sets => *v += value,
+                })
+                .or_insert(value);
+        }
+        weeks
+    }
+}
+
+/// Monday of the week containing `date`
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+fn pct_change(from: f64, to: f64) -> Option<f64> {
+    (from != 0.0).then(|| (to - from) / from * 100.0)
+}
+
+fn round1(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, month, day).unwrap()
+    }
+
+    fn blocks() -> Vec<CompareSeries> {
+        vec![
+            CompareSeries {
+                label: "Block A".to_string(),
+                start: d(3, 3), // Monday
+                block_weeks: None,
+                values: vec![(d(3, 4), 100.0), (d(3, 6), 102.0), (d(3, 11), 105.0)],
+            },
+            CompareSeries {
+                label: "Block B".to_string(),
+                start: d(6, 4), // Wednesday
+                block_weeks: Some(2),
+                values: vec![
+                    (d(6, 4), 104.0),
+                    (d(6, 10), 110.0),
+                    (d(6, 12), 112.0),
+                    (d(6, 20), 115.0),
+                ],
+            },
+        ]
+    }
+
+    #[test]
+    fn test_align_and_summarize() {
+        let series = blocks();
+
+        // Calendar weeks: B's 6/4 and 6/10 fall in different Monday weeks
+        let points = CompareCharts::align(
+            &series,
+            CompareMetric::E1rm,
+            CompareAlignment::WeekIndex,
+            DisplayUnit::Kg,
+        );
+        let b: Vec<_> = points.iter().filter(|p| p.series == "Block B").collect();
+        assert_eq!(
+            b.iter().map(|p| (p.week, p.value)).collect::<Vec<_>>(),
+            vec![(1, 104.0), (2, 112.0), (3, 115.0)]
+        );
+        assert_eq!(b[0].delta, Some(2.0));
+        assert!(b[2].delta.is_none());
+
+        // Plan weeks start on the block's first day and stop at its length
+        let points = CompareCharts::align(
+            &series,
+            CompareMetric::E1rm,
+            CompareAlignment::PlanWeek,
+            DisplayUnit::Kg,
+        );
+        let b: Vec<_> = points.iter().filter(|p| p.series == "Block B").collect();
+        assert_eq!(
+            b.iter().map(|p| (p.week, p.value)).collect::<Vec<_>>(),
+            vec![(1, 110.0), (2, 112.0)]
+        );
+
+        let summary = CompareCharts::summarize(&points);
+        assert_eq!(summary[0].series, "Block A");
+        assert_eq!((summary[0].first, summary[0].last), (102.0, 105.0));
+        assert!((summary[1].change_pct.unwrap() - 1.818).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_compare_specs() {
+        let series = blocks();
+
+        let layered = CompareCharts::compare(
+            &series,
+            CompareMetric::Tonnage,
+            CompareAlignment::WeekIndex,
+            CompareMeasure::ChangePct,
+            CompareLayout::Layered,
+            DisplayUnit::Lb,
+            None,
+        )
+        .to_json();
+        assert_eq!(layered["title"], "Tonnage (lb): Block A vs Block B");
+        assert_eq!(layered["layer"].as_array().unwrap().len(), 2);
+        assert_eq!(layered["layer"][0]["encoding"]["y"]["field"], "change_pct");
+        // Tonnage sums within a week, converted to lb
+        assert_eq!(layered["data"]["values"][0]["value"], 445.3);
+
+        let side = CompareCharts::compare(
+            &series,
+            CompareMetric::Sets,
+            CompareAlignment::PlanWeek,
+            CompareMeasure::Value,
+            CompareLayout::SideBySide,
+            DisplayUnit::Kg,
+            Some("A vs B".to_string()),
+        )
+        .to_json();
+        assert_eq!(side["facet"]["field"], "series");
+        assert_eq!(side["columns"], 2);
+        assert_eq!(side["spec"]["encoding"]["x"]["axis"]["title"], "Plan week");
+    }
+}
@@ -1,7 +1,11 @@
-use chrono::NaiveDate;
+use crate::units::{DisplayUnit, LB_PER_KG};
+use chrono::{Datelike, NaiveDate};
 use serde_json::{json, Value};
 use std::collections::HashMap;
 
+/// (date, load, acwr, fitness, fatigue, performance)
+pub type DailyLoadRow = (NaiveDate, f64, Option<f64>, f64, f64, f64);
+
 /// Data transformation utilities for converting metrics to chart-ready format
 pub struct DataTransforms;
 
@@ -11,15 +15,38 @@ impl DataTransforms {
         e1rm_data: &[(String, NaiveDate, f64)], // (exercise, date, e1rm_kg)
         exercise_filter: Option<&str>,
     ) -> Vec<Value> {
-        e1rm_data
+        let filtered: Vec<&(String, NaiveDate, f64)> = e1rm_data
             .iter()
             .filter(|(exercise, _, _)| exercise_filter.is_none_or(|filter| exercise == filter))
-            .map(|(exercise, date, e1rm)| {
+            .collect();
+
+        // A point is a PR when it beats every earlier e1RM for the same exercise
+        let mut by_date: Vec<usize> = (0..filtered.len()).collect();
+        by_date.sort_by_key(|&i| filtered[i].1);
+        let mut best: HashMap<&str, f64> = HashMap::new();
+        let mut is_pr = vec![false; filtered.len()];
+        for i in by_date {
+            let (exercise, _, e1rm) = filtered[i];
+            let previous = best.entry(exercise.as_str()).or_insert(f64::NEG_INFINITY);
+            if *e1rm > *previous {
+                *previous = *e1rm;
+                is_pr[i] = true;
+            }
+        }
+
+        filtered
+            .iter()
+            .zip(is_pr)
+            .map(|((exercise, date, e1rm), is_pr)| {
+                let week_start =
+                    *date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64);
                 json!({
                     "exercise": exercise,
                     "date": date.format("%Y-%m-%d").to_string(),
+                    "week": week_start.format("%Y-%m-%d").to_string(),
                     "e1rm_kg": e1rm,
-                    "e1rm_lb": e1rm * 2.20462 // Convert to lbs for display
+                    "e1rm_lb": e1rm * LB_PER_KG,
+                    "is_pr": is_pr
                 })
             })
             .collect()
@@ -54,7 +81,7 @@ impl DataTransforms {
                     "sets": sets,
                     "reps": reps,
                     "tonnage_kg": tonnage,
-                    "tonnage_lb": tonnage * 2.20462
+                    "tonnage_lb": tonnage * LB_PER_KG
                 }));
             }
         }
@@ -65,10 +92,185 @@ impl DataTransforms {
         chart_data
     }
 
+    /// Transform weighted muscle-group / movement-pattern volume for stacked bar chart
+    pub fn group_volume_to_chart_data(
+        group_data: &[(String, NaiveDate, f64, f64, f64)], // (group, week_start, sets, reps, tonnage)
+    ) -> Vec<Value> {
+        let mut chart_data: Vec<Value> = group_data
+            .iter()
+            .map(|(group, week_start, sets, reps, tonnage)| {
+                json!({
+                    "week": week_start.format("%Y-%m-%d").to_string(),
+                    "group": group,
+                    "sets": (sets * 100.0).round() / 100.0,
+                    "reps": (reps * 100.0).round() / 100.0,
+                    "tonnage_kg": tonnage,
+                    "tonnage_lb": tonnage * LB_PER_KG
+                })
+            })
+            .collect();
+
+        chart_data.sort_by(|a, b| a["week"].as_str().cmp(&b["week"].as_str()));
+
+        chart_data
+    }
+
+    /// Transform daily training load for the ACWR line chart (days without a ratio are skipped)
+    pub fn acwr_to_chart_data(
+        load_data: &[(NaiveDate, f64, Option<f64>)], // (date, load, acwr)
+    ) -> Vec<Value> {
+        load_data
+            .iter()
+            .filter_map(|(date, load, acwr)| {
+                acwr.map(|ratio| {
+                    json!({
+                        "date": date.format("%Y-%m-%d").to_string(),
+                        "load": load,
+                        "acwr": (ratio * 100.0).round() / 100.0
+                    })
+                })
+            })
+            .collect()
+    }
+
+    /// Transform daily training load into one wide row per day (load, ACWR and model outputs)
+    pub fn daily_load_to_chart_data(load_data: &[DailyLoadRow]) -> Vec<Value> {
+        load_data
+            .iter()
+            .map(|(date, load, acwr, fitness, fatigue, performance)| {
+                json!({
+                    "date": date.format("%Y-%m-%d").to_string(),
+                    "load": load,
+                    "acwr": acwr.map(|ratio| (ratio * 100.0).round() / 100.0),
+                    "fitness": (fitness * 10.0).round() / 10.0,
+                    "fatigue": (fatigue * 10.0).round() / 10.0,
+                    "performance": (performance * 10.0).round() / 10.0
+                })
+            })
+            .collect()
+    }
+
+    /// Transform Banister model output into long format (one row per series per day)
+    pub fn fitness_fatigue_to_chart_data(
+        model_data: &[(NaiveDate, f64, f64, f64)], // (date, fitness, fatigue, performance)
+    ) -> Vec<Value> {
+        let mut chart_data = Vec::with_capacity(model_data.len() * 3);
+        for (date, fitness, fatigue, performance) in model_data {
+            let date = date.format("%Y-%m-%d").to_string();
+            for (series, value) in [
+                ("Fitness", fitness),
+                ("Fatigue", fatigue),
+                ("Performance", performance),
+            ] {
+                chart_data.push(json!({
+                    "date": date,
+                    "series": series,
+                    "value": (value * 10.0).round() / 10.0
+                }));
+            }
+        }
+
+        chart_data
+    }
+
+    /// Transform weekly load, monotony and strain for bar chart
+    pub fn weekly_load_to_chart_data(
+        weekly_data: &[(NaiveDate, f64, Option<f64>, Option<f64>)], // (week_start, load, monotony, strain)
+    ) -> Vec<Value> {
+        weekly_data
+            .iter()
+            .map(|(week_start, load, monotony, strain)| {
+                json!({
+                    "week": week_start.format("%Y-%m-%d").to_string(),
+                    "load": load,
+                    "monotony": monotony.map(|m| (m * 100.0).round() / 100.0),
+                    "strain": strain
+                })
+            })
+            .collect()
+    }
+
+    /// Transform per-session adherence totals into long format (one row per set status)
+    pub fn adherence_to_chart_data(
+        adherence_data: &[(NaiveDate, String, u32, u32, u32)], // (date, day_label, completed, missed, extra)
+    ) -> Vec<Value> {
+        let mut chart_data = Vec::with_capacity(adherence_data.len() * 3);
+        for (date, day_label, completed, missed, extra) in adherence_data {
+            let session = format!("{} {}", date.format("%Y-%m-%d"), day_label);
+            for (status, sets) in [
+                ("Completed", completed),
+                ("Missed", missed),
+                ("Extra", extra),
+            ] {
+                chart_data.push(json!({
+                    "session": session,
+                    "date": date.format("%Y-%m-%d").to_string(),
+                    "day_label": day_label,
+                    "status": status,
+                    "sets": sets
+                }));
+            }
+        }
+
+        chart_data
+    }
+
+    /// Transform projected weekly sets per muscle group for stacked bar chart
+    pub fn planned_volume_to_chart_data(
+        planned_data: &[(u32, String, f64)], // (week, group, weighted_sets)
+    ) -> Vec<Value> {
+        planned_data
+            .iter()
+            .map(|(week, group, sets)| {
+                json!({
+                    "week": week,
+                    "group": group,
+                    "sets": (sets * 100.0).round() / 100.0
+                })
+            })
+            .collect()
+    }
+
+    /// Transform projected per-exercise intensity for line chart (%1RM as a percentage)
+    pub fn planned_intensity_to_chart_data(
+        planned_data: &[(u32, String, Option<f64>, Option<f64>)], // (week, exercise, mean_rpe, mean_pct_1rm)
+        exercise_filter: Option<&str>,
+    ) -> Vec<Value> {
+        planned_data
+            .iter()
+            .filter(|(_, exercise, _, _)| exercise_filter.is_none_or(|f| exercise == f))
+            .map(|(week, exercise, rpe, pct_1rm)| {
+                json!({
+                    "week": week,
+                    "exercise": exercise,
+                    "rpe": rpe.map(|r| (r * 10.0).round() / 10.0),
+                    "pct_1rm": pct_1rm.map(|p| (p * 1000.0).round() / 10.0)
+                })
+            })
+            .collect()
+    }
+
+    /// Transform projected session lengths for line chart (one series per plan day)
+    pub fn planned_duration_to_chart_data(
+        planned_data: &[(u32, u32, String, f64)], // (week, day, day_label, minutes)
+    ) -> Vec<Value> {
+        planned_data
+            .iter()
+            .map(|(week, day, label, minutes)| {
+                json!({
+                    "week": week,
+                    "day": format!("Day {} - {}", day, label),
+                    "minutes": minutes.round()
+                })
+            })
+            .collect()
+    }
+
     /// Transform PR data for table/bar chart display
     pub fn pr_to_chart_data(
         pr_data: &[(String, String, NaiveDate, f64, Option<u32>)], // (exercise, pr_type, date, value, reps)
         exercise_filter: Option<&str>,
+        unit: DisplayUnit, // unit of the display text label
     ) -> Vec<Value> {
         pr_data
             .iter()
@@ -81,12 +283,12 @@ impl DataTransforms {
                     "pr_type": pr_type,
                     "date": date.format("%Y-%m-%d").to_string(),
                     "value_kg": value,
-                    "value_lb": value * 2.20462,
+                    "value_lb": value * LB_PER_KG,
                     "reps": reps,
                     "display_text": if let Some(r) = reps {
-                        format!("{:.1}kg x{}", value, r)
+                        format!("{:.1}{} x{}", unit.convert(*value), unit.label(), r)
                     } else {
-                        format!("{:.1}kg", value)
+                        format!("{:.1}{}", unit.convert(*value), unit.label())
                     }
                 })
             })
@@ -132,6 +334,9 @@ impl DataTransforms {
     }
 
     /// Get body part mapping for common exercises
+    ///
+    /// Fallback used when no exercise catalog is available; the indexer's
+    /// `ExerciseCatalog::primary_body_part_map` derives this from `exercises.db`.
     pub fn get_default_body_part_map() -> HashMap<String, String> {
         let mut map = HashMap::new();
 
@@ -251,6 +456,9 @@ mod tests {
         assert_eq!(filtered_data.len(), 2);
         assert_eq!(filtered_data[0]["exercise"], "BP.BB.FLAT");
         assert_eq!(filtered_data[0]["e1rm_kg"], 120.0);
+        assert_eq!(filtered_data[0]["week"], "2024-12-30");
+        assert_eq!(filtered_data[0]["is_pr"], true);
+        assert_eq!(filtered_data[1]["is_pr"], true);
     }
 
     #[test]
@@ -293,4 +501,31 @@ mod tests {
         assert_eq!(chart_data[2]["session_count"], 1);
         assert_eq!(chart_data[2]["intensity"], 1);
     }
+
+    #[test]
+    fn test_group_volume_chart_data() {
+        let data = vec![
+            (
+                "Triceps".to_string(),
+                NaiveDate::from_ymd_opt(2025, 1, 13).unwrap(),
+                1.5,
+                7.5,
+                600.0,
+            ),
+            (
+                "Pectorals".to_string(),
+                NaiveDate::from_ymd_opt(2025, 1, 6).unwrap(),
+                3.0,
+                15.0,
+                1200.0,
+            ),
+        ];
+
+        let chart_data = DataTransforms::group_volume_to_chart_data(&data);
+
+        assert_eq!(chart_data.len(), 2);
+        assert_eq!(chart_data[0]["week"], "2025-01-06");
+        assert_eq!(chart_data[0]["group"], "Pectorals");
+        assert_eq!(chart_data[1]["sets"], 1.5);
+    }
 }
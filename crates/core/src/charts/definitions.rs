@@ -0,0 +1,503 @@
+use super::vega_specs::{ChartTemplates, TransformSpec, VegaLiteSpec};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum DefinitionError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid JSON chart definition: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Invalid TOML chart definition: {0}")]
+    Toml(#[from] toml::de::Error),
+    #[error("Unsupported chart definition file: {0} (expected .toml or .json)")]
+    UnsupportedFormat(String),
+    #[error("Invalid chart definition '{0}': {1}")]
+    Invalid(String, String),
+    #[error("Chart definition not found: {0}")]
+    NotFound(String),
+}
+
+/// Metrics datasets a chart definition can query
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChartDataset {
+    /// One row per session e1RM (same rows as the built-in e1RM chart)
+    E1rm,
+    /// Weekly volume per body part
+    Volume,
+    /// Weekly weighted volume per muscle group
+    MuscleVolume,
+    /// Weekly weighted volume per movement pattern
+    PatternVolume,
+    /// Daily session-RPE load, ACWR and fitness-fatigue model
+    DailyLoad,
+    /// Weekly load, monotony and strain
+    WeeklyLoad,
+    /// Personal records
+    Prs,
+}
+
+impl ChartDataset {
+    /// Fields on each row of the dataset with their Vega-Lite encoding type
+    pub fn fields(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            ChartDataset::E1rm => &[
+                ("exercise", "nominal"),
+                ("date", "temporal"),
+                ("week", "ordinal"),
+                ("e1rm_kg", "quantitative"),
+                ("e1rm_lb", "quantitative"),
+                ("is_pr", "nominal"),
+            ],
+            ChartDataset::Volume => &[
+                ("week", "ordinal"),
+                ("body_part", "nominal"),
+                ("sets", "quantitative"),
+                ("reps", "quantitative"),
+                ("tonnage_kg", "quantitative"),
+                ("tonnage_lb", "quantitative"),
+            ],
+            ChartDataset::MuscleVolume | ChartDataset::PatternVolume => &[
+                ("week", "ordinal"),
+                ("group", "nominal"),
+                ("sets", "quantitative"),
+                ("reps", "quantitative"),
+                ("tonnage_kg", "quantitative"),
+                ("tonnage_lb", "quantitative"),
+            ],
+            ChartDataset::DailyLoad => &[
+                ("date", "temporal"),
+                ("load", "quantitative"),
+                ("acwr", "quantitative"),
+                ("fitness", "quantitative"),
+                ("fatigue", "quantitative"),
+                ("performance", "quantitative"),
+            ],
+            ChartDataset::WeeklyLoad => &[
+                ("week", "ordinal"),
+                ("load", "quantitative"),
+                ("monotony", "quantitative"),
+                ("strain", "quantitative"),
+            ],
+            ChartDataset::Prs => &[
+                ("exercise", "nominal"),
+                ("pr_type", "nominal"),
+                ("date", "temporal"),
+                ("value_kg", "quantitative"),
+                ("value_lb", "quantitative"),
+                ("reps", "quantitative"),
+                ("display_text", "nominal"),
+            ],
+        }
+    }
+}
+
+/// Aggregation operations understood by both Vega-Lite and `ChartRenderer`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AggregateOp {
+    Count,
+    Sum,
+    Mean,
+    Min,
+    Max,
+}
+
+impl AggregateOp {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AggregateOp::Count => "count",
+            AggregateOp::Sum => "sum",
+            AggregateOp::Mean => "mean",
+            AggregateOp::Min => "min",
+            AggregateOp::Max => "max",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AggregateDef {
+    pub op: AggregateOp,
+    /// Field to aggregate (not needed for `count`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    /// Output field name (defaults to `<op>_<field>`, or `count`)
+    #[serde(default, rename = "as", skip_serializing_if = "Option::is_none")]
+    pub as_field: Option<String>,
+}
+
+impl AggregateDef {
+    pub fn output_field(&self) -> String {
+        match (&self.as_field, &self.field) {
+            (Some(name), _) => name.clone(),
+            (None, Some(field)) => format!("{}_{}", self.op.as_str(), field),
+            (None, None) => self.op.as_str().to_string(),
+        }
+    }
+}
+
+/// The metrics query half of a chart definition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChartQuery {
+    pub dataset: ChartDataset,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exercise: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<NaiveDate>,
+    /// Rolling window ending today; ignored when `start_date` is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_days: Option<u32>,
+    /// Equality filters on row fields
+    #[serde(default, rename = "where", skip_serializing_if = "BTreeMap::is_empty")]
+    pub filters: BTreeMap<String, Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub group_by: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aggregate: Option<AggregateDef>,
+}
+
+impl ChartQuery {
+    /// Date range to query, resolving `last_days` against `today`
+    pub fn date_range(&self, today: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+        let start = self.start_date.or_else(|| {
+            self.last_days
+                .map(|days| today - chrono::Duration::days(days as i64))
+        });
+        match (start, self.end_date) {
+            (None, None) => None,
+            (start, end) => Some((
+                start.unwrap_or_else(|| NaiveDate::from_ymd_opt(2020, 1, 1).unwrap()),
+                end.unwrap_or(today),
+            )),
+        }
+    }
+}
+
+/// Chart templates a definition can select
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChartTemplateKind {
+    Line,
+    Bar,
+    StackedBar,
+    Scatter,
+    Heatmap,
+}
+
+/// A field mapped to an encoding channel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelDef {
+    pub field: String,
+    /// Vega-Lite type; inferred from the dataset when omitted
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub encoding_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+/// The presentation half of a chart definition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChartLayout {
+    pub template: ChartTemplateKind,
+    pub x: ChannelDef,
+    pub y: ChannelDef,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<ChannelDef>,
+    /// Small multiples: one panel per value of this field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub facet: Option<ChannelDef>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub columns: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+}
+
+/// A user-defined chart: a metrics query plus a chart template, stored as TOML or JSON
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChartDefinition {
+    /// Chart type name used by `comp chart emit-spec` (defaults to the file stem)
+    #[serde(default)]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub query: ChartQuery,
+    pub chart: ChartLayout,
+}
+
+impl ChartDefinition {
+    /// Load and validate a definition from a `.toml` or `.json` file
+    pub fn load(path: &Path) -> Result<Self, DefinitionError> {
+        let content = fs::read_to_string(path)?;
+        let mut definition: ChartDefinition = match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => toml::from_str(&content)?,
+            Some("json") => serde_json::from_str(&content)?,
+            _ => {
+                return Err(DefinitionError::UnsupportedFormat(
+                    path.display().to_string(),
+                ))
+            }
+        };
+
+        if definition.name.is_empty() {
+            definition.name = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default()
+                .to_string();
+        }
+        definition.validate()?;
+
+        Ok(definition)
+    }
+
+    /// Check that every referenced field exists in the dataset (or is the aggregate output)
+    pub fn validate(&self) -> Result<(), DefinitionError> {
+        let invalid = |msg: String| Err(DefinitionError::Invalid(self.name.clone(), msg));
+        let dataset = self.query.dataset;
+        let known = |field: &str| dataset.fields().iter().any(|(name, _)| *name == field);
+
+        for field in self
+            .query
+            .filters
+            .keys()
+            .chain(&self.query.group_by)
+            .chain(self.query.aggregate.as_ref().and_then(|a| a.field.as_ref()))
+        {
+            if !known(field) {
+                return invalid(format!("unknown field '{}' for {:?}", field, dataset));
+            }
+        }
+
+        let output = match &self.query.aggregate {
+            Some(aggregate) if aggregate.field.is_none() && aggregate.op != AggregateOp::Count => {
+                return invalid(format!(
+                    "aggregate '{}' needs a field",
+                    aggregate.op.as_str()
+                ));
+            }
+            Some(aggregate) => Some(aggregate.output_field()),
+            None => None,
+        };
+        for channel in self.channels() {
+            let field = channel.field.as_str();
+            let visible = match &output {
+                // Only grouped fields and the aggregate survive aggregation
+                Some(output) => field == output || self.query.group_by.iter().any(|g| g == field),
+                None => known(field),
+            };
+            if !visible {
+                return invalid(format!(
+                    "chart field '{}' is not in the query output",
+                    field
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Build the Vega-Lite spec over raw dataset rows; filters and aggregation are
+    /// expressed as spec transforms so the spec stays editable downstream
+    pub fn build_spec(&self, rows: Vec<Value>) -> VegaLiteSpec {
+        let layout = &self.chart;
+        let mut spec = match layout.template {
+            ChartTemplateKind::Line => ChartTemplates::line_chart(),
+            ChartTemplateKind::Bar => ChartTemplates::bar_chart(),
+            ChartTemplateKind::StackedBar => ChartTemplates::stacked_bar_chart(),
+            ChartTemplateKind::Scatter => ChartTemplates::scatter_plot(),
+            ChartTemplateKind::Heatmap => ChartTemplates::heatmap(),
+        }
+        .title(self.title.clone().unwrap_or_else(|| self.name.clone()))
+        .dimensions(layout.width.unwrap_or(800), layout.height.unwrap_or(400))
+        .x_axis(
+            &layout.x.field,
+            &self.encoding_type(&layout.x),
+            layout.x.title.clone(),
+        )
+        .y_axis(
+            &layout.y.field,
+            &self.encoding_type(&layout.y),
+            layout.y.title.clone(),
+        );
+
+        if let Some(color) = &layout.color {
+            spec = spec.color_field(&color.field, &self.encoding_type(color));
+        }
+        if let Some(facet) = &layout.facet {
+            spec = spec.facet(&facet.field, &self.encoding_type(facet), layout.columns);
+        }
+
+        for (field, value) in &self.query.filters {
+            spec = spec.transform(TransformSpec::filter_equal(field, value.clone()));
+        }
+        if let Some(aggregate) = &self.query.aggregate {
+            let groupby: Vec<&str> = self.query.group_by.iter().map(String::as_str).collect();
+            spec = spec.transform(TransformSpec::aggregate(
+                aggregate.op.as_str(),
+                aggregate.field.as_deref(),
+                &aggregate.output_field(),
+                &groupby,
+            ));
+        }
+
+        spec.data_values(rows)
+    }
+
+    fn channels(&self) -> impl Iterator<Item = &ChannelDef> {
+        [Some(&self.chart.x), Some(&self.chart.y)]
+            .into_iter()
+            .chain([self.chart.color.as_ref(), self.chart.facet.as_ref()])
+            .flatten()
+    }
+
+    fn encoding_type(&self, channel: &ChannelDef) -> String {
+        channel.encoding_type.clone().unwrap_or_else(|| {
+            self.query
+                .dataset
+                .fields()
+                .iter()
+                .find(|(name, _)| *name == channel.field)
+                .map_or("quantitative", |(_, kind)| kind)
+                .to_string()
+        })
+    }
+}
+
+/// Discovery of user chart definitions in a directory
+pub struct ChartDefinitions;
+
+impl ChartDefinitions {
+    /// Every `.toml` / `.json` file in `dir`, sorted by path, with its parse result
+    pub fn discover(dir: &Path) -> Vec<(PathBuf, Result<ChartDefinition, DefinitionError>)> {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                matches!(
+                    path.extension().and_then(|e| e.to_str()),
+                    Some("toml" | "json")
+                )
+            })
+            .collect();
+        paths.sort();
+
+        paths
+            .into_iter()
+            .map(|path| {
+                let definition = ChartDefinition::load(&path);
+                (path, definition)
+            })
+            .collect()
+    }
+
+    /// The definition named `name` in `dir`
+    pub fn find(dir: &Path, name: &str) -> Result<ChartDefinition, DefinitionError> {
+        Self::discover(dir)
+            .into_iter()
+            .find_map(|(path, definition)| match definition {
+                Ok(def) if def.name == name => Some(Ok(def)),
+                // Surface parse errors for the file the user most likely meant
+                Err(e) if path.file_stem().and_then(|s| s.to_str()) == Some(name) => Some(Err(e)),
+                _ => None,
+            })
+            .unwrap_or_else(|| Err(DefinitionError::NotFound(name.to_string())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const TOML_DEF: &str = r#"
+title = "Weekly Squat Tonnage"
+description = "Back squat tonnage per week"
+
+[query]
+dataset = "e1rm"
+exercise = "SQ.BB.BACK"
+last_days = 90
+where = { is_pr = true }
+group_by = ["week"]
+aggregate = { op = "max", field = "e1rm_kg", as = "best" }
+
+[chart]
+template = "bar"
+x = { field = "week", title = "Week" }
+y = { field = "best", title = "Best e1RM (kg)" }
+"#;
+
+    #[test]
+    fn test_load_toml_definition() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("squat-best.toml"), TOML_DEF).unwrap();
+        fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+
+        let found = ChartDefinitions::discover(dir.path());
+        assert_eq!(found.len(), 1);
+
+        let def = ChartDefinitions::find(dir.path(), "squat-best").unwrap();
+        assert_eq!(def.query.dataset, ChartDataset::E1rm);
+        let today = NaiveDate::from_ymd_opt(2025, 9, 1).unwrap();
+        assert_eq!(
+            def.query.date_range(today),
+            Some((NaiveDate::from_ymd_opt(2025, 6, 3).unwrap(), today))
+        );
+
+        let spec = def
+            .build_spec(vec![
+                json!({"week": "2025-08-04", "e1rm_kg": 150.0, "is_pr": true}),
+            ])
+            .to_json();
+        assert_eq!(spec["title"], "Weekly Squat Tonnage");
+        assert_eq!(spec["mark"], "bar");
+        assert_eq!(spec["encoding"]["x"]["type"], "ordinal");
+        assert_eq!(spec["encoding"]["y"]["type"], "quantitative");
+        assert_eq!(spec["transform"][0]["filter"]["equal"], true);
+        assert_eq!(spec["transform"][1]["aggregate"][0]["as"], "best");
+
+        assert!(matches!(
+            ChartDefinitions::find(dir.path(), "missing"),
+            Err(DefinitionError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_definition_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bad.json");
+        fs::write(
+            &path,
+            json!({
+                "query": { "dataset": "weekly_load", "group_by": ["week"],
+                           "aggregate": { "op": "sum", "field": "load" } },
+                "chart": { "template": "line", "x": { "field": "week" }, "y": { "field": "strain" } }
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        // `strain` is dropped by the aggregation (only `week` and `sum_load` remain)
+        let err = ChartDefinition::load(&path).unwrap_err();
+        assert!(err.to_string().contains("'strain'"));
+        assert!(matches!(
+            ChartDefinitions::find(dir.path(), "bad"),
+            Err(DefinitionError::Invalid(..))
+        ));
+    }
+}
@@ -1,7 +1,13 @@
 pub mod builtin_charts;
+pub mod compare;
 pub mod data_transforms;
+pub mod definitions;
+pub mod render;
 pub mod vega_specs;
 
 pub use builtin_charts::*;
+pub use compare::*;
 pub use data_transforms::*;
+pub use definitions::*;
+pub use render::*;
 pub use vega_specs::*;
@@ -0,0 +1,1627 @@
+use chrono::{Datelike, NaiveDate};
+use serde_json::{json, Value};
+use std::cmp::Ordering;
+use std::fmt::Write;
+use thiserror::Error;
+
+/// Default Vega categorical palette (category10 "tableau" variant)
+const PALETTE: [&str; 10] = [
+    "#4c78a8", "#f58518", "#e45756", "#72b7b2", "#54a24b", "#eeca3b", "#b279a2", "#ff9da6",
+    "#9d755d", "#bab0ac",
+];
+const DEFAULT_COLOR: &str = "#4c78a8";
+const DEFAULT_WIDTH: f64 = 600.0;
+const DEFAULT_HEIGHT: f64 = 400.0;
+const MARGIN_LEFT: f64 = 70.0;
+const MARGIN_RIGHT: f64 = 40.0;
+const MARGIN_BOTTOM: f64 = 50.0;
+const LEGEND_WIDTH: f64 = 160.0;
+
+#[derive(Error, Debug)]
+pub enum RenderError {
+    #[error("Invalid chart spec: {0}")]
+    InvalidSpec(String),
+    #[error("Unsupported chart spec: {0}")]
+    Unsupported(String),
+    #[error("PNG rendering failed: {0}")]
+    Png(String),
+}
+
+/// Output formats supported by `ChartRenderer`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderFormat {
+    Svg,
+    Png,
+}
+
+impl std::str::FromStr for RenderFormat {
+    type Err = RenderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "svg" => Ok(RenderFormat::Svg),
+            "png" => Ok(RenderFormat::Png),
+            other => Err(RenderError::Unsupported(format!(
+                "output format '{}' (available: svg, png)",
+                other
+            ))),
+        }
+    }
+}
+
+/// Pure-Rust renderer for the Vega-Lite subset produced by `ChartTemplates`
+/// (line, bar / stacked bar, point and rect heatmap marks with inline data)
+pub struct ChartRenderer;
+
+impl ChartRenderer {
+    /// Render a Vega-Lite JSON spec in the requested format
+    pub fn render(spec: &Value, format: RenderFormat) -> Result<Vec<u8>, RenderError> {
+        match format {
+            RenderFormat::Svg => Ok(Self::render_svg(spec)?.into_bytes()),
+            RenderFormat::Png => Self::render_png_bytes(spec),
+        }
+    }
+
+    /// Render a Vega-Lite JSON spec to an SVG document
+    pub fn render_svg(spec: &Value) -> Result<String, RenderError> {
+        let chart = Chart::parse(spec)?;
+        Ok(chart.to_svg())
+    }
+
+    /// Render a Vega-Lite JSON spec to PNG bytes
+    #[cfg(feature = "png")]
+    pub fn render_png(spec: &Value) -> Result<Vec<u8>, RenderError> {
+        use resvg::{tiny_skia, usvg};
+
+        let svg = Self::render_svg(spec)?;
+        let mut options = usvg::Options::default();
+        options.fontdb_mut().load_system_fonts();
+        let tree =
+            usvg::Tree::from_str(&svg, &options).map_err(|e| RenderError::Png(e.to_string()))?;
+
+        let size = tree.size().to_int_size();
+        let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())
+            .ok_or_else(|| RenderError::Png("chart has zero size".to_string()))?;
+        resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+        pixmap
+            .encode_png()
+            .map_err(|e| RenderError::Png(e.to_string()))
+    }
+
+    #[cfg(feature = "png")]
+    fn render_png_bytes(spec: &Value) -> Result<Vec<u8>, RenderError> {
+        Self::render_png(spec)
+    }
+
+    #[cfg(not(feature = "png"))]
+    fn render_png_bytes(_spec: &Value) -> Result<Vec<u8>, RenderError> {
+        Err(RenderError::Unsupported(
+            "PNG output requires the `png` feature of weightlifting-core".to_string(),
+        ))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MarkKind {
+    Line,
+    Bar,
+    Point,
+    Rect,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FieldKind {
+    Quantitative,
+    Temporal,
+    Ordinal,
+    Nominal,
+}
+
+/// A positional (x/y) encoding channel
+#[derive(Debug, Clone)]
+struct Channel {
+    field: String,
+    kind: FieldKind,
+    title: String,
+    format: Option<String>,
+    label_angle: Option<f64>,
+    grid: Option<bool>,
+    domain: Option<Vec<String>>,
+    labels: Option<Vec<String>>,
+}
+
+impl Channel {
+    fn parse(encoding: &Value, name: &str) -> Result<Option<Channel>, RenderError> {
+        let Some(enc) = encoding.get(name).filter(|v| v.is_object()) else {
+            return Ok(None);
+        };
+        let field = enc["field"]
+            .as_str()
+            .ok_or_else(|| RenderError::InvalidSpec(format!("{} encoding has no field", name)))?
+            .to_string();
+        let kind = match enc["type"].as_str().unwrap_or("nominal") {
+            "quantitative" => FieldKind::Quantitative,
+            "temporal" => FieldKind::Temporal,
+            "ordinal" => FieldKind::Ordinal,
+            "nominal" => FieldKind::Nominal,
+            other => {
+                return Err(RenderError::Unsupported(format!(
+                    "{} encoding type '{}'",
+                    name, other
+                )))
+            }
+        };
+
+        let axis = &enc["axis"];
+        let title = axis["title"]
+            .as_str()
+            .or_else(|| enc["title"].as_str())
+            .unwrap_or(&field)
+            .to_string();
+        let scale = &enc["scale"];
+
+        Ok(Some(Channel {
+            title,
+            format: axis["format"].as_str().map(str::to_string),
+            label_angle: axis["labelAngle"].as_f64(),
+            grid: axis["grid"].as_bool(),
+            domain: scale["domain"]
+                .as_array()
+                .map(|d| d.iter().map(value_key).collect()),
+            labels: scale["range"]
+                .as_array()
+                .map(|r| r.iter().map(value_key).collect()),
+            field,
+            kind,
+        }))
+    }
+}
+
+/// How marks are colored
+#[derive(Debug, Clone)]
+enum ColorMode {
+    Fixed(String),
+    Field {
+        field: String,
+        title: String,
+        domain: Vec<String>,
+        colors: Vec<String>,
+        explicit_domain: bool,
+    },
+}
+
+impl ColorMode {
+    fn color_for(&self, row: &Value) -> &str {
+        match self {
+            ColorMode::Fixed(color) => color,
+            ColorMode::Field {
+                field,
+                domain,
+                colors,
+                ..
+            } => {
+                let key = value_key(&row[field.as_str()]);
+                domain
+                    .iter()
+                    .position(|d| *d == key)
+                    .map_or(DEFAULT_COLOR, |i| colors[i % colors.len()].as_str())
+            }
+        }
+    }
+}
+
+/// Scale from data values to pixel positions
+#[derive(Debug, Clone)]
+enum Scale {
+    Linear {
+        min: f64,
+        max: f64,
+        step: f64,
+        from: f64,
+        to: f64,
+        temporal: bool,
+    },
+    Band {
+        categories: Vec<String>,
+        labels: Vec<String>,
+        from: f64,
+        to: f64,
+    },
+}
+
+impl Scale {
+    fn linear(values: &[f64], include_zero: bool, from: f64, to: f64, temporal: bool) -> Scale {
+        let mut lo = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let mut hi = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        if !lo.is_finite() || !hi.is_finite() {
+            lo = 0.0;
+            hi = 1.0;
+        }
+        if include_zero {
+            lo = lo.min(0.0);
+            hi = hi.max(0.0);
+        }
+        if (hi - lo).abs() < f64::EPSILON {
+            if temporal {
+                lo -= 1.0;
+            }
+            hi = lo + if temporal { 2.0 } else { 1.0 };
+        }
+
+        let mut step = nice_step((hi - lo) / 5.0);
+        if temporal {
+            // Dates are whole days
+            step = step.max(1.0);
+        }
+        Scale::Linear {
+            min: (lo / step).floor() * step,
+            max: (hi / step).ceil() * step,
+            step,
+            from,
+            to,
+            temporal,
+        }
+    }
+
+    fn band(categories: Vec<String>, labels: Option<&Vec<String>>, from: f64, to: f64) -> Scale {
+        let labels = match labels {
+            Some(l) if l.len() == categories.len() => l.clone(),
+            _ => categories.clone(),
+        };
+        Scale::Band {
+            categories,
+            labels,
+            from,
+            to,
+        }
+    }
+
+    /// Pixel position for a value (band scales return the band start)
+    fn position(&self, value: &Value, kind: FieldKind) -> Option<f64> {
+        match self {
+            Scale::Linear {
+                min, max, from, to, ..
+            } => {
+                let v = numeric(value, kind)?;
+                Some(from + (v - min) / (max - min) * (to - from))
+            }
+            Scale::Band {
+                categories, from, ..
+            } => {
+                let key = value_key(value);
+                let idx = categories.iter().position(|c| *c == key)?;
+                Some(from + idx as f64 * self.bandwidth())
+            }
+        }
+    }
+
+    fn linear_position(&self, v: f64) -> f64 {
+        match self {
+            Scale::Linear {
+                min, max, from, to, ..
+            } => from + (v - min) / (max - min) * (to - from),
+            Scale::Band { from, .. } => *from,
+        }
+    }
+
+    fn bandwidth(&self) -> f64 {
+        match self {
+            Scale::Band {
+                categories,
+                from,
+                to,
+                ..
+            } => (to - from).abs() / categories.len().max(1) as f64,
+            Scale::Linear { .. } => 0.0,
+        }
+    }
+
+    /// (pixel position, label) pairs for axis ticks
+    fn ticks(&self, format: Option<&str>) -> Vec<(f64, String)> {
+        match self {
+            Scale::Linear {
+                min,
+                max,
+                step,
+                temporal,
+                ..
+            } => {
+                let mut ticks = Vec::new();
+                let mut v = *min;
+                while v <= max + step * 1e-6 {
+                    let label = if *temporal {
+                        NaiveDate::from_num_days_from_ce_opt(v.round() as i32)
+                            .map(|d| d.format(format.unwrap_or("%Y-%m-%d")).to_string())
+                            .unwrap_or_default()
+                    } else {
+                        format_number(v, *step)
+                    };
+                    ticks.push((self.linear_position(v), label));
+                    v += step;
+                }
+                ticks
+            }
+            Scale::Band { labels, .. } => {
+                let half = self.bandwidth() / 2.0;
+                labels
+                    .iter()
+                    .enumerate()
+                    .map(|(i, label)| {
+                        let start = self
+                            .position(
+                                &Value::String(self.category(i).to_string()),
+                                FieldKind::Nominal,
+                            )
+                            .unwrap_or_default();
+                        (start + half, label.clone())
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    fn category(&self, idx: usize) -> &str {
+        match self {
+            Scale::Band { categories, .. } => &categories[idx],
+            Scale::Linear { .. } => "",
+        }
+    }
+
+    fn is_band(&self) -> bool {
+        matches!(self, Scale::Band { .. })
+    }
+}
+
+/// One mark layer with its own encodings and (transformed) data
+#[derive(Debug, Clone)]
+struct Layer {
+    mark: MarkKind,
+    opacity: Option<f64>,
+    stroke_width: Option<f64>,
+    x: Channel,
+    y: Channel,
+    color: ColorMode,
+    rows: Vec<Value>,
+}
+
+impl Layer {
+    /// Parse a unit view; `shared` holds encodings inherited from a parent layer spec
+    fn parse(view: &Value, shared: &Value, rows: Vec<Value>) -> Result<Layer, RenderError> {
+        let (mark_type, mark) = match &view["mark"] {
+            Value::String(mark) => (mark.clone(), &Value::Null),
+            mark @ Value::Object(_) => {
+                (mark["type"].as_str().unwrap_or_default().to_string(), mark)
+            }
+            _ => return Err(RenderError::InvalidSpec("spec has no mark".to_string())),
+        };
+        let mark_kind = match mark_type.as_str() {
+            "line" => MarkKind::Line,
+            "bar" => MarkKind::Bar,
+            "point" | "circle" | "square" => MarkKind::Point,
+            "rect" => MarkKind::Rect,
+            other => return Err(RenderError::Unsupported(format!("mark '{}'", other))),
+        };
+
+        let mut encoding = match shared {
+            Value::Object(_) => shared.clone(),
+            _ => json!({}),
+        };
+        if let Some(own) = view["encoding"].as_object() {
+            for (channel, enc) in own.iter().filter(|(_, enc)| !enc.is_null()) {
+                encoding[channel.as_str()] = enc.clone();
+            }
+        }
+
+        let x = Channel::parse(&encoding, "x")?
+            .ok_or_else(|| RenderError::InvalidSpec("spec has no x encoding".to_string()))?;
+        let y = Channel::parse(&encoding, "y")?
+            .ok_or_else(|| RenderError::InvalidSpec("spec has no y encoding".to_string()))?;
+
+        let color_enc = &encoding["color"];
+        let color = match color_enc["field"].as_str() {
+            Some(field) => {
+                let scale = &color_enc["scale"];
+                let domain: Vec<String> = match scale["domain"].as_array() {
+                    Some(d) => d.iter().map(value_key).collect(),
+                    None => distinct_keys(rows.iter().map(|r| &r[field]), false),
+                };
+                let colors: Vec<String> = match scale["range"].as_array() {
+                    Some(r) if !r.is_empty() => r.iter().map(value_key).collect(),
+                    _ => PALETTE.iter().map(|c| c.to_string()).collect(),
+                };
+                ColorMode::Field {
+                    field: field.to_string(),
+                    title: color_enc["legend"]["title"]
+                        .as_str()
+                        .unwrap_or(field)
+                        .to_string(),
+                    domain,
+                    colors,
+                    explicit_domain: scale["domain"].is_array(),
+                }
+            }
+            None => ColorMode::Fixed(
+                color_enc["value"]
+                    .as_str()
+                    .or_else(|| mark["color"].as_str())
+                    .unwrap_or(DEFAULT_COLOR)
+                    .to_string(),
+            ),
+        };
+
+        Ok(Layer {
+            mark: mark_kind,
+            opacity: mark["opacity"].as_f64(),
+            stroke_width: mark["strokeWidth"].as_f64(),
+            x,
+            y,
+            color,
+            rows,
+        })
+    }
+
+    fn x_values(&self) -> impl Iterator<Item = &Value> {
+        self.rows.iter().map(|r| &r[self.x.field.as_str()])
+    }
+
+    fn y_values(&self) -> impl Iterator<Item = &Value> {
+        self.rows.iter().map(|r| &r[self.y.field.as_str()])
+    }
+
+    /// Copy of the layer keeping only rows whose `field` matches `key`
+    fn filtered(&self, field: &str, key: &str) -> Layer {
+        Layer {
+            rows: self
+                .rows
+                .iter()
+                .filter(|r| !r[field].is_null() && value_key(&r[field]) == key)
+                .cloned()
+                .collect(),
+            ..self.clone()
+        }
+    }
+
+    fn x_center(&self, scale: &Scale, value: &Value) -> Option<f64> {
+        let pos = scale.position(value, self.x.kind)?;
+        Some(pos + scale.bandwidth() / 2.0)
+    }
+
+    fn y_center(&self, scale: &Scale, value: &Value) -> Option<f64> {
+        let pos = scale.position(value, self.y.kind)?;
+        // Band scales run bottom-up on y, so the band sits above its start
+        Some(pos - scale.bandwidth() / 2.0)
+    }
+
+    fn draw(&self, svg: &mut String, x: &Scale, y: &Scale) {
+        match self.mark {
+            MarkKind::Line => self.draw_lines(svg, x, y),
+            MarkKind::Bar => self.draw_bars(svg, x, y),
+            MarkKind::Point => self.draw_points(svg, x, y),
+            MarkKind::Rect => self.draw_cells(svg, x, y),
+        }
+    }
+
+    fn draw_lines(&self, svg: &mut String, x: &Scale, y: &Scale) {
+        let mut series: Vec<(String, Vec<(f64, f64)>)> = Vec::new();
+        for row in &self.rows {
+            let (Some(px), Some(py)) = (
+                self.x_center(x, &row[self.x.field.as_str()]),
+                self.y_center(y, &row[self.y.field.as_str()]),
+            ) else {
+                continue;
+            };
+            let color = self.color.color_for(row).to_string();
+            match series.iter_mut().find(|(c, _)| *c == color) {
+                Some((_, points)) => points.push((px, py)),
+                None => series.push((color, vec![(px, py)])),
+            }
+        }
+
+        for (color, mut points) in series {
+            points.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let path: Vec<String> = points
+                .iter()
+                .enumerate()
+                .map(|(i, (px, py))| {
+                    format!("{}{:.2},{:.2}", if i == 0 { "M" } else { "L" }, px, py)
+                })
+                .collect();
+            let _ = write!(
+                svg,
+                r#"<path d="{}" fill="none" stroke="{}" stroke-width="{}" stroke-opacity="{}"/>"#,
+                path.join(" "),
+                escape(&color),
+                self.stroke_width.unwrap_or(2.0),
+                self.opacity.unwrap_or(1.0)
+            );
+            if points.len() == 1 {
+                let (px, py) = points[0];
+                let _ = write!(
+                    svg,
+                    r#"<circle cx="{px:.2}" cy="{py:.2}" r="3" fill="{}"/>"#,
+                    escape(&color)
+                );
+            }
+        }
+    }
+
+    fn draw_bars(&self, svg: &mut String, x: &Scale, y: &Scale) {
+        let band = x.bandwidth();
+        let padding = band * 0.1;
+        let mut stacks: Vec<(String, f64, f64)> = Vec::new(); // (x key, positive top, negative bottom)
+
+        for row in &self.rows {
+            let x_value = &row[self.x.field.as_str()];
+            let (Some(start), Some(v)) = (
+                x.position(x_value, self.x.kind),
+                numeric(&row[self.y.field.as_str()], self.y.kind),
+            ) else {
+                continue;
+            };
+
+            let key = value_key(x_value);
+            let idx = match stacks.iter().position(|(k, _, _)| *k == key) {
+                Some(idx) => idx,
+                None => {
+                    stacks.push((key, 0.0, 0.0));
+                    stacks.len() - 1
+                }
+            };
+            let (from, to) = if v >= 0.0 {
+                let base = stacks[idx].1;
+                stacks[idx].1 += v;
+                (base, base + v)
+            } else {
+                let base = stacks[idx].2;
+                stacks[idx].2 += v;
+                (base, base + v)
+            };
+
+            let y0 = y.linear_position(from);
+            let y1 = y.linear_position(to);
+            let _ = write!(
+                svg,
+                r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="{}" fill-opacity="{}"/>"#,
+                start + padding,
+                y0.min(y1),
+                (band - 2.0 * padding).max(1.0),
+                (y0 - y1).abs(),
+                escape(self.color.color_for(row)),
+                self.opacity.unwrap_or(1.0)
+            );
+        }
+    }
+
+    fn draw_points(&self, svg: &mut String, x: &Scale, y: &Scale) {
+        for row in &self.rows {
+            let (Some(px), Some(py)) = (
+                self.x_center(x, &row[self.x.field.as_str()]),
+                self.y_center(y, &row[self.y.field.as_str()]),
+            ) else {
+                continue;
+            };
+            let _ = write!(
+                svg,
+                r#"<circle cx="{px:.2}" cy="{py:.2}" r="4" fill="{}" fill-opacity="{}"/>"#,
+                escape(self.color.color_for(row)),
+                self.opacity.unwrap_or(0.8)
+            );
+        }
+    }
+
+    fn draw_cells(&self, svg: &mut String, x: &Scale, y: &Scale) {
+        let (w, h) = (x.bandwidth(), y.bandwidth());
+        for row in &self.rows {
+            let (Some(px), Some(py)) = (
+                x.position(&row[self.x.field.as_str()], self.x.kind),
+                y.position(&row[self.y.field.as_str()], self.y.kind),
+            ) else {
+                continue;
+            };
+            let _ = write!(
+                svg,
+                r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="{}" fill-opacity="{}" stroke="white"/>"#,
+                px,
+                py - h,
+                w,
+                h,
+                escape(self.color.color_for(row)),
+                self.opacity.unwrap_or(1.0)
+            );
+        }
+    }
+}
+
+/// Small-multiples facet over a field
+struct Facet {
+    field: String,
+    columns: Option<usize>,
+}
+
+/// Parsed chart ready for layout
+struct Chart {
+    title: Option<String>,
+    width: f64,
+    height: f64,
+    layers: Vec<Layer>,
+    facet: Option<Facet>,
+}
+
+impl Chart {
+    fn parse(spec: &Value) -> Result<Chart, RenderError> {
+        if !spec.is_object() {
+            return Err(RenderError::InvalidSpec(
+                "spec must be a JSON object".to_string(),
+            ));
+        }
+        if spec.get("repeat").is_some_and(|r| !r.is_null()) {
+            return Err(RenderError::Unsupported("repeat compositions".to_string()));
+        }
+
+        let mut rows = apply_transforms(inline_rows(&spec["data"])?, &spec["transform"])?;
+
+        // Faceted specs keep the view (and its size) under "spec"
+        let (view, facet) = match spec["facet"]["field"].as_str() {
+            Some(field) => {
+                let view = &spec["spec"];
+                rows = apply_transforms(rows, &view["transform"])?;
+                let facet = Facet {
+                    field: field.to_string(),
+                    columns: spec["columns"].as_u64().map(|c| c.max(1) as usize),
+                };
+                (view, Some(facet))
+            }
+            None if spec.get("facet").is_some_and(|f| !f.is_null()) => {
+                return Err(RenderError::Unsupported(
+                    "row/column facets; use a single facet field".to_string(),
+                ))
+            }
+            None => (spec, None),
+        };
+
+        let mut layers = match view["layer"].as_array() {
+            Some(layer_specs) => {
+                let mut layers = Vec::with_capacity(layer_specs.len());
+                for layer in layer_specs {
+                    if layer.get("layer").is_some() {
+                        return Err(RenderError::Unsupported("nested layers".to_string()));
+                    }
+                    let layer_rows = match layer.get("data") {
+                        Some(data) => inline_rows(data)?,
+                        None => rows.clone(),
+                    };
+                    let layer_rows = apply_transforms(layer_rows, &layer["transform"])?;
+                    layers.push(Layer::parse(layer, &view["encoding"], layer_rows)?);
+                }
+                layers
+            }
+            None => vec![Layer::parse(view, &Value::Null, rows)?],
+        };
+        if layers.is_empty() {
+            return Err(RenderError::InvalidSpec("spec has no layers".to_string()));
+        }
+        unify_color_domains(&mut layers);
+
+        Ok(Chart {
+            title: spec["title"].as_str().map(str::to_string),
+            width: view["width"]
+                .as_f64()
+                .or_else(|| spec["width"].as_f64())
+                .unwrap_or(DEFAULT_WIDTH),
+            height: view["height"]
+                .as_f64()
+                .or_else(|| spec["height"].as_f64())
+                .unwrap_or(DEFAULT_HEIGHT),
+            layers,
+            facet,
+        })
+    }
+
+    /// The first layer provides axis titles and formatting
+    fn primary(&self) -> &Layer {
+        &self.layers[0]
+    }
+
+    /// The first color-by-field layer provides the legend
+    fn legend_layer(&self) -> Option<&Layer> {
+        self.layers
+            .iter()
+            .find(|l| matches!(&l.color, ColorMode::Field { domain, .. } if !domain.is_empty()))
+    }
+
+    /// Bars and heatmap cells always sit on a band scale along x
+    fn x_is_band(&self) -> bool {
+        matches!(
+            self.primary().x.kind,
+            FieldKind::Ordinal | FieldKind::Nominal
+        ) || self
+            .layers
+            .iter()
+            .any(|l| matches!(l.mark, MarkKind::Bar | MarkKind::Rect))
+    }
+
+    fn y_is_band(&self) -> bool {
+        matches!(
+            self.primary().y.kind,
+            FieldKind::Ordinal | FieldKind::Nominal
+        ) || self.layers.iter().any(|l| l.mark == MarkKind::Rect)
+    }
+
+    /// Facet panels: (header, layers restricted to that facet value)
+    fn panels(&self) -> Vec<(Option<String>, Vec<Layer>)> {
+        let Some(facet) = &self.facet else {
+            return vec![(None, self.layers.clone())];
+        };
+        let keys = distinct_keys(
+            self.layers
+                .iter()
+                .flat_map(|l| l.rows.iter().map(|r| &r[facet.field.as_str()])),
+            true,
+        );
+        keys.into_iter()
+            .map(|key| {
+                let layers = self
+                    .layers
+                    .iter()
+                    .map(|l| l.filtered(&facet.field, &key))
+                    .collect();
+                (Some(key), layers)
+            })
+            .collect()
+    }
+
+    fn to_svg(&self) -> String {
+        let top = if self.title.is_some() { 40.0 } else { 15.0 };
+        let header = if self.facet.is_some() { 22.0 } else { 0.0 };
+
+        let rotate = self.rotate_x_labels(&self.x_scale(0.0, self.width));
+        let bottom = MARGIN_BOTTOM + if rotate { 45.0 } else { 0.0 };
+
+        let panels = self.panels();
+        let columns = match &self.facet {
+            Some(facet) => facet
+                .columns
+                .unwrap_or_else(|| (panels.len() as f64).sqrt().ceil() as usize)
+                .clamp(1, panels.len().max(1)),
+            None => 1,
+        };
+        let grid_rows = panels.len().div_ceil(columns).max(1);
+
+        let panel_width = MARGIN_LEFT + self.width + MARGIN_RIGHT;
+        let panel_height = header + self.height + bottom;
+        let legend = self.legend_layer();
+        let total_width =
+            columns as f64 * panel_width + if legend.is_some() { LEGEND_WIDTH } else { 0.0 };
+        let total_height = top + grid_rows as f64 * panel_height;
+
+        let mut svg = String::new();
+        let _ = write!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="DejaVu Sans, Arial, sans-serif" font-size="11">"#,
+            w = total_width,
+            h = total_height
+        );
+        let _ = write!(
+            svg,
+            r#"<rect x="0" y="0" width="{}" height="{}" fill="white"/>"#,
+            total_width, total_height
+        );
+
+        if let Some(title) = &self.title {
+            let _ = write!(
+                svg,
+                r#"<text x="{}" y="24" text-anchor="middle" font-size="15" font-weight="bold">{}</text>"#,
+                (columns as f64 * panel_width) / 2.0,
+                escape(title)
+            );
+        }
+
+        for (i, (name, layers)) in panels.iter().enumerate() {
+            let origin_x = (i % columns) as f64 * panel_width;
+            let left = origin_x + MARGIN_LEFT;
+            let plot_top = top + (i / columns) as f64 * panel_height + header;
+
+            // Scales are shared across panels, as in Vega-Lite's default facet resolution
+            let x_scale = self.x_scale(left, left + self.width);
+            let y_scale = self.y_scale(plot_top + self.height, plot_top);
+
+            if let Some(name) = name {
+                let _ = write!(
+                    svg,
+                    r#"<text x="{}" y="{}" text-anchor="middle" font-weight="bold">{}</text>"#,
+                    left + self.width / 2.0,
+                    plot_top - 8.0,
+                    escape(name)
+                );
+            }
+
+            self.draw_y_axis(&mut svg, &y_scale, origin_x, plot_top);
+            self.draw_x_axis(&mut svg, &x_scale, left, plot_top + self.height, rotate);
+
+            if layers.iter().all(|l| l.rows.is_empty()) {
+                let _ = write!(
+                    svg,
+                    r##"<text x="{}" y="{}" text-anchor="middle" fill="#888">No data</text>"##,
+                    left + self.width / 2.0,
+                    plot_top + self.height / 2.0
+                );
+            }
+            for layer in layers {
+                layer.draw(&mut svg, &x_scale, &y_scale);
+            }
+        }
+
+        if let Some(layer) = legend {
+            draw_legend(&mut svg, layer, columns as f64 * panel_width, top + header);
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    fn x_scale(&self, from: f64, to: f64) -> Scale {
+        let primary = &self.primary().x;
+        if self.x_is_band() {
+            let categories = primary.domain.clone().unwrap_or_else(|| {
+                distinct_keys(
+                    self.layers.iter().flat_map(Layer::x_values),
+                    primary.kind != FieldKind::Nominal,
+                )
+            });
+            Scale::band(categories, primary.labels.as_ref(), from, to)
+        } else {
+            let values: Vec<f64> = self
+                .layers
+                .iter()
+                .flat_map(|l| l.x_values().filter_map(|v| numeric(v, l.x.kind)))
+                .collect();
+            let temporal = primary.kind == FieldKind::Temporal;
+            let all_lines = self.layers.iter().all(|l| l.mark == MarkKind::Line);
+            Scale::linear(&values, !temporal && !all_lines, from, to, temporal)
+        }
+    }
+
+    fn y_scale(&self, from: f64, to: f64) -> Scale {
+        let primary = &self.primary().y;
+        if self.y_is_band() {
+            let categories = primary.domain.clone().unwrap_or_else(|| {
+                distinct_keys(
+                    self.layers.iter().flat_map(Layer::y_values),
+                    primary.kind != FieldKind::Nominal,
+                )
+            });
+            return Scale::band(categories, primary.labels.as_ref(), to, from);
+        }
+
+        let mut values: Vec<f64> = Vec::new();
+        for layer in &self.layers {
+            if layer.mark == MarkKind::Bar {
+                // Stacked totals per x category, positive and negative separately
+                let mut pos: Vec<(String, f64)> = Vec::new();
+                let mut neg: Vec<(String, f64)> = Vec::new();
+                for row in &layer.rows {
+                    let Some(v) = numeric(&row[layer.y.field.as_str()], layer.y.kind) else {
+                        continue;
+                    };
+                    let key = value_key(&row[layer.x.field.as_str()]);
+                    let stack = if v >= 0.0 { &mut pos } else { &mut neg };
+                    match stack.iter_mut().find(|(k, _)| *k == key) {
+                        Some((_, total)) => *total += v,
+                        None => stack.push((key, v)),
+                    }
+                }
+                values.extend(pos.into_iter().chain(neg).map(|(_, v)| v));
+            } else {
+                values.extend(layer.y_values().filter_map(|v| numeric(v, layer.y.kind)));
+            }
+        }
+        let temporal = primary.kind == FieldKind::Temporal;
+        Scale::linear(&values, !temporal, from, to, temporal)
+    }
+
+    fn rotate_x_labels(&self, scale: &Scale) -> bool {
+        if let Some(angle) = self.primary().x.label_angle {
+            return angle.abs() > f64::EPSILON;
+        }
+        match scale {
+            Scale::Band { labels, .. } => {
+                let longest = labels.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+                longest as f64 * 6.5 > scale.bandwidth()
+            }
+            Scale::Linear { .. } => false,
+        }
+    }
+
+    fn draw_y_axis(&self, svg: &mut String, scale: &Scale, origin_x: f64, top: f64) {
+        let channel = &self.primary().y;
+        let left = origin_x + MARGIN_LEFT;
+        let grid = channel.grid.unwrap_or(!scale.is_band());
+        let ticks = thin(scale.ticks(channel.format.as_deref()), self.height / 14.0);
+        for (pos, label) in ticks {
+            if grid {
+                let _ = write!(
+                    svg,
+                    r##"<line x1="{}" y1="{pos}" x2="{}" y2="{pos}" stroke="#e6e6e6"/>"##,
+                    left,
+                    left + self.width
+                );
+            }
+            let _ = write!(
+                svg,
+                r##"<line x1="{}" y1="{pos}" x2="{left}" y2="{pos}" stroke="#888"/><text x="{}" y="{}" text-anchor="end">{}</text>"##,
+                left - 5.0,
+                left - 8.0,
+                pos + 4.0,
+                escape(&label)
+            );
+        }
+        let _ = write!(
+            svg,
+            r##"<line x1="{left}" y1="{top}" x2="{left}" y2="{}" stroke="#888"/>"##,
+            top + self.height
+        );
+        let (title_x, mid) = (origin_x + 16.0, top + self.height / 2.0);
+        let _ = write!(
+            svg,
+            r#"<text x="{title_x}" y="{mid}" text-anchor="middle" font-weight="bold" transform="rotate(-90 {title_x} {mid})">{}</text>"#,
+            escape(&channel.title)
+        );
+    }
+
+    fn draw_x_axis(&self, svg: &mut String, scale: &Scale, left: f64, baseline: f64, rotate: bool) {
+        let channel = &self.primary().x;
+        let _ = write!(
+            svg,
+            r##"<line x1="{left}" y1="{baseline}" x2="{}" y2="{baseline}" stroke="#888"/>"##,
+            left + self.width
+        );
+
+        let min_spacing = if rotate { 14.0 } else { 60.0 };
+        let ticks = thin(
+            scale.ticks(channel.format.as_deref()),
+            self.width / min_spacing,
+        );
+        for (pos, label) in ticks {
+            let _ = write!(
+                svg,
+                r##"<line x1="{pos}" y1="{baseline}" x2="{pos}" y2="{}" stroke="#888"/>"##,
+                baseline + 5.0
+            );
+            let y = baseline + 17.0;
+            if rotate {
+                let _ = write!(
+                    svg,
+                    r#"<text x="{pos}" y="{y}" text-anchor="end" transform="rotate(-45 {pos} {y})">{}</text>"#,
+                    escape(&label)
+                );
+            } else {
+                let _ = write!(
+                    svg,
+                    r#"<text x="{pos}" y="{y}" text-anchor="middle">{}</text>"#,
+                    escape(&label)
+                );
+            }
+        }
+
+        let title_y = baseline + if rotate { 85.0 } else { 40.0 };
+        let _ = write!(
+            svg,
+            r#"<text x="{}" y="{title_y}" text-anchor="middle" font-weight="bold">{}</text>"#,
+            left + self.width / 2.0,
+            escape(&channel.title)
+        );
+    }
+}
+
+fn draw_legend(svg: &mut String, layer: &Layer, left: f64, top: f64) {
+    let ColorMode::Field {
+        title,
+        domain,
+        colors,
+        ..
+    } = &layer.color
+    else {
+        return;
+    };
+
+    let x = left + 10.0;
+    let _ = write!(
+        svg,
+        r#"<text x="{x}" y="{}" font-weight="bold">{}</text>"#,
+        top + 10.0,
+        escape(title)
+    );
+    for (i, entry) in domain.iter().enumerate() {
+        let y = top + 28.0 + i as f64 * 18.0;
+        let color = escape(&colors[i % colors.len()]);
+        match layer.mark {
+            MarkKind::Line => {
+                let _ = write!(
+                    svg,
+                    r#"<line x1="{x}" y1="{y}" x2="{}" y2="{y}" stroke="{color}" stroke-width="2"/>"#,
+                    x + 14.0
+                );
+            }
+            MarkKind::Point => {
+                let _ = write!(
+                    svg,
+                    r#"<circle cx="{}" cy="{y}" r="5" fill="{color}"/>"#,
+                    x + 7.0
+                );
+            }
+            MarkKind::Bar | MarkKind::Rect => {
+                let _ = write!(
+                    svg,
+                    r#"<rect x="{x}" y="{}" width="12" height="12" fill="{color}"/>"#,
+                    y - 6.0
+                );
+            }
+        }
+        let _ = write!(
+            svg,
+            r#"<text x="{}" y="{}">{}</text>"#,
+            x + 20.0,
+            y + 4.0,
+            escape(entry)
+        );
+    }
+}
+
+/// Layers colored by a field share one color domain (first-seen order), as Vega-Lite
+/// unions scale domains across layers
+fn unify_color_domains(layers: &mut [Layer]) {
+    let mut union: Vec<String> = Vec::new();
+    for layer in layers.iter() {
+        if let ColorMode::Field {
+            domain,
+            explicit_domain: false,
+            ..
+        } = &layer.color
+        {
+            for key in domain {
+                if !union.contains(key) {
+                    union.push(key.clone());
+                }
+            }
+        }
+    }
+    for layer in layers.iter_mut() {
+        if let ColorMode::Field {
+            domain,
+            explicit_domain: false,
+            ..
+        } = &mut layer.color
+        {
+            domain.clone_from(&union);
+        }
+    }
+}
+
+/// Inline rows of a data spec; URL sources are not fetched
+fn inline_rows(data: &Value) -> Result<Vec<Value>, RenderError> {
+    if data.get("url").is_some_and(|u| !u.is_null()) {
+        return Err(RenderError::Unsupported(
+            "URL data sources; inline data.values only".to_string(),
+        ));
+    }
+    Ok(data["values"].as_array().cloned().unwrap_or_default())
+}
+
+/// Apply a Vega-Lite transform array (filter, window, aggregate) to inline rows
+fn apply_transforms(mut rows: Vec<Value>, transforms: &Value) -> Result<Vec<Value>, RenderError> {
+    let Some(transforms) = transforms.as_array() else {
+        return Ok(rows);
+    };
+    for transform in transforms {
+        if let Some(filter) = transform.get("filter") {
+            let mut kept = Vec::with_capacity(rows.len());
+            for row in rows {
+                if predicate(filter, &row)? {
+                    kept.push(row);
+                }
+            }
+            rows = kept;
+        } else if let Some(ops) = transform["window"].as_array() {
+            rows = apply_window(rows, ops, transform)?;
+        } else if let Some(ops) = transform["aggregate"].as_array() {
+            rows = apply_aggregate(rows, ops, &string_list(&transform["groupby"]))?;
+        } else {
+            return Err(RenderError::Unsupported(format!("transform {}", transform)));
+        }
+    }
+    Ok(rows)
+}
+
+/// Evaluate a filter predicate object against a row
+fn predicate(filter: &Value, row: &Value) -> Result<bool, RenderError> {
+    let Value::Object(p) = filter else {
+        return Err(RenderError::Unsupported(format!(
+            "filter expression {}",
+            filter
+        )));
+    };
+
+    // Selections start empty, and an empty selection keeps every row
+    if p.contains_key("param") {
+        return Ok(true);
+    }
+    if let Some(all) = p.get("and").and_then(Value::as_array) {
+        for f in all {
+            if !predicate(f, row)? {
+                return Ok(false);
+            }
+        }
+        return Ok(true);
+    }
+    if let Some(any) = p.get("or").and_then(Value::as_array) {
+        for f in any {
+            if predicate(f, row)? {
+                return Ok(true);
+            }
+        }
+        return Ok(false);
+    }
+    if let Some(inner) = p.get("not") {
+        return Ok(!predicate(inner, row)?);
+    }
+
+    let field = p
+        .get("field")
+        .and_then(Value::as_str)
+        .ok_or_else(|| RenderError::Unsupported(format!("filter predicate {}", filter)))?;
+    let value = &row[field];
+
+    if let Some(expected) = p.get("equal") {
+        return Ok(!value.is_null() && compare_values(value, expected) == Ordering::Equal);
+    }
+    if let Some(options) = p.get("oneOf").and_then(Value::as_array) {
+        return Ok(!value.is_null()
+            && options
+                .iter()
+                .any(|o| compare_values(value, o) == Ordering::Equal));
+    }
+    if let Some(valid) = p.get("valid").and_then(Value::as_bool) {
+        let is_valid = !value.is_null() && value.as_f64().is_none_or(f64::is_finite);
+        return Ok(valid == is_valid);
+    }
+
+    let bound = |key: &str| p.get(key).and_then(Value::as_f64);
+    let (mut lower, mut upper) = (bound("gte"), bound("lte"));
+    if let Some(range) = p.get("range").and_then(Value::as_array) {
+        lower = range.first().and_then(Value::as_f64);
+        upper = range.get(1).and_then(Value::as_f64);
+    }
+    let (gt, lt) = (bound("gt"), bound("lt"));
+    if lower.is_none() && upper.is_none() && gt.is_none() && lt.is_none() {
+        return Err(RenderError::Unsupported(format!(
+            "filter predicate {}",
+            filter
+        )));
+    }
+
+    let Some(v) = value.as_f64() else {
+        return Ok(false);
+    };
+    Ok(lower.is_none_or(|b| v >= b)
+        && upper.is_none_or(|b| v <= b)
+        && gt.is_none_or(|b| v > b)
+        && lt.is_none_or(|b| v < b))
+}
+
+/// Window transform: per-group sorted frames, results written onto each row
+fn apply_window(
+    mut rows: Vec<Value>,
+    ops: &[Value],
+    transform: &Value,
+) -> Result<Vec<Value>, RenderError> {
+    let groupby = string_list(&transform["groupby"]);
+    let sort: Vec<(String, bool)> = transform["sort"]
+        .as_array()
+        .map(|fields| {
+            fields
+                .iter()
+                .filter_map(|s| {
+                    let field = s["field"].as_str()?.to_string();
+                    Some((field, s["order"].as_str() == Some("descending")))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let frame = match transform["frame"].as_array() {
+        Some(f) => (
+            f.first().and_then(Value::as_i64),
+            f.get(1).and_then(Value::as_i64),
+        ),
+        None => (None, Some(0)),
+    };
+
+    for mut group in group_indices(&rows, &groupby) {
+        group.sort_by(|&a, &b| {
+            sort.iter()
+                .map(|(field, descending)| {
+                    let ord = compare_values(&rows[a][field.as_str()], &rows[b][field.as_str()]);
+                    if *descending {
+                        ord.reverse()
+                    } else {
+                        ord
+                    }
+                })
+                .find(|ord| *ord != Ordering::Equal)
+                .unwrap_or(Ordering::Equal)
+        });
+
+        let len = group.len() as i64;
+        let mut results: Vec<(usize, Vec<(String, Value)>)> = Vec::with_capacity(group.len());
+        for (pos, &idx) in group.iter().enumerate() {
+            let pos = pos as i64;
+            let lo = frame.0.map_or(0, |f| (pos + f).max(0));
+            let hi = frame.1.map_or(len - 1, |f| (pos + f).min(len - 1));
+            let frame_rows: Vec<&Value> = if lo <= hi {
+                group[lo as usize..=hi as usize]
+                    .iter()
+                    .map(|&i| &rows[i])
+                    .collect()
+            } else {
+                Vec::new()
+            };
+
+            let mut computed = Vec::with_capacity(ops.len());
+            for op in ops {
+                let name = op["op"].as_str().unwrap_or_default();
+                let as_field = op["as"].as_str().unwrap_or(name).to_string();
+                let value = if name == "row_number" {
+                    json!(pos + 1)
+                } else {
+                    summarize(name, op["field"].as_str(), &frame_rows)?
+                };
+                computed.push((as_field, value));
+            }
+            results.push((idx, computed));
+        }
+
+        for (idx, computed) in results {
+            for (field, value) in computed {
+                rows[idx][field.as_str()] = value;
+            }
+        }
+    }
+
+    Ok(rows)
+}
+
+/// Aggregate transform: one output row per group
+fn apply_aggregate(
+    rows: Vec<Value>,
+    ops: &[Value],
+    groupby: &[String],
+) -> Result<Vec<Value>, RenderError> {
+    let mut output = Vec::new();
+    for group in group_indices(&rows, groupby) {
+        let members: Vec<&Value> = group.iter().map(|&i| &rows[i]).collect();
+        let mut row = json!({});
+        for field in groupby {
+            row[field.as_str()] = members[0][field.as_str()].clone();
+        }
+        for op in ops {
+            let name = op["op"].as_str().unwrap_or_default();
+            let as_field = op["as"].as_str().unwrap_or(name);
+            row[as_field] = summarize(name, op["field"].as_str(), &members)?;
+        }
+        output.push(row);
+    }
+    Ok(output)
+}
+
+/// Row indices grouped by the given fields, in first-seen order
+fn group_indices(rows: &[Value], groupby: &[String]) -> Vec<Vec<usize>> {
+    let mut groups: Vec<(Vec<String>, Vec<usize>)> = Vec::new();
+    for (idx, row) in rows.iter().enumerate() {
+        let key: Vec<String> = groupby
+            .iter()
+            .map(|f| value_key(&row[f.as_str()]))
+            .collect();
+        match groups.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, members)) => members.push(idx),
+            None => groups.push((key, vec![idx])),
+        }
+    }
+    groups.into_iter().map(|(_, members)| members).collect()
+}
+
+/// Summary operation over a field of the given rows (`count` counts rows)
+fn summarize(op: &str, field: Option<&str>, rows: &[&Value]) -> Result<Value, RenderError> {
+    let values: Vec<f64> = match field {
+        Some(field) => rows.iter().filter_map(|r| r[field].as_f64()).collect(),
+        None => Vec::new(),
+    };
+    let value = match op {
+        "count" => json!(rows.len()),
+        "valid" => json!(values.len()),
+        "sum" => json!(values.iter().sum::<f64>()),
+        "mean" | "average" if values.is_empty() => Value::Null,
+        "mean" | "average" => json!(values.iter().sum::<f64>() / values.len() as f64),
+        "min" => values
+            .iter()
+            .copied()
+            .reduce(f64::min)
+            .map_or(Value::Null, |v| json!(v)),
+        "max" => values
+            .iter()
+            .copied()
+            .reduce(f64::max)
+            .map_or(Value::Null, |v| json!(v)),
+        other => {
+            return Err(RenderError::Unsupported(format!(
+                "aggregate op '{}'",
+                other
+            )))
+        }
+    };
+    Ok(value)
+}
+
+fn string_list(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|i| i.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Order two data values: numerically when both are numbers, otherwise by string key
+fn compare_values(a: &Value, b: &Value) -> Ordering {
+    match (a.as_f64(), b.as_f64()) {
+        (Some(x), Some(y)) => x.total_cmp(&y),
+        _ => value_key(a).cmp(&value_key(b)),
+    }
+}
+
+/// String key for a data value (used for band categories and color domains)
+fn value_key(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Numeric value for a quantitative or temporal field (dates become days since CE)
+fn numeric(value: &Value, kind: FieldKind) -> Option<f64> {
+    match kind {
+        FieldKind::Temporal => value
+            .as_str()
+            .and_then(|s| NaiveDate::parse_from_str(s.get(..10)?, "%Y-%m-%d").ok())
+            .map(|d| d.num_days_from_ce() as f64),
+        _ => value.as_f64(),
+    }
+}
+
+/// Distinct non-null keys, in data order or sorted
+fn distinct_keys<'a>(values: impl Iterator<Item = &'a Value>, sorted: bool) -> Vec<String> {
+    let mut keys: Vec<String> = Vec::new();
+    for value in values {
+        if value.is_null() {
+            continue;
+        }
+        let key = value_key(value);
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+    if sorted {
+        if keys.iter().all(|k| k.parse::<f64>().is_ok()) {
+            keys.sort_by(|a, b| {
+                a.parse::<f64>()
+                    .unwrap_or_default()
+                    .total_cmp(&b.parse::<f64>().unwrap_or_default())
+            });
+        } else {
+            keys.sort();
+        }
+    }
+    keys
+}
+
+/// Round a raw tick step to 1, 2 or 5 times a power of ten
+fn nice_step(raw: f64) -> f64 {
+    if raw <= 0.0 || !raw.is_finite() {
+        return 1.0;
+    }
+    let magnitude = 10f64.powf(raw.log10().floor());
+    let fraction = raw / magnitude;
+    let nice = if fraction <= 1.0 {
+        1.0
+    } else if fraction <= 2.0 {
+        2.0
+    } else if fraction <= 5.0 {
+        5.0
+    } else {
+        10.0
+    };
+    nice * magnitude
+}
+
+fn format_number(v: f64, step: f64) -> String {
+    if step >= 1.0 {
+        format!("{:.0}", v)
+    } else {
+        let decimals = (-step.log10().floor()) as usize;
+        format!("{:.*}", decimals, v)
+    }
+}
+
+/// Drop ticks so at most `max_ticks` labels are drawn
+fn thin(ticks: Vec<(f64, String)>, max_ticks: f64) -> Vec<(f64, String)> {
+    let max_ticks = max_ticks.max(1.0) as usize;
+    if ticks.len() <= max_ticks {
+        return ticks;
+    }
+    let every = ticks.len().div_ceil(max_ticks);
+    ticks.into_iter().step_by(every).collect()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::charts::{BuiltinCharts, ChartTemplates, VolumeMetric};
+    use crate::units::DisplayUnit;
+    use serde_json::json;
+
+    #[test]
+    fn test_layered_line_chart_svg() {
+        let data = vec![
+            (
+                "BP.BB.FLAT".to_string(),
+                NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+                120.0,
+            ),
+            (
+                "BP.BB.FLAT".to_string(),
+                NaiveDate::from_ymd_opt(2025, 1, 8).unwrap(),
+                125.0,
+            ),
+        ];
+        let spec = BuiltinCharts::e1rm_over_time(&data, Some("BP.BB.FLAT"), DisplayUnit::Kg, None)
+            .to_json();
+
+        let svg = ChartRenderer::render_svg(&spec).unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("E1RM Progress - BP.BB.FLAT"));
+        assert!(svg.contains(r#"stroke="steelblue""#));
+        // Session line and rolling mean, plus a PR point for each new best
+        assert_eq!(svg.matches("<path").count(), 2);
+        assert_eq!(svg.matches(r#"fill="darkorange""#).count(), 2);
+    }
+
+    #[test]
+    fn test_transforms() {
+        let rows = vec![
+            json!({"ex": "a", "week": "2025-01-06", "kg": 100.0, "pr": true}),
+            json!({"ex": "a", "week": "2025-01-06", "kg": 110.0, "pr": false}),
+            json!({"ex": "a", "week": "2025-01-13", "kg": 120.0, "pr": true}),
+            json!({"ex": "b", "week": "2025-01-06", "kg": 50.0, "pr": true}),
+        ];
+        let transforms = json!([
+            {"aggregate": [{"op": "max", "field": "kg", "as": "best"}], "groupby": ["ex", "week"]},
+            {"window": [{"op": "mean", "field": "best", "as": "rolling"}], "frame": [-1, 0], "groupby": ["ex"], "sort": [{"field": "week"}]},
+            {"filter": {"field": "ex", "equal": "a"}}
+        ]);
+
+        let out = apply_transforms(rows.clone(), &transforms).unwrap();
+        assert_eq!(out.len(), 2);
+        assert_eq!(out[0]["best"], 110.0);
+        assert_eq!(out[1]["rolling"], 115.0);
+
+        let prs = apply_transforms(rows.clone(), &json!([{"filter": {"field": "kg", "range": [60, 115]}}, {"filter": {"param": "brush"}}])).unwrap();
+        assert_eq!(prs.len(), 2);
+        assert!(apply_transforms(rows, &json!([{"filter": "datum.kg > 1"}])).is_err());
+    }
+
+    #[test]
+    fn test_faceted_svg() {
+        let data = vec![
+            (
+                "BP.BB.FLAT".to_string(),
+                NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+                120.0,
+            ),
+            (
+                "SQ.BB.BACK".to_string(),
+                NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+                150.0,
+            ),
+        ];
+        let spec = BuiltinCharts::e1rm_by_exercise(&data, 2, DisplayUnit::Kg, None).to_json();
+
+        let svg = ChartRenderer::render_svg(&spec).unwrap();
+        assert!(svg.contains(">BP.BB.FLAT</text>"));
+        assert!(svg.contains(">SQ.BB.BACK</text>"));
+        // Two panels side by side at 300px each plus margins
+        assert!(svg.contains(r#"width="820""#));
+    }
+
+    #[test]
+    fn test_stacked_bar_svg_with_legend() {
+        let week = NaiveDate::from_ymd_opt(2025, 1, 6).unwrap();
+        let data = vec![
+            ("Chest".to_string(), week, 3.0, 15.0, 450.0),
+            ("Legs".to_string(), week, 4.0, 20.0, 800.0),
+        ];
+        let spec = BuiltinCharts::weekly_volume_by_group(
+            &data,
+            "Muscle Group",
+            VolumeMetric::Sets,
+            DisplayUnit::Kg,
+            None,
+        )
+        .to_json();
+
+        let svg = ChartRenderer::render_svg(&spec).unwrap();
+        // Two stacked bars plus two legend swatches, on top of the background
+        assert_eq!(svg.matches("<rect").count(), 1 + 2 + 2);
+        assert!(svg.contains(">Muscle Group</text>"));
+        assert!(svg.contains(">Legs</text>"));
+    }
+
+    #[test]
+    fn test_heatmap_svg_uses_scale_labels() {
+        let sessions = vec![NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()];
+        let spec = BuiltinCharts::session_frequency_heatmap(
+            &sessions,
+            NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 1, 7).unwrap(),
+            None,
+        )
+        .to_json();
+
+        let svg = ChartRenderer::render_svg(&spec).unwrap();
+        assert!(svg.contains(">Mon</text>"));
+        assert!(svg.contains("#9be9a8"));
+    }
+
+    #[test]
+    fn test_unsupported_specs() {
+        let spec = ChartTemplates::line_chart()
+            .x_axis("x", "quantitative", None)
+            .y_axis("y", "quantitative", None)
+            .mark("arc")
+            .to_json();
+        assert!(matches!(
+            ChartRenderer::render_svg(&spec),
+            Err(RenderError::Unsupported(_))
+        ));
+
+        let remote = json!({
+            "mark": "line",
+            "encoding": { "x": { "field": "x", "type": "quantitative" }, "y": { "field": "y", "type": "quantitative" } },
+            "data": { "url": "https://example.com/data.json" }
+        });
+        assert!(ChartRenderer::render_svg(&remote).is_err());
+        assert!("pdf".parse::<RenderFormat>().is_err());
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_png_output() {
+        let spec = ChartTemplates::bar_chart()
+            .x_axis("x", "nominal", None)
+            .y_axis("y", "quantitative", None)
+            .data_values(vec![json!({"x": "a", "y": 1}), json!({"x": "b", "y": 2})])
+            .to_json();
+
+        let png = ChartRenderer::render(&spec, RenderFormat::Png).unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    }
+}
@@ -13,6 +13,12 @@ pub struct VegaLiteSpec {
     pub encoding: EncodingSpec,
     pub data: DataSpec,
     pub config: Option<Value>,
+    pub transform: Vec<TransformSpec>,
+    pub params: Vec<ParamSpec>,
+    pub layer: Vec<VegaLiteSpec>,
+    pub facet: Option<FacetSpec>,
+    pub repeat: Option<RepeatSpec>,
+    pub columns: Option<u32>,
 }
 
 /// Data specification for Vega-Lite
@@ -127,6 +133,166 @@ pub struct ShapeEncoding {
     pub value: Option<String>, // Fixed shape value
 }
 
+/// Data transform applied before encoding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TransformSpec {
+    /// Keep rows matching an expression string or predicate object
+    Filter { filter: Value },
+    /// Windowed computation over sorted rows within each group
+    Window {
+        window: Vec<FieldOp>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        frame: Option<[Option<i64>; 2]>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        groupby: Option<Vec<String>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        sort: Option<Vec<SortField>>,
+    },
+    /// Group rows and summarize them
+    Aggregate {
+        aggregate: Vec<FieldOp>,
+        groupby: Vec<String>,
+    },
+}
+
+impl TransformSpec {
+    /// Filter with a Vega expression, e.g. `datum.sets > 0`
+    pub fn filter_expr(expr: &str) -> Self {
+        TransformSpec::Filter {
+            filter: json!(expr),
+        }
+    }
+
+    /// Filter rows whose field equals a value
+    pub fn filter_equal(field: &str, value: Value) -> Self {
+        TransformSpec::Filter {
+            filter: json!({ "field": field, "equal": value }),
+        }
+    }
+
+    /// Filter rows by a selection parameter
+    pub fn filter_param(param: &str) -> Self {
+        TransformSpec::Filter {
+            filter: json!({ "param": param }),
+        }
+    }
+
+    /// Rolling mean of `field` over a row frame, sorted ascending by `sort_field`
+    pub fn rolling_mean(
+        field: &str,
+        as_field: &str,
+        frame: [Option<i64>; 2],
+        groupby: &[&str],
+        sort_field: &str,
+    ) -> Self {
+        TransformSpec::Window {
+            window: vec![FieldOp::new("mean", Some(field), as_field)],
+            frame: Some(frame),
+            groupby: (!groupby.is_empty()).then(|| groupby.iter().map(|g| g.to_string()).collect()),
+            sort: Some(vec![SortField {
+                field: sort_field.to_string(),
+                order: None,
+            }]),
+        }
+    }
+
+    /// Single-op aggregate grouped by the given fields
+    pub fn aggregate(op: &str, field: Option<&str>, as_field: &str, groupby: &[&str]) -> Self {
+        TransformSpec::Aggregate {
+            aggregate: vec![FieldOp::new(op, field, as_field)],
+            groupby: groupby.iter().map(|g| g.to_string()).collect(),
+        }
+    }
+}
+
+/// Operation used by window and aggregate transforms
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldOp {
+    pub op: String, // "mean", "sum", "count", "min", "max", "row_number"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    #[serde(rename = "as")]
+    pub as_field: String,
+}
+
+impl FieldOp {
+    pub fn new(op: &str, field: Option<&str>, as_field: &str) -> Self {
+        Self {
+            op: op.to_string(),
+            field: field.map(str::to_string),
+            as_field: as_field.to_string(),
+        }
+    }
+}
+
+/// Sort field for window transforms
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SortField {
+    pub field: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<String>, // "ascending", "descending"
+}
+
+/// Parameter (variable or interactive selection)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParamSpec {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub select: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bind: Option<Value>,
+}
+
+impl ParamSpec {
+    /// Interval selection brushed along x
+    pub fn interval_x(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            value: None,
+            select: Some(json!({ "type": "interval", "encodings": ["x"] })),
+            bind: None,
+        }
+    }
+
+    /// Pan and zoom along x
+    pub fn zoom_x(name: &str) -> Self {
+        Self {
+            bind: Some(json!("scales")),
+            ..Self::interval_x(name)
+        }
+    }
+
+    /// Point selection on a field, toggled by clicking legend entries
+    pub fn legend_toggle(name: &str, field: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            value: None,
+            select: Some(json!({ "type": "point", "fields": [field] })),
+            bind: Some(json!("legend")),
+        }
+    }
+}
+
+/// Facet (small multiples) specification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FacetSpec {
+    pub field: String,
+    #[serde(rename = "type")]
+    pub encoding_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+/// Repeat specification: one view per field on the given channel
+#[derive(Debug, Clone)]
+pub struct RepeatSpec {
+    pub fields: Vec<String>,
+    pub channel: String, // "x", "y"
+}
+
 /// Legend specification
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LegendSpec {
@@ -152,6 +318,12 @@ impl VegaLiteSpec {
             encoding: EncodingSpec::default(),
             data: DataSpec::Values { values: vec![] },
             config: None,
+            transform: Vec::new(),
+            params: Vec::new(),
+            layer: Vec::new(),
+            facet: None,
+            repeat: None,
+            columns: None,
         }
     }
 
@@ -187,6 +359,25 @@ impl VegaLiteSpec {
         self
     }
 
+    /// Set mark opacity and stroke width, keeping the mark type and color
+    pub fn mark_style(mut self, opacity: Option<f64>, stroke_width: Option<f64>) -> Self {
+        let mut mark = match self.mark {
+            MarkSpec::Simple(mark_type) => MarkObject {
+                mark_type,
+                color: None,
+                size: None,
+                opacity: None,
+                stroke: None,
+                stroke_width: None,
+            },
+            MarkSpec::Detailed(mark) => mark,
+        };
+        mark.opacity = opacity;
+        mark.stroke_width = stroke_width;
+        self.mark = MarkSpec::Detailed(mark);
+        self
+    }
+
     /// Set X axis encoding
     pub fn x_axis(mut self, field: &str, encoding_type: &str, title: Option<String>) -> Self {
         self.encoding.x = Some(AxisEncoding {
@@ -251,15 +442,77 @@ impl VegaLiteSpec {
         self
     }
 
+    /// Add a data transform
+    pub fn transform(mut self, transform: TransformSpec) -> Self {
+        self.transform.push(transform);
+        self
+    }
+
+    /// Add a parameter or selection
+    pub fn param(mut self, param: ParamSpec) -> Self {
+        self.params.push(param);
+        self
+    }
+
+    /// Add a layer; layers inherit this spec's data unless they set their own.
+    /// Once layers are present the spec's own mark and encoding are ignored.
+    pub fn layer(mut self, layer: VegaLiteSpec) -> Self {
+        self.layer.push(layer);
+        self
+    }
+
+    /// Facet into small multiples by a field; width/height apply to each cell
+    pub fn facet(mut self, field: &str, encoding_type: &str, columns: Option<u32>) -> Self {
+        self.facet = Some(FacetSpec {
+            field: field.to_string(),
+            encoding_type: encoding_type.to_string(),
+            title: None,
+        });
+        self.columns = columns;
+        self
+    }
+
+    /// Repeat the view once per field, substituted into the given channel's encoding
+    pub fn repeat(mut self, fields: Vec<String>, channel: &str, columns: Option<u32>) -> Self {
+        self.repeat = Some(RepeatSpec {
+            fields,
+            channel: channel.to_string(),
+        });
+        self.columns = columns;
+        self
+    }
+
     /// Convert to JSON representation
     pub fn to_json(&self) -> Value {
         let mut spec = json!({
             "$schema": "https://vega.github.io/schema/vega-lite/v5.json",
-            "mark": self.mark,
-            "encoding": self.encoding,
             "data": self.data
         });
 
+        let view = self.view_json(self.repeat.as_ref());
+        if let Some(facet) = &self.facet {
+            spec["facet"] = json!(facet);
+            spec["spec"] = view;
+        } else if let Some(repeat) = &self.repeat {
+            spec["repeat"] = json!(repeat.fields);
+            spec["spec"] = view;
+        } else if let Value::Object(view) = view {
+            for (key, value) in view {
+                spec[key.as_str()] = value;
+            }
+        }
+
+        if let Some(columns) = self
+            .columns
+            .filter(|_| self.facet.is_some() || self.repeat.is_some())
+        {
+            spec["columns"] = json!(columns);
+        }
+
+        if !self.transform.is_empty() {
+            spec["transform"] = json!(self.transform);
+        }
+
         if let Some(title) = &self.title {
             spec["title"] = json!(title);
         }
@@ -268,19 +521,71 @@ impl VegaLiteSpec {
             spec["description"] = json!(description);
         }
 
+        if let Some(config) = &self.config {
+            spec["config"] = config.clone();
+        }
+
+        spec
+    }
+
+    /// Unit or layered view: mark/encoding (or layers), size and params
+    fn view_json(&self, repeat: Option<&RepeatSpec>) -> Value {
+        let mut view = self.body_json(repeat);
+
         if let Some(width) = self.width {
-            spec["width"] = json!(width);
+            view["width"] = json!(width);
         }
 
         if let Some(height) = self.height {
-            spec["height"] = json!(height);
+            view["height"] = json!(height);
         }
 
-        if let Some(config) = &self.config {
-            spec["config"] = config.clone();
+        view
+    }
+
+    /// Layer entry: body plus its own transforms and data, but no size
+    fn layer_json(&self, repeat: Option<&RepeatSpec>) -> Value {
+        let mut layer = self.body_json(repeat);
+
+        if !self.transform.is_empty() {
+            layer["transform"] = json!(self.transform);
         }
 
-        spec
+        let has_data = match &self.data {
+            DataSpec::Values { values } => !values.is_empty(),
+            DataSpec::Url { .. } => true,
+        };
+        if has_data {
+            layer["data"] = json!(self.data);
+        }
+
+        layer
+    }
+
+    fn body_json(&self, repeat: Option<&RepeatSpec>) -> Value {
+        let mut body = if self.layer.is_empty() {
+            let mut encoding = json!(self.encoding);
+            if let Some(repeat) = repeat {
+                if encoding[repeat.channel.as_str()].is_object() {
+                    encoding[repeat.channel.as_str()]["field"] = json!({ "repeat": "repeat" });
+                }
+            }
+            json!({ "mark": self.mark, "encoding": encoding })
+        } else {
+            json!({
+                "layer": self
+                    .layer
+                    .iter()
+                    .map(|layer| layer.layer_json(repeat))
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        if !self.params.is_empty() {
+            body["params"] = json!(self.params);
+        }
+
+        body
     }
 
     /// Convert to JSON string
@@ -374,4 +679,74 @@ mod tests {
         let json = bar_chart.to_json();
         assert_eq!(json["mark"], "bar");
     }
+
+    #[test]
+    fn test_layered_spec() {
+        let spec = VegaLiteSpec::new()
+            .dimensions(400, 300)
+            .data_values(vec![json!({"date": "2025-01-01", "value": 100})])
+            .layer(
+                ChartTemplates::line_chart()
+                    .x_axis("date", "temporal", None)
+                    .y_axis("value", "quantitative", None)
+                    .param(ParamSpec::zoom_x("zoom")),
+            )
+            .layer(
+                ChartTemplates::scatter_plot()
+                    .x_axis("date", "temporal", None)
+                    .y_axis("value", "quantitative", None)
+                    .transform(TransformSpec::filter_equal("is_pr", json!(true))),
+            );
+
+        let json = spec.to_json();
+        assert!(json.get("mark").is_none());
+        assert_eq!(json["width"], 400);
+        assert_eq!(json["data"]["values"].as_array().unwrap().len(), 1);
+
+        let layers = json["layer"].as_array().unwrap();
+        assert_eq!(layers.len(), 2);
+        assert_eq!(layers[0]["mark"], "line");
+        assert_eq!(layers[0]["params"][0]["bind"], "scales");
+        // Layers inherit data and size from the parent
+        assert!(layers[0].get("data").is_none());
+        assert!(layers[0].get("width").is_none());
+        assert_eq!(layers[1]["transform"][0]["filter"]["equal"], true);
+    }
+
+    #[test]
+    fn test_facet_repeat_and_transforms() {
+        let faceted = ChartTemplates::line_chart()
+            .x_axis("date", "temporal", None)
+            .y_axis("e1rm_kg", "quantitative", None)
+            .facet("exercise", "nominal", Some(3))
+            .transform(TransformSpec::rolling_mean(
+                "e1rm_kg",
+                "rolling",
+                [Some(-3), Some(0)],
+                &["exercise"],
+                "date",
+            ));
+        let json = faceted.to_json();
+        assert_eq!(json["facet"]["field"], "exercise");
+        assert_eq!(json["columns"], 3);
+        assert_eq!(json["spec"]["mark"], "line");
+        assert_eq!(json["spec"]["width"], 600);
+        assert_eq!(json["transform"][0]["window"][0]["op"], "mean");
+        assert_eq!(json["transform"][0]["frame"], json!([-3, 0]));
+
+        let repeated = ChartTemplates::bar_chart()
+            .x_axis("week", "ordinal", None)
+            .y_axis("sets", "quantitative", None)
+            .repeat(vec!["sets".to_string(), "reps".to_string()], "y", None);
+        let json = repeated.to_json();
+        assert_eq!(json["repeat"], json!(["sets", "reps"]));
+        assert_eq!(json["spec"]["encoding"]["y"]["field"]["repeat"], "repeat");
+        assert!(json.get("columns").is_none());
+
+        let parsed: TransformSpec = serde_json::from_value(
+            json!({"aggregate": [{"op": "max", "field": "x", "as": "m"}], "groupby": ["g"]}),
+        )
+        .unwrap();
+        assert!(matches!(parsed, TransformSpec::Aggregate { .. }));
+    }
 }
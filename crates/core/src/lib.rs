@@ -1,12 +1,21 @@
 pub mod attachments;
 pub mod charts;
 pub mod export;
+pub mod loads;
 pub mod location;
 pub mod models;
+pub mod overlays;
 pub mod paths;
+pub mod plan_edit;
+pub mod plan_store;
+pub mod report;
+pub mod scheme_templates;
 pub mod schemes;
 pub mod time;
+pub mod training_max;
+pub mod units;
 pub mod versioning;
+pub mod warmup;
 
 #[cfg(test)]
 mod location_test;
@@ -14,11 +23,19 @@ mod location_test;
 pub use attachments::*;
 pub use charts::*;
 pub use export::*;
+pub use loads::*;
 pub use models::*;
 pub use paths::*;
+pub use plan_edit::*;
+pub use plan_store::*;
+pub use report::*;
+pub use scheme_templates::*;
 pub use schemes::*;
 pub use time::*;
+pub use training_max::*;
+pub use units::*;
 pub use versioning::*;
+pub use warmup::*;
 
 /// The JSON schema for plan validation
 pub const PLAN_SCHEMA_V0_3: &str = include_str!("../schema/plan_v0_3.json");
@@ -0,0 +1,790 @@
+use crate::location::{LocationProfile, RoundingPreview};
+use crate::warmup::{WarmupError, WarmupGenerator, WarmupPattern};
+use crate::{
+    Day, IntensityParams, Plan, RepsOrRange, RpeOrRange, SchemeTemplate, Segment, Unit, LB_PER_KG,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+/// Training max in kg per exercise code, e.g. best recent e1RMs from the metrics cache
+pub type TrainingMaxes = BTreeMap<String, f64>;
+
+#[derive(Error, Debug)]
+pub enum LoadError {
+    #[error("Failed to resolve week overlays: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Day {0} not found in plan")]
+    DayNotFound(u32),
+    #[error("Warm-up config: {0}")]
+    Warmup(#[from] WarmupError),
+}
+
+/// How close an RPE set is to an all-out set, in the Epley-based model the indexer
+/// also uses to turn logged RPE sets into e1RMs
+pub fn rpe_adjustment(rpe: f64) -> f64 {
+    match rpe {
+        r if r >= 9.5 => 1.0,
+        r if r >= 9.0 => 0.97,
+        r if r >= 8.5 => 0.94,
+        r if r >= 8.0 => 0.91,
+        r if r >= 7.5 => 0.88,
+        _ => 0.85,
+    }
+}
+
+/// Fraction of 1RM that allows `reps` at `rpe` (an all-out set without one)
+pub fn estimated_pct_1rm(reps: u32, rpe: Option<f64>) -> f64 {
+    rpe.map_or(1.0, rpe_adjustment) / (1.0 + reps as f64 / 30.0)
+}
+
+/// Inverse of `estimated_pct_1rm`: the RPE `reps` at `load_kg` would take against a
+/// 1RM of `one_rm_kg`, from 7 (or easier) up to 10 (at or past an all-out set)
+pub fn estimated_rpe(load_kg: f64, reps: u32, one_rm_kg: f64) -> f64 {
+    let adjustment = load_kg * (1.0 + reps as f64 / 30.0) / one_rm_kg;
+    [
+        (0.85, 7.0),
+        (0.88, 7.5),
+        (0.91, 8.0),
+        (0.94, 8.5),
+        (0.97, 9.0),
+        (1.0, 9.5),
+    ]
+    .iter()
+    .find(|(limit, _)| adjustment <= *limit + 1e-9)
+    .map_or(10.0, |(_, rpe)| *rpe)
+}
+
+/// What a computed load was derived from
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LoadBasis {
+    /// Fraction of the training max of `of` (a complex's anchor lift, else the exercise)
+    Percent1Rm { pct_1rm: f64, of: String },
+    /// Estimated from the training max for an RPE (or 10 - RIR) target
+    Rpe { rpe: f64, reps: u32 },
+    /// Multiple of an earlier set's load in the same segment
+    Anchor { of_set_index: u32, multiplier: f64 },
+    /// Fraction taken off the previous set's load
+    Drop { percent_drop: f64 },
+    /// Load prescribed outright
+    Fixed,
+}
+
+/// Which rounding applies to an exercise
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoadEquipment {
+    Barbell,
+    Dumbbell,
+    /// Machines, cables and anything else: loads are not rounded
+    Other,
+}
+
+impl LoadEquipment {
+    /// Round `kg` to what the location can make with this equipment (None for `Other`)
+    pub fn round(self, location: &LocationProfile, kg: f64) -> Option<RoundingPreview> {
+        match self {
+            LoadEquipment::Barbell => Some(location.round_barbell_load(kg)),
+            LoadEquipment::Dumbbell => Some(location.round_dumbbell_load(kg)),
+            LoadEquipment::Other => None,
+        }
+    }
+}
+
+/// Target load for one prescription entry of a segment
+#[derive(Debug, Clone, Serialize)]
+pub struct SetLoad {
+    /// Index of the entry within its segment, as used by `anchor.of_set_index`
+    pub set_index: u32,
+    pub ex: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub sets: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reps: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub basis: Option<LoadBasis>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_kg: Option<f64>,
+    /// Target rounded to the location's equipment, with the plate breakdown for barbells
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rounded: Option<RoundingPreview>,
+    /// Why no load could be computed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Generated from the plan's `warmup` config; `set_index` is the set it leads into
+    pub is_warmup: bool,
+}
+
+impl SetLoad {
+    /// The load to put on the bar: rounded when a location is known
+    pub fn load_kg(&self) -> Option<f64> {
+        match &self.rounded {
+            Some(rounded) => Some(rounded.rounded_load),
+            None => self.target_kg,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SegmentLoads {
+    pub segment_idx: usize,
+    pub sets: Vec<SetLoad>,
+}
+
+/// Loads for one day of the plan as prescribed in one week
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionLoads {
+    pub week: u32,
+    pub day: u32,
+    pub label: String,
+    pub segments: Vec<SegmentLoads>,
+}
+
+/// How an entry's load is specified before resolution
+#[derive(Debug, Clone)]
+enum LoadSpec {
+    Percent(f64, String),
+    Rpe(f64),
+    Anchor(u32, f64),
+    Drop(f64),
+    Fixed(f64),
+    Unknown(String),
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    ex: String,
+    label: Option<String>,
+    sets: u32,
+    reps: Option<u32>,
+    spec: LoadSpec,
+}
+
+/// Resolves relative prescriptions (%1RM, anchors, drops, complex anchor loads and
+/// RPE targets) into kilograms for each set of a session.
+/// Straight, RPE, percentage, scheme and complex segments carry loads; other
+/// segment types are skipped.
+pub struct LoadCalculator<'a> {
+    plan: &'a Plan,
+    maxes: &'a TrainingMaxes,
+    location: Option<&'a LocationProfile>,
+}
+
+impl<'a> LoadCalculator<'a> {
+    pub fn new(plan: &'a Plan, maxes: &'a TrainingMaxes) -> Self {
+        Self {
+            plan,
+            maxes,
+            location: None,
+        }
+    }
+
+    /// Round every target to what this location's bars, plates and dumbbells can make
+    pub fn location(mut self, location: &'a LocationProfile) -> Self {
+        self.location = Some(location);
+        self
+    }
+
+    /// Loads for day number `day` in 1-based `week`, after week overlays
+    pub fn session(&self, week: u32, day: u32) -> Result<SessionLoads, LoadError> {
+        let days = self.plan.schedule_for_week(week)?;
+        let day = days
+            .iter()
+            .find(|d| d.day == day)
+            .ok_or(LoadError::DayNotFound(day))?;
+        self.day_loads(week, day)
+    }
+
+    /// Loads for every day in 1-based `week`
+    pub fn week(&self, week: u32) -> Result<Vec<SessionLoads>, LoadError> {
+        self.plan
+            .schedule_for_week(week)?
+            .iter()
+            .map(|day| self.day_loads(week, day))
+            .collect()
+    }
+
+    /// Loads for a day that is already resolved for `week`. With a plan `warmup`
+    /// config, the first segment of each exercise starts with its warm-up ladder.
+    pub fn day_loads(&self, week: u32, day: &Day) -> Result<SessionLoads, LoadError> {
+        let generator = match &self.plan.warmup {
+            Some(config) => Some(WarmupGenerator::new(config)?.unit(&self.plan.unit)),
+            None => None,
+        };
+        let mut warmed_up: Vec<String> = Vec::new();
+        let mut segments = Vec::new();
+        for (segment_idx, segment) in day.segments.iter().enumerate() {
+            let mut sets = self.segment_loads(segment);
+            if sets.is_empty() {
+                continue;
+            }
+            // A lift counts as warmed up once a segment of it produced a ladder
+            if let Some(generator) = generator
+                .as_ref()
+                .filter(|_| !warmed_up.contains(&sets[0].ex))
+            {
+                let warmups = self.warmups(generator, &sets);
+                if !warmups.is_empty() {
+                    warmed_up.push(sets[0].ex.clone());
+                    sets.splice(0..0, warmups);
+                }
+            }
+            segments.push(SegmentLoads { segment_idx, sets });
+        }
+
+        Ok(SessionLoads {
+            week,
+            day: day.day,
+            label: day.label.clone(),
+            segments,
+        })
+    }
+
+    /// Warm-up sets leading into the heaviest loaded set of the exercise's first segment
+    fn warmups(&self, generator: &WarmupGenerator<'a>, sets: &[SetLoad]) -> Vec<SetLoad> {
+        let Some((top, working_kg)) = sets
+            .iter()
+            .filter(|set| set.ex == sets[0].ex)
+            .filter_map(|set| set.load_kg().map(|kg| (set, kg)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+        else {
+            return Vec::new();
+        };
+        let mut generator = generator.clone();
+        if let Some(location) = self.location {
+            generator = generator.location(location, self.equipment(&top.ex));
+        }
+
+        let warmup = |reps: Option<u32>, basis, target_kg, rounded, note| SetLoad {
+            set_index: top.set_index,
+            ex: top.ex.clone(),
+            label: Some("Warm-up".to_string()),
+            sets: 1,
+            reps,
+            basis,
+            target_kg,
+            rounded,
+            note,
+            is_warmup: true,
+        };
+        match generator.sets(working_kg, self.maxes.get(&top.ex).copied()) {
+            Ok(ladder) => ladder
+                .into_iter()
+                .map(|set| {
+                    let basis = match generator.pattern() {
+                        WarmupPattern::PercentOfTop => LoadBasis::Anchor {
+                            of_set_index: top.set_index,
+                            multiplier: set.pct,
+                        },
+                        WarmupPattern::PercentOf1Rm => LoadBasis::Percent1Rm {
+                            pct_1rm: set.pct,
+                            of: top.ex.clone(),
+                        },
+                    };
+                    warmup(
+                        Some(set.reps),
+                        Some(basis),
+                        Some(set.target_kg),
+                        set.rounded,
+                        None,
+                    )
+                })
+                .collect(),
+            Err(e) => vec![warmup(None, None, None, None, Some(e.to_string()))],
+        }
+    }
+
+    /// Loads for each prescription entry of a segment, in order
+    pub fn segment_loads(&self, segment: &Segment) -> Vec<SetLoad> {
+        let mut loads: Vec<SetLoad> = Vec::new();
+        for (index, entry) in entries(segment, &self.plan.unit).into_iter().enumerate() {
+            let earlier = |i: usize| loads.get(i).and_then(SetLoad::load_kg);
+            let resolved = match &entry.spec {
+                LoadSpec::Percent(pct, of) => match self.maxes.get(of) {
+                    Some(max) => Ok((
+                        max * pct,
+                        LoadBasis::Percent1Rm {
+                            pct_1rm: *pct,
+                            of: of.clone(),
+                        },
+                    )),
+                    None => Err(format!("no training max for {}", of)),
+                },
+                LoadSpec::Rpe(rpe) => match (self.maxes.get(&entry.ex), entry.reps) {
+                    (Some(max), Some(reps)) => Ok((
+                        max * estimated_pct_1rm(reps, Some(*rpe)),
+                        LoadBasis::Rpe { rpe: *rpe, reps },
+                    )),
+                    (None, _) => Err(format!("no training max for {}", entry.ex)),
+                    (_, None) => Err("RPE target without reps".to_string()),
+                },
+                LoadSpec::Anchor(of_set_index, multiplier) => {
+                    match earlier(*of_set_index as usize)
+                        .filter(|_| (*of_set_index as usize) < index)
+                    {
+                        Some(load) => Ok((
+                            load * multiplier,
+                            LoadBasis::Anchor {
+                                of_set_index: *of_set_index,
+                                multiplier: *multiplier,
+                            },
+                        )),
+                        None => Err(format!("set {} has no load to anchor to", of_set_index)),
+                    }
+                }
+                LoadSpec::Drop(percent_drop) => match index.checked_sub(1).and_then(earlier) {
+                    Some(load) => Ok((
+                        load * (1.0 - percent_drop),
+                        LoadBasis::Drop {
+                            percent_drop: *percent_drop,
+                        },
+                    )),
+                    None => Err("previous set has no load to drop from".to_string()),
+                },
+                LoadSpec::Fixed(kg) => Ok((*kg, LoadBasis::Fixed)),
+                LoadSpec::Unknown(note) => Err(note.clone()),
+            };
+
+            let (target_kg, basis, note) = match resolved {
+                Ok((kg, basis)) => (Some(kg), Some(basis), None),
+                Err(note) => (None, None, Some(note)),
+            };
+            let rounded = match (self.location, target_kg) {
+                (Some(location), Some(kg)) => self.equipment(&entry.ex).round(location, kg),
+                _ => None,
+            };
+
+            loads.push(SetLoad {
+                set_index: index as u32,
+                ex: entry.ex,
+                label: entry.label,
+                sets: entry.sets,
+                reps: entry.reps,
+                basis,
+                target_kg,
+                rounded,
+                note,
+                is_warmup: false,
+            });
+        }
+        loads
+    }
+
+    /// Equipment from `exercise_meta`, else guessed from the exercise code (`SQ.BB.BACK`)
+    pub fn equipment(&self, ex: &str) -> LoadEquipment {
+        let listed = self
+            .plan
+            .exercise_meta
+            .as_ref()
+            .and_then(|meta| meta.get(ex))
+            .and_then(|meta| meta.equipment.as_ref());
+        let has = |names: &[&str]| match listed {
+            Some(equipment) => equipment
+                .iter()
+                .any(|e| names.contains(&e.to_ascii_lowercase().as_str())),
+            None => ex.split('.').any(|part| names[1..].contains(&part)),
+        };
+        if has(&["barbell", "BB"]) {
+            LoadEquipment::Barbell
+        } else if has(&["dumbbell", "DB"]) {
+            LoadEquipment::Dumbbell
+        } else {
+            LoadEquipment::Other
+        }
+    }
+}
+
+/// Flattens a segment into the entries its anchors index
+fn entries(segment: &Segment, unit: &Unit) -> Vec<Entry> {
+    let entry = |ex: &str, label: &Option<String>, sets: u32, reps: Option<u32>, spec| Entry {
+        ex: ex.to_string(),
+        label: label.clone(),
+        sets,
+        reps,
+        spec,
+    };
+    let rpe_spec = |rpe: Option<f64>, rir: Option<f64>| match rpe.or(rir.map(|rir| 10.0 - rir)) {
+        Some(rpe) => LoadSpec::Rpe(rpe),
+        None => LoadSpec::Unknown("no intensity prescribed".to_string()),
+    };
+
+    match segment {
+        Segment::Straight(s) => vec![entry(
+            &s.base.ex,
+            &s.base.label,
+            s.sets
+                .or_else(|| s.sets_range.as_ref().map(|r| r.min))
+                .unwrap_or(1),
+            rep_count(&s.reps),
+            rpe_spec(s.rpe, s.rir),
+        )],
+        // Sets after the anchored set follow it; the ones up to it are worked up by RPE
+        Segment::Rpe(s) => (0..s.sets)
+            .map(|i| {
+                let spec = match &s.anchor {
+                    Some(anchor) if i > anchor.of_set_index => {
+                        LoadSpec::Anchor(anchor.of_set_index, anchor.multiplier)
+                    }
+                    _ => LoadSpec::Rpe(s.rpe),
+                };
+                entry(&s.base.ex, &s.base.label, 1, rep_count(&s.reps), spec)
+            })
+            .collect(),
+        Segment::Percentage(s) => s
+            .prescriptions
+            .iter()
+            .map(|p| {
+                let spec = LoadSpec::Percent(p.pct_1rm, s.base.ex.clone());
+                entry(&s.base.ex, &s.base.label, p.sets, Some(p.reps), spec)
+            })
+            .collect(),
+        Segment::Scheme(s) => match &s.template {
+            Some(template) => template_entries(&s.base.ex, template, unit),
+            None => s
+                .sets
+                .iter()
+                .map(|set| {
+                    let spec = match (&set.anchor, &set.rpe) {
+                        (Some(anchor), _) => {
+                            LoadSpec::Anchor(anchor.of_set_index, anchor.multiplier)
+                        }
+                        (None, Some(RpeOrRange::Fixed(rpe))) => LoadSpec::Rpe(*rpe),
+                        (None, Some(RpeOrRange::Range(r))) => LoadSpec::Rpe((r.min + r.max) / 2.0),
+                        (None, None) => LoadSpec::Unknown("no intensity prescribed".to_string()),
+                    };
+                    let label = set.label.clone().or_else(|| s.base.label.clone());
+                    entry(
+                        &s.base.ex,
+                        &label,
+                        set.sets.unwrap_or(1),
+                        rep_count(&set.reps),
+                        spec,
+                    )
+                })
+                .collect(),
+        },
+        Segment::Complex(s) => {
+            let Some(first) = s.sequence.first() else {
+                return Vec::new();
+            };
+            let anchor = &s.anchor_load;
+            let spec = match (anchor.mode.as_str(), anchor.pct, anchor.kg) {
+                ("pct_1rm", Some(pct), _) => {
+                    LoadSpec::Percent(pct, anchor.ex.clone().unwrap_or_else(|| first.ex.clone()))
+                }
+                ("fixed_kg", _, Some(kg)) => LoadSpec::Fixed(kg),
+                _ => LoadSpec::Unknown(format!("unsupported anchor_load mode {}", anchor.mode)),
+            };
+            let label = s
+                .sequence
+                .iter()
+                .map(|item| item.ex.as_str())
+                .collect::<Vec<_>>()
+                .join(" + ");
+            vec![entry(&first.ex, &Some(label), s.sets, None, spec)]
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Entries of an expanded template. Backoffs from an RPE top set anchor to it and
+/// drop sets chain off their starting set, which may be %1RM or RPE here even though
+/// `expand` needs a fixed starting load.
+fn template_entries(ex: &str, template: &SchemeTemplate, unit: &Unit) -> Vec<Entry> {
+    let spec = |intensity: &IntensityParams| match intensity {
+        IntensityParams::Percent1Rm { percent_1rm } => {
+            LoadSpec::Percent(*percent_1rm, ex.to_string())
+        }
+        IntensityParams::Load { load } => LoadSpec::Fixed(match unit {
+            Unit::Lb => load / LB_PER_KG,
+            Unit::Kg | Unit::Bw => *load,
+        }),
+        IntensityParams::Rpe { rpe } => LoadSpec::Rpe(*rpe),
+        IntensityParams::Rir { rir } => LoadSpec::Rpe(10.0 - rir),
+    };
+
+    let expanded = match template {
+        SchemeTemplate::DropSet(params) => {
+            let mut params = params.clone();
+            params.start.intensity = IntensityParams::Load { load: 1.0 };
+            SchemeTemplate::DropSet(params).expand(ex)
+        }
+        other => other.expand(ex),
+    };
+    let expanded = match expanded {
+        Ok(expanded) => expanded,
+        Err(e) => {
+            return vec![Entry {
+                ex: ex.to_string(),
+                label: None,
+                sets: 1,
+                reps: None,
+                spec: LoadSpec::Unknown(e),
+            }]
+        }
+    };
+
+    expanded
+        .sets
+        .iter()
+        .zip(&expanded.intensities)
+        .enumerate()
+        .map(|(i, (set, intensity))| {
+            let spec = match template {
+                SchemeTemplate::DropSet(params) if i == 0 => spec(&params.start.intensity),
+                SchemeTemplate::DropSet(params) => LoadSpec::Drop(params.drops[i - 1].percent_drop),
+                SchemeTemplate::TopBackoff(params)
+                    if i > 0
+                        && matches!(
+                            intensity,
+                            IntensityParams::Rpe { .. } | IntensityParams::Rir { .. }
+                        ) =>
+                {
+                    LoadSpec::Anchor(0, params.backoff[i - 1].percent)
+                }
+                _ => spec(intensity),
+            };
+            Entry {
+                ex: ex.to_string(),
+                label: set.base.label.clone(),
+                sets: set.sets.unwrap_or(1),
+                reps: rep_count(&set.reps),
+                spec,
+            }
+        })
+        .collect()
+}
+
+fn rep_count(reps: &Option<RepsOrRange>) -> Option<u32> {
+    reps.as_ref()
+        .map(|RepsOrRange::Range(r)| r.target.unwrap_or((r.min + r.max) / 2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn plan() -> Plan {
+        serde_json::from_value(json!({
+            "name": "Loads",
+            "unit": "kg",
+            "dictionary": {},
+            "groups": {},
+            "exercise_meta": { "ROW.CABLE": { "equipment": ["machine"] } },
+            "schedule": [{
+                "day": 1,
+                "label": "Lower",
+                "segments": [
+                    {
+                        "type": "percentage", "ex": "SQ.BB.BACK",
+                        "prescriptions": [{ "sets": 3, "reps": 5, "pct_1rm": 0.75 }],
+                        "per_week": { "2": { "prescriptions": [{ "sets": 3, "reps": 3, "pct_1rm": 0.8 }] } }
+                    },
+                    {
+                        "type": "scheme", "ex": "BP.BB.FLAT",
+                        "sets": [
+                            { "label": "Top", "reps": { "min": 1, "max": 1 }, "rpe": 9.5 },
+                            {
+                                "label": "Back-offs", "sets": 3, "reps": { "min": 5, "max": 5 },
+                                "anchor": { "of_set_index": 0, "multiplier": 0.8 }
+                            }
+                        ]
+                    },
+                    { "type": "comment", "text": "Accessories" },
+                    {
+                        "type": "scheme", "ex": "CURL.DB", "sets": [],
+                        "template": {
+                            "name": "drop_set",
+                            "start": { "reps": 10, "intensity": { "percent_1rm": 0.5 } },
+                            "drops": [{ "percent_drop": 0.2, "reps": 8, "rest_sec": 0 }]
+                        }
+                    },
+                    {
+                        "type": "straight", "ex": "ROW.CABLE", "sets": 3,
+                        "reps": { "min": 10, "max": 10 }, "rir": 2.0
+                    },
+                    {
+                        "type": "complex", "sets": 3, "rest_sec": 120,
+                        "anchor_load": { "mode": "pct_1rm", "ex": "CJ.BB", "pct": 0.7 },
+                        "sequence": [
+                            { "ex": "CLEAN.BB", "reps": { "min": 1, "max": 1 } },
+                            { "ex": "JERK.BB", "reps": { "min": 1, "max": 1 } }
+                        ]
+                    }
+                ]
+            }]
+        }))
+        .unwrap()
+    }
+
+    fn maxes() -> TrainingMaxes {
+        [
+            ("SQ.BB.BACK", 180.0),
+            ("BP.BB.FLAT", 120.0),
+            ("CURL.DB", 30.0),
+            ("ROW.CABLE", 100.0),
+        ]
+        .into_iter()
+        .map(|(ex, kg)| (ex.to_string(), kg))
+        .collect()
+    }
+
+    #[test]
+    fn test_relative_loads_resolve_to_kg() {
+        let plan = plan();
+        let maxes = maxes();
+        let session = LoadCalculator::new(&plan, &maxes).session(1, 1).unwrap();
+        let segment = |idx: usize| {
+            &session
+                .segments
+                .iter()
+                .find(|s| s.segment_idx == idx)
+                .unwrap()
+                .sets
+        };
+        assert_eq!(session.segments.len(), 5);
+
+        let squat = &segment(0)[0];
+        assert_eq!(
+            (squat.sets, squat.reps, squat.target_kg),
+            (3, Some(5), Some(135.0))
+        );
+
+        // RPE 9.5 single at ~96.8% of max, back-offs anchored at 80% of it
+        let bench = segment(1);
+        let top = bench[0].target_kg.unwrap();
+        assert!((top - 120.0 / (1.0 + 1.0 / 30.0)).abs() < 1e-9);
+        assert!((bench[1].target_kg.unwrap() - top * 0.8).abs() < 1e-9);
+        assert_eq!(
+            bench[1].basis,
+            Some(LoadBasis::Anchor {
+                of_set_index: 0,
+                multiplier: 0.8
+            })
+        );
+
+        let curls = segment(3);
+        assert_eq!(curls[0].target_kg, Some(15.0));
+        assert_eq!(curls[1].target_kg, Some(12.0));
+        assert_eq!(curls[1].basis, Some(LoadBasis::Drop { percent_drop: 0.2 }));
+
+        let row = &segment(4)[0];
+        assert!((row.target_kg.unwrap() - 100.0 * 0.91 / (4.0 / 3.0)).abs() < 1e-9);
+
+        // The complex's anchor lift has no training max
+        let complex = &segment(5)[0];
+        assert_eq!(complex.target_kg, None);
+        assert_eq!(complex.note.as_deref(), Some("no training max for CJ.BB"));
+        assert_eq!(complex.label.as_deref(), Some("CLEAN.BB + JERK.BB"));
+
+        let week2 = LoadCalculator::new(&plan, &maxes).session(2, 1).unwrap();
+        assert_eq!(week2.segments[0].sets[0].target_kg, Some(144.0));
+        assert!(matches!(
+            LoadCalculator::new(&plan, &maxes).session(1, 7),
+            Err(LoadError::DayNotFound(7))
+        ));
+    }
+
+    #[test]
+    fn test_location_rounding_and_anchors_use_rounded_loads() {
+        let plan = plan();
+        let maxes = maxes();
+        let gym = LocationProfile::home_gym();
+        let calculator = LoadCalculator::new(&plan, &maxes).location(&gym);
+        assert_eq!(calculator.equipment("SQ.BB.BACK"), LoadEquipment::Barbell);
+        assert_eq!(calculator.equipment("CURL.DB"), LoadEquipment::Dumbbell);
+        assert_eq!(calculator.equipment("ROW.CABLE"), LoadEquipment::Other);
+
+        let session = calculator.session(1, 1).unwrap();
+        let squat = &session.segments[0].sets[0];
+        let plates = squat
+            .rounded
+            .as_ref()
+            .unwrap()
+            .plate_solution
+            .as_ref()
+            .unwrap();
+        assert_eq!(plates.bar_weight, 20.0);
+        assert_eq!(squat.load_kg(), Some(135.0));
+
+        // Back-offs are 80% of the top single as it will actually be loaded
+        let bench = &session.segments[1].sets;
+        let top = bench[0].load_kg().unwrap();
+        assert!((bench[1].target_kg.unwrap() - top * 0.8).abs() < 1e-9);
+
+        let curls = &session.segments[2].sets;
+        assert_eq!(curls[0].rounded.as_ref().unwrap().equipment, "db");
+        assert!(session.segments[3].sets[0].rounded.is_none());
+    }
+
+    #[test]
+    fn test_warmups_lead_into_first_segment_of_each_lift() {
+        let mut plan = plan();
+        plan.warmup = Some(
+            serde_json::from_value(json!({
+                "pattern": "percent_of_top",
+                "stages": [
+                    { "pct_1rm": 0.3, "reps": 8 },
+                    { "pct_1rm": 0.5, "reps": 5 },
+                    { "pct_1rm": 0.7, "reps": 3 }
+                ],
+                "round_to": 2.5,
+                "merge_after_rounding": true
+            }))
+            .unwrap(),
+        );
+        let repeat = plan.schedule[0].segments[0].clone();
+        plan.schedule[0].segments.push(repeat);
+        let maxes = maxes();
+        let gym = LocationProfile::home_gym();
+        let session = LoadCalculator::new(&plan, &maxes)
+            .location(&gym)
+            .session(1, 1)
+            .unwrap();
+
+        let squat = &session.segments[0].sets;
+        let ladder: Vec<(Option<f64>, Option<u32>)> = squat
+            .iter()
+            .take_while(|set| set.is_warmup)
+            .map(|set| (set.load_kg(), set.reps))
+            .collect();
+        assert_eq!(
+            ladder,
+            [
+                (Some(40.0), Some(8)),
+                (Some(67.5), Some(5)),
+                (Some(95.0), Some(3))
+            ]
+        );
+        assert!(squat[0].rounded.as_ref().unwrap().plate_solution.is_some());
+        assert_eq!(
+            squat[0].basis,
+            Some(LoadBasis::Anchor {
+                of_set_index: 0,
+                multiplier: 0.3
+            })
+        );
+        assert!(!squat[3].is_warmup && squat[3].load_kg() == Some(135.0));
+
+        // Already warm for the second squat segment; no load, no warm-ups for the complex
+        let last = session.segments.last().unwrap();
+        assert_eq!(last.segment_idx, 6);
+        assert!(last.sets.iter().all(|set| !set.is_warmup));
+        let complex = session
+            .segments
+            .iter()
+            .find(|s| s.segment_idx == 5)
+            .unwrap();
+        assert_eq!(complex.sets.len(), 1);
+    }
+
+    #[test]
+    fn test_estimated_pct_1rm() {
+        assert_eq!(estimated_pct_1rm(0, None), 1.0);
+        assert!((estimated_pct_1rm(5, Some(8.0)) - 0.91 / (7.0 / 6.0)).abs() < 1e-12);
+        assert!(estimated_pct_1rm(10, Some(7.0)) < estimated_pct_1rm(5, Some(7.0)));
+    }
+}
@@ -29,7 +29,7 @@ pub enum RoundingStrategy {
 }
 
 /// Result of rounding preview - never mutates source
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct RoundingPreview {
     pub original_load: f64,
     pub rounded_load: f64,
@@ -38,7 +38,7 @@ pub struct RoundingPreview {
     pub plate_solution: Option<PlateSolution>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct PlateSolution {
     pub bar_weight: f64,
     pub plates_per_side: Vec<(f64, u32)>, // (plate_weight, count_per_side)
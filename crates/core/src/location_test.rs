@@ -1,6 +1,5 @@
 //! **Death to Windows!** - Rounding preview unit tests
 
-use super::*;
 use crate::location::{LocationProfile, RoundingPreview, RoundingStrategy};
 
 #[test]
@@ -17,8 +17,7 @@ pub struct Plan {
     pub groups: HashMap<String, Vec<String>>,
     /// v0.4: per-group, per-role, per-exercise overrides
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub group_variants:
-        Option<HashMap<String, HashMap<String, HashMap<String, Value>>>>,
+    pub group_variants: Option<GroupVariants>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub exercise_meta: Option<HashMap<String, ExerciseMeta>>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -34,6 +33,9 @@ pub struct Plan {
     pub schedule: Vec<Day>,
 }
 
+/// v0.4: group -> role -> exercise -> override fields
+pub type GroupVariants = HashMap<String, HashMap<String, HashMap<String, Value>>>;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Unit {
@@ -187,6 +189,243 @@ pub enum Segment {
     Time(TimeSegment),
 }
 
+/// An exercise prescribed by a segment, as flattened by [`Segment::prescriptions`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Prescription {
+    pub ex: String,
+    pub alt_group: Option<String>,
+    /// One entry per set; empty when the segment prescribes no sets
+    pub sets: Vec<PrescribedSet>,
+    /// From an optional segment, or an item of a choose, rotate or optional group
+    pub optional: bool,
+}
+
+/// Target for a single prescribed set
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PrescribedSet {
+    /// (min, max); None for timed or open sets
+    pub reps: Option<(u32, u32)>,
+    pub rpe: Option<f64>,
+    pub pct_1rm: Option<f64>,
+    pub work_sec: Option<f64>,
+    /// Rest after the set; superset and circuit round rest is spread across the items
+    pub rest_sec: Option<f64>,
+}
+
+impl Segment {
+    /// Flatten the segment into the exercises it prescribes, in order
+    ///
+    /// Groups contribute the prescriptions of every item. Items of choose, rotate and
+    /// optional groups are marked optional since none of them is required on its own.
+    pub fn prescriptions(&self) -> Vec<Prescription> {
+        let prescription = |ex: &str,
+                            alt_group: &Option<String>,
+                            optional: Option<bool>,
+                            sets: Vec<PrescribedSet>| Prescription {
+            ex: ex.to_string(),
+            alt_group: alt_group.clone(),
+            sets,
+            optional: optional.unwrap_or(false),
+        };
+        let repeat = |n: u32, set: PrescribedSet| vec![set; n as usize];
+        let reps_of =
+            |reps: &Option<RepsOrRange>| reps.as_ref().map(|RepsOrRange::Range(r)| (r.min, r.max));
+        let optional = |items: &[Segment]| {
+            items
+                .iter()
+                .flat_map(Segment::prescriptions)
+                .map(|p| Prescription {
+                    optional: true,
+                    ..p
+                })
+                .collect()
+        };
+
+        match self {
+            Segment::Straight(s) => {
+                let sets = s
+                    .sets
+                    .or_else(|| s.sets_range.as_ref().map(|r| r.min))
+                    .unwrap_or(1);
+                vec![prescription(
+                    &s.base.ex,
+                    &s.base.alt_group,
+                    s.base.optional,
+                    repeat(
+                        sets,
+                        PrescribedSet {
+                            reps: reps_of(&s.reps),
+                            rpe: s.rpe.or_else(|| s.rir.map(|rir| 10.0 - rir)),
+                            work_sec: time_sec(&s.time_sec),
+                            rest_sec: rest_sec(&s.rest_sec),
+                            ..Default::default()
+                        },
+                    ),
+                )]
+            }
+            Segment::Rpe(s) => vec![prescription(
+                &s.base.ex,
+                &s.base.alt_group,
+                s.base.optional,
+                repeat(
+                    s.sets,
+                    PrescribedSet {
+                        reps: reps_of(&s.reps),
+                        rpe: Some(s.rpe),
+                        work_sec: time_sec(&s.time_sec),
+                        rest_sec: rest_sec(&s.rest_sec),
+                        ..Default::default()
+                    },
+                ),
+            )],
+            Segment::Percentage(s) => vec![prescription(
+                &s.base.ex,
+                &s.base.alt_group,
+                s.base.optional,
+                s.prescriptions
+                    .iter()
+                    .flat_map(|p| {
+                        repeat(
+                            p.sets,
+                            PrescribedSet {
+                                reps: Some((p.reps, p.reps)),
+                                pct_1rm: Some(p.pct_1rm),
+                                ..Default::default()
+                            },
+                        )
+                    })
+                    .collect(),
+            )],
+            Segment::Amrap(s) => vec![prescription(
+                &s.base.ex,
+                &s.base.alt_group,
+                s.base.optional,
+                vec![PrescribedSet {
+                    reps: Some((s.base_reps, s.cap_reps)),
+                    ..Default::default()
+                }],
+            )],
+            Segment::Scheme(s) => vec![prescription(
+                &s.base.ex,
+                &s.base.alt_group,
+                s.base.optional,
+                s.sets
+                    .iter()
+                    .flat_map(|scheme_set| {
+                        repeat(
+                            scheme_set.sets.unwrap_or(1),
+                            PrescribedSet {
+                                reps: reps_of(&scheme_set.reps),
+                                rpe: scheme_set.rpe.as_ref().map(|rpe| match rpe {
+                                    RpeOrRange::Fixed(v) => *v,
+                                    RpeOrRange::Range(r) => (r.min + r.max) / 2.0,
+                                }),
+                                work_sec: time_sec(&scheme_set.time_sec),
+                                rest_sec: rest_sec(&scheme_set.rest_sec),
+                                ..Default::default()
+                            },
+                        )
+                    })
+                    .collect(),
+            )],
+            Segment::Time(s) => {
+                let set = PrescribedSet {
+                    rpe: s.rpe,
+                    ..Default::default()
+                };
+                let sets = match &s.interval {
+                    Some(interval) => repeat(
+                        interval.repeats,
+                        PrescribedSet {
+                            work_sec: Some(interval.work_sec as f64),
+                            rest_sec: Some(interval.rest_sec as f64),
+                            ..set
+                        },
+                    ),
+                    None => vec![set],
+                };
+                vec![prescription(
+                    &s.base.ex,
+                    &s.base.alt_group,
+                    s.base.optional,
+                    sets,
+                )]
+            }
+            Segment::Superset(s) => {
+                let rest = s.rest_sec as f64
+                    + s.rest_between_rounds_sec as f64 / s.items.len().max(1) as f64;
+                s.items
+                    .iter()
+                    .map(|item| {
+                        let set = PrescribedSet {
+                            reps: reps_of(&item.reps),
+                            rpe: item.rpe,
+                            work_sec: time_sec(&item.time_sec),
+                            rest_sec: Some(rest),
+                            ..Default::default()
+                        };
+                        prescription(&item.ex, &item.alt_group, None, repeat(item.sets, set))
+                    })
+                    .collect()
+            }
+            Segment::Circuit(s) => {
+                let rest = s.rest_sec as f64
+                    + s.rest_between_rounds_sec as f64 / s.items.len().max(1) as f64;
+                s.items
+                    .iter()
+                    .map(|item| {
+                        let set = PrescribedSet {
+                            reps: reps_of(&item.reps),
+                            work_sec: time_sec(&item.time_sec),
+                            rest_sec: Some(rest),
+                            ..Default::default()
+                        };
+                        prescription(&item.ex, &item.alt_group, None, repeat(s.rounds, set))
+                    })
+                    .collect()
+            }
+            Segment::Complex(s) => {
+                let pct = (s.anchor_load.mode == "pct_1rm")
+                    .then_some(s.anchor_load.pct)
+                    .flatten();
+                let rest = s.rest_sec as f64 / s.sequence.len().max(1) as f64;
+                s.sequence
+                    .iter()
+                    .map(|item| {
+                        let RepsOrRange::Range(r) = &item.reps;
+                        let set = PrescribedSet {
+                            reps: Some((r.min, r.max)),
+                            pct_1rm: pct,
+                            rest_sec: Some(rest),
+                            ..Default::default()
+                        };
+                        prescription(&item.ex, &item.alt_group, None, repeat(s.sets, set))
+                    })
+                    .collect()
+            }
+            Segment::GroupSuperset(s) => s.items.iter().flat_map(Segment::prescriptions).collect(),
+            Segment::GroupChoose(s) => optional(&s.from),
+            Segment::GroupRotate(s) => optional(&s.items),
+            Segment::GroupOptional(s) => optional(&s.items),
+            Segment::Comment(_) => Vec::new(),
+        }
+    }
+}
+
+fn time_sec(time: &Option<TimeOrRange>) -> Option<f64> {
+    time.as_ref().map(|t| match t {
+        TimeOrRange::Fixed(sec) => *sec as f64,
+        TimeOrRange::Range(r) => r.target.unwrap_or((r.min + r.max) / 2) as f64,
+    })
+}
+
+fn rest_sec(rest: &Option<RestOrRange>) -> Option<f64> {
+    rest.as_ref().map(|r| match r {
+        RestOrRange::Fixed(sec) => *sec as f64,
+        RestOrRange::Range(r) => (r.min + r.max) as f64 / 2.0,
+    })
+}
+
 /// Common fields for executable segments
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BaseSegment {
@@ -0,0 +1,211 @@
+use crate::models::{Day, Plan, Segment};
+use serde_json::{Map, Value};
+
+/// Fields holding nested segments or items that may carry their own `per_week` overlays
+const NESTED_FIELDS: [&str; 3] = ["items", "from", "sequence"];
+
+impl Plan {
+    /// Number of weeks in the block, taken from `phase.weeks`, `week_overrides` keys and
+    /// every `per_week` key in the schedule (at least 1)
+    pub fn block_weeks(&self) -> u32 {
+        let mut weeks = 1;
+
+        if let Some(phase) = &self.phase {
+            weeks = weeks.max(phase.weeks.iter().copied().max().unwrap_or(0));
+        }
+        if let Some(overrides) = &self.week_overrides {
+            weeks = weeks.max(week_keys(overrides.keys()));
+        }
+        for day in &self.schedule {
+            for segment in &day.segments {
+                if let Ok(value) = serde_json::to_value(segment) {
+                    weeks = weeks.max(max_overlay_week(&value));
+                }
+            }
+        }
+
+        weeks
+    }
+
+    /// The schedule as prescribed for a 1-based `week`: `per_week` overlays are merged
+    /// shallowly into their segments (arrays replace) and `week_overrides` RPE caps applied.
+    pub fn schedule_for_week(&self, week: u32) -> Result<Vec<Day>, serde_json::Error> {
+        let key = week.to_string();
+        let overrides = self
+            .week_overrides
+            .as_ref()
+            .and_then(|o| o.get(&key))
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        let mut days = Vec::with_capacity(self.schedule.len());
+        for day in &self.schedule {
+            let mut segments = Vec::with_capacity(day.segments.len());
+            for (idx, segment) in day.segments.iter().enumerate() {
+                let mut value = serde_json::to_value(segment)?;
+                apply_overlay(&mut value, &key);
+
+                for over in overrides
+                    .iter()
+                    .filter(|o| o.target.day == day.day && o.target.segment_idx as usize == idx)
+                {
+                    if let Some(cap) = over.modifier.rpe_cap {
+                        cap_rpe(&mut value, cap);
+                    }
+                }
+
+                segments.push(serde_json::from_value::<Segment>(value)?);
+            }
+            days.push(Day {
+                segments,
+                ..day.clone()
+            });
+        }
+
+        Ok(days)
+    }
+}
+
+/// Merge this object's overlay for `week` into it, drop `per_week`, and recurse into
+/// nested segments/items
+fn apply_overlay(value: &mut Value, week: &str) {
+    let Value::Object(obj) = value else {
+        return;
+    };
+
+    if let Some(Value::Object(per_week)) = obj.remove("per_week") {
+        if let Some(Value::Object(overlay)) = per_week.get(week) {
+            for (field, v) in overlay.iter().filter(|(field, _)| *field != "type") {
+                obj.insert(field.clone(), v.clone());
+            }
+        }
+    }
+
+    for field in NESTED_FIELDS {
+        if let Some(Value::Array(children)) = obj.get_mut(field) {
+            for child in children {
+                apply_overlay(child, week);
+            }
+        }
+    }
+}
+
+/// Cap every RPE target (and raise every RIR target) in a segment to honour `cap`
+fn cap_rpe(value: &mut Value, cap: f64) {
+    match value {
+        Value::Object(obj) => {
+            cap_field(obj, "rpe", |v| v.min(cap));
+            cap_field(obj, "rir", |v| v.max(10.0 - cap));
+            for (field, child) in obj.iter_mut() {
+                if field != "rpe" && field != "rir" {
+                    cap_rpe(child, cap);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| cap_rpe(item, cap)),
+        _ => {}
+    }
+}
+
+fn cap_field(obj: &mut Map<String, Value>, field: &str, limit: impl Fn(f64) -> f64) {
+    match obj.get_mut(field) {
+        Some(Value::Object(range)) => {
+            for bound in ["min", "max"] {
+                if let Some(v) = range.get(bound).and_then(Value::as_f64) {
+                    range.insert(bound.to_string(), limit(v).into());
+                }
+            }
+        }
+        Some(v) => {
+            if let Some(n) = v.as_f64() {
+                *v = limit(n).into();
+            }
+        }
+        None => {}
+    }
+}
+
+fn max_overlay_week(value: &Value) -> u32 {
+    match value {
+        Value::Object(obj) => {
+            let own = match obj.get("per_week") {
+                Some(Value::Object(per_week)) => week_keys(per_week.keys()),
+                _ => 0,
+            };
+            obj.values().map(max_overlay_week).fold(own, u32::max)
+        }
+        Value::Array(items) => items.iter().map(max_overlay_week).max().unwrap_or(0),
+        _ => 0,
+    }
+}
+
+fn week_keys<'a>(keys: impl Iterator<Item = &'a String>) -> u32 {
+    keys.filter_map(|k| k.parse::<u32>().ok())
+        .max()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn plan() -> Plan {
+        serde_json::from_value(json!({
+            "name": "Block",
+            "unit": "kg",
+            "dictionary": {},
+            "groups": {},
+            "week_overrides": {
+                "4": [{ "target": { "day": 1, "segment_idx": 0 }, "modifier": { "rpe_cap": 7.0 } }]
+            },
+            "schedule": [{
+                "day": 1,
+                "label": "Lower",
+                "segments": [
+                    {
+                        "type": "straight", "ex": "SQ.BB.BACK", "sets": 3,
+                        "reps": { "min": 5, "max": 5 }, "rpe": 8.0,
+                        "per_week": { "2": { "sets": 4 }, "3": { "sets": 5, "rpe": 9.0 } }
+                    },
+                    {
+                        "type": "superset", "rounds": 3, "rest_sec": 60, "rest_between_rounds_sec": 90,
+                        "items": [
+                            { "ex": "LEG.CURL", "sets": 3, "per_week": { "5": { "sets": 4 } } },
+                            { "ex": "CALF.RAISE", "sets": 3 }
+                        ]
+                    }
+                ]
+            }]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_block_weeks() {
+        assert_eq!(plan().block_weeks(), 5);
+        assert_eq!(Plan::new("Empty".to_string()).block_weeks(), 1);
+    }
+
+    #[test]
+    fn test_schedule_for_week() {
+        let plan = plan();
+
+        let sets = |week: u32| match &plan.schedule_for_week(week).unwrap()[0].segments[0] {
+            Segment::Straight(s) => (s.sets, s.rpe),
+            other => panic!("unexpected segment {:?}", other),
+        };
+        assert_eq!(sets(1), (Some(3), Some(8.0)));
+        assert_eq!(sets(2), (Some(4), Some(8.0)));
+        assert_eq!(sets(3), (Some(5), Some(9.0)));
+        // Week 4 has no overlay but the override caps RPE
+        assert_eq!(sets(4), (Some(3), Some(7.0)));
+
+        let week5 = plan.schedule_for_week(5).unwrap();
+        let Segment::Superset(superset) = &week5[0].segments[1] else {
+            panic!("expected superset");
+        };
+        assert_eq!(superset.items[0].sets, 4);
+        assert!(superset.items[0].per_week.is_none());
+    }
+}
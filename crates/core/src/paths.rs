@@ -84,6 +84,31 @@ impl AppPaths {
         self.cache_dir.join("metrics")
     }
 
+    /// Path for the exercise catalog database: ~/.local/share/weightlifting-desktop/exercises.db
+    pub fn exercise_db_path(&self) -> PathBuf {
+        self.data_dir.join("exercises.db")
+    }
+
+    /// Directory for user chart definitions: ~/.local/share/weightlifting-desktop/charts/
+    pub fn charts_dir(&self) -> PathBuf {
+        self.data_dir.join("charts")
+    }
+
+    /// Directory for user scheme templates: ~/.local/share/weightlifting-desktop/schemes/
+    pub fn scheme_templates_dir(&self) -> PathBuf {
+        self.data_dir.join("schemes")
+    }
+
+    /// Path for the training max registry: ~/.local/share/weightlifting-desktop/training_maxes.json
+    pub fn training_maxes_path(&self) -> PathBuf {
+        self.data_dir.join("training_maxes.json")
+    }
+
+    /// Path for user preferences: ~/.local/share/weightlifting-desktop/preferences.json
+    pub fn preferences_path(&self) -> PathBuf {
+        self.data_dir.join("preferences.json")
+    }
+
     /// Ensure required subdirectories exist
     pub fn ensure_subdirs(&self) -> Result<(), std::io::Error> {
         fs::create_dir_all(self.data_dir.join("plans"))?;
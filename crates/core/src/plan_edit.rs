@@ -0,0 +1,266 @@
+use crate::models::{Day, Plan, Segment};
+use serde_json::Value;
+use thiserror::Error;
+
+/// Errors from in-place plan edits shared by the FFI and the CLI
+#[derive(Error, Debug)]
+pub enum PlanEditError {
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Day index {0} out of bounds")]
+    DayOutOfBounds(usize),
+    #[error("Day {0} already exists")]
+    DuplicateDay(u32),
+    #[error("Segment index {0} out of bounds")]
+    SegmentOutOfBounds(usize),
+    #[error("Exercise code cannot be empty")]
+    EmptyExerciseCode,
+    #[error("Exercise name cannot be empty")]
+    EmptyExerciseName,
+    #[error("Invalid JSON pointer: {0}")]
+    InvalidPointer(String),
+    #[error("Path not found: {0}")]
+    PathNotFound(String),
+    #[error("Invalid value at {0}: {1}")]
+    InvalidValue(String, String),
+}
+
+impl Plan {
+    /// Appends a day to the schedule; day numbers must be unique
+    pub fn add_day(&mut self, day: Day) -> Result<(), PlanEditError> {
+        if self.schedule.iter().any(|d| d.day == day.day) {
+            return Err(PlanEditError::DuplicateDay(day.day));
+        }
+        self.schedule.push(day);
+        Ok(())
+    }
+
+    pub fn remove_day(&mut self, day_index: usize) -> Result<Day, PlanEditError> {
+        if day_index >= self.schedule.len() {
+            return Err(PlanEditError::DayOutOfBounds(day_index));
+        }
+        Ok(self.schedule.remove(day_index))
+    }
+
+    /// Appends a segment to the day at `day_index`
+    pub fn add_segment(&mut self, day_index: usize, segment: Segment) -> Result<(), PlanEditError> {
+        self.day_mut(day_index)?.segments.push(segment);
+        Ok(())
+    }
+
+    pub fn remove_segment(
+        &mut self,
+        day_index: usize,
+        segment_index: usize,
+    ) -> Result<Segment, PlanEditError> {
+        let day = self.day_mut(day_index)?;
+        if segment_index >= day.segments.len() {
+            return Err(PlanEditError::SegmentOutOfBounds(segment_index));
+        }
+        Ok(day.segments.remove(segment_index))
+    }
+
+    pub fn update_segment(
+        &mut self,
+        day_index: usize,
+        segment_index: usize,
+        segment: Segment,
+    ) -> Result<(), PlanEditError> {
+        let day = self.day_mut(day_index)?;
+        let slot = day
+            .segments
+            .get_mut(segment_index)
+            .ok_or(PlanEditError::SegmentOutOfBounds(segment_index))?;
+        *slot = segment;
+        Ok(())
+    }
+
+    /// Adds or renames an exercise dictionary entry; code and name are trimmed
+    pub fn add_dictionary_entry(&mut self, code: &str, name: &str) -> Result<(), PlanEditError> {
+        let code = code.trim();
+        if code.is_empty() {
+            return Err(PlanEditError::EmptyExerciseCode);
+        }
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(PlanEditError::EmptyExerciseName);
+        }
+        self.dictionary.insert(code.to_string(), name.to_string());
+        Ok(())
+    }
+
+    /// Sets the value at an RFC 6901 JSON pointer, e.g. `/schedule/0/segments/1/sets`.
+    /// The parent must exist; object keys are inserted and `-` or the array length
+    /// appends. The edited plan must still deserialize, otherwise nothing changes.
+    pub fn set_pointer(&mut self, pointer: &str, value: Value) -> Result<(), PlanEditError> {
+        let mut doc = serde_json::to_value(&*self)?;
+        let (parent, key) = split_pointer(pointer)?;
+        match doc.pointer_mut(parent) {
+            Some(Value::Object(map)) => {
+                map.insert(key, value);
+            }
+            Some(Value::Array(items)) => {
+                let index = array_index(&key, items.len() + 1, pointer)?;
+                if index == items.len() {
+                    items.push(value);
+                } else {
+                    items[index] = value;
+                }
+            }
+            _ => return Err(PlanEditError::PathNotFound(pointer.to_string())),
+        }
+        self.replace_with(doc, pointer)
+    }
+
+    /// Removes and returns the value at a JSON pointer
+    pub fn remove_pointer(&mut self, pointer: &str) -> Result<Value, PlanEditError> {
+        let mut doc = serde_json::to_value(&*self)?;
+        let (parent, key) = split_pointer(pointer)?;
+        let removed = match doc.pointer_mut(parent) {
+            Some(Value::Object(map)) => map.remove(&key),
+            Some(Value::Array(items)) => {
+                let index = array_index(&key, items.len(), pointer)?;
+                Some(items.remove(index))
+            }
+            _ => None,
+        }
+        .ok_or_else(|| PlanEditError::PathNotFound(pointer.to_string()))?;
+        self.replace_with(doc, pointer)?;
+        Ok(removed)
+    }
+
+    fn day_mut(&mut self, day_index: usize) -> Result<&mut Day, PlanEditError> {
+        self.schedule
+            .get_mut(day_index)
+            .ok_or(PlanEditError::DayOutOfBounds(day_index))
+    }
+
+    fn replace_with(&mut self, doc: Value, pointer: &str) -> Result<(), PlanEditError> {
+        *self = serde_json::from_value(doc)
+            .map_err(|e| PlanEditError::InvalidValue(pointer.to_string(), e.to_string()))?;
+        Ok(())
+    }
+}
+
+/// Splits a pointer into its parent pointer and unescaped last token
+fn split_pointer(pointer: &str) -> Result<(&str, String), PlanEditError> {
+    match pointer.rfind('/') {
+        Some(pos) if pointer.starts_with('/') => {
+            let key = pointer[pos + 1..].replace("~1", "/").replace("~0", "~");
+            Ok((&pointer[..pos], key))
+        }
+        _ => Err(PlanEditError::InvalidPointer(pointer.to_string())),
+    }
+}
+
+/// Parses an array token, accepting `-` as `limit - 1` (the append position)
+fn array_index(key: &str, limit: usize, pointer: &str) -> Result<usize, PlanEditError> {
+    let index = if key == "-" {
+        limit.saturating_sub(1)
+    } else {
+        key.parse()
+            .map_err(|_| PlanEditError::InvalidPointer(pointer.to_string()))?
+    };
+    if index < limit {
+        Ok(index)
+    } else {
+        Err(PlanEditError::PathNotFound(pointer.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn squat() -> Segment {
+        serde_json::from_value(json!({
+            "type": "straight",
+            "ex": "SQ.BB.BACK",
+            "sets": 5,
+            "reps": {"min": 5, "max": 5}
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_day_and_segment_edits() {
+        let mut plan = Plan::new("Test".to_string());
+        plan.add_day(Day {
+            day: 1,
+            label: "Lower".to_string(),
+            time_cap_min: None,
+            goal: None,
+            equipment_policy: None,
+            segments: vec![],
+        })
+        .unwrap();
+        plan.add_segment(0, squat()).unwrap();
+        plan.add_dictionary_entry(" SQ.BB.BACK ", "Back Squat")
+            .unwrap();
+
+        assert_eq!(plan.schedule[0].segments.len(), 1);
+        assert_eq!(plan.dictionary["SQ.BB.BACK"], "Back Squat");
+        assert!(matches!(
+            plan.add_segment(1, squat()),
+            Err(PlanEditError::DayOutOfBounds(1))
+        ));
+        let duplicate = plan.schedule[0].clone();
+        assert!(matches!(
+            plan.add_day(duplicate),
+            Err(PlanEditError::DuplicateDay(1))
+        ));
+        assert_eq!(plan.schedule.len(), 1);
+        assert!(matches!(
+            plan.remove_segment(0, 3),
+            Err(PlanEditError::SegmentOutOfBounds(3))
+        ));
+        assert!(matches!(
+            plan.add_dictionary_entry("BP.BB", "  "),
+            Err(PlanEditError::EmptyExerciseName)
+        ));
+    }
+
+    #[test]
+    fn test_pointer_edits() {
+        let mut plan = Plan::new("Test".to_string());
+        plan.set_pointer(
+            "/schedule/-",
+            json!({"day": 1, "label": "Lower", "segments": []}),
+        )
+        .unwrap();
+        plan.add_segment(0, squat()).unwrap();
+
+        plan.set_pointer("/schedule/0/segments/0/sets", json!(3))
+            .unwrap();
+        plan.set_pointer("/dictionary/SQ.BB.BACK", json!("Back Squat"))
+            .unwrap();
+        match &plan.schedule[0].segments[0] {
+            Segment::Straight(segment) => assert_eq!(segment.sets, Some(3)),
+            other => panic!("unexpected segment {:?}", other),
+        }
+
+        // Edits that break the model leave the plan untouched
+        assert!(matches!(
+            plan.set_pointer("/schedule/0/day", json!("one")),
+            Err(PlanEditError::InvalidValue(_, _))
+        ));
+        assert!(matches!(
+            plan.remove_pointer("/schedule/0/label"),
+            Err(PlanEditError::InvalidValue(_, _))
+        ));
+        assert!(matches!(
+            plan.set_pointer("/schedule/5/label", json!("x")),
+            Err(PlanEditError::PathNotFound(_))
+        ));
+        assert!(matches!(
+            plan.set_pointer("schedule", json!([])),
+            Err(PlanEditError::InvalidPointer(_))
+        ));
+        assert_eq!(plan.schedule[0].label, "Lower");
+
+        let removed = plan.remove_pointer("/schedule/0/segments/0").unwrap();
+        assert_eq!(removed["ex"], "SQ.BB.BACK");
+        assert!(plan.schedule[0].segments.is_empty());
+    }
+}
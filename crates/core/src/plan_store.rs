@@ -0,0 +1,593 @@
+use crate::paths::AppPaths;
+use crate::versioning::{
+    PlanDiff, PlanVersion, PlanVersionManager, VersionMetadata, VersionState, VersionedPlan,
+};
+use crate::Plan;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Version metadata sidecar kept next to the version files in `plans/<id>/`
+const HISTORY_FILE: &str = "history.json";
+
+#[derive(Error, Debug)]
+pub enum PlanStoreError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Plan not found: {0}")]
+    PlanNotFound(String),
+    #[error("Version {1} of plan {0} not found")]
+    VersionNotFound(String, String),
+    #[error("Version {1} of plan {0} already exists")]
+    VersionExists(String, String),
+    #[error("Draft of plan {0} has no changes since {1}")]
+    NoChanges(String, String),
+    #[error("Diff failed: {0}")]
+    Diff(String),
+    #[error("Invalid plan id: {0:?} (must be non-empty, not start with '.', and contain no path separators)")]
+    InvalidId(String),
+    #[error("Invalid plan version: {0:?} (expected \"draft\" or major.minor.patch)")]
+    InvalidRef(String),
+    #[error("Unknown version bump: {0}. Available: major, minor, patch")]
+    InvalidBump(String),
+}
+
+/// Reject plan ids that would escape the plans and drafts directories once joined into a
+/// path: empty ids, ids starting with `.` (including `..`), and ids containing `/` or `\`
+pub fn validate_plan_id(id: &str) -> Result<(), PlanStoreError> {
+    if id.is_empty() || id.starts_with('.') || id.contains(['/', '\\']) {
+        return Err(PlanStoreError::InvalidId(id.to_string()));
+    }
+    Ok(())
+}
+
+/// The working draft or a promoted version of a plan
+#[derive(Debug, Clone)]
+pub enum PlanRef {
+    Draft,
+    Version(PlanVersion),
+}
+
+impl FromStr for PlanRef {
+    type Err = PlanStoreError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "draft" {
+            Ok(PlanRef::Draft)
+        } else {
+            s.parse()
+                .map(PlanRef::Version)
+                .map_err(|_| PlanStoreError::InvalidRef(s.to_string()))
+        }
+    }
+}
+
+impl std::fmt::Display for PlanRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlanRef::Draft => write!(f, "draft"),
+            PlanRef::Version(version) => write!(f, "{}", version),
+        }
+    }
+}
+
+/// Which part of the version number a promotion increments
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VersionBump {
+    Major,
+    Minor,
+    #[default]
+    Patch,
+}
+
+impl FromStr for VersionBump {
+    type Err = PlanStoreError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "major" => Ok(VersionBump::Major),
+            "minor" => Ok(VersionBump::Minor),
+            "patch" => Ok(VersionBump::Patch),
+            other => Err(PlanStoreError::InvalidBump(other.to_string())),
+        }
+    }
+}
+
+/// One promoted version in a plan's history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanHistoryEntry {
+    pub version: PlanVersion,
+    pub state: VersionState,
+    pub metadata: VersionMetadata,
+}
+
+/// A stored plan: its draft and/or promoted versions
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanSummary {
+    pub id: String,
+    /// Name from the draft, else the latest version (None if unreadable)
+    pub name: Option<String>,
+    pub has_draft: bool,
+    pub versions: Vec<PlanVersion>,
+}
+
+impl PlanSummary {
+    pub fn latest(&self) -> Option<&PlanVersion> {
+        self.versions.last()
+    }
+}
+
+/// Plan lifecycle on disk: drafts in `drafts/<id>.json`, promoted versions as plain plan
+/// JSON in `plans/<id>/<version>.json`, with their metadata in `plans/<id>/history.json`
+pub struct PlanStore<'a> {
+    paths: &'a AppPaths,
+}
+
+impl<'a> PlanStore<'a> {
+    pub fn new(paths: &'a AppPaths) -> Self {
+        Self { paths }
+    }
+
+    /// Every plan with a draft or at least one version, sorted by id
+    pub fn list(&self) -> Result<Vec<PlanSummary>, PlanStoreError> {
+        let mut ids = std::collections::BTreeSet::new();
+        for path in json_files(&self.paths.drafts_dir())? {
+            if let Some(stem) = path.file_stem() {
+                ids.insert(stem.to_string_lossy().to_string());
+            }
+        }
+        let plans_dir = self.paths.data_dir.join("plans");
+        if plans_dir.is_dir() {
+            for entry in fs::read_dir(&plans_dir)? {
+                let entry = entry?;
+                if entry.file_type()?.is_dir() {
+                    ids.insert(entry.file_name().to_string_lossy().to_string());
+                }
+            }
+        }
+
+        let mut summaries = Vec::new();
+        for id in ids {
+            let versions: Vec<PlanVersion> = self
+                .history(&id)?
+                .into_iter()
+                .map(|entry| entry.version)
+                .collect();
+            let has_draft = self.paths.draft_path(&id).exists();
+            if !has_draft && versions.is_empty() {
+                continue;
+            }
+
+            let newest = if has_draft {
+                Some(PlanRef::Draft)
+            } else {
+                versions.last().cloned().map(PlanRef::Version)
+            };
+            let name = newest
+                .and_then(|plan_ref| self.load(&id, &plan_ref).ok())
+                .map(|plan| plan.name);
+
+            summaries.push(PlanSummary {
+                id,
+                name,
+                has_draft,
+                versions,
+            });
+        }
+        Ok(summaries)
+    }
+
+    /// Promoted versions, oldest first. Version files without a history entry (e.g. copied in
+    /// by hand) are listed as promoted, dated by their modification time.
+    pub fn history(&self, id: &str) -> Result<Vec<PlanHistoryEntry>, PlanStoreError> {
+        validate_plan_id(id)?;
+        let plan_dir = self.paths.plan_dir(id);
+        let history_path = plan_dir.join(HISTORY_FILE);
+        let mut entries: Vec<PlanHistoryEntry> = if history_path.exists() {
+            serde_json::from_str(&fs::read_to_string(&history_path)?)?
+        } else {
+            Vec::new()
+        };
+        entries.retain(|entry| self.version_path(id, &entry.version).exists());
+
+        for path in json_files(&plan_dir)? {
+            let Some(version) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<PlanVersion>().ok())
+            else {
+                continue;
+            };
+            if entries
+                .iter()
+                .any(|e| e.version.sort_key() == version.sort_key())
+            {
+                continue;
+            }
+            let created_at = fs::metadata(&path)?
+                .modified()
+                .map(|time| chrono::DateTime::<chrono::Utc>::from(time).to_rfc3339())
+                .unwrap_or_default();
+            entries.push(PlanHistoryEntry {
+                version,
+                state: VersionState::Promoted,
+                metadata: VersionMetadata {
+                    created_at,
+                    author: None,
+                    message: None,
+                    tags: Vec::new(),
+                    parent_version: None,
+                },
+            });
+        }
+
+        entries.sort_by_key(|entry| entry.version.sort_key());
+        Ok(entries)
+    }
+
+    pub fn load(&self, id: &str, plan_ref: &PlanRef) -> Result<Plan, PlanStoreError> {
+        Ok(serde_json::from_str(&self.read(id, plan_ref)?)?)
+    }
+
+    /// The stored plan JSON exactly as written on disk
+    pub fn read(&self, id: &str, plan_ref: &PlanRef) -> Result<String, PlanStoreError> {
+        validate_plan_id(id)?;
+        let path = match plan_ref {
+            PlanRef::Draft => self.paths.draft_path(id),
+            PlanRef::Version(version) => self.version_path(id, version),
+        };
+        if !path.exists() {
+            return Err(self.not_found(id, plan_ref));
+        }
+        Ok(fs::read_to_string(path)?)
+    }
+
+    /// Diff two refs with `PlanVersionManager::compute_diff`. The draft is labelled as the
+    /// next patch version with a `draft` build tag.
+    pub fn diff(&self, id: &str, from: &PlanRef, to: &PlanRef) -> Result<PlanDiff, PlanStoreError> {
+        let history = self.history(id)?;
+        let draft_version = {
+            let mut version = history
+                .last()
+                .map(|entry| entry.version.clone())
+                .unwrap_or_else(|| PlanVersion::new(0, 0, 0));
+            version.bump_patch();
+            version.with_build("draft".to_string())
+        };
+        let version_of = |plan_ref: &PlanRef| match plan_ref {
+            PlanRef::Draft => draft_version.clone(),
+            PlanRef::Version(version) => version.clone(),
+        };
+
+        let mut manager = PlanVersionManager::new();
+        for plan_ref in [from, to] {
+            let plan = self.load(id, plan_ref)?;
+            manager.add_version(
+                id.to_string(),
+                VersionedPlan::new_draft(plan, version_of(plan_ref)),
+            );
+        }
+
+        manager
+            .compute_diff(id, &version_of(from), &version_of(to))
+            .map_err(PlanStoreError::Diff)
+    }
+
+    /// Promote the current draft to the next version (1.0.0 for the first), keeping the
+    /// draft as the working copy
+    pub fn promote(
+        &self,
+        id: &str,
+        bump: VersionBump,
+        message: Option<String>,
+        author: Option<String>,
+    ) -> Result<PlanHistoryEntry, PlanStoreError> {
+        let plan = self.load(id, &PlanRef::Draft)?;
+        let mut history = self.history(id)?;
+        let parent = history.last().map(|entry| entry.version.clone());
+
+        let version = match &parent {
+            Some(parent) => {
+                let diff = self.diff(id, &PlanRef::Version(parent.clone()), &PlanRef::Draft)?;
+                if diff.changes.is_empty() {
+                    return Err(PlanStoreError::NoChanges(
+                        id.to_string(),
+                        parent.to_string(),
+                    ));
+                }
+                let mut version = PlanVersion::new(parent.major, parent.minor, parent.patch);
+                match bump {
+                    VersionBump::Major => version.bump_major(),
+                    VersionBump::Minor => version.bump_minor(),
+                    VersionBump::Patch => version.bump_patch(),
+                }
+                version
+            }
+            None => PlanVersion::new(1, 0, 0),
+        };
+
+        let version_path = self.version_path(id, &version);
+        if version_path.exists() {
+            return Err(PlanStoreError::VersionExists(
+                id.to_string(),
+                version.to_string(),
+            ));
+        }
+
+        let mut versioned = VersionedPlan::new_draft(plan, version);
+        versioned.metadata.parent_version = parent;
+        versioned.stage_for_promotion(message);
+        versioned.promote(author);
+
+        fs::create_dir_all(self.paths.plan_dir(id))?;
+        fs::write(
+            &version_path,
+            serde_json::to_string_pretty(&versioned.plan)?,
+        )?;
+
+        let entry = PlanHistoryEntry {
+            version: versioned.version,
+            state: versioned.state,
+            metadata: versioned.metadata,
+        };
+        history.push(entry.clone());
+        self.write_history(id, &history)?;
+        Ok(entry)
+    }
+
+    /// Add (or with `remove`, drop) a tag on a promoted version
+    pub fn tag(
+        &self,
+        id: &str,
+        version: &PlanVersion,
+        tag: &str,
+        remove: bool,
+    ) -> Result<PlanHistoryEntry, PlanStoreError> {
+        let mut history = self.history(id)?;
+        let entry = history
+            .iter_mut()
+            .find(|entry| entry.version.sort_key() == version.sort_key())
+            .ok_or_else(|| self.not_found(id, &PlanRef::Version(version.clone())))?;
+
+        if remove {
+            entry.metadata.tags.retain(|t| t != tag);
+        } else if !entry.metadata.tags.iter().any(|t| t == tag) {
+            entry.metadata.tags.push(tag.to_string());
+        }
+
+        let entry = entry.clone();
+        self.write_history(id, &history)?;
+        Ok(entry)
+    }
+
+    /// Delete the draft, one version, or (with `None`) the draft and every version
+    pub fn delete(&self, id: &str, target: Option<&PlanRef>) -> Result<(), PlanStoreError> {
+        validate_plan_id(id)?;
+        match target {
+            Some(PlanRef::Draft) => {
+                let path = self.paths.draft_path(id);
+                if !path.exists() {
+                    return Err(self.not_found(id, &PlanRef::Draft));
+                }
+                fs::remove_file(path)?;
+            }
+            Some(PlanRef::Version(version)) => {
+                let path = self.version_path(id, version);
+                if !path.exists() {
+                    return Err(self.not_found(id, &PlanRef::Version(version.clone())));
+                }
+                fs::remove_file(path)?;
+                let history = self.history(id)?;
+                if history.is_empty() {
+                    fs::remove_dir_all(self.paths.plan_dir(id))?;
+                } else {
+                    self.write_history(id, &history)?;
+                }
+            }
+            None => {
+                let draft_path = self.paths.draft_path(id);
+                let plan_dir = self.paths.plan_dir(id);
+                if !draft_path.exists() && !plan_dir.exists() {
+                    return Err(PlanStoreError::PlanNotFound(id.to_string()));
+                }
+                if draft_path.exists() {
+                    fs::remove_file(draft_path)?;
+                }
+                if plan_dir.exists() {
+                    fs::remove_dir_all(plan_dir)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn version_path(&self, id: &str, version: &PlanVersion) -> PathBuf {
+        let version = PlanVersion::new(version.major, version.minor, version.patch);
+        self.paths.active_plan_path(id, &version.to_string())
+    }
+
+    fn write_history(&self, id: &str, history: &[PlanHistoryEntry]) -> Result<(), PlanStoreError> {
+        let path = self.paths.plan_dir(id).join(HISTORY_FILE);
+        fs::write(path, serde_json::to_string_pretty(history)?)?;
+        Ok(())
+    }
+
+    fn not_found(&self, id: &str, plan_ref: &PlanRef) -> PlanStoreError {
+        match plan_ref {
+            PlanRef::Version(version) if self.paths.plan_dir(id).exists() => {
+                PlanStoreError::VersionNotFound(id.to_string(), version.to_string())
+            }
+            PlanRef::Draft if self.paths.plan_dir(id).exists() => {
+                PlanStoreError::VersionNotFound(id.to_string(), "draft".to_string())
+            }
+            _ => PlanStoreError::PlanNotFound(id.to_string()),
+        }
+    }
+}
+
+/// `*.json` files directly inside `dir` (none if it does not exist)
+fn json_files(dir: &Path) -> Result<Vec<PathBuf>, std::io::Error> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_lifecycle() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = AppPaths {
+            data_dir: dir.path().join("data"),
+            state_dir: dir.path().join("state"),
+            cache_dir: dir.path().join("cache"),
+        };
+        paths.ensure_subdirs().unwrap();
+        let store = PlanStore::new(&paths);
+
+        let mut plan = Plan::new("Block".to_string());
+        let write_draft = |plan: &Plan| {
+            fs::write(
+                paths.draft_path("block"),
+                serde_json::to_string(plan).unwrap(),
+            )
+            .unwrap()
+        };
+        write_draft(&plan);
+
+        let first = store
+            .promote(
+                "block",
+                VersionBump::Minor,
+                Some("Initial".to_string()),
+                None,
+            )
+            .unwrap();
+        assert_eq!(first.version.to_string(), "1.0.0");
+        assert!(matches!(first.state, VersionState::Promoted));
+        assert_eq!(first.metadata.tags, vec!["promoted"]);
+
+        // Unchanged drafts are refused
+        assert!(matches!(
+            store.promote("block", VersionBump::Patch, None, None),
+            Err(PlanStoreError::NoChanges(_, _))
+        ));
+
+        plan.name = "Block v2".to_string();
+        write_draft(&plan);
+        let diff = store
+            .diff(
+                "block",
+                &"1.0.0".parse().unwrap(),
+                &"draft".parse().unwrap(),
+            )
+            .unwrap();
+        assert_eq!(diff.changes.len(), 1);
+        assert_eq!(diff.changes[0].path, "/name");
+        assert_eq!(diff.to_version.to_string(), "1.0.1+draft");
+
+        let second = store
+            .promote("block", VersionBump::Minor, None, Some("coach".to_string()))
+            .unwrap();
+        assert_eq!(second.version.to_string(), "1.1.0");
+        assert_eq!(
+            second.metadata.parent_version.map(|v| v.to_string()),
+            Some("1.0.0".to_string())
+        );
+
+        let v1: PlanVersion = "1.0.0".parse().unwrap();
+        store.tag("block", &v1, "stable", false).unwrap();
+        let history = store.history("block").unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].metadata.message.as_deref(), Some("Initial"));
+        assert!(history[0].metadata.tags.contains(&"stable".to_string()));
+
+        let summary = &store.list().unwrap()[0];
+        assert_eq!(summary.name.as_deref(), Some("Block v2"));
+        assert_eq!(
+            summary.latest().map(|v| v.to_string()),
+            Some("1.1.0".to_string())
+        );
+
+        store
+            .delete("block", Some(&PlanRef::Version(v1.clone())))
+            .unwrap();
+        assert!(matches!(
+            store.load("block", &PlanRef::Version(v1)),
+            Err(PlanStoreError::VersionNotFound(_, _))
+        ));
+        store.delete("block", None).unwrap();
+        assert!(store.list().unwrap().is_empty());
+        assert!(matches!(
+            store.delete("block", None),
+            Err(PlanStoreError::PlanNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_rejects_ids_outside_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = AppPaths {
+            data_dir: dir.path().join("data"),
+            state_dir: dir.path().join("state"),
+            cache_dir: dir.path().join("cache"),
+        };
+        paths.ensure_subdirs().unwrap();
+        let victim = dir.path().join("victim");
+        fs::create_dir_all(&victim).unwrap();
+        fs::write(victim.join("keep.txt"), "x").unwrap();
+        let store = PlanStore::new(&paths);
+
+        for id in ["../../victim", "", ".hidden", "a/b", "a\\b"] {
+            assert!(matches!(
+                store.delete(id, None),
+                Err(PlanStoreError::InvalidId(_))
+            ));
+            assert!(matches!(
+                store.load(id, &PlanRef::Draft),
+                Err(PlanStoreError::InvalidId(_))
+            ));
+            assert!(matches!(
+                store.history(id),
+                Err(PlanStoreError::InvalidId(_))
+            ));
+        }
+        assert!(victim.join("keep.txt").exists());
+        assert!(validate_plan_id("block-2026").is_ok());
+    }
+
+    #[test]
+    fn test_parse_refs_and_bumps() {
+        assert!(matches!("draft".parse::<PlanRef>(), Ok(PlanRef::Draft)));
+        assert!(matches!(
+            "1.2.0".parse::<PlanRef>(),
+            Ok(PlanRef::Version(version)) if version.to_string() == "1.2.0"
+        ));
+        for bad in ["", "latest", "1.2", "../../x"] {
+            assert!(matches!(
+                bad.parse::<PlanRef>(),
+                Err(PlanStoreError::InvalidRef(_))
+            ));
+        }
+        assert_eq!("minor".parse::<VersionBump>().unwrap(), VersionBump::Minor);
+        assert!(matches!(
+            "huge".parse::<VersionBump>(),
+            Err(PlanStoreError::InvalidBump(_))
+        ));
+    }
+}
@@ -0,0 +1,296 @@
+use crate::charts::{ChartRenderer, VegaLiteSpec};
+use crate::models::{Plan, Segment};
+use chrono::NaiveDate;
+use serde_json::Value;
+use std::fmt::Write;
+
+/// Inline stylesheet; the report must open offline, so nothing is fetched at view time
+const STYLE: &str = "body{font-family:sans-serif;margin:2em auto;max-width:1000px;color:#222}\
+h1{margin-bottom:0}.range{color:#666;margin-top:.2em}\
+section{margin:2em 0}figure{margin:1em 0}figure svg{max-width:100%;height:auto}\
+table{border-collapse:collapse;width:100%}th,td{border-bottom:1px solid #ddd;padding:.3em .6em;text-align:left}\
+td.num{text-align:right}.error{color:#b00}";
+
+/// A free-text note logged against a session
+#[derive(Debug, Clone)]
+pub struct SessionNote {
+    pub date: NaiveDate,
+    pub day_label: Option<String>,
+    pub exercise: Option<String>,
+    pub text: String,
+}
+
+/// Per-session adherence totals for the report table
+#[derive(Debug, Clone)]
+pub struct ReportAdherence {
+    pub plan_name: String,
+    pub completion_pct: f64,
+    pub sessions: Vec<(NaiveDate, String, u32, u32, u32)>, // (date, day_label, completed, missed, extra)
+}
+
+/// Self-contained HTML training report: charts are rendered to inline SVG and their
+/// specs (with data values) embedded as JSON, so the file needs no network access
+pub struct HtmlReport {
+    title: String,
+    date_range: (NaiveDate, NaiveDate),
+    charts: Vec<(String, VegaLiteSpec)>,
+    adherence: Option<ReportAdherence>,
+    plan: Option<Plan>,
+    notes: Vec<SessionNote>,
+}
+
+impl HtmlReport {
+    pub fn new(title: String, date_range: (NaiveDate, NaiveDate)) -> Self {
+        Self {
+            title,
+            date_range,
+            charts: Vec::new(),
+            adherence: None,
+            plan: None,
+            notes: Vec::new(),
+        }
+    }
+
+    /// Named chart specs, e.g. from `ChartGenerator::generate_all_charts`
+    pub fn charts(mut self, charts: Vec<(String, VegaLiteSpec)>) -> Self {
+        self.charts = charts;
+        self
+    }
+
+    pub fn adherence(mut self, adherence: ReportAdherence) -> Self {
+        self.adherence = Some(adherence);
+        self
+    }
+
+    pub fn plan(mut self, plan: Plan) -> Self {
+        self.plan = Some(plan);
+        self
+    }
+
+    pub fn notes(mut self, notes: Vec<SessionNote>) -> Self {
+        self.notes = notes;
+        self
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        let (start, end) = self.date_range;
+
+        html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+        let _ = writeln!(html, "<title>{}</title>", escape(&self.title));
+        let _ = writeln!(html, "<style>{}</style>\n</head>\n<body>", STYLE);
+        let _ = writeln!(html, "<h1>{}</h1>", escape(&self.title));
+        let _ = writeln!(
+            html,
+            "<p class=\"range\">{} &ndash; {}</p>",
+            start.format("%Y-%m-%d"),
+            end.format("%Y-%m-%d")
+        );
+
+        if let Some(plan) = &self.plan {
+            self.write_plan(&mut html, plan);
+        }
+        if !self.charts.is_empty() {
+            self.write_charts(&mut html);
+        }
+        if let Some(adherence) = &self.adherence {
+            self.write_adherence(&mut html, adherence);
+        }
+        self.write_notes(&mut html);
+
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    fn write_plan(&self, html: &mut String, plan: &Plan) {
+        html.push_str("<section id=\"plan\">\n<h2>Plan</h2>\n");
+        let _ = write!(html, "<p><strong>{}</strong>", escape(&plan.name));
+        if let Some(author) = &plan.author {
+            let _ = write!(html, " by {}", escape(author));
+        }
+        let _ = writeln!(
+            html,
+            " &middot; {} week block &middot; {} days/week &middot; {:?}</p>",
+            plan.block_weeks(),
+            plan.schedule.len(),
+            plan.unit
+        );
+
+        html.push_str("<table>\n<tr><th>Day</th><th>Exercises</th><th>Time cap</th></tr>\n");
+        for day in &plan.schedule {
+            let mut exercises = Vec::new();
+            for segment in &day.segments {
+                collect_exercises(segment, &mut exercises);
+            }
+            let names: Vec<String> = exercises
+                .iter()
+                .map(|ex| escape(plan.dictionary.get(ex).unwrap_or(ex)))
+                .collect();
+            let _ = writeln!(
+                html,
+                "<tr><td>{} &ndash; {}</td><td>{}</td><td>{}</td></tr>",
+                day.day,
+                escape(&day.label),
+                names.join(", "),
+                day.time_cap_min
+                    .map(|m| format!("{} min", m))
+                    .unwrap_or_default()
+            );
+        }
+        html.push_str("</table>\n</section>\n");
+    }
+
+    fn write_charts(&self, html: &mut String) {
+        html.push_str("<section id=\"charts\">\n<h2>Charts</h2>\n");
+        for (name, spec) in &self.charts {
+            let json = spec.to_json();
+            let _ = writeln!(html, "<figure id=\"chart-{}\">", escape(name));
+            match ChartRenderer::render_svg(&json) {
+                Ok(svg) => html.push_str(&svg),
+                Err(e) => {
+                    let _ = write!(
+                        html,
+                        "<p class=\"error\">{}: {}</p>",
+                        escape(name),
+                        escape(&e.to_string())
+                    );
+                }
+            }
+            // The spec (data inline) rides along so the chart can be reopened in Vega tooling
+            let _ = writeln!(
+                html,
+                "\n<script type=\"application/json\" class=\"vega-lite-spec\">{}</script>\n</figure>",
+                script_json(&json)
+            );
+        }
+        html.push_str("</section>\n");
+    }
+
+    fn write_adherence(&self, html: &mut String, adherence: &ReportAdherence) {
+        html.push_str("<section id=\"adherence\">\n<h2>Adherence</h2>\n");
+        let _ = writeln!(
+            html,
+            "<p>{}: {:.0}% of prescribed sets completed</p>",
+            escape(&adherence.plan_name),
+            adherence.completion_pct
+        );
+        html.push_str(
+            "<table>\n<tr><th>Date</th><th>Day</th><th>Completed</th><th>Missed</th><th>Extra</th></tr>\n",
+        );
+        for (date, day_label, completed, missed, extra) in &adherence.sessions {
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>",
+                date.format("%Y-%m-%d"),
+                escape(day_label),
+                completed,
+                missed,
+                extra
+            );
+        }
+        html.push_str("</table>\n</section>\n");
+    }
+
+    fn write_notes(&self, html: &mut String) {
+        html.push_str("<section id=\"notes\">\n<h2>Session Notes</h2>\n");
+        if self.notes.is_empty() {
+            html.push_str("<p>No notes logged.</p>\n");
+        } else {
+            html.push_str(
+                "<table>\n<tr><th>Date</th><th>Day</th><th>Exercise</th><th>Note</th></tr>\n",
+            );
+            for note in &self.notes {
+                let _ = writeln!(
+                    html,
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    note.date.format("%Y-%m-%d"),
+                    escape(note.day_label.as_deref().unwrap_or_default()),
+                    escape(note.exercise.as_deref().unwrap_or_default()),
+                    escape(&note.text)
+                );
+            }
+            html.push_str("</table>\n");
+        }
+        html.push_str("</section>\n");
+    }
+}
+
+/// Exercise codes in schedule order, without duplicates, including segments with no sets
+fn collect_exercises(segment: &Segment, out: &mut Vec<String>) {
+    for prescription in segment.prescriptions() {
+        if !out.contains(&prescription.ex) {
+            out.push(prescription.ex);
+        }
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// JSON that cannot terminate its enclosing `<script>` element
+fn script_json(value: &Value) -> String {
+    value.to_string().replace("</", "<\\/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::charts::ChartGenerator;
+    use crate::units::DisplayUnit;
+
+    #[test]
+    fn test_html_report() {
+        let d = |day| NaiveDate::from_ymd_opt(2025, 8, day).unwrap();
+        let plan: Plan = serde_json::from_value(serde_json::json!({
+            "name": "Block <A>",
+            "unit": "kg",
+            "dictionary": { "SQ.BB.BACK": "Back Squat", "BP.BB.FLAT": "Bench Press" },
+            "groups": {},
+            "schedule": [{
+                "day": 1, "label": "Lower", "time_cap_min": 60,
+                "segments": [
+                    { "type": "straight", "ex": "SQ.BB.BACK", "sets": 3 },
+                    { "type": "rpe", "ex": "BP.BB.FLAT", "sets": 0, "rpe": 8.0 }
+                ]
+            }]
+        }))
+        .unwrap();
+        let charts = ChartGenerator::generate_all_charts(
+            &[("SQ.BB.BACK".to_string(), d(4), 150.0)],
+            &[("SQ.BB.BACK".to_string(), d(4), 3, 15, 2000.0)],
+            &[],
+            &[d(4)],
+            (d(1), d(31)),
+            DisplayUnit::Kg,
+        );
+
+        let html = HtmlReport::new("August".to_string(), (d(1), d(31)))
+            .plan(plan)
+            .charts(charts)
+            .adherence(ReportAdherence {
+                plan_name: "Block <A>".to_string(),
+                completion_pct: 75.0,
+                sessions: vec![(d(4), "Lower".to_string(), 3, 1, 0)],
+            })
+            .notes(vec![SessionNote {
+                date: d(4),
+                day_label: Some("Lower".to_string()),
+                exercise: Some("SQ.BB.BACK".to_string()),
+                text: "Knee felt </script> fine".to_string(),
+            }])
+            .to_html();
+
+        assert!(html.contains("<strong>Block &lt;A&gt;</strong>"));
+        assert!(html.contains("Back Squat, Bench Press"));
+        assert!(html.contains("<figure id=\"chart-e1rm_overview\">\n<svg"));
+        assert_eq!(html.matches("class=\"vega-lite-spec\"").count(), 4);
+        assert!(html.contains("75% of prescribed sets completed"));
+        assert!(html.contains("Knee felt &lt;/script&gt; fine"));
+        // Fully offline: no external scripts, stylesheets or fonts
+        assert!(!html.contains("src=\"http") && !html.contains("href=\"http"));
+    }
+}
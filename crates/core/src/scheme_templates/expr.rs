@@ -0,0 +1,352 @@
+use std::collections::BTreeMap;
+
+/// Arithmetic over numbers and template parameters, e.g. `top.load * 0.9`.
+/// Supports `+ - * /`, parentheses, unary minus and the functions `min`,
+/// `max`, `round` (optionally to a step), `floor`, `ceil` and `abs`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemeExpr {
+    Number(f64),
+    Var(String),
+    Neg(Box<SchemeExpr>),
+    Binary(BinaryOp, Box<SchemeExpr>, Box<SchemeExpr>),
+    Call(String, Vec<SchemeExpr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+/// Longest expression source accepted; also bounds the depth of left-nested `+`/`*` chains
+const MAX_EXPR_LEN: usize = 1024;
+
+/// Deepest nesting of parentheses, calls and unary minus accepted by the parser
+const MAX_EXPR_DEPTH: usize = 64;
+
+const FUNCTIONS: &[(&str, usize, usize)] = &[
+    ("min", 2, usize::MAX),
+    ("max", 2, usize::MAX),
+    ("round", 1, 2),
+    ("floor", 1, 1),
+    ("ceil", 1, 1),
+    ("abs", 1, 1),
+];
+
+impl SchemeExpr {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        if source.len() > MAX_EXPR_LEN {
+            return Err(format!(
+                "expression is {} characters long; the limit is {}",
+                source.len(),
+                MAX_EXPR_LEN
+            ));
+        }
+        let tokens = tokenize(source)?;
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            depth: 0,
+        };
+        let expr = parser.expr()?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some(token) => Err(format!("unexpected '{}' in '{}'", token, source)),
+        }
+    }
+
+    /// Evaluates against parameter values; unknown names, division by zero and
+    /// non-finite results are errors
+    pub fn eval(&self, vars: &BTreeMap<String, f64>) -> Result<f64, String> {
+        let value = match self {
+            SchemeExpr::Number(n) => *n,
+            SchemeExpr::Var(name) => *vars
+                .get(name)
+                .ok_or_else(|| format!("unknown parameter '{}'", name))?,
+            SchemeExpr::Neg(inner) => -inner.eval(vars)?,
+            SchemeExpr::Binary(op, lhs, rhs) => {
+                let (lhs, rhs) = (lhs.eval(vars)?, rhs.eval(vars)?);
+                match op {
+                    BinaryOp::Add => lhs + rhs,
+                    BinaryOp::Sub => lhs - rhs,
+                    BinaryOp::Mul => lhs * rhs,
+                    BinaryOp::Div if rhs == 0.0 => return Err("division by zero".to_string()),
+                    BinaryOp::Div => lhs / rhs,
+                }
+            }
+            SchemeExpr::Call(name, args) => {
+                let args = args
+                    .iter()
+                    .map(|arg| arg.eval(vars))
+                    .collect::<Result<Vec<_>, _>>()?;
+                match (name.as_str(), args.as_slice()) {
+                    ("min", _) => args.iter().copied().fold(f64::INFINITY, f64::min),
+                    ("max", _) => args.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+                    ("round", [x]) => x.round(),
+                    ("round", [_, step]) if *step <= 0.0 => {
+                        return Err("round step must be positive".to_string())
+                    }
+                    ("round", [x, step]) => (x / step).round() * step,
+                    ("floor", [x]) => x.floor(),
+                    ("ceil", [x]) => x.ceil(),
+                    ("abs", [x]) => x.abs(),
+                    _ => return Err(format!("unknown function '{}'", name)),
+                }
+            }
+        };
+        if value.is_finite() {
+            Ok(value)
+        } else {
+            Err("expression result is not a finite number".to_string())
+        }
+    }
+
+    /// Every parameter name the expression reads
+    pub fn variables(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        self.collect_variables(&mut names);
+        names
+    }
+
+    fn collect_variables<'a>(&'a self, names: &mut Vec<&'a str>) {
+        match self {
+            SchemeExpr::Number(_) => {}
+            SchemeExpr::Var(name) => names.push(name),
+            SchemeExpr::Neg(inner) => inner.collect_variables(names),
+            SchemeExpr::Binary(_, lhs, rhs) => {
+                lhs.collect_variables(names);
+                rhs.collect_variables(names);
+            }
+            SchemeExpr::Call(_, args) => args.iter().for_each(|arg| arg.collect_variables(names)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Symbol(char),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Number(n) => write!(f, "{}", n),
+            Token::Ident(name) => write!(f, "{}", name),
+            Token::Symbol(c) => write!(f, "{}", c),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit() || c == '.' {
+            let mut end = start;
+            while let Some(&(i, c)) = chars.peek() {
+                if !(c.is_ascii_digit() || c == '.') {
+                    break;
+                }
+                end = i + c.len_utf8();
+                chars.next();
+            }
+            let text = &source[start..end];
+            let number = text
+                .parse()
+                .map_err(|_| format!("invalid number '{}'", text))?;
+            tokens.push(Token::Number(number));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let mut end = start;
+            while let Some(&(i, c)) = chars.peek() {
+                if !(c.is_ascii_alphanumeric() || c == '_' || c == '.') {
+                    break;
+                }
+                end = i + c.len_utf8();
+                chars.next();
+            }
+            tokens.push(Token::Ident(source[start..end].to_string()));
+        } else if "+-*/(),".contains(c) {
+            tokens.push(Token::Symbol(c));
+            chars.next();
+        } else {
+            return Err(format!("unexpected character '{}' in '{}'", c, source));
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent over `expr := term (+|- term)*`, `term := unary (*|/ unary)*`,
+/// `unary := - unary | primary`, `primary := number | name | name(args) | (expr)`
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// Current nesting of parentheses, calls and unary minus
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, symbol: char) -> bool {
+        if self.peek() == Some(&Token::Symbol(symbol)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Runs `parse` one nesting level deeper, failing past `MAX_EXPR_DEPTH`
+    fn nested<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, String>,
+    ) -> Result<T, String> {
+        if self.depth >= MAX_EXPR_DEPTH {
+            return Err(format!(
+                "expression nests deeper than {} levels",
+                MAX_EXPR_DEPTH
+            ));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn expr(&mut self) -> Result<SchemeExpr, String> {
+        let mut lhs = self.term()?;
+        loop {
+            let op = if self.eat('+') {
+                BinaryOp::Add
+            } else if self.eat('-') {
+                BinaryOp::Sub
+            } else {
+                return Ok(lhs);
+            };
+            lhs = SchemeExpr::Binary(op, Box::new(lhs), Box::new(self.term()?));
+        }
+    }
+
+    fn term(&mut self) -> Result<SchemeExpr, String> {
+        let mut lhs = self.unary()?;
+        loop {
+            let op = if self.eat('*') {
+                BinaryOp::Mul
+            } else if self.eat('/') {
+                BinaryOp::Div
+            } else {
+                return Ok(lhs);
+            };
+            lhs = SchemeExpr::Binary(op, Box::new(lhs), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<SchemeExpr, String> {
+        if self.eat('-') {
+            Ok(SchemeExpr::Neg(Box::new(self.nested(Self::unary)?)))
+        } else {
+            self.primary()
+        }
+    }
+
+    fn primary(&mut self) -> Result<SchemeExpr, String> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(SchemeExpr::Number(n)),
+            Some(Token::Ident(name)) if self.eat('(') => {
+                let mut args = Vec::new();
+                if !self.eat(')') {
+                    loop {
+                        args.push(self.nested(Self::expr)?);
+                        if self.eat(')') {
+                            break;
+                        }
+                        if !self.eat(',') {
+                            return Err(format!("expected ',' or ')' in call to '{}'", name));
+                        }
+                    }
+                }
+                match FUNCTIONS.iter().find(|(f, _, _)| *f == name) {
+                    Some((_, min, max)) if (*min..=*max).contains(&args.len()) => {
+                        Ok(SchemeExpr::Call(name, args))
+                    }
+                    Some(_) => Err(format!("wrong number of arguments to '{}'", name)),
+                    None => Err(format!("unknown function '{}'", name)),
+                }
+            }
+            Some(Token::Ident(name)) => Ok(SchemeExpr::Var(name)),
+            Some(Token::Symbol('(')) => {
+                let inner = self.nested(Self::expr)?;
+                if self.eat(')') {
+                    Ok(inner)
+                } else {
+                    Err("missing ')'".to_string())
+                }
+            }
+            Some(token) => Err(format!("unexpected '{}'", token)),
+            None => Err("unexpected end of expression".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_eval() {
+        let vars = BTreeMap::from([("top.load".to_string(), 100.0), ("i".to_string(), 2.0)]);
+        let eval = |source: &str| SchemeExpr::parse(source).unwrap().eval(&vars);
+
+        assert_eq!(eval("top.load * 0.9"), Ok(90.0));
+        assert_eq!(eval("-(1 + 2) * 3 - 4 / 2"), Ok(-11.0));
+        assert_eq!(eval("round(top.load * 0.83, 2.5)"), Ok(82.5));
+        assert_eq!(eval("max(1, i, 0.5) + min(3, 4)"), Ok(5.0));
+        assert_eq!(
+            SchemeExpr::parse("top.load - i * 5").unwrap().variables(),
+            ["top.load", "i"]
+        );
+
+        assert!(eval("top.load / (i - 2)").is_err());
+        assert!(eval("missing + 1").is_err());
+        assert!(SchemeExpr::parse("2 +").is_err());
+        assert!(SchemeExpr::parse("sqrt(4)").is_err());
+        assert!(SchemeExpr::parse("round()").is_err());
+        assert!(SchemeExpr::parse("(1 + 2").is_err());
+        assert!(SchemeExpr::parse("1 $ 2").is_err());
+    }
+
+    #[test]
+    fn test_rejects_deep_and_long_expressions() {
+        let parens = format!("{}1{}", "(".repeat(20_000), ")".repeat(20_000));
+        assert!(SchemeExpr::parse(&parens).is_err());
+        let negations = format!("{}1", "-".repeat(200_000));
+        assert!(SchemeExpr::parse(&negations).is_err());
+
+        let deep = format!("{}1{}", "(".repeat(100), ")".repeat(100));
+        assert!(SchemeExpr::parse(&deep)
+            .unwrap_err()
+            .contains("nests deeper"));
+        let calls = format!("{}1{}", "abs(".repeat(100), ")".repeat(100));
+        assert!(SchemeExpr::parse(&calls).is_err());
+
+        let nested = format!("{}1{}", "(".repeat(10), ")".repeat(10));
+        assert_eq!(
+            SchemeExpr::parse(&nested).unwrap().eval(&BTreeMap::new()),
+            Ok(1.0)
+        );
+    }
+}
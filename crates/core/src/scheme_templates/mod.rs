@@ -0,0 +1,591 @@
+//! User-defined scheme templates: a parameter schema plus a recipe of set rows
+//! whose fields are numbers or expressions over the parameters.
+//!
+//! ```toml
+//! title = "Top set + percentage backoffs"
+//!
+//! [[params]]
+//! name = "top.load"
+//! min = 20
+//!
+//! [[params]]
+//! name = "backoffs"
+//! kind = "integer"
+//! default = 3
+//!
+//! [[sets]]
+//! label = "Top"
+//! reps = 3
+//! load = "top.load"
+//!
+//! [[sets]]
+//! label = "Backoff {n}"
+//! repeat = "backoffs"
+//! reps = 5
+//! load = "round(top.load * (0.9 - 0.025 * i), 2.5)"
+//! ```
+
+mod expr;
+
+pub use expr::{BinaryOp, SchemeExpr};
+
+use crate::schemes::{check_intensity, create_straight_segment, finish, MAX_REPS, MAX_SETS};
+use crate::{ExpandedScheme, IntensityParams, RestOrRange};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum SchemeTemplateError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid JSON scheme template: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Invalid TOML scheme template: {0}")]
+    Toml(#[from] toml::de::Error),
+    #[error("Unsupported scheme template file: {0} (expected .toml or .json)")]
+    UnsupportedFormat(String),
+    #[error("Invalid scheme template '{0}': {1}")]
+    Invalid(String, String),
+    #[error("Scheme template not found: {0}")]
+    NotFound(String),
+}
+
+/// Variable holding the 1-based repetition index inside a repeated row
+pub const REPEAT_INDEX: &str = "i";
+
+/// Upper bound on `repeat` so a typo cannot expand into thousands of rows
+const MAX_REPEAT: u32 = 50;
+
+/// A literal number or an expression over template parameters
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Formula {
+    Number(f64),
+    Expression(String),
+}
+
+impl Formula {
+    pub fn eval(&self, vars: &BTreeMap<String, f64>) -> Result<f64, String> {
+        match self {
+            Formula::Number(n) => Ok(*n),
+            Formula::Expression(source) => SchemeExpr::parse(source)?.eval(vars),
+        }
+    }
+
+    /// Parameter names read by the formula, or the parse error
+    fn variables(&self) -> Result<Vec<String>, String> {
+        match self {
+            Formula::Number(_) => Ok(Vec::new()),
+            Formula::Expression(source) => Ok(SchemeExpr::parse(source)?
+                .variables()
+                .into_iter()
+                .map(str::to_string)
+                .collect()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParamKind {
+    #[default]
+    Number,
+    Integer,
+}
+
+/// One entry of a template's parameter schema
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateParam {
+    pub name: String,
+    #[serde(default)]
+    pub kind: ParamKind,
+    /// Used when a plan does not supply the parameter; without one it is required
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl TemplateParam {
+    fn check_value(&self, value: f64) -> Result<(), String> {
+        if !value.is_finite() {
+            return Err(format!("{} must be a finite number", self.name));
+        }
+        if self.kind == ParamKind::Integer && value.fract() != 0.0 {
+            return Err(format!(
+                "{} must be a whole number, got {}",
+                self.name, value
+            ));
+        }
+        if self.min.is_some_and(|min| value < min) || self.max.is_some_and(|max| value > max) {
+            return Err(format!(
+                "{} must be within {}..{}, got {}",
+                self.name,
+                self.min.map(|v| v.to_string()).unwrap_or_default(),
+                self.max.map(|v| v.to_string()).unwrap_or_default(),
+                value
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// One row of the expansion recipe. Every row needs `reps` and exactly one of
+/// `rpe`, `rir`, `percent_1rm` or `load`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SetRecipe {
+    /// Set label; `{n}` is replaced by the repetition index
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// How many times the row is emitted (default 1); formulas may read `i`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repeat: Option<Formula>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sets: Option<Formula>,
+    pub reps: Formula,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rpe: Option<Formula>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rir: Option<Formula>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub percent_1rm: Option<Formula>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub load: Option<Formula>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rest_sec: Option<Formula>,
+}
+
+impl SetRecipe {
+    fn formulas(&self) -> impl Iterator<Item = (&'static str, &Formula)> {
+        [
+            ("repeat", self.repeat.as_ref()),
+            ("sets", self.sets.as_ref()),
+            ("reps", Some(&self.reps)),
+            ("rpe", self.rpe.as_ref()),
+            ("rir", self.rir.as_ref()),
+            ("percent_1rm", self.percent_1rm.as_ref()),
+            ("load", self.load.as_ref()),
+            ("rest_sec", self.rest_sec.as_ref()),
+        ]
+        .into_iter()
+        .filter_map(|(field, formula)| formula.map(|f| (field, f)))
+    }
+
+    fn intensity(&self, vars: &BTreeMap<String, f64>) -> Result<IntensityParams, String> {
+        Ok(
+            match (&self.rpe, &self.rir, &self.percent_1rm, &self.load) {
+                (Some(rpe), None, None, None) => IntensityParams::Rpe {
+                    rpe: rpe.eval(vars)?,
+                },
+                (None, Some(rir), None, None) => IntensityParams::Rir {
+                    rir: rir.eval(vars)?,
+                },
+                (None, None, Some(percent), None) => IntensityParams::Percent1Rm {
+                    percent_1rm: percent.eval(vars)?,
+                },
+                (None, None, None, Some(load)) => IntensityParams::Load {
+                    load: load.eval(vars)?,
+                },
+                _ => return Err("needs exactly one of rpe, rir, percent_1rm or load".to_string()),
+            },
+        )
+    }
+}
+
+/// A data-driven scheme template loaded from the templates directory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomTemplate {
+    /// Identifier used to look the template up (defaults to the file stem)
+    #[serde(default)]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub params: Vec<TemplateParam>,
+    pub sets: Vec<SetRecipe>,
+}
+
+impl CustomTemplate {
+    /// Load and validate a template from a `.toml` or `.json` file
+    pub fn load(path: &Path) -> Result<Self, SchemeTemplateError> {
+        let content = fs::read_to_string(path)?;
+        let mut template: CustomTemplate = match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => toml::from_str(&content)?,
+            Some("json") => serde_json::from_str(&content)?,
+            _ => {
+                return Err(SchemeTemplateError::UnsupportedFormat(
+                    path.display().to_string(),
+                ))
+            }
+        };
+
+        if template.name.is_empty() {
+            template.name = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default()
+                .to_string();
+        }
+        template.validate()?;
+
+        Ok(template)
+    }
+
+    /// Check the schema and that every formula parses and only reads declared parameters
+    pub fn validate(&self) -> Result<(), SchemeTemplateError> {
+        match self.problems().into_iter().next() {
+            Some(problem) => Err(SchemeTemplateError::Invalid(self.name.clone(), problem)),
+            None => Ok(()),
+        }
+    }
+
+    /// Every schema problem, for reporting them all at once
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let mut seen = Vec::new();
+        for param in &self.params {
+            if !is_param_name(&param.name) || param.name == REPEAT_INDEX {
+                problems.push(format!("invalid parameter name '{}'", param.name));
+            }
+            if seen.contains(&param.name.as_str()) {
+                problems.push(format!("duplicate parameter '{}'", param.name));
+            }
+            seen.push(&param.name);
+            if let (Some(min), Some(max)) = (param.min, param.max) {
+                if min > max {
+                    problems.push(format!("{} has min above max", param.name));
+                }
+            }
+            if let Some(default) = param.default {
+                if let Err(e) = param.check_value(default) {
+                    problems.push(format!("default: {}", e));
+                }
+            }
+        }
+
+        if self.sets.is_empty() {
+            problems.push("template has no set rows".to_string());
+        }
+        for (row, recipe) in self.sets.iter().enumerate() {
+            let intensities = [&recipe.rpe, &recipe.rir, &recipe.percent_1rm, &recipe.load];
+            if intensities.iter().filter(|f| f.is_some()).count() != 1 {
+                problems.push(format!(
+                    "sets[{}] needs exactly one of rpe, rir, percent_1rm or load",
+                    row
+                ));
+            }
+            for (field, formula) in recipe.formulas() {
+                match formula.variables() {
+                    Ok(names) => {
+                        for name in names {
+                            let known = name == REPEAT_INDEX || seen.contains(&name.as_str());
+                            if !known {
+                                problems.push(format!(
+                                    "sets[{}].{} reads unknown parameter '{}'",
+                                    row, field, name
+                                ));
+                            }
+                        }
+                    }
+                    Err(e) => problems.push(format!("sets[{}].{}: {}", row, field, e)),
+                }
+            }
+        }
+        problems
+    }
+
+    /// Parameter values with defaults filled in; missing, unknown and
+    /// out-of-range values are errors
+    pub fn resolve_params(
+        &self,
+        values: &BTreeMap<String, f64>,
+    ) -> Result<BTreeMap<String, f64>, Vec<String>> {
+        let mut problems = Vec::new();
+        let mut resolved = BTreeMap::new();
+        for param in &self.params {
+            match values.get(&param.name).copied().or(param.default) {
+                Some(value) => match param.check_value(value) {
+                    Ok(()) => {
+                        resolved.insert(param.name.clone(), value);
+                    }
+                    Err(e) => problems.push(e),
+                },
+                None => problems.push(format!("missing parameter '{}'", param.name)),
+            }
+        }
+        for name in values.keys() {
+            if !self.params.iter().any(|param| &param.name == name) {
+                problems.push(format!("unknown parameter '{}'", name));
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(resolved)
+        } else {
+            Err(problems)
+        }
+    }
+
+    /// Evaluate the recipe into concrete sets
+    pub fn expand(
+        &self,
+        exercise: &str,
+        values: &BTreeMap<String, f64>,
+    ) -> Result<ExpandedScheme, String> {
+        let problems = self.problems();
+        if !problems.is_empty() {
+            return Err(problems.join("; "));
+        }
+        let mut vars = self.resolve_params(values).map_err(|e| e.join("; "))?;
+
+        let mut sets = Vec::new();
+        let mut intensities = Vec::new();
+        let mut total_reps = 0;
+        for (row, recipe) in self.sets.iter().enumerate() {
+            let context = |e: String| format!("sets[{}]: {}", row, e);
+            let repeat = match &recipe.repeat {
+                Some(formula) => whole(formula.eval(&vars).map_err(context)?, 0, MAX_REPEAT)
+                    .map_err(|e| context(format!("repeat {}", e)))?,
+                None => 1,
+            };
+
+            for i in 1..=repeat {
+                vars.insert(REPEAT_INDEX.to_string(), i as f64);
+                let count = |field: &str, formula: &Formula, max: u32| {
+                    whole(formula.eval(&vars)?, 1, max).map_err(|e| format!("{} {}", field, e))
+                };
+                let set_count = match &recipe.sets {
+                    Some(formula) => count("sets", formula, MAX_SETS).map_err(context)?,
+                    None => 1,
+                };
+                let reps = count("reps", &recipe.reps, MAX_REPS).map_err(context)?;
+                let intensity = recipe.intensity(&vars).map_err(context)?;
+                let mut intensity_problems = Vec::new();
+                check_intensity("intensity", &intensity, &mut intensity_problems);
+                if let Some(problem) = intensity_problems.into_iter().next() {
+                    return Err(context(problem));
+                }
+
+                let label = recipe
+                    .label
+                    .as_ref()
+                    .map(|label| label.replace("{n}", &i.to_string()));
+                let mut set =
+                    create_straight_segment(exercise, set_count, reps, &intensity, label)?;
+                if let Some(rest) = &recipe.rest_sec {
+                    let rest = whole(rest.eval(&vars).map_err(context)?, 0, u32::MAX)
+                        .map_err(|e| context(format!("rest_sec {}", e)))?;
+                    set.rest_sec = Some(RestOrRange::Fixed(rest));
+                }
+
+                total_reps = set_count.saturating_mul(reps).saturating_add(total_reps);
+                sets.push(set);
+                intensities.push(intensity);
+            }
+            vars.remove(REPEAT_INDEX);
+        }
+
+        if sets.is_empty() {
+            return Err("template expanded to no sets".to_string());
+        }
+        Ok(finish(exercise, sets, intensities, total_reps))
+    }
+}
+
+fn is_param_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
+
+/// Converts an evaluated formula to a count within `min..=max`
+fn whole(value: f64, min: u32, max: u32) -> Result<u32, String> {
+    if value.fract() != 0.0 {
+        return Err(format!("must be a whole number, got {}", value));
+    }
+    if value < min as f64 || value > max as f64 {
+        return Err(format!("must be within {}..{}, got {}", min, max, value));
+    }
+    Ok(value as u32)
+}
+
+/// Discovery of user scheme templates in a directory
+pub struct SchemeTemplates;
+
+impl SchemeTemplates {
+    /// Every `.toml` / `.json` file in `dir`, sorted by path, with its parse result
+    pub fn discover(dir: &Path) -> Vec<(PathBuf, Result<CustomTemplate, SchemeTemplateError>)> {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                matches!(
+                    path.extension().and_then(|e| e.to_str()),
+                    Some("toml" | "json")
+                )
+            })
+            .collect();
+        paths.sort();
+
+        paths
+            .into_iter()
+            .map(|path| {
+                let template = CustomTemplate::load(&path);
+                (path, template)
+            })
+            .collect()
+    }
+
+    /// The template named `name` in `dir`
+    pub fn find(dir: &Path, name: &str) -> Result<CustomTemplate, SchemeTemplateError> {
+        Self::discover(dir)
+            .into_iter()
+            .find_map(|(path, template)| match template {
+                Ok(template) if template.name == name => Some(Ok(template)),
+                // Surface parse errors for the file the user most likely meant
+                Err(e) if path.file_stem().and_then(|s| s.to_str()) == Some(name) => Some(Err(e)),
+                _ => None,
+            })
+            .unwrap_or_else(|| Err(SchemeTemplateError::NotFound(name.to_string())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CustomSchemeParams, SchemeTemplate};
+
+    const TOML_TEMPLATE: &str = r#"
+title = "Top set + percentage backoffs"
+
+[[params]]
+name = "top.load"
+min = 20
+
+[[params]]
+name = "backoffs"
+kind = "integer"
+default = 3
+max = 6
+
+[[sets]]
+label = "Top"
+reps = 3
+load = "top.load"
+
+[[sets]]
+label = "Backoff {n}"
+repeat = "backoffs"
+reps = 5
+load = "round(top.load * (0.9 - 0.025 * i), 2.5)"
+rest_sec = 120
+"#;
+
+    fn values(pairs: &[(&str, f64)]) -> BTreeMap<String, f64> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), *value))
+            .collect()
+    }
+
+    #[test]
+    fn test_load_and_expand() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("top_pct.toml"), TOML_TEMPLATE).unwrap();
+        std::fs::write(dir.path().join("broken.toml"), "sets = 3").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+
+        let found = SchemeTemplates::discover(dir.path());
+        assert_eq!(found.len(), 2);
+        let template = SchemeTemplates::find(dir.path(), "top_pct").unwrap();
+        assert_eq!(template.params.len(), 2);
+        assert!(matches!(
+            SchemeTemplates::find(dir.path(), "broken"),
+            Err(SchemeTemplateError::Toml(_))
+        ));
+        assert!(matches!(
+            SchemeTemplates::find(dir.path(), "missing"),
+            Err(SchemeTemplateError::NotFound(_))
+        ));
+
+        let scheme = SchemeTemplate::Custom(CustomSchemeParams {
+            template,
+            values: values(&[("top.load", 140.0)]),
+        });
+        let expanded = scheme.expand("SQ.BB.BACK").unwrap();
+        assert_eq!(expanded.sets.len(), 4);
+        assert_eq!(expanded.total_volume_reps, 18);
+        assert_eq!(
+            expanded.intensities,
+            [140.0, 122.5, 120.0, 115.0].map(|load| IntensityParams::Load { load })
+        );
+        assert_eq!(expanded.sets[3].base.label.as_deref(), Some("Backoff 3"));
+
+        // Round-trips through plan JSON with the template embedded
+        let json = serde_json::to_value(&scheme).unwrap();
+        assert_eq!(json["name"], "custom");
+        let back: SchemeTemplate = serde_json::from_value(json).unwrap();
+        assert_eq!(back.expand("SQ.BB.BACK").unwrap().sets.len(), 4);
+    }
+
+    #[test]
+    fn test_parameter_and_recipe_errors() {
+        let template: CustomTemplate = toml::from_str(TOML_TEMPLATE).unwrap();
+        let problems = template
+            .resolve_params(&values(&[("backoffs", 2.5), ("typo", 1.0)]))
+            .unwrap_err();
+        assert_eq!(problems.len(), 3, "{:?}", problems);
+        assert!(template
+            .expand("SQ", &values(&[("top.load", 10.0)]))
+            .unwrap_err()
+            .contains("top.load must be within 20.."));
+        assert!(template
+            .expand("SQ", &values(&[("top.load", 100.0), ("backoffs", 0.0)]))
+            .is_ok());
+
+        let mut bad = template.clone();
+        bad.sets[0].rpe = Some(Formula::Number(8.0));
+        bad.sets[1].reps = Formula::Expression("reps_for(i)".to_string());
+        bad.sets[1].load = Some(Formula::Expression("bar + top.load".to_string()));
+        bad.params.push(bad.params[0].clone());
+        let problems = bad.problems();
+        assert_eq!(problems.len(), 4, "{:?}", problems);
+        assert!(matches!(
+            bad.validate(),
+            Err(SchemeTemplateError::Invalid(_, _))
+        ));
+
+        let mut heavy = template;
+        heavy.sets[1].load = None;
+        heavy.sets[1].percent_1rm = Some(Formula::Expression("0.9 + 0.2 * i".to_string()));
+        let error = heavy
+            .expand("SQ", &values(&[("top.load", 100.0)]))
+            .unwrap_err();
+        assert!(
+            error.starts_with("sets[1]: intensity.percent_1rm"),
+            "{}",
+            error
+        );
+
+        let mut huge = heavy;
+        huge.sets.truncate(1);
+        huge.sets[0].sets = Some(Formula::Number(5_000_000.0));
+        huge.sets[0].reps = Formula::Number(1000.0);
+        let error = huge
+            .expand("SQ", &values(&[("top.load", 100.0)]))
+            .unwrap_err();
+        assert!(error.contains("sets must be within 1..100"), "{}", error);
+    }
+}
@@ -1,7 +1,11 @@
-use crate::{BaseSegment, RepsOrRange, RepsRange, StraightSegment};
+use crate::{
+    BaseSegment, CustomTemplate, Intensifier, IntensifierKind, RepsOrRange, RepsRange, RestOrRange,
+    StraightSegment, TimeInterval,
+};
 /// **Death to Windows!** - Scheme templates for Sprint 2
 /// Parameterized workout schemes with expansion capabilities
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Scheme template types with parameter schemas
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,6 +17,22 @@ pub enum SchemeTemplate {
     DropSet(DropSetParams),
     #[serde(rename = "cluster")]
     Cluster(ClusterParams),
+    #[serde(rename = "five_three_one")]
+    FiveThreeOne(FiveThreeOneParams),
+    #[serde(rename = "pyramid")]
+    Pyramid(PyramidParams),
+    #[serde(rename = "ramp")]
+    Ramp(RampParams),
+    #[serde(rename = "rest_pause")]
+    RestPause(RestPauseParams),
+    #[serde(rename = "myo_reps")]
+    MyoReps(RestPauseParams),
+    #[serde(rename = "emom")]
+    Emom(EmomParams),
+    #[serde(rename = "wave")]
+    Wave(WaveParams),
+    #[serde(rename = "custom")]
+    Custom(CustomSchemeParams),
 }
 
 /// Top-set + Backoff scheme parameters
@@ -66,8 +86,111 @@ pub struct ClusterParams {
     pub intensity: IntensityParams,
 }
 
-/// Intensity specification for schemes
+/// Wendler 5/3/1 main sets for one week of the four-week wave
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FiveThreeOneParams {
+    pub week: u32, // 1-3 working weeks, 4 is the deload
+    /// Training max load; without it sets are prescribed as %1RM via `tm_percent`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub training_max: Option<f64>,
+    #[serde(default = "default_tm_percent")]
+    pub tm_percent: f64, // training max as a fraction of 1RM
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rest_sec: Option<u32>,
+}
+
+fn default_tm_percent() -> f64 {
+    0.9
+}
+
+/// Pyramid scheme parameters; steps are listed lightest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PyramidParams {
+    pub steps: Vec<PercentStep>,
+    #[serde(default)]
+    pub direction: PyramidDirection,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rest_sec: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PyramidDirection {
+    #[default]
+    Ascending,
+    Descending,
+    /// Up then back down, without repeating the top step
+    Full,
+}
+
+/// One set of a pyramid or wave at a fraction of 1RM
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PercentStep {
+    pub reps: u32,
+    pub percent: f64, // fraction of 1RM
+}
+
+/// Ramp-to-top-set parameters: evenly spaced ramp sets, then the top set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RampParams {
+    pub ramp_sets: u32,
+    pub ramp_reps: u32,
+    pub start_percent: f64, // first ramp set, fraction of 1RM
+    pub end_percent: f64,   // last ramp set, fraction of 1RM
+    pub top: TopSetParams,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rest_sec: Option<u32>,
+}
+
+/// Rest-pause and myo-rep parameters: an activation set followed by mini-sets
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestPauseParams {
+    pub activation_reps: u32,
+    pub mini_sets: u32,
+    pub mini_reps: u32,
+    /// Pause between mini-sets; defaults to 15s for rest-pause and 5s for myo-reps
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pause_sec: Option<u32>,
+    pub intensity: IntensityParams,
+}
+
+/// Every-minute-on-the-minute parameters; `every_sec: 120` gives E2MOM
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmomParams {
+    pub rounds: u32,
+    pub reps: u32,
+    #[serde(default = "default_emom_every_sec")]
+    pub every_sec: u32,
+    pub intensity: IntensityParams,
+}
+
+fn default_emom_every_sec() -> u32 {
+    60
+}
+
+/// Wave loading parameters: the steps are repeated `waves` times, each wave
+/// adding `increment` to every step's percentage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaveParams {
+    pub steps: Vec<PercentStep>,
+    pub waves: u32,
+    #[serde(default)]
+    pub increment: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rest_sec: Option<u32>,
+}
+
+/// A user-defined template (see `scheme_templates`) embedded with the values
+/// chosen for its parameters, so plans expand without the templates directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomSchemeParams {
+    pub template: CustomTemplate,
+    #[serde(default)]
+    pub values: BTreeMap<String, f64>,
+}
+
+/// Intensity specification for schemes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum IntensityParams {
     Rpe { rpe: f64 },
@@ -77,10 +200,13 @@ pub enum IntensityParams {
 }
 
 /// Expanded scheme result containing derived sets
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ExpandedScheme {
     pub exercise: String,
     pub sets: Vec<StraightSegment>,
+    /// Intensity behind each entry of `sets`; %1RM and load have no place on
+    /// `StraightSegment`, so they are only kept here
+    pub intensities: Vec<IntensityParams>,
     pub estimated_duration: u32, // seconds
     pub total_volume_reps: u32,
 }
@@ -88,16 +214,226 @@ pub struct ExpandedScheme {
 impl SchemeTemplate {
     /// Expand scheme template into concrete sets
     pub fn expand(&self, exercise: &str) -> Result<ExpandedScheme, String> {
+        let problems = self.check_params();
+        if !problems.is_empty() {
+            return Err(problems.join("; "));
+        }
         match self {
             SchemeTemplate::TopBackoff(params) => expand_top_backoff(exercise, params),
             SchemeTemplate::DropSet(params) => expand_drop_set(exercise, params),
             SchemeTemplate::Cluster(params) => expand_cluster(exercise, params),
+            SchemeTemplate::FiveThreeOne(params) => expand_five_three_one(exercise, params),
+            SchemeTemplate::Pyramid(params) => expand_pyramid(exercise, params),
+            SchemeTemplate::Ramp(params) => expand_ramp(exercise, params),
+            SchemeTemplate::RestPause(params) => {
+                expand_rest_pause(exercise, params, IntensifierKind::RestPause)
+            }
+            SchemeTemplate::MyoReps(params) => {
+                expand_rest_pause(exercise, params, IntensifierKind::Myo)
+            }
+            SchemeTemplate::Emom(params) => expand_emom(exercise, params),
+            SchemeTemplate::Wave(params) => expand_wave(exercise, params),
+            SchemeTemplate::Custom(params) => params.template.expand(exercise, &params.values),
+        }
+    }
+
+    /// Checks template parameters without expanding; the validator reports each
+    /// problem and `expand` refuses templates that have any
+    pub fn check_params(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        match self {
+            SchemeTemplate::TopBackoff(params) => {
+                check_count("top.reps", params.top.reps, MAX_REPS, &mut problems);
+                check_intensity("top.intensity", &params.top.intensity, &mut problems);
+                for (i, backoff) in params.backoff.iter().enumerate() {
+                    check_percent(
+                        &format!("backoff[{}].percent", i),
+                        backoff.percent,
+                        &mut problems,
+                    );
+                    check_count(
+                        &format!("backoff[{}].sets", i),
+                        backoff.sets,
+                        MAX_SETS,
+                        &mut problems,
+                    );
+                    check_count(
+                        &format!("backoff[{}].reps", i),
+                        backoff.reps,
+                        MAX_REPS,
+                        &mut problems,
+                    );
+                }
+            }
+            SchemeTemplate::DropSet(params) => {
+                check_count("start.reps", params.start.reps, MAX_REPS, &mut problems);
+                check_intensity("start.intensity", &params.start.intensity, &mut problems);
+                for (i, drop) in params.drops.iter().enumerate() {
+                    if !(drop.percent_drop > 0.0 && drop.percent_drop < 1.0) {
+                        problems.push(format!(
+                            "drops[{}].percent_drop must be between 0 and 1, got {}",
+                            i, drop.percent_drop
+                        ));
+                    }
+                    check_count(
+                        &format!("drops[{}].reps", i),
+                        drop.reps,
+                        MAX_REPS,
+                        &mut problems,
+                    );
+                }
+            }
+            SchemeTemplate::Cluster(params) => {
+                check_count("cluster_size", params.cluster_size, MAX_REPS, &mut problems);
+                check_count("clusters", params.clusters, MAX_SETS, &mut problems);
+                check_intensity("intensity", &params.intensity, &mut problems);
+            }
+            SchemeTemplate::FiveThreeOne(params) => {
+                if !(1..=4).contains(&params.week) {
+                    problems.push(format!("week must be 1-4, got {}", params.week));
+                }
+                if let Some(training_max) = params.training_max {
+                    check_load("training_max", training_max, &mut problems);
+                }
+                check_percent("tm_percent", params.tm_percent, &mut problems);
+            }
+            SchemeTemplate::Pyramid(params) => {
+                check_steps(&params.steps, &mut problems);
+            }
+            SchemeTemplate::Ramp(params) => {
+                check_count("ramp_sets", params.ramp_sets, MAX_SETS, &mut problems);
+                check_count("ramp_reps", params.ramp_reps, MAX_REPS, &mut problems);
+                check_percent("start_percent", params.start_percent, &mut problems);
+                check_percent("end_percent", params.end_percent, &mut problems);
+                if params.end_percent < params.start_percent {
+                    problems.push("end_percent must not be below start_percent".to_string());
+                }
+                check_count("top.reps", params.top.reps, MAX_REPS, &mut problems);
+                check_intensity("top.intensity", &params.top.intensity, &mut problems);
+            }
+            SchemeTemplate::RestPause(params) | SchemeTemplate::MyoReps(params) => {
+                check_count(
+                    "activation_reps",
+                    params.activation_reps,
+                    MAX_REPS,
+                    &mut problems,
+                );
+                check_count("mini_sets", params.mini_sets, MAX_SETS, &mut problems);
+                check_count("mini_reps", params.mini_reps, MAX_REPS, &mut problems);
+                check_intensity("intensity", &params.intensity, &mut problems);
+            }
+            SchemeTemplate::Emom(params) => {
+                check_count("rounds", params.rounds, MAX_SETS, &mut problems);
+                check_count("reps", params.reps, MAX_REPS, &mut problems);
+                check_count("every_sec", params.every_sec, MAX_SECONDS, &mut problems);
+                check_intensity("intensity", &params.intensity, &mut problems);
+            }
+            SchemeTemplate::Wave(params) => {
+                check_steps(&params.steps, &mut problems);
+                check_count("waves", params.waves, MAX_SETS, &mut problems);
+                if !(0.0..=0.2).contains(&params.increment) {
+                    problems.push(format!(
+                        "increment must be between 0 and 0.2, got {}",
+                        params.increment
+                    ));
+                }
+                let top = params
+                    .steps
+                    .iter()
+                    .map(|step| step.percent)
+                    .fold(0.0, f64::max);
+                let last_wave = top + params.increment * params.waves.saturating_sub(1) as f64;
+                if last_wave > MAX_PERCENT {
+                    problems.push(format!(
+                        "final wave reaches {:.0}% of 1RM",
+                        last_wave * 100.0
+                    ));
+                }
+            }
+            SchemeTemplate::Custom(params) => {
+                problems.extend(params.template.problems());
+                if problems.is_empty() {
+                    if let Err(e) = params.template.resolve_params(&params.values) {
+                        problems.extend(e);
+                    }
+                }
+            }
+        }
+        problems
+    }
+}
+
+/// Highest fraction of 1RM a template may prescribe (supramaximal holds and partials)
+const MAX_PERCENT: f64 = 1.2;
+
+/// Upper bound on set, round and wave counts so a typo cannot expand into millions of sets
+pub(crate) const MAX_SETS: u32 = 100;
+
+/// Upper bound on reps per set
+pub(crate) const MAX_REPS: u32 = 1000;
+
+/// Upper bound on interval lengths in seconds
+const MAX_SECONDS: u32 = 3600;
+
+fn check_count(field: &str, value: u32, max: u32, problems: &mut Vec<String>) {
+    if value == 0 {
+        problems.push(format!("{} must be greater than 0", field));
+    } else if value > max {
+        problems.push(format!("{} must be at most {}, got {}", field, max, value));
+    }
+}
+
+fn check_percent(field: &str, value: f64, problems: &mut Vec<String>) {
+    if !(value > 0.0 && value <= MAX_PERCENT) {
+        problems.push(format!(
+            "{} must be a fraction between 0 and {}, got {}",
+            field, MAX_PERCENT, value
+        ));
+    }
+}
+
+fn check_load(field: &str, value: f64, problems: &mut Vec<String>) {
+    if !(value > 0.0 && value.is_finite()) {
+        problems.push(format!("{} must be a positive load, got {}", field, value));
+    }
+}
+
+pub(crate) fn check_intensity(
+    field: &str,
+    intensity: &IntensityParams,
+    problems: &mut Vec<String>,
+) {
+    match intensity {
+        IntensityParams::Rpe { rpe } => {
+            if !(1.0..=10.0).contains(rpe) {
+                problems.push(format!("{}.rpe must be 1-10, got {}", field, rpe));
+            }
         }
+        IntensityParams::Rir { rir } => {
+            if !(0.0..=10.0).contains(rir) {
+                problems.push(format!("{}.rir must be 0-10, got {}", field, rir));
+            }
+        }
+        IntensityParams::Percent1Rm { percent_1rm } => {
+            check_percent(&format!("{}.percent_1rm", field), *percent_1rm, problems)
+        }
+        IntensityParams::Load { load } => check_load(&format!("{}.load", field), *load, problems),
+    }
+}
+
+fn check_steps(steps: &[PercentStep], problems: &mut Vec<String>) {
+    if steps.is_empty() {
+        problems.push("steps must not be empty".to_string());
+    }
+    for (i, step) in steps.iter().enumerate() {
+        check_count(&format!("steps[{}].reps", i), step.reps, MAX_REPS, problems);
+        check_percent(&format!("steps[{}].percent", i), step.percent, problems);
     }
 }
 
 fn expand_top_backoff(exercise: &str, params: &TopBackoffParams) -> Result<ExpandedScheme, String> {
     let mut sets = Vec::new();
+    let mut intensities = vec![params.top.intensity.clone()];
     let mut total_reps = 0;
 
     // Create top set
@@ -130,22 +466,17 @@ fn expand_top_backoff(exercise: &str, params: &TopBackoffParams) -> Result<Expan
             &backoff_intensity,
             Some(format!("Backoff {}", i + 1)),
         )?;
-        total_reps += backoff.sets * backoff.reps;
+        total_reps += backoff.sets.saturating_mul(backoff.reps);
         sets.push(backoff_set);
+        intensities.push(backoff_intensity);
     }
 
-    let estimated_duration = estimate_duration(&sets);
-
-    Ok(ExpandedScheme {
-        exercise: exercise.to_string(),
-        sets,
-        estimated_duration,
-        total_volume_reps: total_reps,
-    })
+    Ok(finish(exercise, sets, intensities, total_reps))
 }
 
 fn expand_drop_set(exercise: &str, params: &DropSetParams) -> Result<ExpandedScheme, String> {
     let mut sets = Vec::new();
+    let mut intensities = vec![params.start.intensity.clone()];
     let mut total_reps = 0;
 
     // Starting set
@@ -178,16 +509,10 @@ fn expand_drop_set(exercise: &str, params: &DropSetParams) -> Result<ExpandedSch
         )?;
         total_reps += drop.reps;
         sets.push(drop_set);
+        intensities.push(drop_intensity);
     }
 
-    let estimated_duration = estimate_duration(&sets);
-
-    Ok(ExpandedScheme {
-        exercise: exercise.to_string(),
-        sets,
-        estimated_duration,
-        total_volume_reps: total_reps,
-    })
+    Ok(finish(exercise, sets, intensities, total_reps))
 }
 
 fn expand_cluster(exercise: &str, params: &ClusterParams) -> Result<ExpandedScheme, String> {
@@ -205,18 +530,223 @@ fn expand_cluster(exercise: &str, params: &ClusterParams) -> Result<ExpandedSche
         )?;
         sets.push(cluster_set);
     }
+    let intensities = vec![params.intensity.clone(); sets.len()];
 
-    let estimated_duration = estimate_duration(&sets);
+    Ok(finish(exercise, sets, intensities, total_reps))
+}
+
+/// Percentages of the training max and reps for each 5/3/1 week; the last
+/// set of weeks 1-3 is taken for as many reps as possible
+const FIVE_THREE_ONE_WEEKS: [[(f64, u32); 3]; 4] = [
+    [(0.65, 5), (0.75, 5), (0.85, 5)],
+    [(0.70, 3), (0.80, 3), (0.90, 3)],
+    [(0.75, 5), (0.85, 3), (0.95, 1)],
+    [(0.40, 5), (0.50, 5), (0.60, 5)],
+];
+
+fn expand_five_three_one(
+    exercise: &str,
+    params: &FiveThreeOneParams,
+) -> Result<ExpandedScheme, String> {
+    let week = FIVE_THREE_ONE_WEEKS[params.week as usize - 1];
+    let mut sets = Vec::new();
+    let mut intensities = Vec::new();
+    let mut total_reps = 0;
+
+    for (i, &(percent, reps)) in week.iter().enumerate() {
+        let intensity = match params.training_max {
+            Some(training_max) => IntensityParams::Load {
+                load: training_max * percent,
+            },
+            None => IntensityParams::Percent1Rm {
+                percent_1rm: percent * params.tm_percent,
+            },
+        };
+        let amrap = params.week < 4 && i == week.len() - 1;
+        let label = format!(
+            "{:.0}% TM x{}{}",
+            percent * 100.0,
+            reps,
+            if amrap { "+" } else { "" }
+        );
+        let mut set = create_straight_segment(exercise, 1, reps, &intensity, Some(label))?;
+        set.rest_sec = params.rest_sec.map(RestOrRange::Fixed);
+        total_reps += reps;
+        sets.push(set);
+        intensities.push(intensity);
+    }
+
+    Ok(finish(exercise, sets, intensities, total_reps))
+}
+
+fn expand_pyramid(exercise: &str, params: &PyramidParams) -> Result<ExpandedScheme, String> {
+    let ascending = params.steps.iter();
+    let steps: Vec<&PercentStep> = match params.direction {
+        PyramidDirection::Ascending => ascending.collect(),
+        PyramidDirection::Descending => ascending.rev().collect(),
+        PyramidDirection::Full => ascending.clone().chain(ascending.rev().skip(1)).collect(),
+    };
+    expand_percent_steps(exercise, steps.into_iter().cloned(), params.rest_sec, |i| {
+        format!("Step {}", i + 1)
+    })
+}
+
+fn expand_ramp(exercise: &str, params: &RampParams) -> Result<ExpandedScheme, String> {
+    let spacing = if params.ramp_sets > 1 {
+        (params.end_percent - params.start_percent) / (params.ramp_sets - 1) as f64
+    } else {
+        0.0
+    };
+    let ramp = (0..params.ramp_sets).map(|i| PercentStep {
+        reps: params.ramp_reps,
+        percent: params.start_percent + spacing * i as f64,
+    });
+    let mut expanded = expand_percent_steps(exercise, ramp, params.rest_sec, |i| {
+        format!("Ramp {}", i + 1)
+    })?;
+
+    let label = if params.top.reps == 1 {
+        "Top single"
+    } else {
+        "Top set"
+    };
+    let mut top = create_straight_segment(
+        exercise,
+        1,
+        params.top.reps,
+        &params.top.intensity,
+        Some(label.to_string()),
+    )?;
+    top.rest_sec = params.rest_sec.map(RestOrRange::Fixed);
+    expanded.sets.push(top);
+    expanded.intensities.push(params.top.intensity.clone());
+    expanded.total_volume_reps += params.top.reps;
+    expanded.estimated_duration = estimate_duration(&expanded.sets);
+    Ok(expanded)
+}
+
+fn expand_rest_pause(
+    exercise: &str,
+    params: &RestPauseParams,
+    kind: IntensifierKind,
+) -> Result<ExpandedScheme, String> {
+    let (label, default_pause) = match kind {
+        IntensifierKind::Myo => ("Myo-reps", 5),
+        _ => ("Rest-pause", 15),
+    };
+    let mut set = create_straight_segment(
+        exercise,
+        1,
+        params.activation_reps,
+        &params.intensity,
+        Some(label.to_string()),
+    )?;
+    set.intensifier = Some(Intensifier {
+        kind,
+        when: None,
+        drop_pct: None,
+        steps: Some(params.mini_sets),
+        clusters: None,
+        reps_per_cluster: Some(params.mini_reps),
+        intra_rest_sec: Some(params.pause_sec.unwrap_or(default_pause)),
+    });
+    let total_reps = params.activation_reps + params.mini_sets * params.mini_reps;
+
+    Ok(finish(
+        exercise,
+        vec![set],
+        vec![params.intensity.clone()],
+        total_reps,
+    ))
+}
+
+fn expand_emom(exercise: &str, params: &EmomParams) -> Result<ExpandedScheme, String> {
+    let label = match params.every_sec {
+        60 => "EMOM".to_string(),
+        sec if sec % 60 == 0 => format!("E{}MOM", sec / 60),
+        sec => format!("Every {}s", sec),
+    };
+    let mut set = create_straight_segment(
+        exercise,
+        params.rounds,
+        params.reps,
+        &params.intensity,
+        Some(label),
+    )?;
+    set.interval = Some(TimeInterval {
+        work: params.every_sec,
+        rest: 0,
+        repeats: params.rounds,
+    });
+
+    Ok(finish(
+        exercise,
+        vec![set],
+        vec![params.intensity.clone()],
+        params.rounds * params.reps,
+    ))
+}
 
-    Ok(ExpandedScheme {
+fn expand_wave(exercise: &str, params: &WaveParams) -> Result<ExpandedScheme, String> {
+    let per_wave = params.steps.len();
+    let steps = (0..params.waves).flat_map(|wave| {
+        params.steps.iter().map(move |step| PercentStep {
+            reps: step.reps,
+            percent: step.percent + params.increment * wave as f64,
+        })
+    });
+    expand_percent_steps(exercise, steps, params.rest_sec, |i| {
+        format!("Wave {} set {}", i / per_wave + 1, i % per_wave + 1)
+    })
+}
+
+/// One single set per step at its %1RM, labelled by `label(index)`
+fn expand_percent_steps<I, L>(
+    exercise: &str,
+    steps: I,
+    rest_sec: Option<u32>,
+    label: L,
+) -> Result<ExpandedScheme, String>
+where
+    I: Iterator<Item = PercentStep>,
+    L: Fn(usize) -> String,
+{
+    let mut sets = Vec::new();
+    let mut intensities = Vec::new();
+    let mut total_reps = 0;
+
+    for (i, step) in steps.enumerate() {
+        let intensity = IntensityParams::Percent1Rm {
+            percent_1rm: step.percent,
+        };
+        let label = format!("{} ({:.0}%)", label(i), step.percent * 100.0);
+        let mut set = create_straight_segment(exercise, 1, step.reps, &intensity, Some(label))?;
+        set.rest_sec = rest_sec.map(RestOrRange::Fixed);
+        total_reps += step.reps;
+        sets.push(set);
+        intensities.push(intensity);
+    }
+
+    Ok(finish(exercise, sets, intensities, total_reps))
+}
+
+pub(crate) fn finish(
+    exercise: &str,
+    sets: Vec<StraightSegment>,
+    intensities: Vec<IntensityParams>,
+    total_volume_reps: u32,
+) -> ExpandedScheme {
+    let estimated_duration = estimate_duration(&sets);
+    ExpandedScheme {
         exercise: exercise.to_string(),
         sets,
+        intensities,
         estimated_duration,
-        total_volume_reps: total_reps,
-    })
+        total_volume_reps,
+    }
 }
 
-fn create_straight_segment(
+pub(crate) fn create_straight_segment(
     exercise: &str,
     sets: u32,
     reps: u32,
@@ -274,19 +804,284 @@ fn create_straight_segment(
 
 fn estimate_duration(sets: &[StraightSegment]) -> u32 {
     // Simple estimation: 30 seconds per set + rest time
-    let mut total = 0;
+    // Saturates rather than overflowing on absurd counts or rests
+    let mut total: u32 = 0;
     for set in sets {
+        // Interval sets take exactly their timed windows
+        if let Some(interval) = &set.interval {
+            total = total.saturating_add(
+                interval
+                    .repeats
+                    .saturating_mul(interval.work.saturating_add(interval.rest)),
+            );
+            continue;
+        }
+
         let set_count = set.sets.unwrap_or(1);
-        total += set_count * 30; // 30 seconds per set execution
+        total = total.saturating_add(set_count.saturating_mul(30)); // 30 seconds per set execution
+
+        // Mini-sets: about 10 seconds of work after each pause
+        if let Some(intensifier) = &set.intensifier {
+            let mini_sets = intensifier.steps.unwrap_or(0);
+            let pause = intensifier.intra_rest_sec.unwrap_or(0);
+            total = total.saturating_add(
+                set_count
+                    .saturating_mul(mini_sets)
+                    .saturating_mul(pause.saturating_add(10)),
+            );
+        }
 
         if let Some(rest) = &set.rest_sec {
-            match rest {
-                crate::RestOrRange::Fixed(seconds) => total += set_count * seconds,
+            let seconds = match rest {
+                crate::RestOrRange::Fixed(seconds) => *seconds,
                 crate::RestOrRange::Range(range) => {
-                    total += set_count * ((range.min + range.max) / 2)
+                    ((range.min as u64 + range.max as u64) / 2) as u32
                 }
-            }
+            };
+            total = total.saturating_add(set_count.saturating_mul(seconds));
         }
     }
     total
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn template(value: serde_json::Value) -> SchemeTemplate {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn labels(expanded: &ExpandedScheme) -> Vec<&str> {
+        expanded
+            .sets
+            .iter()
+            .map(|set| set.base.label.as_deref().unwrap_or(""))
+            .collect()
+    }
+
+    #[test]
+    fn test_templates_round_trip() {
+        let steps = json!([{"reps": 8, "percent": 0.7}, {"reps": 5, "percent": 0.8}]);
+        let templates = [
+            json!({
+                "name": "five_three_one",
+                "week": 3,
+                "training_max": 140.0,
+                "tm_percent": 0.9,
+                "rest_sec": 180
+            }),
+            json!({"name": "pyramid", "steps": steps, "direction": "full"}),
+            json!({
+                "name": "ramp",
+                "ramp_sets": 4,
+                "ramp_reps": 3,
+                "start_percent": 0.5,
+                "end_percent": 0.8,
+                "top": {"reps": 1, "intensity": {"rpe": 8.0}}
+            }),
+            json!({
+                "name": "rest_pause",
+                "activation_reps": 8,
+                "mini_sets": 3,
+                "mini_reps": 3,
+                "intensity": {"rir": 1.0}
+            }),
+            json!({
+                "name": "myo_reps",
+                "activation_reps": 15,
+                "mini_sets": 4,
+                "mini_reps": 4,
+                "pause_sec": 5,
+                "intensity": {"load": 40.0}
+            }),
+            json!({
+                "name": "emom",
+                "rounds": 10,
+                "reps": 2,
+                "every_sec": 120,
+                "intensity": {"percent_1rm": 0.75}
+            }),
+            json!({"name": "wave", "steps": steps, "waves": 2, "increment": 0.025}),
+        ];
+        for value in templates {
+            let parsed = template(value.clone());
+            let again = serde_json::to_value(&parsed).unwrap();
+            assert_eq!(again, value);
+            assert!(template(again).check_params().is_empty());
+            assert!(parsed.expand("SQ.BB.BACK").is_ok(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn test_five_three_one_weeks() {
+        let week_one = json!({"name": "five_three_one", "week": 1, "training_max": 100.0});
+        let expanded = template(week_one).expand("SQ.BB.BACK").unwrap();
+        assert_eq!(labels(&expanded), ["65% TM x5", "75% TM x5", "85% TM x5+"]);
+        assert_eq!(
+            expanded.intensities[2],
+            IntensityParams::Load { load: 85.0 }
+        );
+        assert_eq!(expanded.total_volume_reps, 15);
+
+        let deload = template(json!({"name": "five_three_one", "week": 4}))
+            .expand("SQ.BB.BACK")
+            .unwrap();
+        assert!(!labels(&deload)[2].ends_with('+'));
+        match deload.intensities[0] {
+            IntensityParams::Percent1Rm { percent_1rm } => {
+                assert!((percent_1rm - 0.36).abs() < 1e-9)
+            }
+            ref other => panic!("unexpected intensity {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_pyramid_ramp_and_wave_orders() {
+        let steps = json!([
+            {"reps": 10, "percent": 0.6},
+            {"reps": 8, "percent": 0.7},
+            {"reps": 6, "percent": 0.8}
+        ]);
+        let full = template(json!({"name": "pyramid", "steps": steps, "direction": "full"}))
+            .expand("BP.BB.FLAT")
+            .unwrap();
+        let reps: Vec<u32> = full
+            .sets
+            .iter()
+            .map(|s| match &s.reps {
+                Some(RepsOrRange::Range(range)) => range.min,
+                other => panic!("unexpected reps {:?}", other),
+            })
+            .collect();
+        assert_eq!(reps, [10, 8, 6, 8, 10]);
+        let descending = json!({"name": "pyramid", "steps": steps, "direction": "descending"});
+        let descending = template(descending).expand("BP.BB.FLAT").unwrap();
+        assert_eq!(labels(&descending)[0], "Step 1 (80%)");
+
+        let ramp = template(json!({
+            "name": "ramp",
+            "ramp_sets": 3,
+            "ramp_reps": 3,
+            "start_percent": 0.5,
+            "end_percent": 0.8,
+            "top": {"reps": 1, "intensity": {"rpe": 9.0}}
+        }))
+        .expand("DL.BB")
+        .unwrap();
+        assert_eq!(
+            labels(&ramp),
+            ["Ramp 1 (50%)", "Ramp 2 (65%)", "Ramp 3 (80%)", "Top single"]
+        );
+        assert_eq!(ramp.sets[3].rpe, Some(9.0));
+
+        let wave = template(json!({
+            "name": "wave",
+            "steps": [{"reps": 3, "percent": 0.8}, {"reps": 1, "percent": 0.9}],
+            "waves": 2,
+            "increment": 0.05,
+            "rest_sec": 120
+        }))
+        .expand("SQ.BB.BACK")
+        .unwrap();
+        assert_eq!(
+            labels(&wave),
+            [
+                "Wave 1 set 1 (80%)",
+                "Wave 1 set 2 (90%)",
+                "Wave 2 set 1 (85%)",
+                "Wave 2 set 2 (95%)"
+            ]
+        );
+        // Four sets of 30 seconds, each followed by 120 seconds rest
+        assert_eq!(wave.estimated_duration, 4 * 150);
+    }
+
+    #[test]
+    fn test_intensifier_and_interval_templates() {
+        let rest_pause = template(json!({
+            "name": "rest_pause",
+            "activation_reps": 8,
+            "mini_sets": 3,
+            "mini_reps": 3,
+            "intensity": {"rpe": 9.0}
+        }))
+        .expand("BP.BB.FLAT")
+        .unwrap();
+        let intensifier = rest_pause.sets[0].intensifier.as_ref().unwrap();
+        assert!(matches!(intensifier.kind, IntensifierKind::RestPause));
+        assert_eq!(intensifier.intra_rest_sec, Some(15));
+        assert_eq!(rest_pause.total_volume_reps, 17);
+        assert_eq!(rest_pause.estimated_duration, 30 + 3 * 25);
+
+        let myo = template(json!({
+            "name": "myo_reps",
+            "activation_reps": 15,
+            "mini_sets": 4,
+            "mini_reps": 4,
+            "intensity": {"rir": 1.0}
+        }))
+        .expand("CURL.DB")
+        .unwrap();
+        let intensifier = myo.sets[0].intensifier.as_ref().unwrap();
+        assert_eq!(intensifier.intra_rest_sec, Some(5));
+
+        let e2mom = template(json!({
+            "name": "emom",
+            "rounds": 8,
+            "reps": 2,
+            "every_sec": 120,
+            "intensity": {"percent_1rm": 0.8}
+        }))
+        .expand("CJ.BB")
+        .unwrap();
+        assert_eq!(labels(&e2mom), ["E2MOM"]);
+        assert_eq!(e2mom.estimated_duration, 16 * 60);
+        assert_eq!(e2mom.total_volume_reps, 16);
+    }
+
+    #[test]
+    fn test_check_params() {
+        let five_three_one = json!({"name": "five_three_one", "week": 5, "tm_percent": 90.0});
+        assert_eq!(template(five_three_one).check_params().len(), 2);
+
+        let wave = template(json!({
+            "name": "wave",
+            "steps": [{"reps": 1, "percent": 1.1}],
+            "waves": 4,
+            "increment": 0.05
+        }));
+        let error = wave.expand("SQ.BB.BACK").unwrap_err();
+        assert!(error.contains("final wave"), "{}", error);
+
+        let top_backoff = template(json!({
+            "name": "top_backoff",
+            "top": {"reps": 3, "intensity": {"rpe": 8.0}},
+            "backoff": [{"percent": 90.0, "sets": 3, "reps": 5}]
+        }));
+        assert_eq!(top_backoff.check_params().len(), 1);
+
+        let pyramid = template(json!({"name": "pyramid", "steps": []}));
+        assert!(pyramid.expand("SQ.BB.BACK").is_err());
+
+        // Huge counts are rejected instead of overflowing the rep and duration totals
+        let emom = template(json!({
+            "name": "emom",
+            "every_sec": 4_000_000,
+            "rounds": 5_000_000,
+            "reps": 1000,
+            "intensity": {"rpe": 7.0}
+        }));
+        assert_eq!(emom.check_params().len(), 2);
+        let sets = [create_straight_segment(
+            "SQ.BB.BACK",
+            u32::MAX,
+            5,
+            &IntensityParams::Rpe { rpe: 8.0 },
+            None,
+        )
+        .unwrap()];
+        assert_eq!(estimate_duration(&sets), u32::MAX);
+    }
+}
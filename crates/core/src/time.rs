@@ -8,7 +8,7 @@ pub fn now_utc_rfc3339() -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::{offset::TimeZone, DateTime};
+    use chrono::DateTime;
 
     #[test]
     fn test_now_utc_rfc3339_is_utc() {
@@ -0,0 +1,164 @@
+use crate::loads::TrainingMaxes;
+use crate::paths::AppPaths;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum TrainingMaxError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Invalid training max for {0}: {1} (must be a positive number of kg)")]
+    InvalidMax(String, f64),
+}
+
+/// Where a training max came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrainingMaxSource {
+    /// Entered by the user
+    Manual,
+    /// Seeded from an estimated 1RM in the metrics cache
+    E1rm,
+    /// Advanced by the progression engine
+    Progression,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrainingMaxEntry {
+    pub kg: f64,
+    pub updated: NaiveDate,
+    pub source: TrainingMaxSource,
+}
+
+/// Training max per exercise code, stored at `AppPaths::training_maxes_path`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrainingMaxRegistry {
+    #[serde(default)]
+    pub entries: BTreeMap<String, TrainingMaxEntry>,
+}
+
+impl TrainingMaxRegistry {
+    /// Load the registry; a missing file is an empty registry
+    pub fn load(paths: &AppPaths) -> Result<Self, TrainingMaxError> {
+        match fs::read_to_string(paths.training_maxes_path()) {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, paths: &AppPaths) -> Result<(), TrainingMaxError> {
+        fs::create_dir_all(&paths.data_dir)?;
+        fs::write(
+            paths.training_maxes_path(),
+            serde_json::to_string_pretty(self)?,
+        )?;
+        Ok(())
+    }
+
+    pub fn get(&self, ex: &str) -> Option<&TrainingMaxEntry> {
+        self.entries.get(ex)
+    }
+
+    pub fn set(
+        &mut self,
+        ex: &str,
+        kg: f64,
+        source: TrainingMaxSource,
+        updated: NaiveDate,
+    ) -> Result<(), TrainingMaxError> {
+        if !kg.is_finite() || kg <= 0.0 {
+            return Err(TrainingMaxError::InvalidMax(ex.to_string(), kg));
+        }
+        self.entries.insert(
+            ex.to_string(),
+            TrainingMaxEntry {
+                kg,
+                updated,
+                source,
+            },
+        );
+        Ok(())
+    }
+
+    pub fn remove(&mut self, ex: &str) -> Option<TrainingMaxEntry> {
+        self.entries.remove(ex)
+    }
+
+    /// Add `fraction` of each e1RM for exercises without a training max yet (5/3/1
+    /// style programs use 0.9). Existing entries are kept; returns the codes added.
+    pub fn seed(&mut self, e1rms: &TrainingMaxes, fraction: f64, date: NaiveDate) -> Vec<String> {
+        let mut added = Vec::new();
+        for (ex, e1rm) in e1rms {
+            let kg = e1rm * fraction;
+            if self.entries.contains_key(ex) || !kg.is_finite() || kg <= 0.0 {
+                continue;
+            }
+            self.entries.insert(
+                ex.clone(),
+                TrainingMaxEntry {
+                    kg,
+                    updated: date,
+                    source: TrainingMaxSource::E1rm,
+                },
+            );
+            added.push(ex.clone());
+        }
+        added
+    }
+
+    /// Plain kg values, as taken by `LoadCalculator` and the progression engine
+    pub fn maxes(&self) -> TrainingMaxes {
+        self.entries
+            .iter()
+            .map(|(ex, entry)| (ex.clone(), entry.kg))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_round_trip_and_seed() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = AppPaths {
+            data_dir: dir.path().join("data"),
+            state_dir: dir.path().join("state"),
+            cache_dir: dir.path().join("cache"),
+        };
+        let date = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+
+        let mut registry = TrainingMaxRegistry::load(&paths).unwrap();
+        assert!(registry.entries.is_empty());
+        registry
+            .set("SQ.BB.BACK", 180.0, TrainingMaxSource::Manual, date)
+            .unwrap();
+        assert!(registry
+            .set("BP.BB.FLAT", -5.0, TrainingMaxSource::Manual, date)
+            .is_err());
+
+        let e1rms = TrainingMaxes::from([
+            ("SQ.BB.BACK".to_string(), 200.0),
+            ("BP.BB.FLAT".to_string(), 130.0),
+        ]);
+        assert_eq!(registry.seed(&e1rms, 0.9, date), ["BP.BB.FLAT"]);
+        assert_eq!(registry.maxes()["SQ.BB.BACK"], 180.0);
+        assert!((registry.maxes()["BP.BB.FLAT"] - 117.0).abs() < 1e-9);
+        assert_eq!(
+            registry.get("BP.BB.FLAT").unwrap().source,
+            TrainingMaxSource::E1rm
+        );
+
+        registry.save(&paths).unwrap();
+        assert_eq!(TrainingMaxRegistry::load(&paths).unwrap(), registry);
+        assert!(registry.remove("SQ.BB.BACK").is_some());
+        assert!(registry.get("SQ.BB.BACK").is_none());
+    }
+}
@@ -0,0 +1,98 @@
+use crate::models::Unit;
+use crate::paths::AppPaths;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/// Pounds per kilogram; metrics are stored in kg and converted only for display
+pub const LB_PER_KG: f64 = 2.20462;
+
+/// Weight unit used for chart axes, labels and CSV exports
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DisplayUnit {
+    #[default]
+    Kg,
+    Lb,
+}
+
+impl DisplayUnit {
+    /// Short unit label, also used as the suffix of unit-specific data fields
+    pub fn label(&self) -> &'static str {
+        match self {
+            DisplayUnit::Kg => "kg",
+            DisplayUnit::Lb => "lb",
+        }
+    }
+
+    /// Convert a stored kg value into this unit
+    pub fn convert(&self, kg: f64) -> f64 {
+        match self {
+            DisplayUnit::Kg => kg,
+            DisplayUnit::Lb => kg * LB_PER_KG,
+        }
+    }
+
+    /// Unit-specific field name, e.g. `e1rm` -> `e1rm_lb`
+    pub fn field(&self, base: &str) -> String {
+        format!("{}_{}", base, self.label())
+    }
+}
+
+/// Bodyweight plans still log external load in kg
+impl From<&Unit> for DisplayUnit {
+    fn from(unit: &Unit) -> Self {
+        match unit {
+            Unit::Lb => DisplayUnit::Lb,
+            Unit::Kg | Unit::Bw => DisplayUnit::Kg,
+        }
+    }
+}
+
+impl std::str::FromStr for DisplayUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "kg" => Ok(DisplayUnit::Kg),
+            "lb" | "lbs" => Ok(DisplayUnit::Lb),
+            other => Err(format!("Unknown unit: {}. Available: kg, lb", other)),
+        }
+    }
+}
+
+/// User preferences stored at `AppPaths::preferences_path`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UserPreferences {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_unit: Option<DisplayUnit>,
+}
+
+impl UserPreferences {
+    /// Load preferences, falling back to defaults when the file is missing or unreadable
+    pub fn load(paths: &AppPaths) -> Self {
+        fs::read_to_string(paths.preferences_path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, paths: &AppPaths) -> Result<(), std::io::Error> {
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(paths.preferences_path(), content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_unit() {
+        assert_eq!("LB".parse::<DisplayUnit>(), Ok(DisplayUnit::Lb));
+        assert!("stone".parse::<DisplayUnit>().is_err());
+        assert_eq!(DisplayUnit::from(&Unit::Bw), DisplayUnit::Kg);
+        assert_eq!(DisplayUnit::Lb.field("tonnage"), "tonnage_lb");
+        assert!((DisplayUnit::Lb.convert(100.0) - 220.462).abs() < 1e-9);
+        assert_eq!(DisplayUnit::Kg.convert(100.0), 100.0);
+    }
+}
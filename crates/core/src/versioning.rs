@@ -117,6 +117,39 @@ impl PlanVersion {
         self.minor = 0;
         self.patch = 0;
     }
+
+    /// (major, minor, patch) for ordering; the build identifier is ignored
+    pub fn sort_key(&self) -> (u32, u32, u32) {
+        (self.major, self.minor, self.patch)
+    }
+}
+
+impl std::str::FromStr for PlanVersion {
+    type Err = String;
+
+    /// Parse `major.minor.patch` with an optional `+build` suffix
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid version format: {}. Expected major.minor.patch", s);
+        let (numbers, build) = match s.split_once('+') {
+            Some((numbers, build)) => (numbers, Some(build.to_string())),
+            None => (s, None),
+        };
+
+        let parts: Vec<u32> = numbers
+            .split('.')
+            .map(|part| part.parse::<u32>().map_err(|_| invalid()))
+            .collect::<Result<_, _>>()?;
+        let [major, minor, patch] = parts[..] else {
+            return Err(invalid());
+        };
+
+        Ok(Self {
+            major,
+            minor,
+            patch,
+            build,
+        })
+    }
 }
 
 impl std::fmt::Display for PlanVersion {
@@ -190,6 +223,14 @@ impl PlanVersionManager {
         Ok(versioned_plan)
     }
 
+    /// Register an existing version (e.g. one loaded from disk), keeping versions ordered
+    pub fn add_version(&mut self, plan_id: String, versioned_plan: VersionedPlan) {
+        let versions = self.versions.entry(plan_id).or_default();
+        let key = versioned_plan.version.sort_key();
+        let idx = versions.partition_point(|v| v.version.sort_key() <= key);
+        versions.insert(idx, versioned_plan);
+    }
+
     /// Get the latest version of a plan
     pub fn get_latest_version(&self, plan_id: &str) -> Option<&VersionedPlan> {
         self.versions.get(plan_id)?.last()
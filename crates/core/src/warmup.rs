@@ -0,0 +1,289 @@
+use crate::loads::LoadEquipment;
+use crate::location::{LocationProfile, RoundingPreview};
+use crate::{Segment, Unit, WarmupConfig, LB_PER_KG};
+use serde::Serialize;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Ladder used when a config has no `stages`: (fraction, reps)
+pub const DEFAULT_WARMUP_STAGES: [(f64, u32); 3] = [(0.4, 5), (0.6, 3), (0.8, 2)];
+
+#[derive(Error, Debug)]
+pub enum WarmupError {
+    #[error("Unknown warm-up pattern: {0}. Available: percent_of_top, percent_of_1rm")]
+    UnknownPattern(String),
+    #[error("Invalid warm-up: {0}")]
+    Invalid(String),
+    #[error("Warm-up pattern percent_of_1rm needs a training max")]
+    MissingTrainingMax,
+}
+
+/// What a stage's `pct_1rm` is a fraction of
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarmupPattern {
+    /// The working set's load
+    #[default]
+    PercentOfTop,
+    /// The exercise's training max
+    PercentOf1Rm,
+}
+
+impl FromStr for WarmupPattern {
+    type Err = WarmupError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "percent_of_top" => Ok(WarmupPattern::PercentOfTop),
+            "percent_of_1rm" => Ok(WarmupPattern::PercentOf1Rm),
+            other => Err(WarmupError::UnknownPattern(other.to_string())),
+        }
+    }
+}
+
+/// One generated warm-up set
+#[derive(Debug, Clone, Serialize)]
+pub struct WarmupSet {
+    /// Stage fraction of the working load (or training max for `percent_of_1rm`)
+    pub pct: f64,
+    pub reps: u32,
+    /// Stage load after `round_to`
+    pub target_kg: f64,
+    /// Target rounded to the location's equipment, with the plate breakdown for barbells
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rounded: Option<RoundingPreview>,
+    /// Always set; logged as `is_warmup`
+    pub is_warmup: bool,
+}
+
+impl WarmupSet {
+    /// The load to put on the bar: rounded when a location is known
+    pub fn load_kg(&self) -> f64 {
+        self.rounded
+            .as_ref()
+            .map_or(self.target_kg, |rounded| rounded.rounded_load)
+    }
+}
+
+/// Builds the warm-up ladder leading into a working set from a plan's `warmup` config.
+///
+/// Stages run lightest first. Loads are rounded to `round_to` (in the plan's unit),
+/// then to the location's equipment; stages that land at or above the working load
+/// are dropped, and with `merge_after_rounding` stages that round to the same load
+/// become one set with the higher rep count.
+#[derive(Debug, Clone)]
+pub struct WarmupGenerator<'a> {
+    config: &'a WarmupConfig,
+    pattern: WarmupPattern,
+    stages: Vec<(f64, u32)>,
+    step_kg: Option<f64>,
+    location: Option<&'a LocationProfile>,
+    equipment: LoadEquipment,
+}
+
+impl<'a> WarmupGenerator<'a> {
+    pub fn new(config: &'a WarmupConfig) -> Result<Self, WarmupError> {
+        let pattern = match config.pattern.as_deref() {
+            Some(pattern) => pattern.parse()?,
+            None => WarmupPattern::default(),
+        };
+        let mut stages: Vec<(f64, u32)> = match &config.stages {
+            Some(stages) => stages.iter().map(|s| (s.pct_1rm, s.reps)).collect(),
+            None => DEFAULT_WARMUP_STAGES.to_vec(),
+        };
+        if let Some((pct, reps)) = stages
+            .iter()
+            .find(|(pct, reps)| pct.is_nan() || *pct <= 0.0 || *pct > 1.0 || *reps == 0)
+        {
+            return Err(WarmupError::Invalid(format!(
+                "stage {} x {} needs a pct_1rm in (0, 1] and at least one rep",
+                pct, reps
+            )));
+        }
+        stages.sort_by(|a, b| a.0.total_cmp(&b.0));
+        if let Some(step) = config.round_to.filter(|step| step.is_nan() || *step <= 0.0) {
+            return Err(WarmupError::Invalid(format!(
+                "round_to must be positive, got {}",
+                step
+            )));
+        }
+
+        Ok(Self {
+            config,
+            pattern,
+            stages,
+            step_kg: config.round_to,
+            location: None,
+            equipment: LoadEquipment::Other,
+        })
+    }
+
+    /// Read `round_to` in the plan's unit
+    pub fn unit(mut self, unit: &Unit) -> Self {
+        self.step_kg = match unit {
+            Unit::Lb => self.config.round_to.map(|step| step / LB_PER_KG),
+            _ => self.config.round_to,
+        };
+        self
+    }
+
+    /// Round stage loads to what this location's bars, plates and dumbbells can make
+    pub fn location(mut self, location: &'a LocationProfile, equipment: LoadEquipment) -> Self {
+        self.location = Some(location);
+        self.equipment = equipment;
+        self
+    }
+
+    pub fn pattern(&self) -> WarmupPattern {
+        self.pattern
+    }
+
+    /// Stage fractions and reps, lightest first
+    pub fn stages(&self) -> &[(f64, u32)] {
+        &self.stages
+    }
+
+    /// Warm-up sets before a working set of `working_kg`
+    pub fn sets(
+        &self,
+        working_kg: f64,
+        training_max_kg: Option<f64>,
+    ) -> Result<Vec<WarmupSet>, WarmupError> {
+        let base = match self.pattern {
+            WarmupPattern::PercentOfTop => working_kg,
+            WarmupPattern::PercentOf1Rm => {
+                training_max_kg.ok_or(WarmupError::MissingTrainingMax)?
+            }
+        };
+
+        let mut sets: Vec<WarmupSet> = Vec::new();
+        for &(pct, reps) in &self.stages {
+            let target_kg = match self.step_kg {
+                Some(step) => (base * pct / step).round() * step,
+                None => base * pct,
+            };
+            let set = WarmupSet {
+                pct,
+                reps,
+                target_kg,
+                rounded: self
+                    .location
+                    .and_then(|location| self.equipment.round(location, target_kg)),
+                is_warmup: true,
+            };
+            let load = set.load_kg();
+            if load <= 0.0 || load >= working_kg - 1e-6 {
+                continue;
+            }
+            match sets.last_mut() {
+                Some(last)
+                    if self.config.merge_after_rounding == Some(true)
+                        && (last.load_kg() - load).abs() < 1e-6 =>
+                {
+                    last.reps = last.reps.max(reps);
+                }
+                _ => sets.push(set),
+            }
+        }
+        Ok(sets)
+    }
+}
+
+/// The exercise a segment's warm-ups are for: the lift of a straight, RPE, percentage
+/// or scheme segment, or a complex's first movement
+pub fn warmup_exercise(segment: &Segment) -> Option<&str> {
+    match segment {
+        Segment::Straight(s) => Some(&s.base.ex),
+        Segment::Rpe(s) => Some(&s.base.ex),
+        Segment::Percentage(s) => Some(&s.base.ex),
+        Segment::Scheme(s) => Some(&s.base.ex),
+        Segment::Complex(c) => c.sequence.first().map(|item| item.ex.as_str()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(value: serde_json::Value) -> WarmupConfig {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_ladder_rounding_and_merging() {
+        let warmup = config(serde_json::json!({
+            "pattern": "percent_of_top",
+            "stages": [
+                { "pct_1rm": 0.7, "reps": 3 },
+                { "pct_1rm": 0.3, "reps": 8 },
+                { "pct_1rm": 0.5, "reps": 5 },
+                { "pct_1rm": 0.95, "reps": 1 }
+            ],
+            "round_to": 2.5,
+            "merge_after_rounding": true
+        }));
+        let generator = WarmupGenerator::new(&warmup).unwrap();
+        let sets = generator.sets(100.0, None).unwrap();
+        let summary: Vec<(f64, u32)> = sets.iter().map(|s| (s.load_kg(), s.reps)).collect();
+        assert_eq!(summary, [(30.0, 8), (50.0, 5), (70.0, 3), (95.0, 1)]);
+        assert!(sets.iter().all(|s| s.is_warmup));
+
+        // On the home gym bar the two light stages both land on the empty bar
+        let home = LocationProfile::home_gym();
+        let light = generator
+            .location(&home, LoadEquipment::Barbell)
+            .sets(40.0, None)
+            .unwrap();
+        let bar = light[0].rounded.as_ref().unwrap().rounded_load;
+        assert_eq!((light[0].load_kg(), light[0].reps), (bar, 8));
+        assert!(light
+            .windows(2)
+            .all(|pair| pair[0].load_kg() < pair[1].load_kg()));
+        assert!(light.iter().all(|s| s.load_kg() < 40.0));
+
+        // Without merging the duplicate stays
+        let unmerged = config(serde_json::json!({
+            "stages": [{ "pct_1rm": 0.3, "reps": 8 }, { "pct_1rm": 0.32, "reps": 5 }],
+            "round_to": 5
+        }));
+        let sets = WarmupGenerator::new(&unmerged)
+            .unwrap()
+            .sets(100.0, None)
+            .unwrap();
+        assert_eq!(sets.len(), 2);
+        assert_eq!(sets[0].load_kg(), sets[1].load_kg());
+    }
+
+    #[test]
+    fn test_patterns_units_and_errors() {
+        let of_max = config(serde_json::json!({
+            "pattern": "percent_of_1rm",
+            "stages": [{ "pct_1rm": 0.5, "reps": 5 }],
+            "round_to": 5
+        }));
+        let generator = WarmupGenerator::new(&of_max).unwrap().unit(&Unit::Lb);
+        let sets = generator.sets(150.0, Some(200.0)).unwrap();
+        let step = 5.0 / LB_PER_KG;
+        assert!((sets[0].target_kg / step - (sets[0].target_kg / step).round()).abs() < 1e-9);
+        assert!((sets[0].target_kg - 100.0).abs() < step);
+        assert!(matches!(
+            generator.sets(150.0, None),
+            Err(WarmupError::MissingTrainingMax)
+        ));
+
+        let empty = config(serde_json::json!({}));
+        let defaults = WarmupGenerator::new(&empty).unwrap();
+        assert_eq!(defaults.stages(), DEFAULT_WARMUP_STAGES);
+        assert_eq!(defaults.sets(100.0, None).unwrap().len(), 3);
+
+        for bad in [
+            serde_json::json!({ "pattern": "ramp" }),
+            serde_json::json!({ "stages": [{ "pct_1rm": 1.5, "reps": 3 }] }),
+            serde_json::json!({ "stages": [{ "pct_1rm": 0.5, "reps": 0 }] }),
+            serde_json::json!({ "round_to": 0 }),
+        ] {
+            assert!(WarmupGenerator::new(&config(bad)).is_err());
+        }
+    }
+}
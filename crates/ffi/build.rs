@@ -12,7 +12,13 @@ fn main() {
     // Create include directory if it doesn't exist
     std::fs::create_dir_all(PathBuf::from(&crate_dir).join("include")).unwrap();
 
+    // Prefix enum variants (FFIErrorCode_None, FFIErrorCategory_None) so they
+    // don't collide in C's flat namespace
+    let mut config = cbindgen::Config::default();
+    config.enumeration.prefix_with_name = true;
+
     cbindgen::Builder::new()
+        .with_config(config)
         .with_crate(crate_dir)
         .with_language(cbindgen::Language::C)
         .with_pragma_once(true)
@@ -0,0 +1,22 @@
+//! Writes arbitrary bytes to a plan file and opens it through both open entry points.
+//! libfuzzer-sys's panic hook aborts on any panic, including ones the FFI guard
+//! would catch, so panics surface as crashes.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use std::ffi::CString;
+use weightlifting_ffi::{ffi_free_result, ffi_handle_open, ffi_plan_free, ffi_plan_open};
+
+fuzz_target!(|data: &[u8]| {
+    let path = std::env::temp_dir().join(format!("plan_open_{}.json", std::process::id()));
+    if std::fs::write(&path, data).is_err() {
+        return;
+    }
+    let c_path = CString::new(path.to_string_lossy().into_owned()).unwrap();
+
+    ffi_free_result(ffi_plan_open(c_path.as_ptr()));
+
+    let mut handle = std::ptr::null_mut();
+    ffi_free_result(ffi_handle_open(c_path.as_ptr(), &mut handle));
+    ffi_plan_free(handle);
+});
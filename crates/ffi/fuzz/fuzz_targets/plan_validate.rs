@@ -0,0 +1,25 @@
+//! Validates arbitrary input as plan JSON, both directly and through a handle.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use std::ffi::CString;
+use weightlifting_ffi::{
+    ffi_free_result, ffi_handle_check, ffi_handle_from_json, ffi_handle_validate, ffi_plan_free,
+    ffi_plan_validate,
+};
+
+fuzz_target!(|data: &[u8]| {
+    // C strings end at the first NUL, as they would coming from a host
+    let json = data.split(|&b| b == 0).next().unwrap_or_default();
+    let json = CString::new(json).unwrap();
+
+    ffi_free_result(ffi_plan_validate(json.as_ptr()));
+
+    let mut handle = std::ptr::null_mut();
+    ffi_free_result(ffi_handle_from_json(json.as_ptr(), &mut handle));
+    if !handle.is_null() {
+        ffi_free_result(ffi_handle_validate(handle));
+        ffi_free_result(ffi_handle_check(handle));
+        ffi_plan_free(handle);
+    }
+});
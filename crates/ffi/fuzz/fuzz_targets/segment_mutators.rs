@@ -0,0 +1,64 @@
+//! Drives the segment mutators with arbitrary segment JSON and indices.
+//! The first three bytes pick the operation, day and segment; the rest is the
+//! segment JSON, applied both to plan JSON and to a handle.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use std::ffi::{CStr, CString};
+use weightlifting_ffi::{
+    ffi_free_result, ffi_handle_from_json, ffi_handle_segment_add, ffi_handle_segment_remove,
+    ffi_handle_segment_update, ffi_handle_validate, ffi_plan_free, ffi_plan_validate,
+    ffi_segment_add, ffi_segment_remove, ffi_segment_update, FFIResult,
+};
+
+const PLAN: &str = r#"{
+    "name": "Fuzz",
+    "unit": "kg",
+    "dictionary": {"SQ.BB.BACK": "Back Squat"},
+    "groups": {},
+    "schedule": [
+        {"day": 1, "label": "Lower", "segments": [
+            {"type": "straight", "ex": "SQ.BB.BACK", "sets": 5, "reps": {"min": 5, "max": 5}}
+        ]},
+        {"day": 2, "label": "Upper", "segments": []}
+    ]
+}"#;
+
+fuzz_target!(|data: &[u8]| {
+    let [op, day, segment, rest @ ..] = data else {
+        return;
+    };
+    let (day, segment) = (*day as usize % 4, *segment as usize % 4);
+    let json = rest.split(|&b| b == 0).next().unwrap_or_default();
+    let json = CString::new(json).unwrap();
+    let plan = CString::new(PLAN).unwrap();
+
+    let result = match op % 3 {
+        0 => ffi_segment_add(plan.as_ptr(), day, json.as_ptr()),
+        1 => ffi_segment_update(plan.as_ptr(), day, segment, json.as_ptr()),
+        _ => ffi_segment_remove(plan.as_ptr(), day, segment),
+    };
+    // Edited plans go back through validation, as an editor would
+    if let Some(edited) = data_string(&result) {
+        ffi_free_result(ffi_plan_validate(edited.as_ptr()));
+    }
+    ffi_free_result(result);
+
+    let mut handle = std::ptr::null_mut();
+    ffi_free_result(ffi_handle_from_json(plan.as_ptr(), &mut handle));
+    let result = match op % 3 {
+        0 => ffi_handle_segment_add(handle, day, json.as_ptr()),
+        1 => ffi_handle_segment_update(handle, day, segment, json.as_ptr()),
+        _ => ffi_handle_segment_remove(handle, day, segment),
+    };
+    ffi_free_result(result);
+    ffi_free_result(ffi_handle_validate(handle));
+    ffi_plan_free(handle);
+});
+
+fn data_string(result: &FFIResult) -> Option<CString> {
+    if !result.success || result.data.is_null() {
+        return None;
+    }
+    Some(unsafe { CStr::from_ptr(result.data) }.to_owned())
+}
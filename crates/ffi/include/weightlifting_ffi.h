@@ -9,19 +9,109 @@
 #include <stdlib.h>
 
 /**
- * Opaque handle to a Plan object
+ * Broad class of an FFI failure, for callers that only need to decide how to react
+ */
+typedef enum FFIErrorCategory {
+  /**
+   * The call succeeded
+   */
+  FFIErrorCategory_None = 0,
+  /**
+   * Null pointers, invalid UTF-8, malformed JSON or unknown argument values
+   */
+  FFIErrorCategory_Parse = 1,
+  /**
+   * A day, segment or JSON pointer that does not exist
+   */
+  FFIErrorCategory_Bounds = 2,
+  /**
+   * The plan or a value in it breaks the plan rules
+   */
+  FFIErrorCategory_Validation = 3,
+  /**
+   * Filesystem, app directory or metrics cache failures
+   */
+  FFIErrorCategory_Io = 4,
+  /**
+   * A stored plan or version that does not exist, or a conflicting one that does
+   */
+  FFIErrorCategory_State = 5,
+  /**
+   * Unexpected failures such as serialization errors or a caught panic
+   */
+  FFIErrorCategory_Internal = 6,
+} FFIErrorCategory;
+
+/**
+ * Stable numeric error codes; the hundreds digit matches the category
+ */
+typedef enum FFIErrorCode {
+  FFIErrorCode_None = 0,
+  FFIErrorCode_NullPointer = 100,
+  FFIErrorCode_InvalidUtf8 = 101,
+  FFIErrorCode_InvalidJson = 102,
+  FFIErrorCode_InvalidArgument = 103,
+  FFIErrorCode_IndexOutOfBounds = 200,
+  FFIErrorCode_PathNotFound = 201,
+  FFIErrorCode_ValidationFailed = 300,
+  FFIErrorCode_InvalidValue = 301,
+  FFIErrorCode_Io = 400,
+  FFIErrorCode_NotFound = 500,
+  FFIErrorCode_Conflict = 501,
+  FFIErrorCode_Internal = 600,
+  FFIErrorCode_Panic = 601,
+} FFIErrorCode;
+
+/**
+ * Opaque handle to a Plan object, owned by the caller until `ffi_plan_free`
  */
 typedef struct PlanHandle PlanHandle;
 
 /**
  * Represents a result returned from FFI calls
+ * On failure `error` holds a message, `error_code` and `error_category` classify it,
+ * and `error_detail` optionally holds a JSON payload (e.g. validation errors)
  */
 typedef struct FFIResult {
   bool success;
   char *data;
   char *error;
+  enum FFIErrorCode error_code;
+  enum FFIErrorCategory error_category;
+  char *error_detail;
 } FFIResult;
 
+/**
+ * Fired synchronously after a handle edit or save with the new dirty flag
+ */
+typedef void (*FFIChangeCallback)(void *user_data, bool dirty);
+
+/**
+ * Fired from `ffi_handle_tick` with the validation result JSON
+ * ({"errors": [...], "warnings": [...]}); the string is only valid during the call
+ */
+typedef void (*FFIValidationCallback)(void *user_data, const char *result_json);
+
+/**
+ * Fired from `ffi_handle_tick` after an autosave attempt with the draft path on
+ * success or an error message on failure; the other argument is null
+ */
+typedef void (*FFIAutosaveCallback)(void *user_data, const char *path, const char *error);
+
+/**
+ * Callbacks registered with `ffi_handle_set_observer`; any of them may be null.
+ * `user_data` is passed back unchanged and must stay valid while registered.
+ * Callbacks run on the thread that called into the library, just before that call
+ * returns and after it has finished with the handle, so they may call back into the
+ * handle API (including `ffi_plan_free`).
+ */
+typedef struct FFIPlanObserver {
+  void *user_data;
+  FFIChangeCallback on_change;
+  FFIValidationCallback on_validation;
+  FFIAutosaveCallback on_autosave;
+} FFIPlanObserver;
+
 /**
  * Frees a C string allocated by Rust
  */
@@ -124,6 +214,226 @@ struct FFIResult ffi_dictionary_add_entry(const char *plan_json,
                                           const char *exercise_code,
                                           const char *exercise_name);
 
+/**
+ * Creates a handle to a new empty plan; free it with `ffi_plan_free`
+ */
+struct PlanHandle *ffi_handle_new(void);
+
+/**
+ * Opens a plan file into a new handle stored in `out_handle`
+ */
+struct FFIResult ffi_handle_open(const char *path, struct PlanHandle **out_handle);
+
+/**
+ * Parses plan JSON into a new handle stored in `out_handle`
+ */
+struct FFIResult ffi_handle_from_json(const char *plan_json, struct PlanHandle **out_handle);
+
+/**
+ * Serializes the plan behind a handle to JSON
+ */
+struct FFIResult ffi_plan_to_json(struct PlanHandle *handle);
+
+/**
+ * Saves the plan behind a handle to a file path as pretty JSON and clears its dirty flag
+ */
+struct FFIResult ffi_handle_save(struct PlanHandle *handle, const char *path);
+
+/**
+ * Validates the plan behind a handle
+ * Returns the validation result as JSON: {"errors": [...], "warnings": [...]}
+ */
+struct FFIResult ffi_handle_validate(struct PlanHandle *handle);
+
+/**
+ * Checks that the plan behind a handle has no validation errors
+ * Succeeds with the warnings as a JSON array; otherwise fails with
+ * `FFIErrorCode_ValidationFailed` and the full result ({"errors", "warnings"})
+ * as `error_detail`
+ */
+struct FFIResult ffi_handle_check(struct PlanHandle *handle);
+
+/**
+ * Number of days in the schedule (0 for a null handle)
+ */
+uintptr_t ffi_handle_day_count(const struct PlanHandle *handle);
+
+/**
+ * Number of segments in a day (0 for a null handle or an out-of-bounds day)
+ */
+uintptr_t ffi_handle_segment_count(const struct PlanHandle *handle, uintptr_t day_index);
+
+/**
+ * Gets one day as JSON
+ */
+struct FFIResult ffi_handle_day_get(struct PlanHandle *handle, uintptr_t day_index);
+
+/**
+ * Gets one segment as JSON
+ */
+struct FFIResult ffi_handle_segment_get(struct PlanHandle *handle,
+                                        uintptr_t day_index,
+                                        uintptr_t segment_index);
+
+/**
+ * Appends a day; returns the new day's index
+ */
+struct FFIResult ffi_handle_day_add(struct PlanHandle *handle, const char *day_json);
+
+/**
+ * Removes a day
+ */
+struct FFIResult ffi_handle_day_remove(struct PlanHandle *handle, uintptr_t day_index);
+
+/**
+ * Appends a segment to a day; returns the new segment's index
+ */
+struct FFIResult ffi_handle_segment_add(struct PlanHandle *handle,
+                                        uintptr_t day_index,
+                                        const char *segment_json);
+
+/**
+ * Replaces a segment
+ */
+struct FFIResult ffi_handle_segment_update(struct PlanHandle *handle,
+                                           uintptr_t day_index,
+                                           uintptr_t segment_index,
+                                           const char *segment_json);
+
+/**
+ * Removes a segment
+ */
+struct FFIResult ffi_handle_segment_remove(struct PlanHandle *handle,
+                                           uintptr_t day_index,
+                                           uintptr_t segment_index);
+
+/**
+ * Gets all exercise groups as JSON (object of group name to exercise codes)
+ */
+struct FFIResult ffi_handle_groups_get(struct PlanHandle *handle);
+
+/**
+ * Adds or updates an exercise group
+ * exercises_json: JSON array of exercise codes ["ex1", "ex2"]
+ */
+struct FFIResult ffi_handle_group_add(struct PlanHandle *handle,
+                                      const char *group_name,
+                                      const char *exercises_json);
+
+/**
+ * Removes an exercise group
+ */
+struct FFIResult ffi_handle_group_remove(struct PlanHandle *handle, const char *group_name);
+
+/**
+ * Adds or updates an exercise dictionary entry
+ */
+struct FFIResult ffi_handle_dictionary_add_entry(struct PlanHandle *handle,
+                                                 const char *exercise_code,
+                                                 const char *exercise_name);
+
+/**
+ * Sets the value at a JSON pointer, e.g. "/schedule/0/label"
+ * value_json: JSON value to store
+ */
+struct FFIResult ffi_handle_set(struct PlanHandle *handle,
+                                const char *pointer,
+                                const char *value_json);
+
+/**
+ * Removes the value at a JSON pointer; returns the removed value as JSON
+ */
+struct FFIResult ffi_handle_remove(struct PlanHandle *handle, const char *pointer);
+
+/**
+ * Registers observer callbacks, replacing any previous ones; pass null to remove them.
+ * Validation is debounced: it runs on the first tick after `debounce_ms` without
+ * edits, once per batch of edits. A new observer gets a validation on the next tick.
+ */
+struct FFIResult ffi_handle_set_observer(struct PlanHandle *handle,
+                                         const struct FFIPlanObserver *observer,
+                                         uint64_t debounce_ms);
+
+/**
+ * Enables autosave of a dirty plan to `<drafts dir>/<plan_id>.json` at most every
+ * `interval_ms`; pass a null `plan_id` to disable it. Autosaves never clear the
+ * dirty flag, only `ffi_handle_save` does. Returns the draft path.
+ */
+struct FFIResult ffi_handle_set_autosave(struct PlanHandle *handle,
+                                         const char *plan_id,
+                                         uint64_t interval_ms);
+
+/**
+ * Runs the debounced validation and autosave that are due, firing their callbacks
+ * Returns JSON: {"dirty": bool, "validated": bool, "autosaved": path or null}
+ */
+struct FFIResult ffi_handle_tick(struct PlanHandle *handle);
+
+/**
+ * Whether the plan changed since it was opened or last saved (false for a null handle)
+ */
+bool ffi_handle_is_dirty(const struct PlanHandle *handle);
+
+/**
+ * Diffs two stored states of a plan
+ * from, to: "draft" or a version such as "1.2.0"; null defaults to the latest
+ * version and the draft respectively
+ * Returns the PlanDiff as JSON
+ */
+struct FFIResult ffi_plan_diff(const char *plan_id, const char *from, const char *to);
+
+/**
+ * Stages plans for export and reports conflicts, dependencies and the manifest
+ * request_json: {"plans": [{"plan_id", "version"?, "plan"?}], "author"?, "description"?}
+ * Returns {"can_export", "conflicts", "dependencies", "manifest"} as JSON
+ */
+struct FFIResult ffi_export_stage(const char *request_json);
+
+/**
+ * Generates a built-in chart's Vega-Lite spec from the metrics cache
+ * chart_type: e1rm, e1rm-grid, volume, muscle-volume, pattern-volume, acwr,
+ * fitness-fatigue, weekly-load, monotony, strain, pr or heatmap
+ * options_json: {"exercise"?, "start_date"?, "end_date"?, "unit"?, "title"?} or null
+ * Returns the spec as JSON
+ */
+struct FFIResult ffi_chart_spec(const char *chart_type, const char *options_json);
+
+/**
+ * Returns the built-in location profiles (home gym, commercial gym) as a JSON array
+ */
+struct FFIResult ffi_location_profiles(void);
+
+/**
+ * Previews rounding a target load to what a location's equipment can make
+ * profile_json: a LocationProfile, or null for the home gym profile
+ * equipment: "bb" (barbell) or "db" (dumbbell)
+ * Returns the RoundingPreview as JSON with a display string under "text"
+ */
+struct FFIResult ffi_location_round(const char *profile_json,
+                                    const char *equipment,
+                                    double target_kg);
+
+/**
+ * Generates the warm-up ladder leading into a working set
+ * warmup_json: a plan's "warmup" config (round_to in kg)
+ * training_max_kg: needed by the percent_of_1rm pattern; NaN or <= 0 for none
+ * equipment: "bb" or "db" to round to profile_json's equipment (null profile = home gym),
+ * or null to skip location rounding
+ * Returns the WarmupSet list as JSON
+ */
+struct FFIResult ffi_warmup_sets(const char *warmup_json,
+                                 double working_kg,
+                                 double training_max_kg,
+                                 const char *equipment,
+                                 const char *profile_json);
+
+/**
+ * Expands a scheme template into concrete straight sets
+ * template_json: e.g. {"name": "top_backoff", ...}
+ * Returns the ExpandedScheme as JSON
+ */
+struct FFIResult ffi_scheme_expand(const char *template_json, const char *exercise);
+
 /**
  * Gets the application support directory path for the platform
  */
@@ -0,0 +1,177 @@
+use serde_json::Value;
+use std::any::Any;
+use weightlifting_core::{PlanEditError, PlanStoreError};
+use weightlifting_indexer::CacheError;
+use weightlifting_validate::ValidationResult;
+
+/// Broad class of an FFI failure, for callers that only need to decide how to react
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FFIErrorCategory {
+    /// The call succeeded
+    None = 0,
+    /// Null pointers, invalid UTF-8, malformed JSON or unknown argument values
+    Parse = 1,
+    /// A day, segment or JSON pointer that does not exist
+    Bounds = 2,
+    /// The plan or a value in it breaks the plan rules
+    Validation = 3,
+    /// Filesystem, app directory or metrics cache failures
+    Io = 4,
+    /// A stored plan or version that does not exist, or a conflicting one that does
+    State = 5,
+    /// Unexpected failures such as serialization errors or a caught panic
+    Internal = 6,
+}
+
+/// Stable numeric error codes; the hundreds digit matches the category
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FFIErrorCode {
+    None = 0,
+    NullPointer = 100,
+    InvalidUtf8 = 101,
+    InvalidJson = 102,
+    InvalidArgument = 103,
+    IndexOutOfBounds = 200,
+    PathNotFound = 201,
+    ValidationFailed = 300,
+    InvalidValue = 301,
+    Io = 400,
+    NotFound = 500,
+    Conflict = 501,
+    Internal = 600,
+    Panic = 601,
+}
+
+impl FFIErrorCode {
+    pub fn category(self) -> FFIErrorCategory {
+        match self {
+            FFIErrorCode::None => FFIErrorCategory::None,
+            FFIErrorCode::NullPointer
+            | FFIErrorCode::InvalidUtf8
+            | FFIErrorCode::InvalidJson
+            | FFIErrorCode::InvalidArgument => FFIErrorCategory::Parse,
+            FFIErrorCode::IndexOutOfBounds | FFIErrorCode::PathNotFound => FFIErrorCategory::Bounds,
+            FFIErrorCode::ValidationFailed | FFIErrorCode::InvalidValue => {
+                FFIErrorCategory::Validation
+            }
+            FFIErrorCode::Io => FFIErrorCategory::Io,
+            FFIErrorCode::NotFound | FFIErrorCode::Conflict => FFIErrorCategory::State,
+            FFIErrorCode::Internal | FFIErrorCode::Panic => FFIErrorCategory::Internal,
+        }
+    }
+}
+
+/// A failure reported through `FFIResult`: code, message and optional JSON detail
+#[derive(Debug, Clone)]
+pub struct FFIError {
+    pub code: FFIErrorCode,
+    pub message: String,
+    pub detail: Option<Value>,
+}
+
+impl FFIError {
+    pub fn new(code: FFIErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            detail: None,
+        }
+    }
+
+    pub fn with_detail(mut self, detail: Value) -> Self {
+        self.detail = Some(detail);
+        self
+    }
+
+    pub fn null_pointer() -> Self {
+        Self::new(FFIErrorCode::NullPointer, "Null pointer passed")
+    }
+
+    pub fn parse(message: impl Into<String>) -> Self {
+        Self::new(FFIErrorCode::InvalidJson, message)
+    }
+
+    pub fn invalid_argument(message: impl Into<String>) -> Self {
+        Self::new(FFIErrorCode::InvalidArgument, message)
+    }
+
+    pub fn io(message: impl Into<String>) -> Self {
+        Self::new(FFIErrorCode::Io, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(FFIErrorCode::Internal, message)
+    }
+
+    /// A panic caught at the FFI boundary, with its message when it has one
+    pub fn panic(payload: &(dyn Any + Send)) -> Self {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        Self::new(FFIErrorCode::Panic, format!("Internal panic: {}", message))
+    }
+
+    /// A plan with validation errors; the detail carries the full `ValidationResult`
+    pub fn validation(result: &ValidationResult) -> Self {
+        let detail = serde_json::to_value(result).unwrap_or(Value::Null);
+        Self::new(
+            FFIErrorCode::ValidationFailed,
+            format!("Plan has {} validation errors", result.errors.len()),
+        )
+        .with_detail(detail)
+    }
+}
+
+impl std::fmt::Display for FFIError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl From<PlanEditError> for FFIError {
+    fn from(error: PlanEditError) -> Self {
+        let code = match &error {
+            PlanEditError::DayOutOfBounds(_) | PlanEditError::SegmentOutOfBounds(_) => {
+                FFIErrorCode::IndexOutOfBounds
+            }
+            PlanEditError::PathNotFound(_) => FFIErrorCode::PathNotFound,
+            PlanEditError::DuplicateDay(_) => FFIErrorCode::Conflict,
+            PlanEditError::InvalidPointer(_) => FFIErrorCode::InvalidArgument,
+            PlanEditError::EmptyExerciseCode
+            | PlanEditError::EmptyExerciseName
+            | PlanEditError::InvalidValue(_, _) => FFIErrorCode::InvalidValue,
+            PlanEditError::Json(_) => FFIErrorCode::Internal,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+impl From<PlanStoreError> for FFIError {
+    fn from(error: PlanStoreError) -> Self {
+        let code = match &error {
+            PlanStoreError::PlanNotFound(_) | PlanStoreError::VersionNotFound(_, _) => {
+                FFIErrorCode::NotFound
+            }
+            PlanStoreError::VersionExists(_, _) | PlanStoreError::NoChanges(_, _) => {
+                FFIErrorCode::Conflict
+            }
+            PlanStoreError::Io(_) => FFIErrorCode::Io,
+            PlanStoreError::Json(_) => FFIErrorCode::InvalidJson,
+            PlanStoreError::Diff(_) => FFIErrorCode::Internal,
+            PlanStoreError::InvalidId(_)
+            | PlanStoreError::InvalidRef(_)
+            | PlanStoreError::InvalidBump(_) => FFIErrorCode::InvalidArgument,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+impl From<CacheError> for FFIError {
+    fn from(error: CacheError) -> Self {
+        Self::io(error.to_string())
+    }
+}
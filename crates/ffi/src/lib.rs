@@ -1,20 +1,58 @@
+use chrono::NaiveDate;
 use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
+use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::path::PathBuf;
-use weightlifting_core::models::{Day, Plan, Segment};
+use std::time::{Duration, Instant};
+use weightlifting_core::location::LocationProfile;
+use weightlifting_core::models::{Day, Plan, Segment, WarmupConfig};
+use weightlifting_core::{
+    validate_plan_id, AppPaths, ExportStager, LoadEquipment, PlanEditError, PlanRef, PlanStore,
+    PlanVersion, SchemeTemplate, UserPreferences, VegaLiteSpec, VersionedPlan, WarmupGenerator,
+};
+use weightlifting_indexer::{builtin_chart_spec, ChartFilters, MetricsCache};
 use weightlifting_validate::PlanValidator;
 
+mod error;
+mod observer;
+
+pub use error::{FFIError, FFIErrorCategory, FFIErrorCode};
+use observer::{Autosave, ChangeTracker, Notification};
+pub use observer::{
+    FFIAutosaveCallback, FFIChangeCallback, FFIPlanObserver, FFIValidationCallback,
+};
+
 /// Represents a result returned from FFI calls
+/// On failure `error` holds a message, `error_code` and `error_category` classify it,
+/// and `error_detail` optionally holds a JSON payload (e.g. validation errors)
 #[repr(C)]
 pub struct FFIResult {
     pub success: bool,
     pub data: *mut c_char,
     pub error: *mut c_char,
+    pub error_code: FFIErrorCode,
+    pub error_category: FFIErrorCategory,
+    pub error_detail: *mut c_char,
 }
 
-/// Opaque handle to a Plan object
+/// Opaque handle to a Plan object, owned by the caller until `ffi_plan_free`
 pub struct PlanHandle {
     plan: Plan,
+    tracker: ChangeTracker,
+    observer: Option<FFIPlanObserver>,
+    /// Callbacks queued by the current call, fired by `with_handle` once it returns
+    pending: Vec<Notification>,
+}
+
+impl PlanHandle {
+    fn new(plan: Plan) -> Self {
+        Self {
+            plan,
+            tracker: ChangeTracker::new(),
+            observer: None,
+            pending: Vec::new(),
+        }
+    }
 }
 
 // ============================================================================
@@ -23,60 +61,121 @@ pub struct PlanHandle {
 
 /// Frees a C string allocated by Rust
 #[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub extern "C" fn ffi_free_string(ptr: *mut c_char) {
-    if !ptr.is_null() {
-        unsafe {
-            let _ = CString::from_raw(ptr);
+    guarded_or((), || {
+        if !ptr.is_null() {
+            unsafe {
+                let _ = CString::from_raw(ptr);
+            }
         }
-    }
+    })
 }
 
 /// Frees an FFIResult
 #[no_mangle]
 pub extern "C" fn ffi_free_result(result: FFIResult) {
-    ffi_free_string(result.data);
-    ffi_free_string(result.error);
+    guarded_or((), || {
+        ffi_free_string(result.data);
+        ffi_free_string(result.error);
+        ffi_free_string(result.error_detail);
+    })
 }
 
 /// Frees a PlanHandle
 #[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub extern "C" fn ffi_plan_free(handle: *mut PlanHandle) {
-    if !handle.is_null() {
-        unsafe {
-            let _ = Box::from_raw(handle);
+    guarded_or((), || {
+        if !handle.is_null() {
+            unsafe {
+                let _ = Box::from_raw(handle);
+            }
         }
-    }
+    })
 }
 
 // ============================================================================
 // Helper Functions
 // ============================================================================
 
+/// Converts to a C string; interior NULs, which C strings cannot carry,
+/// are replaced with U+FFFD instead of failing
+fn c_string(s: String) -> CString {
+    let s = if s.contains('\0') {
+        s.replace('\0', "\u{FFFD}")
+    } else {
+        s
+    };
+    CString::new(s).unwrap_or_default()
+}
+
+fn into_c_string(s: String) -> *mut c_char {
+    c_string(s).into_raw()
+}
+
+/// Runs an entry point body, turning a panic into an `FFIErrorCode_Panic` result
+/// instead of unwinding into the host. A handle that panicked mid-call may hold
+/// a partially edited plan.
+fn guarded<F>(f: F) -> FFIResult
+where
+    F: FnOnce() -> FFIResult,
+{
+    catch_unwind(AssertUnwindSafe(f))
+        .unwrap_or_else(|payload| create_error_result(FFIError::panic(payload.as_ref())))
+}
+
+/// Like `guarded` for entry points that do not return an `FFIResult`;
+/// a panic yields `fallback`
+fn guarded_or<T, F>(fallback: T, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or(fallback)
+}
+
 fn create_success_result(data: String) -> FFIResult {
     FFIResult {
         success: true,
-        data: CString::new(data).unwrap().into_raw(),
+        data: into_c_string(data),
         error: std::ptr::null_mut(),
+        error_code: FFIErrorCode::None,
+        error_category: FFIErrorCategory::None,
+        error_detail: std::ptr::null_mut(),
     }
 }
 
-fn create_error_result(error: String) -> FFIResult {
+fn create_error_result(error: FFIError) -> FFIResult {
+    let detail = error
+        .detail
+        .map(|detail| into_c_string(detail.to_string()))
+        .unwrap_or(std::ptr::null_mut());
     FFIResult {
         success: false,
         data: std::ptr::null_mut(),
-        error: CString::new(error).unwrap().into_raw(),
+        error: into_c_string(error.message),
+        error_code: error.code,
+        error_category: error.code.category(),
+        error_detail: detail,
     }
 }
 
-fn c_str_to_string(ptr: *const c_char) -> Result<String, String> {
+fn into_result(result: Result<String, FFIError>) -> FFIResult {
+    match result {
+        Ok(data) => create_success_result(data),
+        Err(e) => create_error_result(e),
+    }
+}
+
+fn c_str_to_string(ptr: *const c_char) -> Result<String, FFIError> {
     if ptr.is_null() {
-        return Err("Null pointer passed".to_string());
+        return Err(FFIError::null_pointer());
     }
     unsafe {
         CStr::from_ptr(ptr)
             .to_str()
             .map(|s| s.to_string())
-            .map_err(|e| format!("Invalid UTF-8: {}", e))
+            .map_err(|e| FFIError::new(FFIErrorCode::InvalidUtf8, format!("Invalid UTF-8: {}", e)))
     }
 }
 
@@ -88,33 +187,45 @@ fn c_str_to_string(ptr: *const c_char) -> Result<String, String> {
 /// Returns a JSON string with the plan data
 #[no_mangle]
 pub extern "C" fn ffi_plan_new() -> FFIResult {
-    let plan = Plan::new("New Plan".to_string());
-    match serde_json::to_string(&plan) {
-        Ok(json) => create_success_result(json),
-        Err(e) => create_error_result(format!("Failed to serialize plan: {}", e)),
-    }
+    guarded(|| {
+        let plan = Plan::new("New Plan".to_string());
+        match serde_json::to_string(&plan) {
+            Ok(json) => create_success_result(json),
+            Err(e) => create_error_result(FFIError::internal(format!(
+                "Failed to serialize plan: {}",
+                e
+            ))),
+        }
+    })
 }
 
 /// Opens a plan from a file path
 /// Returns a JSON string with the plan data
 #[no_mangle]
 pub extern "C" fn ffi_plan_open(path: *const c_char) -> FFIResult {
-    let path_str = match c_str_to_string(path) {
-        Ok(s) => s,
-        Err(e) => return create_error_result(e),
-    };
-
-    let path = PathBuf::from(path_str);
-    match std::fs::read_to_string(&path) {
-        Ok(content) => match serde_json::from_str::<Plan>(&content) {
-            Ok(plan) => match serde_json::to_string(&plan) {
-                Ok(json) => create_success_result(json),
-                Err(e) => create_error_result(format!("Failed to serialize plan: {}", e)),
+    guarded(|| {
+        let path_str = match c_str_to_string(path) {
+            Ok(s) => s,
+            Err(e) => return create_error_result(e),
+        };
+
+        let path = PathBuf::from(path_str);
+        match std::fs::read_to_string(&path) {
+            Ok(content) => match serde_json::from_str::<Plan>(&content) {
+                Ok(plan) => match serde_json::to_string(&plan) {
+                    Ok(json) => create_success_result(json),
+                    Err(e) => create_error_result(FFIError::internal(format!(
+                        "Failed to serialize plan: {}",
+                        e
+                    ))),
+                },
+                Err(e) => {
+                    create_error_result(FFIError::parse(format!("Failed to parse plan: {}", e)))
+                }
             },
-            Err(e) => create_error_result(format!("Failed to parse plan: {}", e)),
-        },
-        Err(e) => create_error_result(format!("Failed to read file: {}", e)),
-    }
+            Err(e) => create_error_result(FFIError::io(format!("Failed to read file: {}", e))),
+        }
+    })
 }
 
 /// Saves a plan to a file path
@@ -122,52 +233,74 @@ pub extern "C" fn ffi_plan_open(path: *const c_char) -> FFIResult {
 /// path: File path to save to
 #[no_mangle]
 pub extern "C" fn ffi_plan_save(plan_json: *const c_char, path: *const c_char) -> FFIResult {
-    let plan_str = match c_str_to_string(plan_json) {
-        Ok(s) => s,
-        Err(e) => return create_error_result(e),
-    };
-
-    let path_str = match c_str_to_string(path) {
-        Ok(s) => s,
-        Err(e) => return create_error_result(e),
-    };
-
-    let plan: Plan = match serde_json::from_str(&plan_str) {
-        Ok(p) => p,
-        Err(e) => return create_error_result(format!("Failed to parse plan JSON: {}", e)),
-    };
-
-    let path = PathBuf::from(path_str);
-    match std::fs::write(&path, serde_json::to_string_pretty(&plan).unwrap()) {
-        Ok(_) => create_success_result("Plan saved successfully".to_string()),
-        Err(e) => create_error_result(format!("Failed to write file: {}", e)),
-    }
+    guarded(|| {
+        let plan_str = match c_str_to_string(plan_json) {
+            Ok(s) => s,
+            Err(e) => return create_error_result(e),
+        };
+
+        let path_str = match c_str_to_string(path) {
+            Ok(s) => s,
+            Err(e) => return create_error_result(e),
+        };
+
+        let plan: Plan = match serde_json::from_str(&plan_str) {
+            Ok(p) => p,
+            Err(e) => {
+                return create_error_result(FFIError::parse(format!(
+                    "Failed to parse plan JSON: {}",
+                    e
+                )))
+            }
+        };
+
+        let path = PathBuf::from(path_str);
+        match std::fs::write(&path, serde_json::to_string_pretty(&plan).unwrap()) {
+            Ok(_) => create_success_result("Plan saved successfully".to_string()),
+            Err(e) => create_error_result(FFIError::io(format!("Failed to write file: {}", e))),
+        }
+    })
 }
 
 /// Validates a plan
 /// Returns a JSON array of validation errors (empty if valid)
 #[no_mangle]
 pub extern "C" fn ffi_plan_validate(plan_json: *const c_char) -> FFIResult {
-    let plan_str = match c_str_to_string(plan_json) {
-        Ok(s) => s,
-        Err(e) => return create_error_result(e),
-    };
-
-    let plan: Plan = match serde_json::from_str(&plan_str) {
-        Ok(p) => p,
-        Err(e) => return create_error_result(format!("Failed to parse plan JSON: {}", e)),
-    };
-
-    let validator = match PlanValidator::new() {
-        Ok(v) => v,
-        Err(e) => return create_error_result(format!("Failed to create validator: {}", e)),
-    };
-
-    let result = validator.validate(&plan);
-    match serde_json::to_string(&result) {
-        Ok(json) => create_success_result(json),
-        Err(e) => create_error_result(format!("Failed to serialize errors: {}", e)),
-    }
+    guarded(|| {
+        let plan_str = match c_str_to_string(plan_json) {
+            Ok(s) => s,
+            Err(e) => return create_error_result(e),
+        };
+
+        let plan: Plan = match serde_json::from_str(&plan_str) {
+            Ok(p) => p,
+            Err(e) => {
+                return create_error_result(FFIError::parse(format!(
+                    "Failed to parse plan JSON: {}",
+                    e
+                )))
+            }
+        };
+
+        let validator = match PlanValidator::new() {
+            Ok(v) => v,
+            Err(e) => {
+                return create_error_result(FFIError::internal(format!(
+                    "Failed to create validator: {}",
+                    e
+                )))
+            }
+        };
+
+        let result = validator.validate(&plan);
+        match serde_json::to_string(&result) {
+            Ok(json) => create_success_result(json),
+            Err(e) => create_error_result(FFIError::internal(format!(
+                "Failed to serialize errors: {}",
+                e
+            ))),
+        }
+    })
 }
 
 // ============================================================================
@@ -185,36 +318,49 @@ pub extern "C" fn ffi_segment_add(
     day_index: usize,
     segment_json: *const c_char,
 ) -> FFIResult {
-    let plan_str = match c_str_to_string(plan_json) {
-        Ok(s) => s,
-        Err(e) => return create_error_result(e),
-    };
-
-    let segment_str = match c_str_to_string(segment_json) {
-        Ok(s) => s,
-        Err(e) => return create_error_result(e),
-    };
-
-    let mut plan: Plan = match serde_json::from_str(&plan_str) {
-        Ok(p) => p,
-        Err(e) => return create_error_result(format!("Failed to parse plan JSON: {}", e)),
-    };
-
-    let segment: Segment = match serde_json::from_str(&segment_str) {
-        Ok(s) => s,
-        Err(e) => return create_error_result(format!("Failed to parse segment JSON: {}", e)),
-    };
-
-    if day_index >= plan.schedule.len() {
-        return create_error_result(format!("Day index {} out of bounds", day_index));
-    }
-
-    plan.schedule[day_index].segments.push(segment);
+    guarded(|| {
+        let plan_str = match c_str_to_string(plan_json) {
+            Ok(s) => s,
+            Err(e) => return create_error_result(e),
+        };
+
+        let segment_str = match c_str_to_string(segment_json) {
+            Ok(s) => s,
+            Err(e) => return create_error_result(e),
+        };
+
+        let mut plan: Plan = match serde_json::from_str(&plan_str) {
+            Ok(p) => p,
+            Err(e) => {
+                return create_error_result(FFIError::parse(format!(
+                    "Failed to parse plan JSON: {}",
+                    e
+                )))
+            }
+        };
+
+        let segment: Segment = match serde_json::from_str(&segment_str) {
+            Ok(s) => s,
+            Err(e) => {
+                return create_error_result(FFIError::parse(format!(
+                    "Failed to parse segment JSON: {}",
+                    e
+                )))
+            }
+        };
+
+        if let Err(e) = plan.add_segment(day_index, segment) {
+            return create_error_result(e.into());
+        }
 
-    match serde_json::to_string(&plan) {
-        Ok(json) => create_success_result(json),
-        Err(e) => create_error_result(format!("Failed to serialize plan: {}", e)),
-    }
+        match serde_json::to_string(&plan) {
+            Ok(json) => create_success_result(json),
+            Err(e) => create_error_result(FFIError::internal(format!(
+                "Failed to serialize plan: {}",
+                e
+            ))),
+        }
+    })
 }
 
 /// Removes a segment from a plan
@@ -224,30 +370,34 @@ pub extern "C" fn ffi_segment_remove(
     day_index: usize,
     segment_index: usize,
 ) -> FFIResult {
-    let plan_str = match c_str_to_string(plan_json) {
-        Ok(s) => s,
-        Err(e) => return create_error_result(e),
-    };
-
-    let mut plan: Plan = match serde_json::from_str(&plan_str) {
-        Ok(p) => p,
-        Err(e) => return create_error_result(format!("Failed to parse plan JSON: {}", e)),
-    };
-
-    if day_index >= plan.schedule.len() {
-        return create_error_result(format!("Day index {} out of bounds", day_index));
-    }
-
-    if segment_index >= plan.schedule[day_index].segments.len() {
-        return create_error_result(format!("Segment index {} out of bounds", segment_index));
-    }
-
-    plan.schedule[day_index].segments.remove(segment_index);
+    guarded(|| {
+        let plan_str = match c_str_to_string(plan_json) {
+            Ok(s) => s,
+            Err(e) => return create_error_result(e),
+        };
+
+        let mut plan: Plan = match serde_json::from_str(&plan_str) {
+            Ok(p) => p,
+            Err(e) => {
+                return create_error_result(FFIError::parse(format!(
+                    "Failed to parse plan JSON: {}",
+                    e
+                )))
+            }
+        };
+
+        if let Err(e) = plan.remove_segment(day_index, segment_index) {
+            return create_error_result(e.into());
+        }
 
-    match serde_json::to_string(&plan) {
-        Ok(json) => create_success_result(json),
-        Err(e) => create_error_result(format!("Failed to serialize plan: {}", e)),
-    }
+        match serde_json::to_string(&plan) {
+            Ok(json) => create_success_result(json),
+            Err(e) => create_error_result(FFIError::internal(format!(
+                "Failed to serialize plan: {}",
+                e
+            ))),
+        }
+    })
 }
 
 /// Updates a segment in a plan
@@ -258,48 +408,57 @@ pub extern "C" fn ffi_segment_update(
     segment_index: usize,
     segment_json: *const c_char,
 ) -> FFIResult {
-    let plan_str = match c_str_to_string(plan_json) {
-        Ok(s) => s,
-        Err(e) => return create_error_result(e),
-    };
-
-    let segment_str = match c_str_to_string(segment_json) {
-        Ok(s) => s,
-        Err(e) => return create_error_result(e),
-    };
-
-    let mut plan: Plan = match serde_json::from_str(&plan_str) {
-        Ok(p) => p,
-        Err(e) => return create_error_result(format!("Failed to parse plan JSON: {}", e)),
-    };
-
-    let segment: Segment = match serde_json::from_str(&segment_str) {
-        Ok(s) => s,
-        Err(e) => return create_error_result(format!("Failed to parse segment JSON: {}", e)),
-    };
-
-    // Debug logging
-    eprintln!("[FFI] Received segment JSON: {}", segment_str);
-    eprintln!("[FFI] Parsed segment: {:?}", segment);
-
-    if day_index >= plan.schedule.len() {
-        return create_error_result(format!("Day index {} out of bounds", day_index));
-    }
-
-    if segment_index >= plan.schedule[day_index].segments.len() {
-        return create_error_result(format!("Segment index {} out of bounds", segment_index));
-    }
-
-    plan.schedule[day_index].segments[segment_index] = segment;
+    guarded(|| {
+        let plan_str = match c_str_to_string(plan_json) {
+            Ok(s) => s,
+            Err(e) => return create_error_result(e),
+        };
+
+        let segment_str = match c_str_to_string(segment_json) {
+            Ok(s) => s,
+            Err(e) => return create_error_result(e),
+        };
+
+        let mut plan: Plan = match serde_json::from_str(&plan_str) {
+            Ok(p) => p,
+            Err(e) => {
+                return create_error_result(FFIError::parse(format!(
+                    "Failed to parse plan JSON: {}",
+                    e
+                )))
+            }
+        };
+
+        let segment: Segment = match serde_json::from_str(&segment_str) {
+            Ok(s) => s,
+            Err(e) => {
+                return create_error_result(FFIError::parse(format!(
+                    "Failed to parse segment JSON: {}",
+                    e
+                )))
+            }
+        };
+
+        // Debug logging
+        eprintln!("[FFI] Received segment JSON: {}", segment_str);
+        eprintln!("[FFI] Parsed segment: {:?}", segment);
+
+        if let Err(e) = plan.update_segment(day_index, segment_index, segment) {
+            return create_error_result(e.into());
+        }
 
-    // Debug logging - check what we're serializing
-    let updated_segment = &plan.schedule[day_index].segments[segment_index];
-    eprintln!("[FFI] Updated segment in plan: {:?}", updated_segment);
+        // Debug logging - check what we're serializing
+        let updated_segment = &plan.schedule[day_index].segments[segment_index];
+        eprintln!("[FFI] Updated segment in plan: {:?}", updated_segment);
 
-    match serde_json::to_string(&plan) {
-        Ok(json) => create_success_result(json),
-        Err(e) => create_error_result(format!("Failed to serialize plan: {}", e)),
-    }
+        match serde_json::to_string(&plan) {
+            Ok(json) => create_success_result(json),
+            Err(e) => create_error_result(FFIError::internal(format!(
+                "Failed to serialize plan: {}",
+                e
+            ))),
+        }
+    })
 }
 
 // ============================================================================
@@ -309,57 +468,82 @@ pub extern "C" fn ffi_segment_update(
 /// Adds a day to a plan
 #[no_mangle]
 pub extern "C" fn ffi_day_add(plan_json: *const c_char, day_json: *const c_char) -> FFIResult {
-    let plan_str = match c_str_to_string(plan_json) {
-        Ok(s) => s,
-        Err(e) => return create_error_result(e),
-    };
-
-    let day_str = match c_str_to_string(day_json) {
-        Ok(s) => s,
-        Err(e) => return create_error_result(e),
-    };
-
-    let mut plan: Plan = match serde_json::from_str(&plan_str) {
-        Ok(p) => p,
-        Err(e) => return create_error_result(format!("Failed to parse plan JSON: {}", e)),
-    };
-
-    let day: Day = match serde_json::from_str(&day_str) {
-        Ok(d) => d,
-        Err(e) => return create_error_result(format!("Failed to parse day JSON: {}", e)),
-    };
-
-    plan.schedule.push(day);
+    guarded(|| {
+        let plan_str = match c_str_to_string(plan_json) {
+            Ok(s) => s,
+            Err(e) => return create_error_result(e),
+        };
+
+        let day_str = match c_str_to_string(day_json) {
+            Ok(s) => s,
+            Err(e) => return create_error_result(e),
+        };
+
+        let mut plan: Plan = match serde_json::from_str(&plan_str) {
+            Ok(p) => p,
+            Err(e) => {
+                return create_error_result(FFIError::parse(format!(
+                    "Failed to parse plan JSON: {}",
+                    e
+                )))
+            }
+        };
+
+        let day: Day = match serde_json::from_str(&day_str) {
+            Ok(d) => d,
+            Err(e) => {
+                return create_error_result(FFIError::parse(format!(
+                    "Failed to parse day JSON: {}",
+                    e
+                )))
+            }
+        };
+
+        if let Err(e) = plan.add_day(day) {
+            return create_error_result(e.into());
+        }
 
-    match serde_json::to_string(&plan) {
-        Ok(json) => create_success_result(json),
-        Err(e) => create_error_result(format!("Failed to serialize plan: {}", e)),
-    }
+        match serde_json::to_string(&plan) {
+            Ok(json) => create_success_result(json),
+            Err(e) => create_error_result(FFIError::internal(format!(
+                "Failed to serialize plan: {}",
+                e
+            ))),
+        }
+    })
 }
 
 /// Removes a day from a plan
 #[no_mangle]
 pub extern "C" fn ffi_day_remove(plan_json: *const c_char, day_index: usize) -> FFIResult {
-    let plan_str = match c_str_to_string(plan_json) {
-        Ok(s) => s,
-        Err(e) => return create_error_result(e),
-    };
-
-    let mut plan: Plan = match serde_json::from_str(&plan_str) {
-        Ok(p) => p,
-        Err(e) => return create_error_result(format!("Failed to parse plan JSON: {}", e)),
-    };
-
-    if day_index >= plan.schedule.len() {
-        return create_error_result(format!("Day index {} out of bounds", day_index));
-    }
-
-    plan.schedule.remove(day_index);
+    guarded(|| {
+        let plan_str = match c_str_to_string(plan_json) {
+            Ok(s) => s,
+            Err(e) => return create_error_result(e),
+        };
+
+        let mut plan: Plan = match serde_json::from_str(&plan_str) {
+            Ok(p) => p,
+            Err(e) => {
+                return create_error_result(FFIError::parse(format!(
+                    "Failed to parse plan JSON: {}",
+                    e
+                )))
+            }
+        };
+
+        if let Err(e) = plan.remove_day(day_index) {
+            return create_error_result(e.into());
+        }
 
-    match serde_json::to_string(&plan) {
-        Ok(json) => create_success_result(json),
-        Err(e) => create_error_result(format!("Failed to serialize plan: {}", e)),
-    }
+        match serde_json::to_string(&plan) {
+            Ok(json) => create_success_result(json),
+            Err(e) => create_error_result(FFIError::internal(format!(
+                "Failed to serialize plan: {}",
+                e
+            ))),
+        }
+    })
 }
 
 // ============================================================================
@@ -369,20 +553,30 @@ pub extern "C" fn ffi_day_remove(plan_json: *const c_char, day_index: usize) ->
 /// Gets all exercise groups from a plan (returns HashMap<String, Vec<String>>)
 #[no_mangle]
 pub extern "C" fn ffi_groups_get(plan_json: *const c_char) -> FFIResult {
-    let plan_str = match c_str_to_string(plan_json) {
-        Ok(s) => s,
-        Err(e) => return create_error_result(e),
-    };
-
-    let plan: Plan = match serde_json::from_str(&plan_str) {
-        Ok(p) => p,
-        Err(e) => return create_error_result(format!("Failed to parse plan JSON: {}", e)),
-    };
-
-    match serde_json::to_string(&plan.groups) {
-        Ok(json) => create_success_result(json),
-        Err(e) => create_error_result(format!("Failed to serialize groups: {}", e)),
-    }
+    guarded(|| {
+        let plan_str = match c_str_to_string(plan_json) {
+            Ok(s) => s,
+            Err(e) => return create_error_result(e),
+        };
+
+        let plan: Plan = match serde_json::from_str(&plan_str) {
+            Ok(p) => p,
+            Err(e) => {
+                return create_error_result(FFIError::parse(format!(
+                    "Failed to parse plan JSON: {}",
+                    e
+                )))
+            }
+        };
+
+        match serde_json::to_string(&plan.groups) {
+            Ok(json) => create_success_result(json),
+            Err(e) => create_error_result(FFIError::internal(format!(
+                "Failed to serialize groups: {}",
+                e
+            ))),
+        }
+    })
 }
 
 /// Adds or updates an exercise group in a plan
@@ -394,113 +588,915 @@ pub extern "C" fn ffi_group_add(
     group_name: *const c_char,
     exercises_json: *const c_char,
 ) -> FFIResult {
-    let plan_str = match c_str_to_string(plan_json) {
-        Ok(s) => s,
-        Err(e) => return create_error_result(e),
-    };
+    guarded(|| {
+        let plan_str = match c_str_to_string(plan_json) {
+            Ok(s) => s,
+            Err(e) => return create_error_result(e),
+        };
+
+        let name = match c_str_to_string(group_name) {
+            Ok(s) => s,
+            Err(e) => return create_error_result(e),
+        };
+
+        let exercises_str = match c_str_to_string(exercises_json) {
+            Ok(s) => s,
+            Err(e) => return create_error_result(e),
+        };
+
+        let mut plan: Plan = match serde_json::from_str(&plan_str) {
+            Ok(p) => p,
+            Err(e) => {
+                return create_error_result(FFIError::parse(format!(
+                    "Failed to parse plan JSON: {}",
+                    e
+                )))
+            }
+        };
+
+        let exercises: Vec<String> = match serde_json::from_str(&exercises_str) {
+            Ok(e) => e,
+            Err(e) => {
+                return create_error_result(FFIError::parse(format!(
+                    "Failed to parse exercises JSON: {}",
+                    e
+                )))
+            }
+        };
+
+        plan.groups.insert(name, exercises);
+
+        match serde_json::to_string(&plan) {
+            Ok(json) => create_success_result(json),
+            Err(e) => create_error_result(FFIError::internal(format!(
+                "Failed to serialize plan: {}",
+                e
+            ))),
+        }
+    })
+}
 
-    let name = match c_str_to_string(group_name) {
-        Ok(s) => s,
-        Err(e) => return create_error_result(e),
-    };
+/// Removes an exercise group from a plan
+#[no_mangle]
+pub extern "C" fn ffi_group_remove(
+    plan_json: *const c_char,
+    group_name: *const c_char,
+) -> FFIResult {
+    guarded(|| {
+        let plan_str = match c_str_to_string(plan_json) {
+            Ok(s) => s,
+            Err(e) => return create_error_result(e),
+        };
+
+        let name = match c_str_to_string(group_name) {
+            Ok(s) => s,
+            Err(e) => return create_error_result(e),
+        };
+
+        let mut plan: Plan = match serde_json::from_str(&plan_str) {
+            Ok(p) => p,
+            Err(e) => {
+                return create_error_result(FFIError::parse(format!(
+                    "Failed to parse plan JSON: {}",
+                    e
+                )))
+            }
+        };
+
+        plan.groups.remove(&name);
+
+        match serde_json::to_string(&plan) {
+            Ok(json) => create_success_result(json),
+            Err(e) => create_error_result(FFIError::internal(format!(
+                "Failed to serialize plan: {}",
+                e
+            ))),
+        }
+    })
+}
 
-    let exercises_str = match c_str_to_string(exercises_json) {
-        Ok(s) => s,
-        Err(e) => return create_error_result(e),
-    };
+// ============================================================================
+// Dictionary Operations
+// ============================================================================
 
-    let mut plan: Plan = match serde_json::from_str(&plan_str) {
-        Ok(p) => p,
-        Err(e) => return create_error_result(format!("Failed to parse plan JSON: {}", e)),
-    };
+/// Adds or updates an exercise dictionary entry in the plan
+#[no_mangle]
+pub extern "C" fn ffi_dictionary_add_entry(
+    plan_json: *const c_char,
+    exercise_code: *const c_char,
+    exercise_name: *const c_char,
+) -> FFIResult {
+    guarded(|| {
+        let plan_str = match c_str_to_string(plan_json) {
+            Ok(s) => s,
+            Err(e) => return create_error_result(e),
+        };
+
+        let code = match c_str_to_string(exercise_code) {
+            Ok(s) => s,
+            Err(e) => return create_error_result(e),
+        };
+
+        let name = match c_str_to_string(exercise_name) {
+            Ok(s) => s,
+            Err(e) => return create_error_result(e),
+        };
+
+        let mut plan: Plan = match serde_json::from_str(&plan_str) {
+            Ok(p) => p,
+            Err(e) => {
+                return create_error_result(FFIError::parse(format!(
+                    "Failed to parse plan JSON: {}",
+                    e
+                )))
+            }
+        };
+
+        if let Err(e) = plan.add_dictionary_entry(&code, &name) {
+            return create_error_result(e.into());
+        }
 
-    let exercises: Vec<String> = match serde_json::from_str(&exercises_str) {
-        Ok(e) => e,
-        Err(e) => return create_error_result(format!("Failed to parse exercises JSON: {}", e)),
+        match serde_json::to_string(&plan) {
+            Ok(json) => create_success_result(json),
+            Err(e) => create_error_result(FFIError::internal(format!(
+                "Failed to serialize plan: {}",
+                e
+            ))),
+        }
+    })
+}
+
+// ============================================================================
+// Plan Handle API
+// ============================================================================
+//
+// Handle functions keep the plan on the Rust side between calls instead of
+// round-tripping the whole plan as JSON. Create a handle with
+// `ffi_handle_new`, `ffi_handle_open` or `ffi_handle_from_json`, mutate and
+// query it, serialize it with `ffi_plan_to_json` only when needed, and release
+// it with `ffi_plan_free`. Every function rejects a null handle with an error.
+
+fn with_handle<F>(handle: *mut PlanHandle, f: F) -> FFIResult
+where
+    F: FnOnce(&mut PlanHandle) -> Result<String, FFIError>,
+{
+    if handle.is_null() {
+        return create_error_result(FFIError::new(FFIErrorCode::NullPointer, "Null plan handle"));
+    }
+    // The borrow ends before any callback runs, so callbacks may re-enter the API
+    let (result, observer, pending) = {
+        let handle = unsafe { &mut *handle };
+        let result = f(handle);
+        (result, handle.observer, std::mem::take(&mut handle.pending))
     };
+    if let Some(observer) = observer {
+        for notification in &pending {
+            notification.fire(&observer);
+        }
+    }
+    into_result(result)
+}
 
-    plan.groups.insert(name, exercises);
+fn with_plan<F>(handle: *mut PlanHandle, f: F) -> FFIResult
+where
+    F: FnOnce(&mut Plan) -> Result<String, FFIError>,
+{
+    with_handle(handle, |handle| f(&mut handle.plan))
+}
+
+/// Like `with_plan` for edits: success marks the plan dirty and fires `on_change`
+fn edit_plan<F>(handle: *mut PlanHandle, f: F) -> FFIResult
+where
+    F: FnOnce(&mut Plan) -> Result<String, FFIError>,
+{
+    with_handle(handle, |handle| {
+        let data = f(&mut handle.plan)?;
+        handle.record_change();
+        Ok(data)
+    })
+}
 
-    match serde_json::to_string(&plan) {
-        Ok(json) => create_success_result(json),
-        Err(e) => create_error_result(format!("Failed to serialize plan: {}", e)),
+fn into_handle(plan: Plan, out_handle: *mut *mut PlanHandle) -> FFIResult {
+    if out_handle.is_null() {
+        return create_error_result(FFIError::null_pointer());
     }
+    unsafe {
+        *out_handle = Box::into_raw(Box::new(PlanHandle::new(plan)));
+    }
+    create_success_result(String::new())
 }
 
-/// Removes an exercise group from a plan
+fn parse_json<T: serde::de::DeserializeOwned>(
+    ptr: *const c_char,
+    what: &str,
+) -> Result<T, FFIError> {
+    let json = c_str_to_string(ptr)?;
+    serde_json::from_str(&json)
+        .map_err(|e| FFIError::parse(format!("Failed to parse {} JSON: {}", what, e)))
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<String, FFIError> {
+    serde_json::to_string(value)
+        .map_err(|e| FFIError::internal(format!("Failed to serialize: {}", e)))
+}
+
+/// Creates a handle to a new empty plan; free it with `ffi_plan_free`
 #[no_mangle]
-pub extern "C" fn ffi_group_remove(
+pub extern "C" fn ffi_handle_new() -> *mut PlanHandle {
+    guarded_or(std::ptr::null_mut(), || {
+        Box::into_raw(Box::new(PlanHandle::new(Plan::new("New Plan".to_string()))))
+    })
+}
+
+/// Opens a plan file into a new handle stored in `out_handle`
+#[no_mangle]
+pub extern "C" fn ffi_handle_open(
+    path: *const c_char,
+    out_handle: *mut *mut PlanHandle,
+) -> FFIResult {
+    guarded(|| {
+        let path = match c_str_to_string(path) {
+            Ok(s) => PathBuf::from(s),
+            Err(e) => return create_error_result(e),
+        };
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                return create_error_result(FFIError::io(format!("Failed to read file: {}", e)))
+            }
+        };
+        match serde_json::from_str::<Plan>(&content) {
+            Ok(plan) => into_handle(plan, out_handle),
+            Err(e) => create_error_result(FFIError::parse(format!("Failed to parse plan: {}", e))),
+        }
+    })
+}
+
+/// Parses plan JSON into a new handle stored in `out_handle`
+#[no_mangle]
+pub extern "C" fn ffi_handle_from_json(
     plan_json: *const c_char,
+    out_handle: *mut *mut PlanHandle,
+) -> FFIResult {
+    guarded(|| match parse_json::<Plan>(plan_json, "plan") {
+        Ok(plan) => into_handle(plan, out_handle),
+        Err(e) => create_error_result(e),
+    })
+}
+
+/// Serializes the plan behind a handle to JSON
+#[no_mangle]
+pub extern "C" fn ffi_plan_to_json(handle: *mut PlanHandle) -> FFIResult {
+    guarded(|| with_plan(handle, |plan| to_json(plan)))
+}
+
+/// Saves the plan behind a handle to a file path as pretty JSON and clears its dirty flag
+#[no_mangle]
+pub extern "C" fn ffi_handle_save(handle: *mut PlanHandle, path: *const c_char) -> FFIResult {
+    guarded(|| {
+        with_handle(handle, |handle| {
+            let path = PathBuf::from(c_str_to_string(path)?);
+            let json = serde_json::to_string_pretty(&handle.plan)
+                .map_err(|e| FFIError::internal(format!("Failed to serialize plan: {}", e)))?;
+            std::fs::write(&path, json)
+                .map_err(|e| FFIError::io(format!("Failed to write file: {}", e)))?;
+            handle.mark_saved();
+            Ok("Plan saved successfully".to_string())
+        })
+    })
+}
+
+/// Validates the plan behind a handle
+/// Returns the validation result as JSON: {"errors": [...], "warnings": [...]}
+#[no_mangle]
+pub extern "C" fn ffi_handle_validate(handle: *mut PlanHandle) -> FFIResult {
+    guarded(|| {
+        with_plan(handle, |plan| {
+            let validator = PlanValidator::new()
+                .map_err(|e| FFIError::internal(format!("Failed to create validator: {}", e)))?;
+            to_json(&validator.validate(plan))
+        })
+    })
+}
+
+/// Checks that the plan behind a handle has no validation errors
+/// Succeeds with the warnings as a JSON array; otherwise fails with
+/// `FFIErrorCode_ValidationFailed` and the full result ({"errors", "warnings"})
+/// as `error_detail`
+#[no_mangle]
+pub extern "C" fn ffi_handle_check(handle: *mut PlanHandle) -> FFIResult {
+    guarded(|| {
+        with_plan(handle, |plan| {
+            let validator = PlanValidator::new()
+                .map_err(|e| FFIError::internal(format!("Failed to create validator: {}", e)))?;
+            let result = validator.validate(plan);
+            if !result.errors.is_empty() {
+                return Err(FFIError::validation(&result));
+            }
+            to_json(&result.warnings)
+        })
+    })
+}
+
+/// Number of days in the schedule (0 for a null handle)
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn ffi_handle_day_count(handle: *const PlanHandle) -> usize {
+    guarded_or(0, || {
+        if handle.is_null() {
+            return 0;
+        }
+        let plan = unsafe { &(*handle).plan };
+        plan.schedule.len()
+    })
+}
+
+/// Number of segments in a day (0 for a null handle or an out-of-bounds day)
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn ffi_handle_segment_count(handle: *const PlanHandle, day_index: usize) -> usize {
+    guarded_or(0, || {
+        if handle.is_null() {
+            return 0;
+        }
+        let plan = unsafe { &(*handle).plan };
+        plan.schedule
+            .get(day_index)
+            .map_or(0, |day| day.segments.len())
+    })
+}
+
+/// Gets one day as JSON
+#[no_mangle]
+pub extern "C" fn ffi_handle_day_get(handle: *mut PlanHandle, day_index: usize) -> FFIResult {
+    guarded(|| {
+        with_plan(handle, |plan| match plan.schedule.get(day_index) {
+            Some(day) => to_json(day),
+            None => Err(PlanEditError::DayOutOfBounds(day_index).into()),
+        })
+    })
+}
+
+/// Gets one segment as JSON
+#[no_mangle]
+pub extern "C" fn ffi_handle_segment_get(
+    handle: *mut PlanHandle,
+    day_index: usize,
+    segment_index: usize,
+) -> FFIResult {
+    guarded(|| {
+        with_plan(handle, |plan| {
+            let day = plan
+                .schedule
+                .get(day_index)
+                .ok_or(PlanEditError::DayOutOfBounds(day_index))?;
+            match day.segments.get(segment_index) {
+                Some(segment) => to_json(segment),
+                None => Err(PlanEditError::SegmentOutOfBounds(segment_index).into()),
+            }
+        })
+    })
+}
+
+/// Appends a day; returns the new day's index
+#[no_mangle]
+pub extern "C" fn ffi_handle_day_add(
+    handle: *mut PlanHandle,
+    day_json: *const c_char,
+) -> FFIResult {
+    guarded(|| {
+        edit_plan(handle, |plan| {
+            plan.add_day(parse_json::<Day>(day_json, "day")?)?;
+            Ok((plan.schedule.len() - 1).to_string())
+        })
+    })
+}
+
+/// Removes a day
+#[no_mangle]
+pub extern "C" fn ffi_handle_day_remove(handle: *mut PlanHandle, day_index: usize) -> FFIResult {
+    guarded(|| {
+        edit_plan(handle, |plan| {
+            plan.remove_day(day_index)?;
+            Ok(String::new())
+        })
+    })
+}
+
+/// Appends a segment to a day; returns the new segment's index
+#[no_mangle]
+pub extern "C" fn ffi_handle_segment_add(
+    handle: *mut PlanHandle,
+    day_index: usize,
+    segment_json: *const c_char,
+) -> FFIResult {
+    guarded(|| {
+        edit_plan(handle, |plan| {
+            let segment = parse_json::<Segment>(segment_json, "segment")?;
+            plan.add_segment(day_index, segment)?;
+            Ok((plan.schedule[day_index].segments.len() - 1).to_string())
+        })
+    })
+}
+
+/// Replaces a segment
+#[no_mangle]
+pub extern "C" fn ffi_handle_segment_update(
+    handle: *mut PlanHandle,
+    day_index: usize,
+    segment_index: usize,
+    segment_json: *const c_char,
+) -> FFIResult {
+    guarded(|| {
+        edit_plan(handle, |plan| {
+            let segment = parse_json::<Segment>(segment_json, "segment")?;
+            plan.update_segment(day_index, segment_index, segment)?;
+            Ok(String::new())
+        })
+    })
+}
+
+/// Removes a segment
+#[no_mangle]
+pub extern "C" fn ffi_handle_segment_remove(
+    handle: *mut PlanHandle,
+    day_index: usize,
+    segment_index: usize,
+) -> FFIResult {
+    guarded(|| {
+        edit_plan(handle, |plan| {
+            plan.remove_segment(day_index, segment_index)?;
+            Ok(String::new())
+        })
+    })
+}
+
+/// Gets all exercise groups as JSON (object of group name to exercise codes)
+#[no_mangle]
+pub extern "C" fn ffi_handle_groups_get(handle: *mut PlanHandle) -> FFIResult {
+    guarded(|| with_plan(handle, |plan| to_json(&plan.groups)))
+}
+
+/// Adds or updates an exercise group
+/// exercises_json: JSON array of exercise codes ["ex1", "ex2"]
+#[no_mangle]
+pub extern "C" fn ffi_handle_group_add(
+    handle: *mut PlanHandle,
     group_name: *const c_char,
+    exercises_json: *const c_char,
 ) -> FFIResult {
-    let plan_str = match c_str_to_string(plan_json) {
-        Ok(s) => s,
-        Err(e) => return create_error_result(e),
-    };
+    guarded(|| {
+        edit_plan(handle, |plan| {
+            let name = c_str_to_string(group_name)?;
+            let exercises = parse_json::<Vec<String>>(exercises_json, "exercises")?;
+            plan.groups.insert(name, exercises);
+            Ok(String::new())
+        })
+    })
+}
 
-    let name = match c_str_to_string(group_name) {
-        Ok(s) => s,
-        Err(e) => return create_error_result(e),
-    };
+/// Removes an exercise group
+#[no_mangle]
+pub extern "C" fn ffi_handle_group_remove(
+    handle: *mut PlanHandle,
+    group_name: *const c_char,
+) -> FFIResult {
+    guarded(|| {
+        edit_plan(handle, |plan| {
+            plan.groups.remove(&c_str_to_string(group_name)?);
+            Ok(String::new())
+        })
+    })
+}
 
-    let mut plan: Plan = match serde_json::from_str(&plan_str) {
-        Ok(p) => p,
-        Err(e) => return create_error_result(format!("Failed to parse plan JSON: {}", e)),
-    };
+/// Adds or updates an exercise dictionary entry
+#[no_mangle]
+pub extern "C" fn ffi_handle_dictionary_add_entry(
+    handle: *mut PlanHandle,
+    exercise_code: *const c_char,
+    exercise_name: *const c_char,
+) -> FFIResult {
+    guarded(|| {
+        edit_plan(handle, |plan| {
+            let code = c_str_to_string(exercise_code)?;
+            let name = c_str_to_string(exercise_name)?;
+            plan.add_dictionary_entry(&code, &name)?;
+            Ok(String::new())
+        })
+    })
+}
 
-    plan.groups.remove(&name);
+/// Sets the value at a JSON pointer, e.g. "/schedule/0/label"
+/// value_json: JSON value to store
+#[no_mangle]
+pub extern "C" fn ffi_handle_set(
+    handle: *mut PlanHandle,
+    pointer: *const c_char,
+    value_json: *const c_char,
+) -> FFIResult {
+    guarded(|| {
+        edit_plan(handle, |plan| {
+            let pointer = c_str_to_string(pointer)?;
+            let value = parse_json(value_json, "value")?;
+            plan.set_pointer(&pointer, value)?;
+            Ok(String::new())
+        })
+    })
+}
 
-    match serde_json::to_string(&plan) {
-        Ok(json) => create_success_result(json),
-        Err(e) => create_error_result(format!("Failed to serialize plan: {}", e)),
-    }
+/// Removes the value at a JSON pointer; returns the removed value as JSON
+#[no_mangle]
+pub extern "C" fn ffi_handle_remove(handle: *mut PlanHandle, pointer: *const c_char) -> FFIResult {
+    guarded(|| {
+        edit_plan(handle, |plan| {
+            let pointer = c_str_to_string(pointer)?;
+            let removed = plan.remove_pointer(&pointer)?;
+            to_json(&removed)
+        })
+    })
 }
 
 // ============================================================================
-// Dictionary Operations
+// Observers & Autosave
 // ============================================================================
 
-/// Adds or updates an exercise dictionary entry in the plan
+// Hosts register callbacks instead of polling `ffi_plan_validate`. Edits made
+// through a handle mark it dirty and fire `on_change` immediately; validation
+// and autosave are driven by `ffi_handle_tick`, which the host calls from its
+// run-loop timer (the GTK editor does the same from a glib timeout). All
+// callbacks therefore run on the host's own thread. They are queued while a
+// call works on the handle and fired as it returns, never mid-edit.
+
+/// Registers observer callbacks, replacing any previous ones; pass null to remove them.
+/// Validation is debounced: it runs on the first tick after `debounce_ms` without
+/// edits, once per batch of edits. A new observer gets a validation on the next tick.
 #[no_mangle]
-pub extern "C" fn ffi_dictionary_add_entry(
-    plan_json: *const c_char,
-    exercise_code: *const c_char,
-    exercise_name: *const c_char,
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn ffi_handle_set_observer(
+    handle: *mut PlanHandle,
+    observer: *const FFIPlanObserver,
+    debounce_ms: u64,
 ) -> FFIResult {
-    let plan_str = match c_str_to_string(plan_json) {
-        Ok(s) => s,
-        Err(e) => return create_error_result(e),
-    };
+    guarded(|| {
+        with_handle(handle, |handle| {
+            handle.observer = unsafe { observer.as_ref() }.copied();
+            handle.tracker.debounce = Duration::from_millis(debounce_ms);
+            if handle.observer.is_some() {
+                handle.tracker.request_validation();
+            }
+            Ok(String::new())
+        })
+    })
+}
 
-    let code = match c_str_to_string(exercise_code) {
-        Ok(s) => s.trim().to_string(),
-        Err(e) => return create_error_result(e),
-    };
+/// Enables autosave of a dirty plan to `<drafts dir>/<plan_id>.json` at most every
+/// `interval_ms`; pass a null `plan_id` to disable it. Autosaves never clear the
+/// dirty flag, only `ffi_handle_save` does. Returns the draft path.
+#[no_mangle]
+pub extern "C" fn ffi_handle_set_autosave(
+    handle: *mut PlanHandle,
+    plan_id: *const c_char,
+    interval_ms: u64,
+) -> FFIResult {
+    guarded(|| {
+        with_handle(handle, |handle| {
+            if plan_id.is_null() {
+                handle.tracker.autosave = None;
+                return Ok(String::new());
+            }
+            let plan_id = c_str_to_string(plan_id)?;
+            validate_plan_id(&plan_id)?;
+            let path = weightlifting_core::paths::get_drafts_dir()
+                .map_err(|e| FFIError::io(format!("Failed to get drafts dir: {}", e)))?
+                .join(format!("{}.json", plan_id));
+            let data = path.to_string_lossy().into_owned();
+            handle.tracker.autosave = Some(Autosave {
+                path,
+                interval: Duration::from_millis(interval_ms),
+            });
+            Ok(data)
+        })
+    })
+}
 
-    if code.is_empty() {
-        return create_error_result("Exercise code cannot be empty".to_string());
-    }
+/// Runs the debounced validation and autosave that are due, firing their callbacks
+/// Returns JSON: {"dirty": bool, "validated": bool, "autosaved": path or null}
+#[no_mangle]
+pub extern "C" fn ffi_handle_tick(handle: *mut PlanHandle) -> FFIResult {
+    guarded(|| with_handle(handle, |handle| to_json(&handle.tick(Instant::now())?)))
+}
 
-    let name = match c_str_to_string(exercise_name) {
-        Ok(s) => s.trim().to_string(),
-        Err(e) => return create_error_result(e),
-    };
+/// Whether the plan changed since it was opened or last saved (false for a null handle)
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn ffi_handle_is_dirty(handle: *const PlanHandle) -> bool {
+    guarded_or(false, || {
+        !handle.is_null() && unsafe { &(*handle).tracker }.is_dirty()
+    })
+}
 
-    if name.is_empty() {
-        return create_error_result("Exercise name cannot be empty".to_string());
+// ============================================================================
+// Versioning & Export
+// ============================================================================
+
+fn plan_ref(ptr: *const c_char) -> Result<Option<PlanRef>, FFIError> {
+    if ptr.is_null() {
+        return Ok(None);
     }
+    Ok(Some(c_str_to_string(ptr)?.parse()?))
+}
+
+/// Diffs two stored states of a plan
+/// from, to: "draft" or a version such as "1.2.0"; null defaults to the latest
+/// version and the draft respectively
+/// Returns the PlanDiff as JSON
+#[no_mangle]
+pub extern "C" fn ffi_plan_diff(
+    plan_id: *const c_char,
+    from: *const c_char,
+    to: *const c_char,
+) -> FFIResult {
+    guarded(|| {
+        into_result((|| -> Result<String, FFIError> {
+            let plan_id = c_str_to_string(plan_id)?;
+            let paths = AppPaths::new()
+                .map_err(|e| FFIError::io(format!("Failed to get app paths: {}", e)))?;
+            let store = PlanStore::new(&paths);
+            let from = match plan_ref(from)? {
+                Some(from) => from,
+                None => store
+                    .history(&plan_id)?
+                    .last()
+                    .map(|entry| PlanRef::Version(entry.version.clone()))
+                    .ok_or_else(|| {
+                        FFIError::new(
+                            FFIErrorCode::NotFound,
+                            format!("Plan {} has no promoted versions", plan_id),
+                        )
+                    })?,
+            };
+            let to = plan_ref(to)?.unwrap_or(PlanRef::Draft);
+            let diff = store.diff(&plan_id, &from, &to)?;
+            to_json(&diff)
+        })())
+    })
+}
+
+#[derive(serde::Deserialize)]
+struct ExportRequest {
+    plans: Vec<ExportRequestPlan>,
+    author: Option<String>,
+    description: Option<String>,
+}
+
+/// A plan to stage: inline `plan` JSON, or the stored draft or `version` of `plan_id`
+#[derive(serde::Deserialize)]
+struct ExportRequestPlan {
+    plan_id: String,
+    version: Option<String>,
+    plan: Option<Plan>,
+}
+
+/// Stages plans for export and reports conflicts, dependencies and the manifest
+/// request_json: {"plans": [{"plan_id", "version"?, "plan"?}], "author"?, "description"?}
+/// Returns {"can_export", "conflicts", "dependencies", "manifest"} as JSON
+#[no_mangle]
+pub extern "C" fn ffi_export_stage(request_json: *const c_char) -> FFIResult {
+    guarded(|| {
+        into_result((|| -> Result<String, FFIError> {
+            let request = parse_json::<ExportRequest>(request_json, "export request")?;
+            let mut stager = ExportStager::new();
+            // Only stored plans need the app directories
+            let paths = if request.plans.iter().any(|staged| staged.plan.is_none()) {
+                Some(
+                    AppPaths::new()
+                        .map_err(|e| FFIError::io(format!("Failed to get app paths: {}", e)))?,
+                )
+            } else {
+                None
+            };
+            let store = paths.as_ref().map(PlanStore::new);
+
+            for staged in request.plans {
+                let plan_ref = match staged.version.as_deref() {
+                    Some(version) => version.parse()?,
+                    None => PlanRef::Draft,
+                };
+                let version = match &plan_ref {
+                    PlanRef::Version(version) => version.clone(),
+                    PlanRef::Draft => PlanVersion::new(1, 0, 0),
+                };
+                let plan = match (staged.plan, &store) {
+                    (Some(plan), _) => plan,
+                    (None, Some(store)) => store.load(&staged.plan_id, &plan_ref)?,
+                    (None, None) => unreachable!("store is opened when any plan is not inline"),
+                };
+                // Duplicate IDs are recorded as conflicts rather than failing the call
+                let _ = stager.add_plan(staged.plan_id, VersionedPlan::new_draft(plan, version));
+            }
+
+            stager.analyze();
+            let manifest = stager.generate_manifest(request.author, request.description);
+            to_json(&serde_json::json!({
+                "can_export": stager.can_export(),
+                "conflicts": stager.get_conflicts(),
+                "dependencies": stager.get_dependencies(),
+                "manifest": manifest,
+            }))
+        })())
+    })
+}
 
-    let mut plan: Plan = match serde_json::from_str(&plan_str) {
-        Ok(p) => p,
-        Err(e) => return create_error_result(format!("Failed to parse plan JSON: {}", e)),
+// ============================================================================
+// Charts
+// ============================================================================
+
+/// Options for `ffi_chart_spec`; dates are YYYY-MM-DD
+#[derive(Default, serde::Deserialize)]
+struct ChartOptions {
+    exercise: Option<String>,
+    start_date: Option<NaiveDate>,
+    end_date: Option<NaiveDate>,
+    unit: Option<String>,
+    title: Option<String>,
+}
+
+/// Generates a built-in chart's Vega-Lite spec from the metrics cache
+/// chart_type: e1rm, e1rm-grid, volume, muscle-volume, pattern-volume, acwr,
+/// fitness-fatigue, weekly-load, monotony, strain, pr or heatmap
+/// options_json: {"exercise"?, "start_date"?, "end_date"?, "unit"?, "title"?} or null
+/// Returns the spec as JSON
+#[no_mangle]
+pub extern "C" fn ffi_chart_spec(
+    chart_type: *const c_char,
+    options_json: *const c_char,
+) -> FFIResult {
+    guarded(|| {
+        into_result((|| -> Result<String, FFIError> {
+            let chart_type = c_str_to_string(chart_type)?;
+            let options = if options_json.is_null() {
+                ChartOptions::default()
+            } else {
+                parse_json::<ChartOptions>(options_json, "chart options")?
+            };
+            let paths = AppPaths::new()
+                .map_err(|e| FFIError::io(format!("Failed to get app paths: {}", e)))?;
+            let cache = MetricsCache::new(&paths)?;
+            let spec = chart_spec_from_options(&paths, &cache, &chart_type, options)?;
+            to_json(&spec.to_json())
+        })())
+    })
+}
+
+/// Resolves the unit and date range in `options` and builds the built-in chart
+fn chart_spec_from_options(
+    paths: &AppPaths,
+    cache: &MetricsCache,
+    chart_type: &str,
+    options: ChartOptions,
+) -> Result<VegaLiteSpec, FFIError> {
+    let unit = match options.unit.as_deref() {
+        Some(unit) => unit.parse().map_err(FFIError::invalid_argument)?,
+        None => UserPreferences::load(paths)
+            .display_unit
+            .unwrap_or_default(),
+    };
+    let date_range = match (options.start_date, options.end_date) {
+        (None, None) => None,
+        (start, end) => Some((
+            start.unwrap_or(NaiveDate::MIN),
+            end.unwrap_or_else(|| chrono::Local::now().date_naive()),
+        )),
+    };
+    let filters = ChartFilters {
+        exercise: options.exercise.as_deref(),
+        date_range,
+        unit,
+        title: options.title,
     };
+    builtin_chart_spec(cache, paths, chart_type, &filters)?
+        .ok_or_else(|| FFIError::invalid_argument(format!("Unknown chart type: {}", chart_type)))
+}
 
-    plan.dictionary.insert(code, name);
+// ============================================================================
+// Location Profiles & Schemes
+// ============================================================================
 
-    match serde_json::to_string(&plan) {
-        Ok(json) => create_success_result(json),
-        Err(e) => create_error_result(format!("Failed to serialize plan: {}", e)),
-    }
+/// Returns the built-in location profiles (home gym, commercial gym) as a JSON array
+#[no_mangle]
+pub extern "C" fn ffi_location_profiles() -> FFIResult {
+    guarded(|| {
+        match to_json(&[
+            LocationProfile::home_gym(),
+            LocationProfile::commercial_gym(),
+        ]) {
+            Ok(json) => create_success_result(json),
+            Err(e) => create_error_result(e),
+        }
+    })
+}
+
+/// Previews rounding a target load to what a location's equipment can make
+/// profile_json: a LocationProfile, or null for the home gym profile
+/// equipment: "bb" (barbell) or "db" (dumbbell)
+/// Returns the RoundingPreview as JSON with a display string under "text"
+#[no_mangle]
+pub extern "C" fn ffi_location_round(
+    profile_json: *const c_char,
+    equipment: *const c_char,
+    target_kg: f64,
+) -> FFIResult {
+    guarded(|| {
+        into_result((|| -> Result<String, FFIError> {
+            let profile = if profile_json.is_null() {
+                LocationProfile::home_gym()
+            } else {
+                parse_json::<LocationProfile>(profile_json, "location profile")?
+            };
+            let preview = match c_str_to_string(equipment)?.as_str() {
+                "bb" => profile.round_barbell_load(target_kg),
+                "db" => profile.round_dumbbell_load(target_kg),
+                other => {
+                    return Err(FFIError::invalid_argument(format!(
+                        "Unknown equipment: {}. Available: bb, db",
+                        other
+                    )))
+                }
+            };
+            let mut value = serde_json::to_value(&preview)
+                .map_err(|e| FFIError::internal(format!("Failed to serialize: {}", e)))?;
+            value["text"] = preview.format_preview().into();
+            to_json(&value)
+        })())
+    })
+}
+
+/// Generates the warm-up ladder leading into a working set
+/// warmup_json: a plan's "warmup" config (round_to in kg)
+/// training_max_kg: needed by the percent_of_1rm pattern; NaN or <= 0 for none
+/// equipment: "bb" or "db" to round to profile_json's equipment (null profile = home gym),
+/// or null to skip location rounding
+/// Returns the WarmupSet list as JSON
+#[no_mangle]
+pub extern "C" fn ffi_warmup_sets(
+    warmup_json: *const c_char,
+    working_kg: f64,
+    training_max_kg: f64,
+    equipment: *const c_char,
+    profile_json: *const c_char,
+) -> FFIResult {
+    guarded(|| {
+        into_result((|| -> Result<String, FFIError> {
+            let config = parse_json::<WarmupConfig>(warmup_json, "warmup config")?;
+            let generator = WarmupGenerator::new(&config)
+                .map_err(|e| FFIError::new(FFIErrorCode::InvalidValue, e.to_string()))?;
+            let profile = if profile_json.is_null() {
+                LocationProfile::home_gym()
+            } else {
+                parse_json::<LocationProfile>(profile_json, "location profile")?
+            };
+            let generator = if equipment.is_null() {
+                generator
+            } else {
+                let equipment = match c_str_to_string(equipment)?.as_str() {
+                    "bb" => LoadEquipment::Barbell,
+                    "db" => LoadEquipment::Dumbbell,
+                    other => {
+                        return Err(FFIError::invalid_argument(format!(
+                            "Unknown equipment: {}. Available: bb, db",
+                            other
+                        )))
+                    }
+                };
+                generator.location(&profile, equipment)
+            };
+            let training_max = Some(training_max_kg).filter(|kg| *kg > 0.0);
+            let sets = generator
+                .sets(working_kg, training_max)
+                .map_err(|e| FFIError::new(FFIErrorCode::InvalidValue, e.to_string()))?;
+            to_json(&sets)
+        })())
+    })
+}
+
+/// Expands a scheme template into concrete straight sets
+/// template_json: e.g. {"name": "top_backoff", ...}
+/// Returns the ExpandedScheme as JSON
+#[no_mangle]
+pub extern "C" fn ffi_scheme_expand(
+    template_json: *const c_char,
+    exercise: *const c_char,
+) -> FFIResult {
+    guarded(|| {
+        into_result((|| -> Result<String, FFIError> {
+            let template = parse_json::<SchemeTemplate>(template_json, "scheme template")?;
+            let exercise = c_str_to_string(exercise)?;
+            let expanded = template
+                .expand(&exercise)
+                .map_err(|e| FFIError::new(FFIErrorCode::InvalidValue, e))?;
+            to_json(&expanded)
+        })())
+    })
 }
 
 // ============================================================================
@@ -510,28 +1506,31 @@ pub extern "C" fn ffi_dictionary_add_entry(
 /// Gets the application support directory path for the platform
 #[no_mangle]
 pub extern "C" fn ffi_get_app_support_dir() -> FFIResult {
-    match weightlifting_core::paths::get_app_support_dir() {
+    guarded(|| match weightlifting_core::paths::get_app_support_dir() {
         Ok(path) => create_success_result(path.to_string_lossy().to_string()),
-        Err(e) => create_error_result(format!("Failed to get app support dir: {}", e)),
-    }
+        Err(e) => create_error_result(FFIError::io(format!(
+            "Failed to get app support dir: {}",
+            e
+        ))),
+    })
 }
 
 /// Gets the cache directory path for the platform
 #[no_mangle]
 pub extern "C" fn ffi_get_cache_dir() -> FFIResult {
-    match weightlifting_core::paths::get_cache_dir() {
+    guarded(|| match weightlifting_core::paths::get_cache_dir() {
         Ok(path) => create_success_result(path.to_string_lossy().to_string()),
-        Err(e) => create_error_result(format!("Failed to get cache dir: {}", e)),
-    }
+        Err(e) => create_error_result(FFIError::io(format!("Failed to get cache dir: {}", e))),
+    })
 }
 
 /// Gets the drafts directory path for the platform
 #[no_mangle]
 pub extern "C" fn ffi_get_drafts_dir() -> FFIResult {
-    match weightlifting_core::paths::get_drafts_dir() {
+    guarded(|| match weightlifting_core::paths::get_drafts_dir() {
         Ok(path) => create_success_result(path.to_string_lossy().to_string()),
-        Err(e) => create_error_result(format!("Failed to get drafts dir: {}", e)),
-    }
+        Err(e) => create_error_result(FFIError::io(format!("Failed to get drafts dir: {}", e))),
+    })
 }
 
 #[cfg(test)]
@@ -555,4 +1554,170 @@ mod tests {
         ffi_free_result(result);
         ffi_free_result(validate_result);
     }
+
+    fn result_json(result: FFIResult) -> serde_json::Value {
+        assert!(result.success, "{:?}", unsafe {
+            CStr::from_ptr(result.error)
+        });
+        let json = unsafe { CStr::from_ptr(result.data) }.to_str().unwrap();
+        let value = serde_json::from_str(json).unwrap();
+        ffi_free_result(result);
+        value
+    }
+
+    #[test]
+    fn test_location_round_and_scheme_expand() {
+        let equipment = CString::new("bb").unwrap();
+        let preview = result_json(ffi_location_round(
+            std::ptr::null(),
+            equipment.as_ptr(),
+            101.0,
+        ));
+        assert_eq!(preview["equipment"], "bb");
+        assert!(preview["text"].is_string());
+
+        let template = CString::new(
+            r#"{"name": "top_backoff", "top": {"reps": 3, "intensity": {"rpe": 8.0}},
+                "backoff": [{"percent": 0.9, "sets": 3, "reps": 5}]}"#,
+        )
+        .unwrap();
+        let exercise = CString::new("SQ.BB.BACK").unwrap();
+        let expanded = result_json(ffi_scheme_expand(template.as_ptr(), exercise.as_ptr()));
+        assert_eq!(expanded["exercise"], "SQ.BB.BACK");
+        assert!(!expanded["sets"].as_array().unwrap().is_empty());
+
+        let warmup = CString::new(
+            r#"{"stages": [{"pct_1rm": 0.5, "reps": 5}, {"pct_1rm": 0.8, "reps": 3}],
+                "round_to": 2.5}"#,
+        )
+        .unwrap();
+        let sets = result_json(ffi_warmup_sets(
+            warmup.as_ptr(),
+            100.0,
+            f64::NAN,
+            std::ptr::null(),
+            std::ptr::null(),
+        ));
+        assert_eq!(sets[0]["target_kg"], 50.0);
+        assert_eq!(sets[1]["reps"], 3);
+        assert_eq!(sets[1]["is_warmup"], true);
+
+        let of_max = CString::new(r#"{"pattern": "percent_of_1rm"}"#).unwrap();
+        let result = ffi_warmup_sets(
+            of_max.as_ptr(),
+            100.0,
+            f64::NAN,
+            equipment.as_ptr(),
+            std::ptr::null(),
+        );
+        assert_eq!(result.error_code, FFIErrorCode::InvalidValue);
+        ffi_free_result(result);
+    }
+
+    #[test]
+    fn test_error_codes() {
+        let handle = ffi_handle_new();
+        let result = ffi_handle_segment_remove(handle, 2, 0);
+        assert!(!result.success);
+        assert_eq!(result.error_code, FFIErrorCode::IndexOutOfBounds);
+        assert_eq!(result.error_category, FFIErrorCategory::Bounds);
+        assert!(result.error_detail.is_null());
+        ffi_free_result(result);
+
+        let day = CString::new(r#"{"day": 1, "label": "Lower", "segments": []}"#).unwrap();
+        let segment = CString::new(r#"{"type": "straight", "ex": "BP.BB.FLAT"}"#).unwrap();
+        ffi_free_result(ffi_handle_day_add(handle, day.as_ptr()));
+        ffi_free_result(ffi_handle_segment_add(handle, 0, segment.as_ptr()));
+        let result = ffi_handle_check(handle);
+        assert_eq!(result.error_code, FFIErrorCode::ValidationFailed);
+        let detail = unsafe { CStr::from_ptr(result.error_detail) }
+            .to_str()
+            .unwrap();
+        let detail: serde_json::Value = serde_json::from_str(detail).unwrap();
+        assert!(!detail["errors"].as_array().unwrap().is_empty());
+        ffi_free_result(result);
+        ffi_plan_free(handle);
+    }
+
+    #[test]
+    fn test_panics_and_nuls_stay_inside() {
+        let result = guarded(|| panic!("boom"));
+        assert!(!result.success);
+        assert_eq!(result.error_code, FFIErrorCode::Panic);
+        assert_eq!(result.error_category, FFIErrorCategory::Internal);
+        let message = unsafe { CStr::from_ptr(result.error) }.to_str().unwrap();
+        assert_eq!(message, "Internal panic: boom");
+        ffi_free_result(result);
+        assert_eq!(guarded_or(7usize, || panic!("boom")), 7);
+
+        // serde echoes the decoded variant, interior NUL included, into the message
+        let handle = ffi_handle_new();
+        let segment = CString::new(r#"{"type": "a\u0000b"}"#).unwrap();
+        let result = ffi_handle_segment_add(handle, 0, segment.as_ptr());
+        assert_eq!(result.error_code, FFIErrorCode::InvalidJson);
+        let message = unsafe { CStr::from_ptr(result.error) }.to_str().unwrap();
+        assert!(message.contains("a\u{FFFD}b"), "{}", message);
+        ffi_free_result(result);
+        ffi_plan_free(handle);
+    }
+
+    #[test]
+    fn test_export_stage_inline_plans() {
+        let plan = serde_json::to_value(Plan::new("Block".to_string())).unwrap();
+        let request = serde_json::json!({
+            "plans": [
+                {"plan_id": "block", "plan": plan},
+                {"plan_id": "block", "version": "2.0.0", "plan": plan}
+            ]
+        });
+        let request = CString::new(request.to_string()).unwrap();
+        let staged = result_json(ffi_export_stage(request.as_ptr()));
+        assert_eq!(staged["can_export"], false);
+        assert_eq!(staged["conflicts"].as_array().unwrap().len(), 1);
+        assert!(staged["manifest"].is_object());
+    }
+
+    struct Probe {
+        handle: *mut PlanHandle,
+        changes: Vec<(bool, usize)>,
+        validations: usize,
+    }
+
+    extern "C" fn probe_change(user_data: *mut std::ffi::c_void, dirty: bool) {
+        let probe = unsafe { &mut *(user_data as *mut Probe) };
+        probe
+            .changes
+            .push((dirty, ffi_handle_day_count(probe.handle)));
+        ffi_free_result(ffi_handle_tick(probe.handle));
+    }
+
+    extern "C" fn probe_validation(user_data: *mut std::ffi::c_void, _result: *const c_char) {
+        let probe = unsafe { &mut *(user_data as *mut Probe) };
+        probe.validations += 1;
+    }
+
+    #[test]
+    fn test_callbacks_may_reenter_handle() {
+        let handle = ffi_handle_new();
+        let mut probe = Probe {
+            handle,
+            changes: Vec::new(),
+            validations: 0,
+        };
+        let observer = FFIPlanObserver {
+            user_data: &mut probe as *mut Probe as *mut std::ffi::c_void,
+            on_change: Some(probe_change),
+            on_validation: Some(probe_validation),
+            on_autosave: None,
+        };
+        ffi_free_result(ffi_handle_set_observer(handle, &observer, 0));
+
+        let day = CString::new(r#"{"day": 1, "label": "Lower", "segments": []}"#).unwrap();
+        result_json(ffi_handle_day_add(handle, day.as_ptr()));
+        ffi_plan_free(handle);
+
+        // on_change saw the finished edit and its nested tick ran the validation
+        assert_eq!(probe.changes, vec![(true, 1)]);
+        assert_eq!(probe.validations, 1);
+    }
 }
@@ -0,0 +1,265 @@
+use crate::{c_string, FFIError, PlanHandle};
+use serde_json::json;
+use std::ffi::{c_void, CString};
+use std::os::raw::c_char;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use weightlifting_validate::PlanValidator;
+
+/// Fired synchronously after a handle edit or save with the new dirty flag
+pub type FFIChangeCallback = Option<extern "C" fn(user_data: *mut c_void, dirty: bool)>;
+
+/// Fired from `ffi_handle_tick` with the validation result JSON
+/// ({"errors": [...], "warnings": [...]}); the string is only valid during the call
+pub type FFIValidationCallback =
+    Option<extern "C" fn(user_data: *mut c_void, result_json: *const c_char)>;
+
+/// Fired from `ffi_handle_tick` after an autosave attempt with the draft path on
+/// success or an error message on failure; the other argument is null
+pub type FFIAutosaveCallback =
+    Option<extern "C" fn(user_data: *mut c_void, path: *const c_char, error: *const c_char)>;
+
+/// Callbacks registered with `ffi_handle_set_observer`; any of them may be null.
+/// `user_data` is passed back unchanged and must stay valid while registered.
+/// Callbacks run on the thread that called into the library, just before that call
+/// returns and after it has finished with the handle, so they may call back into the
+/// handle API (including `ffi_plan_free`).
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct FFIPlanObserver {
+    pub user_data: *mut c_void,
+    pub on_change: FFIChangeCallback,
+    pub on_validation: FFIValidationCallback,
+    pub on_autosave: FFIAutosaveCallback,
+}
+
+/// A callback queued while a handle call has the handle borrowed. `with_handle` fires
+/// the queue only once that borrow has ended, so a callback re-entering the API never
+/// aliases the `&mut PlanHandle` of the call that triggered it.
+pub(crate) enum Notification {
+    Change(bool),
+    Validation(CString),
+    Autosave(Result<CString, CString>),
+}
+
+impl Notification {
+    pub fn fire(&self, observer: &FFIPlanObserver) {
+        let user_data = observer.user_data;
+        match self {
+            Notification::Change(dirty) => {
+                if let Some(on_change) = observer.on_change {
+                    on_change(user_data, *dirty);
+                }
+            }
+            Notification::Validation(result) => {
+                if let Some(on_validation) = observer.on_validation {
+                    on_validation(user_data, result.as_ptr());
+                }
+            }
+            Notification::Autosave(written) => {
+                if let Some(on_autosave) = observer.on_autosave {
+                    match written {
+                        Ok(path) => on_autosave(user_data, path.as_ptr(), std::ptr::null()),
+                        Err(error) => on_autosave(user_data, std::ptr::null(), error.as_ptr()),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Where and how often a handle autosaves its draft
+#[derive(Debug, Clone)]
+pub(crate) struct Autosave {
+    pub path: PathBuf,
+    pub interval: Duration,
+}
+
+/// Dirty flag plus the edit generations behind debounced validation and autosave.
+/// Every edit bumps the generation; validation and autosave remember the last
+/// generation they handled so repeated ticks do no work until the plan changes.
+#[derive(Debug)]
+pub(crate) struct ChangeTracker {
+    dirty: bool,
+    generation: u64,
+    validated: Option<u64>,
+    autosaved: u64,
+    last_change: Instant,
+    last_autosave: Instant,
+    pub debounce: Duration,
+    pub autosave: Option<Autosave>,
+}
+
+impl ChangeTracker {
+    pub fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            dirty: false,
+            generation: 0,
+            validated: Some(0),
+            autosaved: 0,
+            last_change: now,
+            last_autosave: now,
+            debounce: Duration::ZERO,
+            autosave: None,
+        }
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    pub fn record_change(&mut self, now: Instant) {
+        self.dirty = true;
+        self.generation += 1;
+        self.last_change = now;
+    }
+
+    /// The plan was written to its file; autosaves do not count
+    pub fn mark_saved(&mut self) {
+        self.dirty = false;
+    }
+
+    /// Forces a validation on the next due tick, e.g. for a newly registered observer
+    pub fn request_validation(&mut self) {
+        self.validated = None;
+    }
+
+    /// True once the plan changed since the last validation and has been
+    /// quiet for the debounce period
+    pub fn validation_due(&self, now: Instant) -> bool {
+        self.validated != Some(self.generation)
+            && now.saturating_duration_since(self.last_change) >= self.debounce
+    }
+
+    pub fn mark_validated(&mut self) {
+        self.validated = Some(self.generation);
+    }
+
+    /// True when the dirty plan changed since the last autosave and the interval elapsed
+    pub fn autosave_due(&self, now: Instant) -> bool {
+        match &self.autosave {
+            Some(autosave) => {
+                self.dirty
+                    && self.autosaved != self.generation
+                    && now.saturating_duration_since(self.last_autosave) >= autosave.interval
+            }
+            None => false,
+        }
+    }
+
+    /// Records an autosave attempt; failed attempts are retried after the interval
+    pub fn mark_autosave_attempt(&mut self, now: Instant, saved: bool) {
+        self.last_autosave = now;
+        if saved {
+            self.autosaved = self.generation;
+        }
+    }
+}
+
+impl PlanHandle {
+    /// Marks the plan dirty after a successful edit and queues `on_change`
+    pub(crate) fn record_change(&mut self) {
+        self.tracker.record_change(Instant::now());
+        self.notify(Notification::Change(true));
+    }
+
+    pub(crate) fn mark_saved(&mut self) {
+        self.tracker.mark_saved();
+        self.notify(Notification::Change(false));
+    }
+
+    fn notify(&mut self, notification: Notification) {
+        if self.observer.is_some() {
+            self.pending.push(notification);
+        }
+    }
+
+    /// Runs the validation and autosave that are due, queueing their callbacks.
+    /// Returns {"dirty", "validated", "autosaved"} where `autosaved` is the draft
+    /// path written by this tick, if any.
+    pub(crate) fn tick(&mut self, now: Instant) -> Result<serde_json::Value, FFIError> {
+        let validated = self.tracker.validation_due(now);
+        if validated {
+            let validator = PlanValidator::new()
+                .map_err(|e| FFIError::internal(format!("Failed to create validator: {}", e)))?;
+            let result = serde_json::to_string(&validator.validate(&self.plan))
+                .map_err(|e| FFIError::internal(format!("Failed to serialize: {}", e)))?;
+            self.tracker.mark_validated();
+            self.notify(Notification::Validation(c_string(result)));
+        }
+
+        let mut autosaved = None;
+        if let (true, Some(autosave)) = (self.tracker.autosave_due(now), &self.tracker.autosave) {
+            let path = autosave.path.clone();
+            let written = write_draft(&self.plan, &path);
+            self.tracker.mark_autosave_attempt(now, written.is_ok());
+            self.notify(Notification::Autosave(match &written {
+                Ok(()) => Ok(c_string(path.to_string_lossy().into_owned())),
+                Err(e) => Err(c_string(e.to_string())),
+            }));
+            if written.is_ok() {
+                autosaved = Some(path.to_string_lossy().into_owned());
+            }
+        }
+
+        Ok(json!({
+            "dirty": self.tracker.is_dirty(),
+            "validated": validated,
+            "autosaved": autosaved,
+        }))
+    }
+}
+
+/// Writes the plan as a draft; like the GTK autosave this never clears the dirty flag
+fn write_draft(plan: &weightlifting_core::Plan, path: &Path) -> Result<(), FFIError> {
+    let json = serde_json::to_string_pretty(plan)
+        .map_err(|e| FFIError::internal(format!("Failed to serialize plan: {}", e)))?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| FFIError::io(format!("Failed to create drafts dir: {}", e)))?;
+    }
+    std::fs::write(path, json).map_err(|e| FFIError::io(format!("Failed to write draft: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debounce_and_autosave_schedule() {
+        let start = Instant::now();
+        let mut tracker = ChangeTracker::new();
+        tracker.debounce = Duration::from_millis(300);
+        tracker.autosave = Some(Autosave {
+            path: PathBuf::from("draft.json"),
+            interval: Duration::from_secs(5),
+        });
+        assert!(!tracker.validation_due(start));
+        assert!(!tracker.autosave_due(start + Duration::from_secs(10)));
+
+        tracker.record_change(start);
+        tracker.record_change(start + Duration::from_millis(200));
+        assert!(tracker.is_dirty());
+        assert!(!tracker.validation_due(start + Duration::from_millis(400)));
+        assert!(tracker.validation_due(start + Duration::from_millis(500)));
+        tracker.mark_validated();
+        assert!(!tracker.validation_due(start + Duration::from_secs(1)));
+
+        assert!(!tracker.autosave_due(start + Duration::from_secs(1)));
+        let due = start + Duration::from_secs(6);
+        assert!(tracker.autosave_due(due));
+        tracker.mark_autosave_attempt(due, false);
+        assert!(!tracker.autosave_due(due + Duration::from_secs(1)));
+        assert!(tracker.autosave_due(due + Duration::from_secs(5)));
+        tracker.mark_autosave_attempt(due + Duration::from_secs(5), true);
+        assert!(!tracker.autosave_due(due + Duration::from_secs(20)));
+
+        // Saving clears the dirty flag, which also stops autosaves
+        tracker.record_change(due + Duration::from_secs(21));
+        tracker.mark_saved();
+        assert!(!tracker.autosave_due(due + Duration::from_secs(40)));
+        tracker.request_validation();
+        assert!(tracker.validation_due(due + Duration::from_secs(40)));
+    }
+}
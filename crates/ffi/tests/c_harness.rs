@@ -0,0 +1,53 @@
+//! Builds tests/harness.c against the generated header and the cdylib, then runs it.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+#[test]
+fn c_harness() {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let tmp_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
+    // Test binaries live in target/<profile>/deps, next to the cdylib's directory
+    let lib_dir = std::env::current_exe()
+        .unwrap()
+        .parent()
+        .and_then(|deps| deps.parent())
+        .unwrap()
+        .to_path_buf();
+    let harness = tmp_dir.join("ffi_harness");
+
+    // `cargo test` does not produce the cdylib, so build it into the same profile
+    let mut build = Command::new(env!("CARGO"));
+    build.args(["build", "-p", "weightlifting-ffi"]);
+    if lib_dir.ends_with("release") {
+        build.arg("--release");
+    }
+    assert!(build.status().unwrap().success(), "cdylib build failed");
+
+    let status = Command::new(std::env::var("CC").unwrap_or_else(|_| "cc".to_string()))
+        .arg(manifest_dir.join("tests/harness.c"))
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg("-L")
+        .arg(&lib_dir)
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .arg("-lweightlifting_ffi")
+        .arg("-o")
+        .arg(&harness)
+        .status()
+        .expect("failed to run the C compiler");
+    assert!(status.success(), "harness.c failed to compile");
+
+    // Autosave writes to the drafts dir, so keep it inside the target dir
+    let output = Command::new(&harness)
+        .arg(tmp_dir.join("harness_plan.json"))
+        .env("HOME", tmp_dir.join("home"))
+        .env("XDG_STATE_HOME", tmp_dir.join("state"))
+        .output()
+        .expect("failed to run the harness");
+    assert!(
+        output.status.success(),
+        "harness failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,210 @@
+/*
+ * C harness for the plan handle API, built and run by tests/c_harness.rs.
+ * Exercises the header exactly as a host application would.
+ */
+#include <stdio.h>
+#include <string.h>
+
+#include "weightlifting_ffi.h"
+
+static int failures = 0;
+
+#define CHECK(cond)                                                        \
+    do {                                                                   \
+        if (!(cond)) {                                                     \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__,         \
+                    __LINE__, #cond);                                      \
+            failures++;                                                    \
+        }                                                                  \
+    } while (0)
+
+/* Checks a result's success flag and frees it */
+static void expect(FFIResult result, bool success) {
+    if (result.success != success) {
+        fprintf(stderr, "expected %s, got error: %s\n",
+                success ? "success" : "failure",
+                result.error ? result.error : "(none)");
+        failures++;
+    }
+    ffi_free_result(result);
+}
+
+/* Checks a failed result's error code and category and frees it */
+static void expect_error(FFIResult result, FFIErrorCode code, FFIErrorCategory category) {
+    CHECK(!result.success);
+    CHECK(result.error != NULL);
+    if (result.error_code != code || result.error_category != category) {
+        fprintf(stderr, "expected error %d/%d, got %d/%d: %s\n", code, category,
+                result.error_code, result.error_category,
+                result.error ? result.error : "(none)");
+        failures++;
+    }
+    ffi_free_result(result);
+}
+
+/* Checks that a successful result's data contains `needle` and frees it */
+static void expect_data(FFIResult result, const char *needle) {
+    CHECK(result.success);
+    CHECK(result.data != NULL && strstr(result.data, needle) != NULL);
+    ffi_free_result(result);
+}
+
+/* Records what the observer callbacks saw */
+typedef struct {
+    int changes;
+    bool dirty;
+    int validations;
+    bool last_valid;
+    int autosaves;
+    char autosave_path[1024];
+} ObserverLog;
+
+static void on_change(void *user_data, bool dirty) {
+    ObserverLog *log = user_data;
+    log->changes++;
+    log->dirty = dirty;
+}
+
+static void on_validation(void *user_data, const char *result_json) {
+    ObserverLog *log = user_data;
+    log->validations++;
+    log->last_valid = strstr(result_json, "\"errors\":[]") != NULL;
+}
+
+static void on_autosave(void *user_data, const char *path, const char *error) {
+    ObserverLog *log = user_data;
+    CHECK(path != NULL && error == NULL);
+    if (path != NULL) {
+        log->autosaves++;
+        snprintf(log->autosave_path, sizeof log->autosave_path, "%s", path);
+    }
+}
+
+static void observer_checks(void) {
+    ObserverLog log = {0};
+    FFIPlanObserver observer = {&log, on_change, on_validation, on_autosave};
+    PlanHandle *plan = ffi_handle_new();
+    expect(ffi_handle_set_observer(plan, &observer, 0), true);
+    expect_data(ffi_handle_set_autosave(plan, "harness_draft", 0), "harness_draft.json");
+    expect_error(ffi_handle_set_autosave(plan, "../escape", 0), FFIErrorCode_InvalidArgument,
+                 FFIErrorCategory_Parse);
+
+    /* A new observer gets an initial validation; a clean plan is not autosaved */
+    expect_data(ffi_handle_tick(plan), "\"autosaved\":null");
+    CHECK(log.validations == 1 && log.last_valid);
+    CHECK(log.autosaves == 0);
+
+    /* Edits fire on_change at once; validation and autosave wait for a tick */
+    expect(ffi_handle_day_add(plan, "{\"day\": 1, \"label\": \"A\", \"segments\": []}"), true);
+    expect(ffi_handle_segment_add(plan, 0, "{\"type\": \"straight\", \"ex\": \"XX\"}"), true);
+    expect_error(ffi_handle_segment_add(plan, 5, "{\"type\": \"comment\", \"text\": \"x\"}"),
+                 FFIErrorCode_IndexOutOfBounds, FFIErrorCategory_Bounds);
+    CHECK(log.changes == 2 && log.dirty);
+    CHECK(ffi_handle_is_dirty(plan));
+    CHECK(log.validations == 1);
+
+    expect_data(ffi_handle_tick(plan), "\"validated\":true");
+    CHECK(log.validations == 2 && !log.last_valid);
+    CHECK(log.autosaves == 1 && strstr(log.autosave_path, "harness_draft.json") != NULL);
+    FILE *draft = fopen(log.autosave_path, "r");
+    CHECK(draft != NULL);
+    if (draft != NULL) fclose(draft);
+
+    /* Nothing changed, so the next tick does no work; autosave kept the plan dirty */
+    expect_data(ffi_handle_tick(plan), "\"validated\":false");
+    CHECK(log.validations == 2 && log.autosaves == 1);
+    CHECK(ffi_handle_is_dirty(plan));
+
+    expect(ffi_handle_set_observer(plan, NULL, 0), true);
+    expect(ffi_handle_day_remove(plan, 0), true);
+    CHECK(log.changes == 2);
+    ffi_plan_free(plan);
+}
+
+int main(int argc, char **argv) {
+    const char *save_path = argc > 1 ? argv[1] : "harness_plan.json";
+
+    PlanHandle *plan = ffi_handle_new();
+    CHECK(plan != NULL);
+    CHECK(ffi_handle_day_count(plan) == 0);
+
+    expect_data(ffi_handle_day_add(plan, "{\"day\": 1, \"label\": \"Lower\", \"segments\": []}"), "0");
+    expect(ffi_handle_dictionary_add_entry(plan, "SQ.BB.BACK", "Back Squat"), true);
+    expect_data(ffi_handle_segment_add(plan, 0,
+                                       "{\"type\": \"straight\", \"ex\": \"SQ.BB.BACK\", "
+                                       "\"sets\": 5, \"reps\": {\"min\": 5, \"max\": 5}}"),
+                "0");
+    CHECK(ffi_handle_day_count(plan) == 1);
+    CHECK(ffi_handle_segment_count(plan, 0) == 1);
+    CHECK(ffi_handle_segment_count(plan, 7) == 0);
+
+    expect(ffi_handle_set(plan, "/schedule/0/segments/0/sets", "3"), true);
+    expect_data(ffi_handle_segment_get(plan, 0, 0), "\"sets\":3");
+    expect(ffi_handle_segment_update(plan, 0, 0,
+                                     "{\"type\": \"comment\", \"text\": \"Warm up\"}"),
+           true);
+    expect_data(ffi_handle_day_get(plan, 0), "Warm up");
+
+    expect(ffi_handle_group_add(plan, "squats", "[\"SQ.BB.BACK\"]"), true);
+    expect_data(ffi_handle_groups_get(plan), "squats");
+    expect(ffi_handle_group_remove(plan, "squats"), true);
+
+    /* Errors carry a code and category and leave the handle usable */
+    expect_error(ffi_handle_segment_add(plan, 4, "{\"type\": \"comment\", \"text\": \"x\"}"),
+                 FFIErrorCode_IndexOutOfBounds, FFIErrorCategory_Bounds);
+    expect_error(ffi_handle_segment_add(plan, 0, "not json"), FFIErrorCode_InvalidJson,
+                 FFIErrorCategory_Parse);
+    expect_error(ffi_handle_set(plan, "/schedule/0/day", "\"one\""), FFIErrorCode_InvalidValue,
+                 FFIErrorCategory_Validation);
+    expect_error(ffi_handle_remove(plan, "/schedule/9"), FFIErrorCode_PathNotFound,
+                 FFIErrorCategory_Bounds);
+    expect_error(ffi_handle_dictionary_add_entry(plan, " ", "Nothing"), FFIErrorCode_InvalidValue,
+                 FFIErrorCategory_Validation);
+    expect_error(ffi_handle_day_add(NULL, "{}"), FFIErrorCode_NullPointer, FFIErrorCategory_Parse);
+    CHECK(ffi_handle_segment_count(plan, 0) == 1);
+
+    /* Validation failures carry the ValidationErrorInfo lists as JSON detail */
+    expect(ffi_handle_check(plan), true);
+    expect(ffi_handle_segment_add(plan, 0, "{\"type\": \"straight\", \"ex\": \"BP.BB.FLAT\"}"), true);
+    FFIResult check = ffi_handle_check(plan);
+    CHECK(!check.success);
+    CHECK(check.error_code == FFIErrorCode_ValidationFailed);
+    CHECK(check.error_category == FFIErrorCategory_Validation);
+    CHECK(check.error_detail != NULL && strstr(check.error_detail, "\"E102\"") != NULL);
+    ffi_free_result(check);
+    expect(ffi_handle_segment_remove(plan, 0, 1), true);
+
+    expect_data(ffi_handle_validate(plan), "\"errors\"");
+    expect_data(ffi_plan_to_json(plan), "\"label\":\"Lower\"");
+    CHECK(ffi_handle_is_dirty(plan));
+    expect(ffi_handle_save(plan, save_path), true);
+    CHECK(!ffi_handle_is_dirty(plan));
+
+    PlanHandle *reopened = NULL;
+    expect(ffi_handle_open(save_path, &reopened), true);
+    CHECK(reopened != NULL);
+    CHECK(ffi_handle_segment_count(reopened, 0) == 1);
+    expect_data(ffi_handle_remove(reopened, "/schedule/0/segments/0"), "Warm up");
+    CHECK(ffi_handle_segment_count(reopened, 0) == 0);
+    expect(ffi_handle_day_remove(reopened, 0), true);
+    CHECK(ffi_handle_day_count(reopened) == 0);
+    ffi_plan_free(reopened);
+
+    PlanHandle *parsed = NULL;
+    expect_error(ffi_handle_from_json("{\"name\": 1}", &parsed), FFIErrorCode_InvalidJson,
+                 FFIErrorCategory_Parse);
+    CHECK(parsed == NULL);
+
+    ffi_plan_free(plan);
+    ffi_plan_free(NULL);
+    CHECK(!ffi_handle_is_dirty(NULL));
+
+    observer_checks();
+
+    if (failures > 0) {
+        fprintf(stderr, "%d checks failed\n", failures);
+        return 1;
+    }
+    printf("all checks passed\n");
+    return 0;
+}
@@ -0,0 +1,98 @@
+//! Replays hostile inputs through the entry points the fuzz targets in `fuzz/`
+//! cover. Every call must return a result rather than panic, and every string
+//! handed back must be a valid C string.
+
+use std::ffi::{CStr, CString};
+use weightlifting_ffi::*;
+
+const PLAN: &str = r#"{"name": "Hostile", "unit": "kg", "dictionary": {}, "groups": {},
+    "schedule": [{"day": 1, "label": "Lower", "segments": []}]}"#;
+
+const INPUTS: &[&str] = &[
+    "",
+    "not json",
+    "null",
+    "[]",
+    "{}",
+    "{\"type\": \"a\\u0000b\"}",
+    "{\"type\": \"straight\", \"ex\": \"\\u0000\", \"sets\": 4294967296}",
+    "{\"type\": \"straight\", \"ex\": \"SQ\", \"reps\": {\"min\": 9, \"max\": 1}}",
+    "{\"type\": \"comment\", \"text\": \"\\u0000\"}",
+    "{\"name\": \"a\\u0000b\", \"unit\": \"kg\", \"dictionary\": {}, \"groups\": {}, \"schedule\": []}",
+    "{\"name\": \"Zero\", \"unit\": \"kg\", \"dictionary\": {}, \"groups\": {},
+      \"schedule\": [{\"day\": 0, \"label\": \"\", \"segments\": [{\"type\": \"straight\", \"ex\": \"X\"}]}]}",
+    "{\"name\": \"x\", \"unit\": \"stone\"}",
+    "[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[[",
+];
+
+/// Checks the result carries no panic and only readable strings, then frees it
+fn check(result: FFIResult) {
+    let error = (!result.error.is_null()).then(|| {
+        unsafe { CStr::from_ptr(result.error) }
+            .to_string_lossy()
+            .into_owned()
+    });
+    assert_ne!(result.error_code, FFIErrorCode::Panic, "{:?}", error);
+    assert_eq!(result.success, result.error.is_null());
+    if !result.error_detail.is_null() {
+        let detail = unsafe { CStr::from_ptr(result.error_detail) };
+        serde_json::from_slice::<serde_json::Value>(detail.to_bytes()).unwrap();
+    }
+    ffi_free_result(result);
+}
+
+#[test]
+fn hostile_plan_json() {
+    let dir = std::path::PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
+    for (i, input) in INPUTS.iter().enumerate() {
+        let json = CString::new(*input).unwrap();
+        check(ffi_plan_validate(json.as_ptr()));
+
+        let path = dir.join(format!("hostile_{}.json", i));
+        std::fs::write(&path, input).unwrap();
+        let path = CString::new(path.to_str().unwrap()).unwrap();
+        check(ffi_plan_open(path.as_ptr()));
+
+        let mut handle = std::ptr::null_mut();
+        check(ffi_handle_from_json(json.as_ptr(), &mut handle));
+        if !handle.is_null() {
+            check(ffi_handle_validate(handle));
+            check(ffi_handle_check(handle));
+            check(ffi_plan_to_json(handle));
+            ffi_plan_free(handle);
+        }
+    }
+}
+
+#[test]
+fn hostile_segment_mutations() {
+    let plan = CString::new(PLAN).unwrap();
+    for input in INPUTS {
+        let segment = CString::new(*input).unwrap();
+        for index in [0, 1, usize::MAX] {
+            check(ffi_segment_add(plan.as_ptr(), index, segment.as_ptr()));
+            check(ffi_segment_update(
+                plan.as_ptr(),
+                0,
+                index,
+                segment.as_ptr(),
+            ));
+            check(ffi_segment_remove(plan.as_ptr(), index, index));
+            check(ffi_segment_add(segment.as_ptr(), index, segment.as_ptr()));
+
+            let mut handle = std::ptr::null_mut();
+            check(ffi_handle_from_json(plan.as_ptr(), &mut handle));
+            check(ffi_handle_segment_add(handle, index, segment.as_ptr()));
+            check(ffi_handle_segment_update(
+                handle,
+                0,
+                index,
+                segment.as_ptr(),
+            ));
+            check(ffi_handle_segment_remove(handle, 0, index));
+            check(ffi_handle_set(handle, segment.as_ptr(), segment.as_ptr()));
+            check(ffi_handle_check(handle));
+            ffi_plan_free(handle);
+        }
+    }
+}
@@ -0,0 +1,623 @@
+use crate::csv_parser::SessionRecord;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use weightlifting_core::{Day, Plan, Prescription, Segment};
+
+/// Adherence of one session segment (or one unplanned exercise) to the plan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SegmentAdherence {
+    pub session_id: String,
+    pub date: NaiveDate,
+    /// 1-based plan week the session fell in
+    pub week: u32,
+    pub day_label: String,
+    pub segment_id: u32,
+    /// Prescribed exercise code, or the logged code for unplanned sets
+    pub ex: String,
+    pub alt_group: Option<String>,
+    pub prescribed_sets: u32,
+    pub performed_sets: u32,
+    pub missed_sets: u32,
+    pub extra_sets: u32,
+    pub swapped_sets: u32,
+    /// Exercise codes logged in place of the prescribed one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub swapped_to: Vec<String>,
+    pub reps_under: u32,
+    pub reps_over: u32,
+    pub reps_in_range: u32,
+    /// Mean of (logged RPE - target RPE) over sets with both values
+    pub mean_rpe_drift: Option<f64>,
+}
+
+/// Swap rate for an alternative group across the report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwapRate {
+    pub alt_group: String,
+    pub performed_sets: u32,
+    pub swapped_sets: u32,
+    pub swap_rate: f64,
+    pub swapped_to: Vec<String>,
+}
+
+/// Totals across all sessions in the report
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AdherenceSummary {
+    pub sessions: u32,
+    pub prescribed_sets: u32,
+    pub performed_sets: u32,
+    pub missed_sets: u32,
+    pub extra_sets: u32,
+    pub reps_under: u32,
+    pub reps_over: u32,
+    pub reps_in_range: u32,
+    pub mean_rpe_drift: Option<f64>,
+    /// Share of prescribed sets that were performed, in percent
+    pub completion_pct: f64,
+}
+
+/// Prescribed-vs-performed report for one plan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdherenceReport {
+    pub plan_name: String,
+    pub summary: AdherenceSummary,
+    pub segments: Vec<SegmentAdherence>,
+    pub swap_rates: Vec<SwapRate>,
+}
+
+/// Joins logged sets back to the plan segments that prescribed them
+pub struct AdherenceAnalyzer;
+
+impl AdherenceAnalyzer {
+    /// Build an adherence report for `plan` from logged session records.
+    ///
+    /// Records are matched by `plan_name` (records without one are skipped), then by
+    /// `day_label` and the 1-based `segment_id`. Week 1 starts on the first logged session
+    /// of the plan, and each session is scored against `Plan::schedule_for_week` for its
+    /// week, so `per_week` overlays and `week_overrides` apply. Every segment of the matched
+    /// day is scored for each session, so segments with nothing logged count as missed.
+    /// Warm-up sets are ignored.
+    pub fn analyze(
+        plan: &Plan,
+        sessions: &[SessionRecord],
+    ) -> Result<AdherenceReport, serde_json::Error> {
+        let records: Vec<&SessionRecord> = sessions
+            .iter()
+            .filter(|r| r.is_warmup == 0 && r.plan_name.as_deref() == Some(plan.name.as_str()))
+            .collect();
+        let first_date = records.iter().map(|r| r.date).min();
+
+        // (date, session_id, day_label) -> segment_id -> logged sets
+        type SessionSets<'a> = BTreeMap<u32, Vec<&'a SessionRecord>>;
+        let mut grouped: BTreeMap<(NaiveDate, String, String), SessionSets> = BTreeMap::new();
+        for record in records {
+            let day_label = record.day_label.clone().unwrap_or_default();
+            grouped
+                .entry((record.date, record.session_id.clone(), day_label))
+                .or_default()
+                .entry(record.segment_id)
+                .or_default()
+                .push(record);
+        }
+
+        let week_of = |date: NaiveDate| {
+            first_date.map_or(1, |first| ((date - first).num_days() / 7) as u32 + 1)
+        };
+        let schedules = grouped
+            .keys()
+            .map(|(date, _, _)| week_of(*date))
+            .collect::<BTreeSet<u32>>()
+            .into_iter()
+            .map(|week| Ok((week, plan.schedule_for_week(week)?)))
+            .collect::<Result<BTreeMap<u32, Vec<Day>>, serde_json::Error>>()?;
+
+        let mut segments = Vec::new();
+        let mut session_ids = HashSet::new();
+        for ((date, session_id, day_label), mut by_segment) in grouped {
+            session_ids.insert(session_id.clone());
+            let week = week_of(date);
+            let day = schedules[&week].iter().find(|day| day.label == day_label);
+
+            let mut segment_ids: BTreeSet<u32> = by_segment.keys().copied().collect();
+            if let Some(day) = day {
+                segment_ids.extend(1..=day.segments.len() as u32);
+            }
+
+            for segment_id in segment_ids {
+                let mut records = by_segment.remove(&segment_id).unwrap_or_default();
+                records.sort_by_key(|r| (r.set_num, r.time));
+
+                let prescribed = day
+                    .and_then(|day| {
+                        let index = (segment_id as usize).checked_sub(1)?;
+                        day.segments.get(index)
+                    })
+                    .map(Segment::prescriptions)
+                    .unwrap_or_default();
+
+                let ctx = RowContext {
+                    session_id: &session_id,
+                    date,
+                    week,
+                    day_label: &day_label,
+                    segment_id,
+                };
+                segments.extend(Self::match_segment(plan, &ctx, &prescribed, &records));
+            }
+        }
+
+        let swap_rates = Self::swap_rates(&segments);
+        let summary = Self::summarize(&segments, session_ids.len() as u32);
+
+        Ok(AdherenceReport {
+            plan_name: plan.name.clone(),
+            summary,
+            segments,
+            swap_rates,
+        })
+    }
+
+    /// Match a segment's logged sets to its prescribed exercises
+    fn match_segment(
+        plan: &Plan,
+        ctx: &RowContext,
+        prescribed: &[Prescription],
+        records: &[&SessionRecord],
+    ) -> Vec<SegmentAdherence> {
+        let mut assigned: Vec<Vec<&SessionRecord>> = vec![Vec::new(); prescribed.len()];
+        let mut unplanned: BTreeMap<String, Vec<&SessionRecord>> = BTreeMap::new();
+
+        for record in records {
+            match Self::find_prescription(plan, prescribed, record) {
+                Some(idx) => assigned[idx].push(record),
+                None => unplanned
+                    .entry(record.ex_code.clone())
+                    .or_default()
+                    .push(record),
+            }
+        }
+
+        let mut rows = Vec::new();
+        for (presc, logged) in prescribed.iter().zip(assigned) {
+            if logged.is_empty() && presc.optional {
+                continue;
+            }
+            rows.push(Self::score(ctx, presc, &logged));
+        }
+
+        for (ex, logged) in unplanned {
+            let extra = Prescription {
+                ex,
+                alt_group: None,
+                sets: Vec::new(),
+                optional: false,
+            };
+            rows.push(Self::score(ctx, &extra, &logged));
+        }
+
+        rows
+    }
+
+    /// Index of the prescription a logged set belongs to: exact code first, then alt group,
+    /// then (for sets flagged `adlib`) the segment's only prescription
+    fn find_prescription(
+        plan: &Plan,
+        prescribed: &[Prescription],
+        record: &SessionRecord,
+    ) -> Option<usize> {
+        let ex = record.ex_code.as_str();
+        prescribed
+            .iter()
+            .position(|p| p.ex == ex)
+            .or_else(|| {
+                prescribed.iter().position(|p| {
+                    p.alt_group.as_ref().is_some_and(|group| {
+                        plan.groups
+                            .get(group)
+                            .is_some_and(|members| members.iter().any(|m| m == ex))
+                    })
+                })
+            })
+            .or_else(|| {
+                // An ad-lib set in a single-exercise segment swaps for that exercise; other
+                // unknown codes are unplanned extras
+                (record.adlib == 1 && prescribed.len() == 1).then_some(0)
+            })
+    }
+
+    fn score(
+        ctx: &RowContext,
+        presc: &Prescription,
+        logged: &[&SessionRecord],
+    ) -> SegmentAdherence {
+        let prescribed_sets = presc.sets.len() as u32;
+        let performed_sets = logged.len() as u32;
+
+        let mut row = SegmentAdherence {
+            session_id: ctx.session_id.to_string(),
+            date: ctx.date,
+            week: ctx.week,
+            day_label: ctx.day_label.to_string(),
+            segment_id: ctx.segment_id,
+            ex: presc.ex.clone(),
+            alt_group: presc.alt_group.clone(),
+            prescribed_sets,
+            performed_sets,
+            missed_sets: prescribed_sets.saturating_sub(performed_sets),
+            extra_sets: performed_sets.saturating_sub(prescribed_sets),
+            swapped_sets: 0,
+            swapped_to: Vec::new(),
+            reps_under: 0,
+            reps_over: 0,
+            reps_in_range: 0,
+            mean_rpe_drift: None,
+        };
+
+        let mut drifts = Vec::new();
+        for (record, target) in logged.iter().zip(presc.sets.iter()) {
+            if let (Some(reps), Some((min, max))) = (record.reps, target.reps) {
+                if reps < min {
+                    row.reps_under += 1;
+                } else if reps > max {
+                    row.reps_over += 1;
+                } else {
+                    row.reps_in_range += 1;
+                }
+            }
+            if let (Some(rpe), Some(target_rpe)) = (Self::logged_rpe(record), target.rpe) {
+                drifts.push(rpe - target_rpe);
+            }
+        }
+        if !drifts.is_empty() {
+            row.mean_rpe_drift = Some(drifts.iter().sum::<f64>() / drifts.len() as f64);
+        }
+
+        if prescribed_sets > 0 {
+            for record in logged.iter().filter(|r| r.ex_code != presc.ex) {
+                row.swapped_sets += 1;
+                if !row.swapped_to.contains(&record.ex_code) {
+                    row.swapped_to.push(record.ex_code.clone());
+                }
+            }
+        }
+
+        row
+    }
+
+    fn logged_rpe(record: &SessionRecord) -> Option<f64> {
+        record
+            .rpe
+            .or_else(|| record.rir.map(|rir| 10.0 - rir as f64))
+    }
+
+    fn swap_rates(segments: &[SegmentAdherence]) -> Vec<SwapRate> {
+        let mut by_group: BTreeMap<String, SwapRate> = BTreeMap::new();
+        for row in segments {
+            let Some(group) = &row.alt_group else {
+                continue;
+            };
+            let rate = by_group.entry(group.clone()).or_insert_with(|| SwapRate {
+                alt_group: group.clone(),
+                performed_sets: 0,
+                swapped_sets: 0,
+                swap_rate: 0.0,
+                swapped_to: Vec::new(),
+            });
+            rate.performed_sets += row.performed_sets;
+            rate.swapped_sets += row.swapped_sets;
+            for ex in &row.swapped_to {
+                if !rate.swapped_to.contains(ex) {
+                    rate.swapped_to.push(ex.clone());
+                }
+            }
+        }
+
+        let mut rates: Vec<SwapRate> = by_group.into_values().collect();
+        for rate in &mut rates {
+            rate.swapped_to.sort();
+            if rate.performed_sets > 0 {
+                rate.swap_rate = rate.swapped_sets as f64 / rate.performed_sets as f64;
+            }
+        }
+        rates
+    }
+
+    fn summarize(segments: &[SegmentAdherence], session_count: u32) -> AdherenceSummary {
+        let mut summary = AdherenceSummary {
+            sessions: session_count,
+            ..Default::default()
+        };
+        let mut weighted_drift = 0.0;
+        let mut drift_sets = 0u32;
+
+        for row in segments {
+            summary.prescribed_sets += row.prescribed_sets;
+            summary.performed_sets += row.performed_sets;
+            summary.missed_sets += row.missed_sets;
+            summary.extra_sets += row.extra_sets;
+            summary.reps_under += row.reps_under;
+            summary.reps_over += row.reps_over;
+            summary.reps_in_range += row.reps_in_range;
+            if let Some(drift) = row.mean_rpe_drift {
+                let n = row.performed_sets.min(row.prescribed_sets);
+                weighted_drift += drift * n as f64;
+                drift_sets += n;
+            }
+        }
+
+        if drift_sets > 0 {
+            summary.mean_rpe_drift = Some(weighted_drift / drift_sets as f64);
+        }
+        if summary.prescribed_sets > 0 {
+            let completed = summary.prescribed_sets - summary.missed_sets;
+            summary.completion_pct = completed as f64 / summary.prescribed_sets as f64 * 100.0;
+        }
+
+        summary
+    }
+}
+
+/// Session/segment identity shared by the rows produced for one segment
+struct RowContext<'a> {
+    session_id: &'a str,
+    date: NaiveDate,
+    week: u32,
+    day_label: &'a str,
+    segment_id: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csv_parser::SessionRecordBuilder;
+
+    fn plan() -> Plan {
+        serde_json::from_value(serde_json::json!({
+            "name": "Test Plan",
+            "unit": "kg",
+            "dictionary": {},
+            "groups": { "GROUP_SQUAT": ["SQ.BB.BACK", "SQ.BB.FRONT"] },
+            "schedule": [{
+                "day": 1,
+                "label": "Lower",
+                "segments": [
+                    {
+                        "type": "straight",
+                        "ex": "SQ.BB.BACK",
+                        "alt_group": "GROUP_SQUAT",
+                        "sets": 3,
+                        "reps": { "min": 5, "max": 5 },
+                        "rpe": 8.0
+                    },
+                    { "type": "comment", "text": "Switch stations" },
+                    {
+                        "type": "straight",
+                        "ex": "DL.BB.RDL",
+                        "sets": 2,
+                        "reps": { "min": 8, "max": 10 }
+                    }
+                ]
+            }]
+        }))
+        .unwrap()
+    }
+
+    /// A set logged against the plan's Lower day
+    fn lower() -> SessionRecordBuilder {
+        SessionRecord::builder().plan("Test Plan").day("Lower")
+    }
+
+    #[test]
+    fn test_missed_sets_rep_ranges_and_rpe_drift() {
+        let sessions = vec![
+            lower()
+                .segment(1)
+                .ex("SQ.BB.BACK")
+                .set_num(1)
+                .reps(5)
+                .rpe(8.5)
+                .build(),
+            lower()
+                .segment(1)
+                .ex("SQ.BB.BACK")
+                .set_num(2)
+                .reps(4)
+                .rpe(9.5)
+                .build(),
+            lower()
+                .segment(3)
+                .ex("DL.BB.RDL")
+                .set_num(1)
+                .reps(12)
+                .build(),
+            lower()
+                .segment(3)
+                .ex("DL.BB.RDL")
+                .set_num(2)
+                .reps(10)
+                .build(),
+            lower()
+                .segment(3)
+                .ex("DL.BB.RDL")
+                .set_num(3)
+                .reps(8)
+                .build(),
+        ];
+
+        let report = AdherenceAnalyzer::analyze(&plan(), &sessions).unwrap();
+        assert_eq!(report.segments.len(), 2);
+
+        let squat = &report.segments[0];
+        assert_eq!(squat.prescribed_sets, 3);
+        assert_eq!(squat.missed_sets, 1);
+        assert_eq!(squat.reps_in_range, 1);
+        assert_eq!(squat.reps_under, 1);
+        assert_eq!(squat.mean_rpe_drift, Some(1.0));
+
+        let rdl = &report.segments[1];
+        assert_eq!(rdl.extra_sets, 1);
+        assert_eq!(rdl.reps_over, 1);
+        assert_eq!(rdl.reps_in_range, 1);
+
+        assert_eq!(report.summary.sessions, 1);
+        assert_eq!(report.summary.prescribed_sets, 5);
+        assert_eq!(report.summary.missed_sets, 1);
+        assert_eq!(report.summary.completion_pct, 80.0);
+    }
+
+    #[test]
+    fn test_swaps_and_unplanned_sets() {
+        let sessions = vec![
+            lower()
+                .segment(1)
+                .ex("SQ.BB.FRONT")
+                .set_num(1)
+                .reps(5)
+                .build(),
+            lower()
+                .segment(1)
+                .ex("SQ.BB.BACK")
+                .set_num(2)
+                .reps(5)
+                .build(),
+            lower()
+                .segment(1)
+                .ex("SQ.BB.FRONT")
+                .set_num(3)
+                .reps(5)
+                .build(),
+            lower()
+                .segment(4)
+                .ex("CORE.BW.PLNK")
+                .set_num(1)
+                .reps(1)
+                .build(),
+        ];
+
+        let report = AdherenceAnalyzer::analyze(&plan(), &sessions).unwrap();
+
+        let squat = &report.segments[0];
+        assert_eq!(squat.swapped_sets, 2);
+        assert_eq!(squat.swapped_to, vec!["SQ.BB.FRONT".to_string()]);
+        assert_eq!(squat.missed_sets, 0);
+
+        // The RDL segment had nothing logged, so both its sets are missed
+        let rdl = report
+            .segments
+            .iter()
+            .find(|row| row.ex == "DL.BB.RDL")
+            .unwrap();
+        assert_eq!((rdl.performed_sets, rdl.missed_sets), (0, 2));
+        assert_eq!(report.summary.prescribed_sets, 5);
+        assert_eq!(report.summary.missed_sets, 2);
+        assert_eq!(report.summary.completion_pct, 60.0);
+
+        let unplanned = report
+            .segments
+            .iter()
+            .find(|row| row.ex == "CORE.BW.PLNK")
+            .unwrap();
+        assert_eq!(unplanned.prescribed_sets, 0);
+        assert_eq!(unplanned.extra_sets, 1);
+
+        assert_eq!(report.swap_rates.len(), 1);
+        assert_eq!(report.swap_rates[0].alt_group, "GROUP_SQUAT");
+        assert!((report.swap_rates[0].swap_rate - 2.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_adlib_flag_classifies_swaps() {
+        let sessions = vec![
+            lower()
+                .segment(1)
+                .ex("SQ.BB.BACK")
+                .set_num(1)
+                .reps(5)
+                .build(),
+            lower()
+                .segment(3)
+                .ex("DL.BB.SLDL")
+                .set_num(1)
+                .reps(8)
+                .adlib()
+                .build(),
+            lower()
+                .segment(3)
+                .ex("KB.SWING")
+                .set_num(2)
+                .reps(15)
+                .build(),
+        ];
+
+        let report = AdherenceAnalyzer::analyze(&plan(), &sessions).unwrap();
+
+        let rdl = report
+            .segments
+            .iter()
+            .find(|row| row.ex == "DL.BB.RDL")
+            .unwrap();
+        assert_eq!(rdl.swapped_sets, 1);
+        assert_eq!(rdl.swapped_to, vec!["DL.BB.SLDL".to_string()]);
+        assert_eq!(rdl.missed_sets, 1);
+
+        let swing = report
+            .segments
+            .iter()
+            .find(|row| row.ex == "KB.SWING")
+            .unwrap();
+        assert_eq!((swing.prescribed_sets, swing.extra_sets), (0, 1));
+    }
+
+    #[test]
+    fn test_weekly_overlays_and_foreign_records() {
+        let mut plan = plan();
+        plan.schedule[0].segments.truncate(1);
+        let Segment::Straight(squat) = &mut plan.schedule[0].segments[0] else {
+            panic!("expected straight segment");
+        };
+        squat.base.per_week = Some(
+            serde_json::from_value(serde_json::json!({ "2": { "sets": 4, "rpe": 9.0 } })).unwrap(),
+        );
+
+        let week_two = |set_num| {
+            lower()
+                .ex("SQ.BB.BACK")
+                .set_num(set_num)
+                .rpe(9.0)
+                .session("S2")
+                .date(NaiveDate::from_ymd_opt(2025, 8, 11).unwrap())
+                .build()
+        };
+        let sessions = vec![
+            lower()
+                .segment(1)
+                .ex("SQ.BB.BACK")
+                .set_num(1)
+                .reps(5)
+                .rpe(8.0)
+                .build(),
+            // Not logged against any plan
+            SessionRecord::builder().set_num(4).build(),
+            week_two(1),
+            week_two(2),
+            week_two(3),
+            week_two(4),
+        ];
+
+        let report = AdherenceAnalyzer::analyze(&plan, &sessions).unwrap();
+        assert_eq!(report.segments.len(), 2);
+
+        // Week 1 uses the base prescription; the set without a plan name is not counted
+        let week1 = &report.segments[0];
+        assert_eq!(week1.week, 1);
+        assert_eq!((week1.prescribed_sets, week1.performed_sets), (3, 1));
+        assert_eq!(week1.mean_rpe_drift, Some(0.0));
+
+        // Week 2 picks up the per_week overlay: four sets at RPE 9
+        let week2 = &report.segments[1];
+        assert_eq!(week2.week, 2);
+        assert_eq!((week2.prescribed_sets, week2.missed_sets), (4, 0));
+        assert_eq!(week2.mean_rpe_drift, Some(0.0));
+    }
+}
@@ -1,171 +1,688 @@
-use crate::metrics::{E1RMDataPoint, PRDataPoint, VolumeDataPoint};
-use chrono::{DateTime, Utc};
+use crate::csv_parser::SessionRecord;
+use crate::metrics::{
+    E1RMDataPoint, GroupVolumeDataPoint, PRDataPoint, PRKind, PRSource, VolumeDataPoint,
+    VolumeGrouping,
+};
+use crate::training_load::{DailyLoadDataPoint, WeeklyLoadDataPoint};
+use chrono::{NaiveDate, NaiveTime, Utc};
+use rusqlite::{params, params_from_iter, Connection, Row, Transaction};
 use serde::{Deserialize, Serialize};
-use std::fs::{self, File};
-use std::io::{BufReader, BufWriter};
+use std::fs;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 use weightlifting_core::AppPaths;
 
+/// Current metrics store schema version (stored in `PRAGMA user_version`)
+pub const SCHEMA_VERSION: i64 = 1;
+
+/// Flat JSON files written by earlier versions of the cache
+const LEGACY_CACHE_FILES: [&str; 6] = [
+    "e1rm_data.json",
+    "volume_data.json",
+    "group_volume_data.json",
+    "daily_load_data.json",
+    "weekly_load_data.json",
+    "pr_data.json",
+];
+
 #[derive(Error, Debug)]
 pub enum CacheError {
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
     #[error("JSON serialization error: {0}")]
     Json(#[from] serde_json::Error),
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
     #[error("Cache corruption: {0}")]
     Corruption(String),
 }
 
+/// Last ingest of a session CSV source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestInfo {
+    pub source: String,
+    pub ingested_at: String,
+    pub rows: usize,
+}
+
 /// Status information about cached metrics
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CacheStatus {
+    pub schema_version: i64,
+    pub set_entries: usize,
     pub e1rm_entries: usize,
     pub volume_entries: usize,
+    pub group_volume_entries: usize,
+    pub daily_load_entries: usize,
+    pub weekly_load_entries: usize,
     pub pr_entries: usize,
+    pub sources: Vec<IngestInfo>,
     pub last_updated: Option<String>,
     pub cache_size_bytes: u64,
 }
 
-/// Metrics cache manager
+/// Every derived-metrics table, recomputed together from all ingested sets
+#[derive(Debug, Clone, Default)]
+pub struct DerivedMetrics {
+    pub e1rm: Vec<E1RMDataPoint>,
+    pub volume: Vec<VolumeDataPoint>,
+    /// Empty when no exercise catalog is available
+    pub group_volume: Vec<GroupVolumeDataPoint>,
+    pub daily_load: Vec<DailyLoadDataPoint>,
+    pub weekly_load: Vec<WeeklyLoadDataPoint>,
+    pub prs: Vec<PRDataPoint>,
+}
+
+/// Exercise / date filter applied when querying the store
+#[derive(Debug, Clone, Default)]
+pub struct MetricsQuery {
+    pub exercise: Option<String>,
+    pub start: Option<NaiveDate>,
+    pub end: Option<NaiveDate>,
+}
+
+impl MetricsQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn exercise(mut self, exercise: Option<&str>) -> Self {
+        self.exercise = exercise.map(str::to_string);
+        self
+    }
+
+    pub fn date_range(mut self, range: Option<(NaiveDate, NaiveDate)>) -> Self {
+        if let Some((start, end)) = range {
+            self.start = Some(start);
+            self.end = Some(end);
+        }
+        self
+    }
+
+    /// Build a WHERE clause over the given columns; `extra` conditions are ANDed in first
+    fn where_clause(
+        &self,
+        exercise_col: Option<&str>,
+        date_col: &str,
+        extra: &[(&str, String)],
+    ) -> (String, Vec<String>) {
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+
+        for (column, value) in extra {
+            conditions.push(format!("{} = ?", column));
+            values.push(value.clone());
+        }
+        if let (Some(column), Some(exercise)) = (exercise_col, &self.exercise) {
+            conditions.push(format!("{} = ?", column));
+            values.push(exercise.clone());
+        }
+        if let Some(start) = self.start {
+            conditions.push(format!("{} >= ?", date_col));
+            values.push(format_date(start));
+        }
+        if let Some(end) = self.end {
+            conditions.push(format!("{} <= ?", date_col));
+            values.push(format_date(end));
+        }
+
+        if conditions.is_empty() {
+            (String::new(), values)
+        } else {
+            (format!(" WHERE {}", conditions.join(" AND ")), values)
+        }
+    }
+}
+
+/// Metrics cache manager backed by an embedded SQLite store
 pub struct MetricsCache {
     cache_dir: PathBuf,
+    db_path: Option<PathBuf>,
+    conn: Connection,
 }
 
 impl MetricsCache {
-    pub fn new(app_paths: &AppPaths) -> Self {
+    /// Open (or create) the metrics store in the app's metrics cache directory
+    pub fn new(app_paths: &AppPaths) -> Result<Self, CacheError> {
         let cache_dir = app_paths.metrics_cache_dir();
+        fs::create_dir_all(&cache_dir)?;
+        Self::open(&cache_dir)
+    }
 
-        // Ensure cache directory exists
-        if let Err(e) = fs::create_dir_all(&cache_dir) {
-            eprintln!("Warning: Failed to create cache directory: {}", e);
-        }
+    /// Open (or create) `metrics.db` inside `cache_dir`
+    pub fn open(cache_dir: &Path) -> Result<Self, CacheError> {
+        let db_path = cache_dir.join("metrics.db");
+        let conn = Connection::open(&db_path)?;
+        let cache = Self {
+            cache_dir: cache_dir.to_path_buf(),
+            db_path: Some(db_path),
+            conn,
+        };
+        cache.migrate()?;
+        Ok(cache)
+    }
 
-        Self { cache_dir }
+    /// In-memory store, used for tests and one-off queries
+    pub fn open_in_memory() -> Result<Self, CacheError> {
+        let cache = Self {
+            cache_dir: PathBuf::new(),
+            db_path: None,
+            conn: Connection::open_in_memory()?,
+        };
+        cache.migrate()?;
+        Ok(cache)
     }
 
     pub fn cache_dir(&self) -> &Path {
         &self.cache_dir
     }
 
-    /// Store E1RM data in cache
-    pub fn store_e1rm_data(&self, data: &[E1RMDataPoint]) -> Result<(), CacheError> {
-        let path = self.cache_dir.join("e1rm_data.json");
-        let file = File::create(path)?;
-        let writer = BufWriter::new(file);
-
-        let cached_data = CachedE1RMData {
-            last_updated: Utc::now(),
-            entries: data.to_vec(),
-        };
+    pub fn schema_version(&self) -> Result<i64, CacheError> {
+        Ok(self
+            .conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))?)
+    }
+
+    /// Bring the schema up to `SCHEMA_VERSION`
+    fn migrate(&self) -> Result<(), CacheError> {
+        let version = self.schema_version()?;
+        if version > SCHEMA_VERSION {
+            return Err(CacheError::Corruption(format!(
+                "Metrics store schema version {} is newer than supported version {}",
+                version, SCHEMA_VERSION
+            )));
+        }
+
+        if version < 1 {
+            self.conn.execute_batch(
+                "BEGIN;
+                CREATE TABLE ingest_log (
+                    source TEXT PRIMARY KEY,
+                    ingested_at TEXT NOT NULL,
+                    row_count INTEGER NOT NULL
+                );
+                CREATE TABLE table_updates (
+                    table_name TEXT PRIMARY KEY,
+                    updated_at TEXT NOT NULL
+                );
+                CREATE TABLE sets (
+                    id INTEGER PRIMARY KEY,
+                    source TEXT NOT NULL,
+                    session_id TEXT NOT NULL,
+                    date TEXT NOT NULL,
+                    time TEXT NOT NULL,
+                    plan_name TEXT,
+                    day_label TEXT,
+                    segment_id INTEGER NOT NULL,
+                    superset_id TEXT,
+                    ex_code TEXT NOT NULL,
+                    adlib INTEGER NOT NULL,
+                    set_num INTEGER NOT NULL,
+                    reps INTEGER,
+                    time_sec INTEGER,
+                    weight REAL,
+                    unit TEXT NOT NULL,
+                    is_warmup INTEGER NOT NULL,
+                    rpe REAL,
+                    rir INTEGER,
+                    tempo TEXT,
+                    rest_sec INTEGER,
+                    effort_1to5 INTEGER NOT NULL,
+                    tags TEXT,
+                    notes TEXT,
+                    pr_types TEXT
+                );
+                CREATE INDEX idx_sets_exercise_date ON sets (ex_code, date);
+                CREATE INDEX idx_sets_date ON sets (date);
+                CREATE INDEX idx_sets_source ON sets (source);
+                CREATE TABLE e1rm (
+                    exercise TEXT NOT NULL,
+                    date TEXT NOT NULL,
+                    e1rm_kg REAL NOT NULL,
+                    source_weight REAL NOT NULL,
+                    source_reps INTEGER NOT NULL,
+                    source_rpe REAL,
+                    formula TEXT NOT NULL
+                );
+                CREATE INDEX idx_e1rm_exercise_date ON e1rm (exercise, date);
+                CREATE INDEX idx_e1rm_date ON e1rm (date);
+                CREATE TABLE volume (
+                    category TEXT NOT NULL,
+                    week_start TEXT NOT NULL,
+                    total_sets INTEGER NOT NULL,
+                    total_reps INTEGER NOT NULL,
+                    total_tonnage_kg REAL NOT NULL,
+                    session_count INTEGER NOT NULL
+                );
+                CREATE INDEX idx_volume_category_week ON volume (category, week_start);
+                CREATE INDEX idx_volume_week ON volume (week_start);
+                CREATE TABLE group_volume (
+                    grouping TEXT NOT NULL,
+                    group_name TEXT NOT NULL,
+                    week_start TEXT NOT NULL,
+                    weighted_sets REAL NOT NULL,
+                    weighted_reps REAL NOT NULL,
+                    weighted_tonnage_kg REAL NOT NULL,
+                    exercises TEXT NOT NULL
+                );
+                CREATE INDEX idx_group_volume_grouping_week ON group_volume (grouping, week_start);
+                CREATE TABLE prs (
+                    exercise TEXT NOT NULL,
+                    pr_kind TEXT NOT NULL,
+                    date TEXT NOT NULL,
+                    value REAL NOT NULL,
+                    reps INTEGER,
+                    notes TEXT,
+                    source TEXT NOT NULL
+                );
+                CREATE INDEX idx_prs_exercise_date ON prs (exercise, date);
+                CREATE INDEX idx_prs_date ON prs (date);
+                CREATE TABLE daily_load (
+                    date TEXT PRIMARY KEY,
+                    load REAL NOT NULL,
+                    acute_load REAL NOT NULL,
+                    chronic_load REAL NOT NULL,
+                    acwr REAL,
+                    fitness REAL NOT NULL,
+                    fatigue REAL NOT NULL,
+                    performance REAL NOT NULL
+                );
+                CREATE TABLE weekly_load (
+                    week_start TEXT PRIMARY KEY,
+                    total_load REAL NOT NULL,
+                    mean_daily_load REAL NOT NULL,
+                    monotony REAL,
+                    strain REAL
+                );
+                PRAGMA user_version = 1;
+                COMMIT;",
+            )?;
+        }
 
-        serde_json::to_writer_pretty(writer, &cached_data)?;
         Ok(())
     }
 
-    /// Load E1RM data from cache
-    pub fn load_e1rm_data(&self) -> Result<Vec<E1RMDataPoint>, CacheError> {
-        let path = self.cache_dir.join("e1rm_data.json");
-
-        if !path.exists() {
-            return Ok(Vec::new());
+    /// Replace the raw sets ingested from `source` (e.g. a CSV path) and record the ingest
+    pub fn ingest_sets(
+        &mut self,
+        source: &str,
+        records: &[SessionRecord],
+    ) -> Result<(), CacheError> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM sets WHERE source = ?1", params![source])?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO sets (source, session_id, date, time, plan_name, day_label, segment_id,
+                    superset_id, ex_code, adlib, set_num, reps, time_sec, weight, unit, is_warmup,
+                    rpe, rir, tempo, rest_sec, effort_1to5, tags, notes, pr_types)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16,
+                    ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24)",
+            )?;
+            for r in records {
+                stmt.execute(params![
+                    source,
+                    r.session_id,
+                    format_date(r.date),
+                    r.time.format("%H:%M:%S").to_string(),
+                    r.plan_name,
+                    r.day_label,
+                    r.segment_id,
+                    r.superset_id,
+                    r.ex_code,
+                    r.adlib,
+                    r.set_num,
+                    r.reps,
+                    r.time_sec,
+                    r.weight,
+                    r.unit,
+                    r.is_warmup,
+                    r.rpe,
+                    r.rir,
+                    r.tempo,
+                    r.rest_sec,
+                    r.effort_1to5,
+                    r.tags,
+                    r.notes,
+                    r.pr_types,
+                ])?;
+            }
         }
+        tx.execute(
+            "INSERT OR REPLACE INTO ingest_log (source, ingested_at, row_count) VALUES (?1, ?2, ?3)",
+            params![source, Utc::now().to_rfc3339(), records.len()],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
 
-        let file = File::open(path)?;
-        let reader = BufReader::new(file);
-        let cached_data: CachedE1RMData = serde_json::from_reader(reader)?;
+    /// Query raw sets by exercise and date
+    pub fn query_sets(&self, query: &MetricsQuery) -> Result<Vec<SessionRecord>, CacheError> {
+        let (clause, values) = query.where_clause(Some("ex_code"), "date", &[]);
+        let sql = format!(
+            "SELECT session_id, date, time, plan_name, day_label, segment_id, superset_id, ex_code,
+                adlib, set_num, reps, time_sec, weight, unit, is_warmup, rpe, rir, tempo, rest_sec,
+                effort_1to5, tags, notes, pr_types
+             FROM sets{} ORDER BY date, time, id",
+            clause
+        );
+        self.query(&sql, &values, |row| {
+            Ok(SessionRecord {
+                session_id: row.get(0)?,
+                date: parse_date(row, 1)?,
+                time: parse_time(row, 2)?,
+                plan_name: row.get(3)?,
+                day_label: row.get(4)?,
+                segment_id: row.get(5)?,
+                superset_id: row.get(6)?,
+                ex_code: row.get(7)?,
+                adlib: row.get(8)?,
+                set_num: row.get(9)?,
+                reps: row.get(10)?,
+                time_sec: row.get(11)?,
+                weight: row.get(12)?,
+                unit: row.get(13)?,
+                is_warmup: row.get(14)?,
+                rpe: row.get(15)?,
+                rir: row.get(16)?,
+                tempo: row.get(17)?,
+                rest_sec: row.get(18)?,
+                effort_1to5: row.get(19)?,
+                tags: row.get(20)?,
+                notes: row.get(21)?,
+                pr_types: row.get(22)?,
+            })
+        })
+    }
 
-        Ok(cached_data.entries)
+    /// Store E1RM data in cache
+    pub fn store_e1rm_data(&mut self, data: &[E1RMDataPoint]) -> Result<(), CacheError> {
+        self.replace_table("e1rm", |tx| insert_e1rm(tx, data))
     }
 
-    /// Store volume data in cache
-    pub fn store_volume_data(&self, data: &[VolumeDataPoint]) -> Result<(), CacheError> {
-        let path = self.cache_dir.join("volume_data.json");
-        let file = File::create(path)?;
-        let writer = BufWriter::new(file);
-
-        let cached_data = CachedVolumeData {
-            last_updated: Utc::now(),
-            entries: data.to_vec(),
-        };
+    /// Load E1RM data from cache
+    pub fn load_e1rm_data(&self) -> Result<Vec<E1RMDataPoint>, CacheError> {
+        self.query_e1rm_data(&MetricsQuery::new())
+    }
 
-        serde_json::to_writer_pretty(writer, &cached_data)?;
-        Ok(())
+    /// Query E1RM data by exercise and date
+    pub fn query_e1rm_data(&self, query: &MetricsQuery) -> Result<Vec<E1RMDataPoint>, CacheError> {
+        let (clause, values) = query.where_clause(Some("exercise"), "date", &[]);
+        let sql = format!(
+            "SELECT exercise, date, e1rm_kg, source_weight, source_reps, source_rpe, formula
+             FROM e1rm{} ORDER BY exercise, date",
+            clause
+        );
+        self.query(&sql, &values, |row| {
+            Ok(E1RMDataPoint {
+                exercise: row.get(0)?,
+                date: parse_date(row, 1)?,
+                e1rm_kg: row.get(2)?,
+                source_weight: row.get(3)?,
+                source_reps: row.get(4)?,
+                source_rpe: row.get(5)?,
+                formula: row.get(6)?,
+            })
+        })
+    }
+
+    /// Store volume data in cache
+    pub fn store_volume_data(&mut self, data: &[VolumeDataPoint]) -> Result<(), CacheError> {
+        self.replace_table("volume", |tx| insert_volume(tx, data))
     }
 
     /// Load volume data from cache
     pub fn load_volume_data(&self) -> Result<Vec<VolumeDataPoint>, CacheError> {
-        let path = self.cache_dir.join("volume_data.json");
+        self.query_volume_data(&MetricsQuery::new())
+    }
 
-        if !path.exists() {
-            return Ok(Vec::new());
-        }
+    /// Query weekly volume by category (exercise) and week start
+    pub fn query_volume_data(
+        &self,
+        query: &MetricsQuery,
+    ) -> Result<Vec<VolumeDataPoint>, CacheError> {
+        let (clause, values) = query.where_clause(Some("category"), "week_start", &[]);
+        let sql = format!(
+            "SELECT category, week_start, total_sets, total_reps, total_tonnage_kg, session_count
+             FROM volume{} ORDER BY week_start, category",
+            clause
+        );
+        self.query(&sql, &values, |row| {
+            Ok(VolumeDataPoint {
+                category: row.get(0)?,
+                week_start: parse_date(row, 1)?,
+                total_sets: row.get(2)?,
+                total_reps: row.get(3)?,
+                total_tonnage_kg: row.get(4)?,
+                session_count: row.get(5)?,
+            })
+        })
+    }
+
+    /// Store muscle-group / movement-pattern volume data in cache
+    pub fn store_group_volume_data(
+        &mut self,
+        data: &[GroupVolumeDataPoint],
+    ) -> Result<(), CacheError> {
+        self.replace_table("group_volume", |tx| insert_group_volume(tx, data))
+    }
+
+    /// Load muscle-group / movement-pattern volume data from cache
+    pub fn load_group_volume_data(&self) -> Result<Vec<GroupVolumeDataPoint>, CacheError> {
+        let mut data =
+            self.query_group_volume_data(VolumeGrouping::MuscleGroup, &MetricsQuery::new())?;
+        data.extend(
+            self.query_group_volume_data(VolumeGrouping::MovementPattern, &MetricsQuery::new())?,
+        );
+        Ok(data)
+    }
+
+    /// Query weighted group volume for one grouping by week start
+    pub fn query_group_volume_data(
+        &self,
+        grouping: VolumeGrouping,
+        query: &MetricsQuery,
+    ) -> Result<Vec<GroupVolumeDataPoint>, CacheError> {
+        let (clause, values) =
+            query.where_clause(None, "week_start", &[("grouping", enum_text(&grouping)?)]);
+        let sql = format!(
+            "SELECT group_name, week_start, weighted_sets, weighted_reps, weighted_tonnage_kg, exercises
+             FROM group_volume{} ORDER BY week_start, group_name",
+            clause
+        );
+        self.query(&sql, &values, |row| {
+            let exercises: String = row.get(5)?;
+            Ok(GroupVolumeDataPoint {
+                grouping,
+                group: row.get(0)?,
+                week_start: parse_date(row, 1)?,
+                weighted_sets: row.get(2)?,
+                weighted_reps: row.get(3)?,
+                weighted_tonnage_kg: row.get(4)?,
+                exercises: parse_json(&exercises, 5)?,
+            })
+        })
+    }
 
-        let file = File::open(path)?;
-        let reader = BufReader::new(file);
-        let cached_data: CachedVolumeData = serde_json::from_reader(reader)?;
+    /// Store daily training load (ACWR, fitness-fatigue) data in cache
+    pub fn store_daily_load_data(&mut self, data: &[DailyLoadDataPoint]) -> Result<(), CacheError> {
+        self.replace_table("daily_load", |tx| insert_daily_load(tx, data))
+    }
 
-        Ok(cached_data.entries)
+    /// Load daily training load data from cache
+    pub fn load_daily_load_data(&self) -> Result<Vec<DailyLoadDataPoint>, CacheError> {
+        self.query_daily_load_data(&MetricsQuery::new())
+    }
+
+    /// Query daily training load by date
+    pub fn query_daily_load_data(
+        &self,
+        query: &MetricsQuery,
+    ) -> Result<Vec<DailyLoadDataPoint>, CacheError> {
+        let (clause, values) = query.where_clause(None, "date", &[]);
+        let sql = format!(
+            "SELECT date, load, acute_load, chronic_load, acwr, fitness, fatigue, performance
+             FROM daily_load{} ORDER BY date",
+            clause
+        );
+        self.query(&sql, &values, |row| {
+            Ok(DailyLoadDataPoint {
+                date: parse_date(row, 0)?,
+                load: row.get(1)?,
+                acute_load: row.get(2)?,
+                chronic_load: row.get(3)?,
+                acwr: row.get(4)?,
+                fitness: row.get(5)?,
+                fatigue: row.get(6)?,
+                performance: row.get(7)?,
+            })
+        })
+    }
+
+    /// Store weekly training load (monotony, strain) data in cache
+    pub fn store_weekly_load_data(
+        &mut self,
+        data: &[WeeklyLoadDataPoint],
+    ) -> Result<(), CacheError> {
+        self.replace_table("weekly_load", |tx| insert_weekly_load(tx, data))
+    }
+
+    /// Load weekly training load data from cache
+    pub fn load_weekly_load_data(&self) -> Result<Vec<WeeklyLoadDataPoint>, CacheError> {
+        self.query_weekly_load_data(&MetricsQuery::new())
+    }
+
+    /// Query weekly training load by week start
+    pub fn query_weekly_load_data(
+        &self,
+        query: &MetricsQuery,
+    ) -> Result<Vec<WeeklyLoadDataPoint>, CacheError> {
+        let (clause, values) = query.where_clause(None, "week_start", &[]);
+        let sql = format!(
+            "SELECT week_start, total_load, mean_daily_load, monotony, strain
+             FROM weekly_load{} ORDER BY week_start",
+            clause
+        );
+        self.query(&sql, &values, |row| {
+            Ok(WeeklyLoadDataPoint {
+                week_start: parse_date(row, 0)?,
+                total_load: row.get(1)?,
+                mean_daily_load: row.get(2)?,
+                monotony: row.get(3)?,
+                strain: row.get(4)?,
+            })
+        })
     }
 
     /// Store PR data in cache
-    pub fn store_pr_data(&self, data: &[PRDataPoint]) -> Result<(), CacheError> {
-        let path = self.cache_dir.join("pr_data.json");
-        let file = File::create(path)?;
-        let writer = BufWriter::new(file);
-
-        let cached_data = CachedPRData {
-            last_updated: Utc::now(),
-            entries: data.to_vec(),
-        };
+    pub fn store_pr_data(&mut self, data: &[PRDataPoint]) -> Result<(), CacheError> {
+        self.replace_table("prs", |tx| insert_prs(tx, data))
+    }
 
-        serde_json::to_writer_pretty(writer, &cached_data)?;
+    /// Replace every derived-metrics table in one transaction, so readers never see tables
+    /// computed from different sets of sessions
+    pub fn store_derived(&mut self, metrics: &DerivedMetrics) -> Result<(), CacheError> {
+        let tx = self.conn.transaction()?;
+        replace_rows(&tx, "e1rm", |tx| insert_e1rm(tx, &metrics.e1rm))?;
+        replace_rows(&tx, "volume", |tx| insert_volume(tx, &metrics.volume))?;
+        replace_rows(&tx, "group_volume", |tx| {
+            insert_group_volume(tx, &metrics.group_volume)
+        })?;
+        replace_rows(&tx, "daily_load", |tx| {
+            insert_daily_load(tx, &metrics.daily_load)
+        })?;
+        replace_rows(&tx, "weekly_load", |tx| {
+            insert_weekly_load(tx, &metrics.weekly_load)
+        })?;
+        replace_rows(&tx, "prs", |tx| insert_prs(tx, &metrics.prs))?;
+        tx.commit()?;
         Ok(())
     }
 
     /// Load PR data from cache
     pub fn load_pr_data(&self) -> Result<Vec<PRDataPoint>, CacheError> {
-        let path = self.cache_dir.join("pr_data.json");
-
-        if !path.exists() {
-            return Ok(Vec::new());
-        }
-
-        let file = File::open(path)?;
-        let reader = BufReader::new(file);
-        let cached_data: CachedPRData = serde_json::from_reader(reader)?;
+        self.query_pr_data(&MetricsQuery::new())
+    }
 
-        Ok(cached_data.entries)
+    /// Query PR data by exercise and date
+    pub fn query_pr_data(&self, query: &MetricsQuery) -> Result<Vec<PRDataPoint>, CacheError> {
+        let (clause, values) = query.where_clause(Some("exercise"), "date", &[]);
+        let sql = format!(
+            "SELECT exercise, pr_kind, date, value, reps, notes, source
+             FROM prs{} ORDER BY exercise, date",
+            clause
+        );
+        self.query(&sql, &values, |row| {
+            let kind: String = row.get(1)?;
+            let source: String = row.get(6)?;
+            Ok(PRDataPoint {
+                exercise: row.get(0)?,
+                pr_type: parse_json::<PRKind>(&kind, 1)?,
+                date: parse_date(row, 2)?,
+                value: row.get(3)?,
+                reps: row.get(4)?,
+                notes: row.get(5)?,
+                source: parse_enum_text::<PRSource>(source, 6)?,
+            })
+        })
     }
 
     /// Get cache status and statistics
     pub fn get_status(&self) -> Result<CacheStatus, CacheError> {
-        let e1rm_data = self.load_e1rm_data()?;
-        let volume_data = self.load_volume_data()?;
-        let pr_data = self.load_pr_data()?;
-
-        // Calculate cache size
-        let cache_size = self.calculate_cache_size()?;
+        let sources = self.query(
+            "SELECT source, ingested_at, row_count FROM ingest_log ORDER BY ingested_at DESC",
+            &[],
+            |row| {
+                Ok(IngestInfo {
+                    source: row.get(0)?,
+                    ingested_at: row.get(1)?,
+                    rows: row.get(2)?,
+                })
+            },
+        )?;
 
-        // Get last updated timestamp
-        let last_updated = self.get_last_updated_time();
+        let last_updated: Option<String> = self.conn.query_row(
+            "SELECT MAX(ts) FROM (
+                SELECT MAX(ingested_at) AS ts FROM ingest_log
+                UNION ALL SELECT MAX(updated_at) FROM table_updates
+            )",
+            [],
+            |row| row.get(0),
+        )?;
 
         Ok(CacheStatus {
-            e1rm_entries: e1rm_data.len(),
-            volume_entries: volume_data.len(),
-            pr_entries: pr_data.len(),
+            schema_version: self.schema_version()?,
+            set_entries: self.row_count("sets")?,
+            e1rm_entries: self.row_count("e1rm")?,
+            volume_entries: self.row_count("volume")?,
+            group_volume_entries: self.row_count("group_volume")?,
+            daily_load_entries: self.row_count("daily_load")?,
+            weekly_load_entries: self.row_count("weekly_load")?,
+            pr_entries: self.row_count("prs")?,
+            sources,
             last_updated,
-            cache_size_bytes: cache_size,
+            cache_size_bytes: self.calculate_cache_size(),
         })
     }
 
-    /// Clear all cached data
-    pub fn clear_all(&self) -> Result<(), CacheError> {
-        let cache_files = ["e1rm_data.json", "volume_data.json", "pr_data.json"];
+    /// Clear all cached data (including JSON files left by older versions)
+    pub fn clear_all(&mut self) -> Result<(), CacheError> {
+        self.conn.execute_batch(
+            "BEGIN;
+            DELETE FROM sets;
+            DELETE FROM e1rm;
+            DELETE FROM volume;
+            DELETE FROM group_volume;
+            DELETE FROM prs;
+            DELETE FROM daily_load;
+            DELETE FROM weekly_load;
+            DELETE FROM ingest_log;
+            DELETE FROM table_updates;
+            COMMIT;",
+        )?;
 
-        for file in &cache_files {
+        for file in &LEGACY_CACHE_FILES {
             let path = self.cache_dir.join(file);
-            if path.exists() {
+            if path.is_file() {
                 fs::remove_file(path)?;
             }
         }
@@ -173,135 +690,401 @@ impl MetricsCache {
         Ok(())
     }
 
-    /// Calculate total cache size in bytes
-    fn calculate_cache_size(&self) -> Result<u64, CacheError> {
-        let mut total_size = 0u64;
+    /// Replace every row of a derived-metrics table in one transaction
+    fn replace_table<F>(&mut self, table: &str, insert: F) -> Result<(), CacheError>
+    where
+        F: FnOnce(&Transaction) -> Result<(), CacheError>,
+    {
+        let tx = self.conn.transaction()?;
+        replace_rows(&tx, table, insert)?;
+        tx.commit()?;
+        Ok(())
+    }
 
-        if let Ok(entries) = fs::read_dir(&self.cache_dir) {
-            for entry in entries.flatten() {
-                if let Ok(metadata) = entry.metadata() {
-                    if metadata.is_file() {
-                        total_size += metadata.len();
-                    }
-                }
-            }
-        }
+    fn query<T, F>(&self, sql: &str, values: &[String], map: F) -> Result<Vec<T>, CacheError>
+    where
+        F: FnMut(&Row<'_>) -> rusqlite::Result<T>,
+    {
+        let mut stmt = self.conn.prepare(sql)?;
+        let rows = stmt.query_map(params_from_iter(values.iter()), map)?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
 
-        Ok(total_size)
+    fn row_count(&self, table: &str) -> Result<usize, CacheError> {
+        let count: i64 =
+            self.conn
+                .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+                    row.get(0)
+                })?;
+        Ok(count as usize)
     }
 
-    /// Get the most recent update timestamp from cache files
-    fn get_last_updated_time(&self) -> Option<String> {
-        let cache_files = ["e1rm_data.json", "volume_data.json", "pr_data.json"];
-        let mut latest_time: Option<DateTime<Utc>> = None;
+    /// Size of the database file in bytes (0 for in-memory stores)
+    fn calculate_cache_size(&self) -> u64 {
+        self.db_path
+            .as_ref()
+            .and_then(|path| fs::metadata(path).ok())
+            .map_or(0, |metadata| metadata.len())
+    }
+}
 
-        for file in &cache_files {
-            let path = self.cache_dir.join(file);
-            if !path.exists() {
-                continue;
-            }
+/// Delete a derived-metrics table's rows, insert the new ones and stamp its update time
+fn replace_rows<F>(tx: &Transaction, table: &str, insert: F) -> Result<(), CacheError>
+where
+    F: FnOnce(&Transaction) -> Result<(), CacheError>,
+{
+    tx.execute(&format!("DELETE FROM {}", table), [])?;
+    insert(tx)?;
+    tx.execute(
+        "INSERT OR REPLACE INTO table_updates (table_name, updated_at) VALUES (?1, ?2)",
+        params![table, Utc::now().to_rfc3339()],
+    )?;
+    Ok(())
+}
 
-            // Try to read the last_updated field from each file
-            if let Ok(file) = File::open(&path) {
-                if let Ok(value) =
-                    serde_json::from_reader::<_, serde_json::Value>(BufReader::new(file))
-                {
-                    if let Some(timestamp_str) = value.get("last_updated").and_then(|v| v.as_str())
-                    {
-                        if let Ok(timestamp) = DateTime::parse_from_rfc3339(timestamp_str) {
-                            let utc_time = timestamp.with_timezone(&Utc);
-                            if latest_time.is_none() || utc_time > latest_time.unwrap() {
-                                latest_time = Some(utc_time);
-                            }
-                        }
-                    }
-                }
-            }
-        }
+fn insert_e1rm(tx: &Transaction, data: &[E1RMDataPoint]) -> Result<(), CacheError> {
+    let mut stmt = tx.prepare(
+        "INSERT INTO e1rm (exercise, date, e1rm_kg, source_weight, source_reps, source_rpe, formula)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+    )?;
+    for d in data {
+        stmt.execute(params![
+            d.exercise,
+            format_date(d.date),
+            d.e1rm_kg,
+            d.source_weight,
+            d.source_reps,
+            d.source_rpe,
+            d.formula,
+        ])?;
+    }
+    Ok(())
+}
 
-        latest_time.map(|t| t.to_rfc3339())
+fn insert_volume(tx: &Transaction, data: &[VolumeDataPoint]) -> Result<(), CacheError> {
+    let mut stmt = tx.prepare(
+        "INSERT INTO volume (category, week_start, total_sets, total_reps, total_tonnage_kg, session_count)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    )?;
+    for d in data {
+        stmt.execute(params![
+            d.category,
+            format_date(d.week_start),
+            d.total_sets,
+            d.total_reps,
+            d.total_tonnage_kg,
+            d.session_count,
+        ])?;
     }
+    Ok(())
 }
 
-/// Cached E1RM data with metadata
-#[derive(Debug, Serialize, Deserialize)]
-struct CachedE1RMData {
-    last_updated: DateTime<Utc>,
-    entries: Vec<E1RMDataPoint>,
+fn insert_group_volume(tx: &Transaction, data: &[GroupVolumeDataPoint]) -> Result<(), CacheError> {
+    let mut stmt = tx.prepare(
+        "INSERT INTO group_volume (grouping, group_name, week_start, weighted_sets, weighted_reps,
+            weighted_tonnage_kg, exercises)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+    )?;
+    for d in data {
+        stmt.execute(params![
+            enum_text(&d.grouping)?,
+            d.group,
+            format_date(d.week_start),
+            d.weighted_sets,
+            d.weighted_reps,
+            d.weighted_tonnage_kg,
+            serde_json::to_string(&d.exercises)?,
+        ])?;
+    }
+    Ok(())
 }
 
-/// Cached volume data with metadata
-#[derive(Debug, Serialize, Deserialize)]
-struct CachedVolumeData {
-    last_updated: DateTime<Utc>,
-    entries: Vec<VolumeDataPoint>,
+fn insert_daily_load(tx: &Transaction, data: &[DailyLoadDataPoint]) -> Result<(), CacheError> {
+    let mut stmt = tx.prepare(
+        "INSERT INTO daily_load (date, load, acute_load, chronic_load, acwr, fitness, fatigue, performance)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+    )?;
+    for d in data {
+        stmt.execute(params![
+            format_date(d.date),
+            d.load,
+            d.acute_load,
+            d.chronic_load,
+            d.acwr,
+            d.fitness,
+            d.fatigue,
+            d.performance,
+        ])?;
+    }
+    Ok(())
 }
 
-/// Cached PR data with metadata
-#[derive(Debug, Serialize, Deserialize)]
-struct CachedPRData {
-    last_updated: DateTime<Utc>,
-    entries: Vec<PRDataPoint>,
+fn insert_weekly_load(tx: &Transaction, data: &[WeeklyLoadDataPoint]) -> Result<(), CacheError> {
+    let mut stmt = tx.prepare(
+        "INSERT INTO weekly_load (week_start, total_load, mean_daily_load, monotony, strain)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+    )?;
+    for d in data {
+        stmt.execute(params![
+            format_date(d.week_start),
+            d.total_load,
+            d.mean_daily_load,
+            d.monotony,
+            d.strain,
+        ])?;
+    }
+    Ok(())
+}
+
+fn insert_prs(tx: &Transaction, data: &[PRDataPoint]) -> Result<(), CacheError> {
+    let mut stmt = tx.prepare(
+        "INSERT INTO prs (exercise, pr_kind, date, value, reps, notes, source)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+    )?;
+    for d in data {
+        stmt.execute(params![
+            d.exercise,
+            serde_json::to_string(&d.pr_type)?,
+            format_date(d.date),
+            d.value,
+            d.reps,
+            d.notes,
+            enum_text(&d.source)?,
+        ])?;
+    }
+    Ok(())
+}
+
+fn format_date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+fn parse_date(row: &Row<'_>, idx: usize) -> rusqlite::Result<NaiveDate> {
+    let text: String = row.get(idx)?;
+    NaiveDate::parse_from_str(&text, "%Y-%m-%d").map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+fn parse_time(row: &Row<'_>, idx: usize) -> rusqlite::Result<NaiveTime> {
+    let text: String = row.get(idx)?;
+    NaiveTime::parse_from_str(&text, "%H:%M:%S").map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+fn parse_json<T: serde::de::DeserializeOwned>(text: &str, idx: usize) -> rusqlite::Result<T> {
+    serde_json::from_str(text).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+fn parse_enum_text<T: serde::de::DeserializeOwned>(
+    text: String,
+    idx: usize,
+) -> rusqlite::Result<T> {
+    serde_json::from_value(serde_json::Value::String(text)).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+/// snake_case text for a unit-like enum (e.g. `VolumeGrouping::MuscleGroup` -> "muscle_group")
+fn enum_text<T: Serialize>(value: &T) -> Result<String, CacheError> {
+    match serde_json::to_value(value)? {
+        serde_json::Value::String(text) => Ok(text),
+        other => Err(CacheError::Corruption(format!(
+            "Expected a string enum, got {}",
+            other
+        ))),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::NaiveDate;
     use tempfile::TempDir;
-    use weightlifting_core::AppPaths;
-
-    fn create_test_app_paths() -> (AppPaths, TempDir) {
-        let temp_dir = TempDir::new().unwrap();
 
-        // Create a mock AppPaths that uses our temp directory
-        // This would need AppPaths to support custom directories
-        // For now, we'll test with a direct cache path
-        let paths = AppPaths::new().unwrap();
-        (paths, temp_dir)
+    fn e1rm(exercise: &str, day: u32, e1rm_kg: f64) -> E1RMDataPoint {
+        E1RMDataPoint {
+            exercise: exercise.to_string(),
+            date: NaiveDate::from_ymd_opt(2025, 8, day).unwrap(),
+            e1rm_kg,
+            source_weight: 100.0,
+            source_reps: 5,
+            source_rpe: Some(8.0),
+            formula: "epley_rpe".to_string(),
+        }
     }
 
     #[test]
     fn test_cache_roundtrip() {
         let temp_dir = TempDir::new().unwrap();
-
-        // Create a cache with custom directory
-        let cache = MetricsCache {
-            cache_dir: temp_dir.path().to_path_buf(),
-        };
-
-        // Test E1RM data
-        let e1rm_data = vec![E1RMDataPoint {
-            exercise: "BP.BB.FLAT".to_string(),
-            date: NaiveDate::from_ymd_opt(2025, 8, 22).unwrap(),
-            e1rm_kg: 120.0,
-            source_weight: 100.0,
-            source_reps: 5,
-            source_rpe: Some(8.0),
-            formula: "epley_rpe".to_string(),
-        }];
+        let mut cache = MetricsCache::open(temp_dir.path()).unwrap();
 
         // Store and load
-        cache.store_e1rm_data(&e1rm_data).unwrap();
+        cache
+            .store_e1rm_data(&[e1rm("BP.BB.FLAT", 22, 120.0)])
+            .unwrap();
         let loaded = cache.load_e1rm_data().unwrap();
 
         assert_eq!(loaded.len(), 1);
         assert_eq!(loaded[0].exercise, "BP.BB.FLAT");
         assert_eq!(loaded[0].e1rm_kg, 120.0);
+
+        // PR kinds and sources survive the round trip
+        let pr = PRDataPoint {
+            exercise: "BP.BB.FLAT".to_string(),
+            pr_type: PRKind::RepMax { reps: 5 },
+            date: NaiveDate::from_ymd_opt(2025, 8, 22).unwrap(),
+            value: 100.0,
+            reps: Some(5),
+            notes: None,
+            source: PRSource::Both,
+        };
+        cache.store_pr_data(&[pr]).unwrap();
+        let prs = cache.load_pr_data().unwrap();
+        assert_eq!(prs[0].pr_type, PRKind::RepMax { reps: 5 });
+        assert_eq!(prs[0].source, PRSource::Both);
+
+        // Data persists across reopen
+        drop(cache);
+        let reopened = MetricsCache::open(temp_dir.path()).unwrap();
+        assert_eq!(reopened.load_e1rm_data().unwrap().len(), 1);
     }
 
     #[test]
-    fn test_cache_status() {
-        let temp_dir = TempDir::new().unwrap();
+    fn test_query_filters_by_exercise_and_date() {
+        let mut cache = MetricsCache::open_in_memory().unwrap();
+        cache
+            .store_e1rm_data(&[
+                e1rm("BP.BB.FLAT", 1, 110.0),
+                e1rm("BP.BB.FLAT", 15, 115.0),
+                e1rm("SQ.BB.BACK", 15, 150.0),
+            ])
+            .unwrap();
 
-        let cache = MetricsCache {
-            cache_dir: temp_dir.path().to_path_buf(),
-        };
+        let bench = cache
+            .query_e1rm_data(&MetricsQuery::new().exercise(Some("BP.BB.FLAT")))
+            .unwrap();
+        assert_eq!(bench.len(), 2);
+
+        let range = Some((
+            NaiveDate::from_ymd_opt(2025, 8, 10).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 8, 31).unwrap(),
+        ));
+        let recent = cache
+            .query_e1rm_data(&MetricsQuery::new().date_range(range))
+            .unwrap();
+        assert_eq!(recent.len(), 2);
+        assert!(recent
+            .iter()
+            .all(|d| d.date.format("%d").to_string() == "15"));
+    }
+
+    #[test]
+    fn test_cache_status() {
+        let mut cache = MetricsCache::open_in_memory().unwrap();
 
         // Initially empty
         let status = cache.get_status().unwrap();
+        assert_eq!(status.schema_version, SCHEMA_VERSION);
         assert_eq!(status.e1rm_entries, 0);
         assert_eq!(status.volume_entries, 0);
         assert_eq!(status.pr_entries, 0);
+        assert!(status.sources.is_empty());
+        assert!(status.last_updated.is_none());
+
+        let record = SessionRecord {
+            session_id: "S1".to_string(),
+            date: NaiveDate::from_ymd_opt(2025, 8, 14).unwrap(),
+            time: NaiveTime::from_hms_opt(9, 37, 12).unwrap(),
+            plan_name: Some("PHUL".to_string()),
+            day_label: Some("Upper Power".to_string()),
+            segment_id: 1,
+            superset_id: None,
+            ex_code: "BP.BB.FLAT".to_string(),
+            adlib: 0,
+            set_num: 1,
+            reps: Some(5),
+            time_sec: None,
+            weight: Some(85.0),
+            unit: "kg".to_string(),
+            is_warmup: 0,
+            rpe: Some(7.5),
+            rir: Some(2),
+            tempo: None,
+            rest_sec: Some(150),
+            effort_1to5: 3,
+            tags: None,
+            notes: None,
+            pr_types: None,
+        };
+
+        // Re-ingesting a source replaces its rows
+        let records = vec![record.clone(), record];
+        cache.ingest_sets("week1.csv", &records).unwrap();
+        cache.ingest_sets("week1.csv", &records[..1]).unwrap();
+        cache.ingest_sets("week2.csv", &records[1..]).unwrap();
+
+        let status = cache.get_status().unwrap();
+        assert_eq!(status.set_entries, 2);
+        assert_eq!(status.sources.len(), 2);
+        assert!(status.last_updated.is_some());
+
+        let sets = cache
+            .query_sets(&MetricsQuery::new().exercise(Some("BP.BB.FLAT")))
+            .unwrap();
+        assert_eq!(sets.len(), 2);
+        assert_eq!(sets[0].day_label.as_deref(), Some("Upper Power"));
+
+        cache.clear_all().unwrap();
+        assert_eq!(cache.get_status().unwrap().set_entries, 0);
+    }
+
+    #[test]
+    fn test_derived_metrics_cover_every_source() {
+        use crate::metrics::E1RMCalculator;
+
+        let mut cache = MetricsCache::open_in_memory().unwrap();
+        let set = |session: &str, day: u32, ex: &str, weight: f64| {
+            SessionRecord::builder()
+                .session(session)
+                .date(NaiveDate::from_ymd_opt(2025, 8, day).unwrap())
+                .ex(ex)
+                .weight(weight)
+                .rpe(8.0)
+                .build()
+        };
+        let squat = [set("S1", 11, "SQ.BB.BACK", 140.0)];
+        let bench = [set("S2", 13, "BP.BB.FLAT", 100.0)];
+
+        cache
+            .store_group_volume_data(&[GroupVolumeDataPoint {
+                grouping: VolumeGrouping::MuscleGroup,
+                group: "quads".to_string(),
+                week_start: NaiveDate::from_ymd_opt(2025, 8, 4).unwrap(),
+                weighted_sets: 3.0,
+                weighted_reps: 15.0,
+                weighted_tonnage_kg: 2100.0,
+                exercises: vec!["SQ.BB.BACK".to_string()],
+            }])
+            .unwrap();
+
+        // Processing b.csv after a.csv must keep a.csv's metrics
+        cache.ingest_sets("a.csv", &squat).unwrap();
+        cache.ingest_sets("b.csv", &bench).unwrap();
+        let all_sets = cache.query_sets(&MetricsQuery::new()).unwrap();
+        let derived = DerivedMetrics {
+            e1rm: E1RMCalculator::new()
+                .calculate_historical_e1rms(&all_sets)
+                .unwrap(),
+            ..DerivedMetrics::default()
+        };
+        cache.store_derived(&derived).unwrap();
+
+        let status = cache.get_status().unwrap();
+        assert_eq!(status.set_entries, 2);
+        assert_eq!(status.e1rm_entries, 2);
+        assert_eq!(status.group_volume_entries, 0);
     }
 }
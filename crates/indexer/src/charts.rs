@@ -0,0 +1,226 @@
+use crate::cache::{CacheError, MetricsCache, MetricsQuery};
+use crate::exercise_catalog::ExerciseCatalog;
+use crate::metrics::VolumeGrouping;
+use chrono::NaiveDate;
+use std::collections::BTreeSet;
+use weightlifting_core::{
+    AppPaths, BuiltinCharts, DisplayUnit, LoadMetric, PRDisplayMode, VegaLiteSpec, VolumeMetric,
+};
+
+/// Chart types built from the metrics cache alone, with a short description of each
+pub const BUILTIN_CHARTS: &[(&str, &str)] = &[
+    ("e1rm", "Estimated 1RM over time with rolling best and PRs"),
+    (
+        "e1rm-grid",
+        "Estimated 1RM small multiples, one panel per exercise",
+    ),
+    ("volume", "Weekly tonnage by body part"),
+    ("muscle-volume", "Weekly weighted sets by muscle group"),
+    ("pattern-volume", "Weekly weighted sets by movement pattern"),
+    ("acwr", "Acute:chronic workload ratio"),
+    ("fitness-fatigue", "Banister fitness-fatigue model"),
+    ("weekly-load", "Weekly session-RPE load"),
+    ("monotony", "Weekly training monotony"),
+    ("strain", "Weekly training strain"),
+    ("pr", "Personal record board"),
+    ("heatmap", "Session frequency calendar"),
+];
+
+/// Filters and presentation options for a built-in chart
+#[derive(Debug, Clone, Default)]
+pub struct ChartFilters<'a> {
+    /// Exercise filter for the e1rm and pr charts
+    pub exercise: Option<&'a str>,
+    /// Inclusive date range; the heatmap spans the logged sessions when unset
+    pub date_range: Option<(NaiveDate, NaiveDate)>,
+    pub unit: DisplayUnit,
+    pub title: Option<String>,
+}
+
+/// Muscle-group or movement-pattern grouping and its axis label for a group volume chart
+pub fn group_volume_kind(chart_type: &str) -> (VolumeGrouping, &'static str) {
+    if chart_type == "pattern-volume" {
+        (VolumeGrouping::MovementPattern, "Movement Pattern")
+    } else {
+        (VolumeGrouping::MuscleGroup, "Muscle Group")
+    }
+}
+
+/// Builds the spec for one of `BUILTIN_CHARTS` from the metrics cache
+/// Returns `None` when `chart_type` is not a built-in chart
+pub fn builtin_chart_spec(
+    cache: &MetricsCache,
+    paths: &AppPaths,
+    chart_type: &str,
+    filters: &ChartFilters,
+) -> Result<Option<VegaLiteSpec>, CacheError> {
+    let exercise = filters.exercise;
+    let unit = filters.unit;
+    let title = filters.title.clone();
+    let query = MetricsQuery::new()
+        .exercise(exercise)
+        .date_range(filters.date_range);
+    let all_exercises = MetricsQuery::new().date_range(filters.date_range);
+
+    let spec = match chart_type {
+        "e1rm" | "e1rm-grid" => {
+            let data: Vec<_> = cache
+                .query_e1rm_data(&query)?
+                .into_iter()
+                .map(|d| (d.exercise, d.date, d.e1rm_kg))
+                .collect();
+            if chart_type == "e1rm" {
+                BuiltinCharts::e1rm_over_time(&data, exercise, unit, title)
+            } else {
+                BuiltinCharts::e1rm_by_exercise(&data, 3, unit, title)
+            }
+        }
+        "volume" => {
+            let data: Vec<_> = cache
+                .query_volume_data(&all_exercises)?
+                .into_iter()
+                .map(|d| {
+                    (
+                        d.category,
+                        d.week_start,
+                        d.total_sets,
+                        d.total_reps,
+                        d.total_tonnage_kg,
+                    )
+                })
+                .collect();
+            let db_path = paths.exercise_db_path();
+            let body_part_map = db_path
+                .exists()
+                .then(|| ExerciseCatalog::open(&db_path).ok())
+                .flatten()
+                .map(|catalog| catalog.primary_body_part_map());
+            BuiltinCharts::weekly_volume_by_bodypart(
+                &data,
+                body_part_map.as_ref(),
+                VolumeMetric::Tonnage,
+                unit,
+                title,
+            )
+        }
+        "muscle-volume" | "pattern-volume" => {
+            let (grouping, label) = group_volume_kind(chart_type);
+            let data: Vec<_> = cache
+                .query_group_volume_data(grouping, &all_exercises)?
+                .into_iter()
+                .map(|d| {
+                    (
+                        d.group,
+                        d.week_start,
+                        d.weighted_sets,
+                        d.weighted_reps,
+                        d.weighted_tonnage_kg,
+                    )
+                })
+                .collect();
+            BuiltinCharts::weekly_volume_by_group(&data, label, VolumeMetric::Sets, unit, title)
+        }
+        "acwr" | "fitness-fatigue" => {
+            let daily = cache.query_daily_load_data(&all_exercises)?;
+            if chart_type == "acwr" {
+                let data: Vec<_> = daily.iter().map(|d| (d.date, d.load, d.acwr)).collect();
+                BuiltinCharts::acwr_over_time(&data, title)
+            } else {
+                let data: Vec<_> = daily
+                    .iter()
+                    .map(|d| (d.date, d.fitness, d.fatigue, d.performance))
+                    .collect();
+                BuiltinCharts::fitness_fatigue(&data, title)
+            }
+        }
+        "weekly-load" | "monotony" | "strain" => {
+            let metric = match chart_type {
+                "monotony" => LoadMetric::Monotony,
+                "strain" => LoadMetric::Strain,
+                _ => LoadMetric::Load,
+            };
+            let data: Vec<_> = cache
+                .query_weekly_load_data(&all_exercises)?
+                .into_iter()
+                .map(|d| (d.week_start, d.total_load, d.monotony, d.strain))
+                .collect();
+            BuiltinCharts::weekly_training_load(&data, metric, title)
+        }
+        "pr" => {
+            let data: Vec<_> = cache
+                .query_pr_data(&query)?
+                .into_iter()
+                .map(|d| (d.exercise, d.pr_type.to_string(), d.date, d.value, d.reps))
+                .collect();
+            BuiltinCharts::pr_board(&data, exercise, PRDisplayMode::Bar, unit, title)
+        }
+        "heatmap" => {
+            let sessions: BTreeSet<(NaiveDate, String)> = cache
+                .query_sets(&all_exercises)?
+                .into_iter()
+                .map(|set| (set.date, set.session_id))
+                .collect();
+            let dates: Vec<NaiveDate> = sessions.into_iter().map(|(date, _)| date).collect();
+            let (start, end) = match filters.date_range {
+                Some(range) => range,
+                None => match (dates.first(), dates.last()) {
+                    (Some(first), Some(last)) => (*first, *last),
+                    _ => {
+                        let today = chrono::Local::now().date_naive();
+                        (today, today)
+                    }
+                },
+            };
+            BuiltinCharts::session_frequency_heatmap(&dates, start, end, title)
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(spec))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::E1RMDataPoint;
+
+    #[test]
+    fn test_builtin_chart_spec_dispatch() {
+        let mut cache = MetricsCache::open_in_memory().unwrap();
+        cache
+            .store_e1rm_data(&[E1RMDataPoint {
+                exercise: "BP.BB.FLAT".to_string(),
+                date: NaiveDate::from_ymd_opt(2025, 8, 4).unwrap(),
+                e1rm_kg: 120.0,
+                source_weight: 100.0,
+                source_reps: 5,
+                source_rpe: Some(8.0),
+                formula: "epley_rpe".to_string(),
+            }])
+            .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let paths = AppPaths {
+            data_dir: dir.path().join("data"),
+            state_dir: dir.path().join("state"),
+            cache_dir: dir.path().join("cache"),
+        };
+        let filters = ChartFilters {
+            exercise: Some("BP.BB.FLAT"),
+            ..ChartFilters::default()
+        };
+
+        for (chart_type, _) in BUILTIN_CHARTS {
+            let spec = builtin_chart_spec(&cache, &paths, chart_type, &filters).unwrap();
+            assert!(spec.is_some(), "{} should be built in", chart_type);
+        }
+        let spec = builtin_chart_spec(&cache, &paths, "e1rm", &filters)
+            .unwrap()
+            .unwrap();
+        assert!(spec.to_json().to_string().contains("BP.BB.FLAT"));
+
+        assert!(
+            builtin_chart_spec(&cache, &paths, "planned-volume", &filters)
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -78,6 +78,133 @@ impl SessionRecord {
     }
 }
 
+/// Builds `SessionRecord`s for tests: by default a 100 kg x 5 working set of SQ.BB.BACK,
+/// set 1 of segment 1 in session S1 on 2025-08-04 at 09:00 with no plan
+#[cfg(test)]
+#[derive(Debug, Clone)]
+pub struct SessionRecordBuilder(SessionRecord);
+
+#[cfg(test)]
+impl SessionRecord {
+    pub fn builder() -> SessionRecordBuilder {
+        SessionRecordBuilder(SessionRecord {
+            session_id: "S1".to_string(),
+            date: NaiveDate::from_ymd_opt(2025, 8, 4).unwrap(),
+            time: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            plan_name: None,
+            day_label: None,
+            segment_id: 1,
+            superset_id: None,
+            ex_code: "SQ.BB.BACK".to_string(),
+            adlib: 0,
+            set_num: 1,
+            reps: Some(5),
+            time_sec: None,
+            weight: Some(100.0),
+            unit: "kg".to_string(),
+            is_warmup: 0,
+            rpe: None,
+            rir: None,
+            tempo: None,
+            rest_sec: None,
+            effort_1to5: 3,
+            tags: None,
+            notes: None,
+            pr_types: None,
+        })
+    }
+}
+
+#[cfg(test)]
+impl SessionRecordBuilder {
+    pub fn session(mut self, session_id: &str) -> Self {
+        self.0.session_id = session_id.to_string();
+        self
+    }
+
+    pub fn date(mut self, date: NaiveDate) -> Self {
+        self.0.date = date;
+        self
+    }
+
+    pub fn time(mut self, hour: u32, minute: u32) -> Self {
+        self.0.time = NaiveTime::from_hms_opt(hour, minute, 0).unwrap();
+        self
+    }
+
+    pub fn plan(mut self, plan_name: &str) -> Self {
+        self.0.plan_name = Some(plan_name.to_string());
+        self
+    }
+
+    pub fn day(mut self, day_label: &str) -> Self {
+        self.0.day_label = Some(day_label.to_string());
+        self
+    }
+
+    pub fn segment(mut self, segment_id: u32) -> Self {
+        self.0.segment_id = segment_id;
+        self
+    }
+
+    pub fn set_num(mut self, set_num: u32) -> Self {
+        self.0.set_num = set_num;
+        self
+    }
+
+    pub fn ex(mut self, ex_code: &str) -> Self {
+        self.0.ex_code = ex_code.to_string();
+        self
+    }
+
+    /// Marks the set as a user swap or ad-lib addition
+    pub fn adlib(mut self) -> Self {
+        self.0.adlib = 1;
+        self
+    }
+
+    pub fn reps(mut self, reps: u32) -> Self {
+        self.0.reps = Some(reps);
+        self
+    }
+
+    /// Makes this a timed set: sets the duration and clears the reps
+    pub fn time_sec(mut self, time_sec: u32) -> Self {
+        self.0.reps = None;
+        self.0.time_sec = Some(time_sec);
+        self
+    }
+
+    /// Load in kg
+    pub fn weight(mut self, weight: f64) -> Self {
+        self.0.weight = Some(weight);
+        self.0.unit = "kg".to_string();
+        self
+    }
+
+    /// Unloaded bodyweight set
+    pub fn bodyweight(mut self) -> Self {
+        self.0.weight = None;
+        self.0.unit = "bw".to_string();
+        self
+    }
+
+    pub fn rpe(mut self, rpe: f64) -> Self {
+        self.0.rpe = Some(rpe);
+        self
+    }
+
+    /// Device-reported PR tags, semicolon-separated
+    pub fn pr_types(mut self, pr_types: &str) -> Self {
+        self.0.pr_types = Some(pr_types.to_string());
+        self
+    }
+
+    pub fn build(self) -> SessionRecord {
+        self.0
+    }
+}
+
 /// CSV parser for session data
 pub struct SessionCsvParser {
     // Configuration could go here
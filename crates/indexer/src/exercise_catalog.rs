@@ -0,0 +1,411 @@
+use rusqlite::{Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum CatalogError {
+    #[error("Database error: {0}")]
+    Db(#[from] rusqlite::Error),
+    #[error("Invalid data: {0}")]
+    InvalidData(String),
+}
+
+/// Role of a body part in an exercise (`exercise_body_part.role`), strongest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MuscleRole {
+    Primary,
+    Secondary,
+    Stabilizer,
+    Mobility,
+    Stretch,
+}
+
+impl MuscleRole {
+    fn parse(role: &str) -> Result<Self, CatalogError> {
+        match role {
+            "primary" => Ok(MuscleRole::Primary),
+            "secondary" => Ok(MuscleRole::Secondary),
+            "stabilizer" => Ok(MuscleRole::Stabilizer),
+            "mobility" => Ok(MuscleRole::Mobility),
+            "stretch" => Ok(MuscleRole::Stretch),
+            other => Err(CatalogError::InvalidData(format!(
+                "Unknown role: {}",
+                other
+            ))),
+        }
+    }
+
+    /// Whether sets should be credited to muscles with this role
+    pub fn counts_toward_volume(&self) -> bool {
+        matches!(self, MuscleRole::Primary | MuscleRole::Secondary)
+    }
+}
+
+/// Coarse movement pattern used for grouping volume
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MovementPattern {
+    Squat,
+    Hinge,
+    Lunge,
+    HorizontalPush,
+    VerticalPush,
+    HorizontalPull,
+    VerticalPull,
+    Core,
+    Isolation,
+    Other,
+}
+
+impl MovementPattern {
+    /// Guess the pattern from the exercise code prefix (e.g. "SQ.BB.BACK" -> squat)
+    pub fn from_code(code: &str) -> Self {
+        let prefix = code.split('.').next().unwrap_or("").to_ascii_uppercase();
+        match prefix.as_str() {
+            "SQ" | "SQUAT" => MovementPattern::Squat,
+            "DL" | "HINGE" | "HE" | "RDL" | "GM" => MovementPattern::Hinge,
+            "LUNGE" | "STEPUP" | "SPLIT" => MovementPattern::Lunge,
+            "BP" | "BENCH" | "DIP" | "PUSHUP" | "FLY" => MovementPattern::HorizontalPush,
+            "OHP" => MovementPattern::VerticalPush,
+            "ROW" => MovementPattern::HorizontalPull,
+            "PULLDN" | "PULLUP" | "CHIN" | "LAT" => MovementPattern::VerticalPull,
+            "CORE" => MovementPattern::Core,
+            "CURL" | "TRI" | "TRIC" | "ARMS" | "RAISE" | "REAR" | "CALF" | "QUAD" | "HAM"
+            | "LEG" => MovementPattern::Isolation,
+            _ => MovementPattern::Other,
+        }
+    }
+
+    fn parse(name: &str) -> Option<Self> {
+        serde_json::from_value(serde_json::Value::String(name.to_ascii_lowercase())).ok()
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            MovementPattern::Squat => "Squat",
+            MovementPattern::Hinge => "Hinge",
+            MovementPattern::Lunge => "Lunge",
+            MovementPattern::HorizontalPush => "Horizontal Push",
+            MovementPattern::VerticalPush => "Vertical Push",
+            MovementPattern::HorizontalPull => "Horizontal Pull",
+            MovementPattern::VerticalPull => "Vertical Pull",
+            MovementPattern::Core => "Core",
+            MovementPattern::Isolation => "Isolation",
+            MovementPattern::Other => "Other",
+        }
+    }
+}
+
+impl std::fmt::Display for MovementPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// Contribution of one muscle group to an exercise
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MuscleContribution {
+    pub group_key: String, // body_part key with the side suffix removed, e.g. "triceps"
+    pub group_name: String,
+    pub role: MuscleRole,
+    pub intensity: u32, // 0-100 from exercise_body_part
+    /// Fraction of a set credited to this group (prime mover = 1.0)
+    pub weight: f64,
+}
+
+/// Muscle and pattern data for one exercise code
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExerciseProfile {
+    pub code: String,
+    pub name: String,
+    pub pattern: MovementPattern,
+    pub muscles: Vec<MuscleContribution>,
+}
+
+/// Exercise catalog backed by `exercises.db`
+#[derive(Debug, Clone, Default)]
+pub struct ExerciseCatalog {
+    exercises: HashMap<String, ExerciseProfile>,
+}
+
+/// Group used for exercises that are not in the catalog
+pub const UNMAPPED_GROUP: &str = "Unmapped";
+
+impl ExerciseCatalog {
+    /// Open the catalog read-only from an `exercises.db` file
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, CatalogError> {
+        let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        Self::from_connection(&conn)
+    }
+
+    /// Load the catalog from an open connection
+    pub fn from_connection(conn: &Connection) -> Result<Self, CatalogError> {
+        let mut exercises = HashMap::new();
+
+        let mut stmt = conn.prepare("SELECT id, code, name, metadata_json FROM exercise")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })?;
+
+        let mut codes_by_id = HashMap::new();
+        for row in rows {
+            let (id, code, name, metadata_json) = row?;
+            let pattern = metadata_json
+                .as_deref()
+                .and_then(|json| serde_json::from_str::<serde_json::Value>(json).ok())
+                .and_then(|meta| {
+                    meta.get("movement_pattern")
+                        .and_then(|p| p.as_str())
+                        .and_then(MovementPattern::parse)
+                })
+                .unwrap_or_else(|| MovementPattern::from_code(&code));
+
+            codes_by_id.insert(id, code.clone());
+            exercises.insert(
+                code.clone(),
+                ExerciseProfile {
+                    code,
+                    name,
+                    pattern,
+                    muscles: Vec::new(),
+                },
+            );
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT ebp.exercise_id, bp.key, bp.name, ebp.role, ebp.intensity
+             FROM exercise_body_part ebp
+             JOIN body_part bp ON bp.id = ebp.body_part_id",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, u32>(4)?,
+            ))
+        })?;
+
+        for row in rows {
+            let (exercise_id, key, name, role, intensity) = row?;
+            let Some(profile) = codes_by_id
+                .get(&exercise_id)
+                .and_then(|code| exercises.get_mut(code))
+            else {
+                continue;
+            };
+
+            let role = MuscleRole::parse(&role)?;
+            let group_key = strip_side_suffix(&key, &["_l", "_r"]);
+            let group_name = strip_side_suffix(&name, &[" (L)", " (R)"]);
+
+            // Left/right rows, and rows listing the same muscle under several roles,
+            // collapse into one group with the strongest role and intensity
+            match profile
+                .muscles
+                .iter_mut()
+                .find(|m| m.group_key == group_key)
+            {
+                Some(existing) => {
+                    existing.role = existing.role.min(role);
+                    existing.intensity = existing.intensity.max(intensity);
+                }
+                None => profile.muscles.push(MuscleContribution {
+                    group_key,
+                    group_name,
+                    role,
+                    intensity,
+                    weight: 0.0,
+                }),
+            }
+        }
+
+        for profile in exercises.values_mut() {
+            normalize_weights(&mut profile.muscles);
+        }
+
+        Ok(Self { exercises })
+    }
+
+    pub fn get(&self, code: &str) -> Option<&ExerciseProfile> {
+        self.exercises.get(code)
+    }
+
+    pub fn len(&self) -> usize {
+        self.exercises.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.exercises.is_empty()
+    }
+
+    /// Muscle groups credited for one set of `code`, as (group name, fraction of a set)
+    ///
+    /// Exercises missing from the catalog (or without attributed muscles) are credited
+    /// in full to `UNMAPPED_GROUP`.
+    pub fn muscle_weights(&self, code: &str) -> Vec<(String, f64)> {
+        let weights: Vec<(String, f64)> = self
+            .get(code)
+            .map(|profile| {
+                profile
+                    .muscles
+                    .iter()
+                    .filter(|m| m.role.counts_toward_volume() && m.weight > 0.0)
+                    .map(|m| (m.group_name.clone(), m.weight))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if weights.is_empty() {
+            vec![(UNMAPPED_GROUP.to_string(), 1.0)]
+        } else {
+            weights
+        }
+    }
+
+    /// Movement pattern for `code`, falling back to the code prefix for unknown exercises
+    pub fn movement_pattern(&self, code: &str) -> MovementPattern {
+        self.get(code)
+            .map(|profile| profile.pattern)
+            .unwrap_or_else(|| MovementPattern::from_code(code))
+    }
+
+    /// Exercise code -> prime mover group name, for single-category charts
+    pub fn primary_body_part_map(&self) -> HashMap<String, String> {
+        self.exercises
+            .values()
+            .filter_map(|profile| {
+                profile
+                    .muscles
+                    .iter()
+                    .filter(|m| m.role.counts_toward_volume())
+                    .max_by_key(|m| m.intensity)
+                    .map(|m| (profile.code.clone(), m.group_name.clone()))
+            })
+            .collect()
+    }
+}
+
+fn strip_side_suffix(value: &str, suffixes: &[&str]) -> String {
+    suffixes
+        .iter()
+        .find_map(|suffix| value.strip_suffix(suffix))
+        .unwrap_or(value)
+        .to_string()
+}
+
+/// Scale attributed intensities so the strongest contributor counts as a full set
+fn normalize_weights(muscles: &mut [MuscleContribution]) {
+    let max_intensity = muscles
+        .iter()
+        .filter(|m| m.role.counts_toward_volume())
+        .map(|m| m.intensity)
+        .max()
+        .unwrap_or(0);
+
+    for muscle in muscles.iter_mut() {
+        muscle.weight = if muscle.role.counts_toward_volume() && max_intensity > 0 {
+            muscle.intensity as f64 / max_intensity as f64
+        } else {
+            0.0
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_catalog() -> ExerciseCatalog {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE exercise (id INTEGER PRIMARY KEY, code TEXT, name TEXT, metadata_json TEXT);
+             CREATE TABLE body_part (id INTEGER PRIMARY KEY, key TEXT, name TEXT);
+             CREATE TABLE exercise_body_part (exercise_id INTEGER, body_part_id INTEGER, role TEXT, intensity INTEGER);
+             INSERT INTO exercise VALUES (1, 'BP.BB.FLAT', 'Bench Press', NULL);
+             INSERT INTO exercise VALUES (2, 'SSB.GM', 'Good Morning', '{\"movement_pattern\":\"hinge\"}');
+             INSERT INTO exercise VALUES (3, 'DIP.BW', 'Dip', NULL);
+             INSERT INTO body_part VALUES (9, 'triceps_l', 'Triceps (L)');
+             INSERT INTO body_part VALUES (10, 'triceps_r', 'Triceps (R)');
+             INSERT INTO body_part VALUES (13, 'pecs', 'Pectorals');
+             INSERT INTO body_part VALUES (19, 'abs', 'Abdominals');
+             INSERT INTO exercise_body_part VALUES (1, 13, 'primary', 80);
+             INSERT INTO exercise_body_part VALUES (1, 9, 'secondary', 40);
+             INSERT INTO exercise_body_part VALUES (1, 10, 'secondary', 40);
+             INSERT INTO exercise_body_part VALUES (1, 19, 'stabilizer', 20);
+             INSERT INTO exercise_body_part VALUES (3, 13, 'secondary', 60);
+             INSERT INTO exercise_body_part VALUES (3, 9, 'primary', 70);
+             INSERT INTO exercise_body_part VALUES (3, 10, 'secondary', 90);",
+        )
+        .unwrap();
+        ExerciseCatalog::from_connection(&conn).unwrap()
+    }
+
+    #[test]
+    fn test_weighted_muscle_attribution() {
+        let catalog = test_catalog();
+        let mut weights = catalog.muscle_weights("BP.BB.FLAT");
+        weights.sort_by(|a, b| a.0.cmp(&b.0));
+
+        // Sides merge, stabilizers are not credited, prime mover gets a full set
+        assert_eq!(
+            weights,
+            vec![("Pectorals".to_string(), 1.0), ("Triceps".to_string(), 0.5)]
+        );
+        assert_eq!(
+            catalog.primary_body_part_map().get("BP.BB.FLAT"),
+            Some(&"Pectorals".to_string())
+        );
+
+        // Triceps listed as both primary and secondary are credited once, as primary
+        let dip = catalog.get("DIP.BW").unwrap();
+        let triceps: Vec<_> = dip
+            .muscles
+            .iter()
+            .filter(|m| m.group_key == "triceps")
+            .collect();
+        assert_eq!(triceps.len(), 1);
+        assert_eq!(
+            (triceps[0].role, triceps[0].intensity),
+            (MuscleRole::Primary, 90)
+        );
+        let mut weights = catalog.muscle_weights("DIP.BW");
+        weights.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            weights,
+            vec![
+                ("Pectorals".to_string(), 60.0 / 90.0),
+                ("Triceps".to_string(), 1.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_exercise_and_patterns() {
+        let catalog = test_catalog();
+
+        assert_eq!(
+            catalog.muscle_weights("SQ.BB.BACK"),
+            vec![(UNMAPPED_GROUP.to_string(), 1.0)]
+        );
+        assert_eq!(
+            catalog.movement_pattern("BP.BB.FLAT"),
+            MovementPattern::HorizontalPush
+        );
+        // metadata_json overrides the code heuristic
+        assert_eq!(catalog.movement_pattern("SSB.GM"), MovementPattern::Hinge);
+        assert_eq!(
+            catalog.movement_pattern("SQ.BB.BACK"),
+            MovementPattern::Squat
+        );
+    }
+}
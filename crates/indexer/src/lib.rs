@@ -1,7 +1,19 @@
+pub mod adherence;
 pub mod cache;
+pub mod charts;
 pub mod csv_parser;
+pub mod exercise_catalog;
 pub mod metrics;
+pub mod plan_projection;
+pub mod progression;
+pub mod training_load;
 
+pub use adherence::*;
 pub use cache::*;
+pub use charts::*;
 pub use csv_parser::*;
+pub use exercise_catalog::*;
 pub use metrics::*;
+pub use plan_projection::*;
+pub use progression::*;
+pub use training_load::*;
@@ -1,14 +1,18 @@
 use clap::{Parser, Subcommand};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use weightlifting_core::AppPaths;
 
 pub mod cache;
 pub mod csv_parser;
+pub mod exercise_catalog;
 pub mod metrics;
+pub mod training_load;
 
-use cache::MetricsCache;
+use cache::{DerivedMetrics, MetricsCache, MetricsQuery};
 use csv_parser::SessionCsvParser;
-use metrics::{E1RMCalculator, PRTracker, VolumeCalculator};
+use exercise_catalog::ExerciseCatalog;
+use metrics::{E1RMCalculator, PRTracker, VolumeCalculator, VolumeGrouping};
+use training_load::TrainingLoadCalculator;
 
 /// Weightlifting data indexer for generating cached metrics from session CSV data
 #[derive(Parser)]
@@ -30,6 +34,9 @@ enum Commands {
         /// Force re-processing even if cache is up to date
         #[arg(long)]
         force: bool,
+        /// Exercise catalog for muscle-group volume (defaults to exercises.db in the data dir)
+        #[arg(long)]
+        exercise_db: Option<PathBuf>,
     },
     /// Clear all cached metrics
     Clear,
@@ -49,11 +56,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     };
 
-    let cache = MetricsCache::new(&paths);
+    let mut cache = MetricsCache::new(&paths)?;
 
     match cli.command {
-        Commands::Process { input, force } => {
-            process_csv_data(&input, &cache, force).await?;
+        Commands::Process {
+            input,
+            force,
+            exercise_db,
+        } => {
+            let exercise_db = exercise_db.unwrap_or_else(|| paths.exercise_db_path());
+            process_csv_data(&input, &mut cache, &exercise_db, force).await?;
         }
         Commands::Clear => {
             cache.clear_all()?;
@@ -69,7 +81,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
 async fn process_csv_data(
     input: &PathBuf,
-    cache: &MetricsCache,
+    cache: &mut MetricsCache,
+    exercise_db: &Path,
     _force: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
     println!("Processing CSV data from: {}", input.display());
@@ -78,23 +91,53 @@ async fn process_csv_data(
     let sessions = parser.parse_csv_file(input)?;
 
     println!("Parsed {} session records", sessions.len());
+    // Key raw sets by canonical path so re-processing the same file replaces its rows
+    let source = input.canonicalize().unwrap_or_else(|_| input.clone());
+    cache.ingest_sets(&source.display().to_string(), &sessions)?;
+
+    // Recompute derived metrics from every ingested source, not just this file, since
+    // each table is replaced wholesale
+    let all_sets = cache.query_sets(&MetricsQuery::new())?;
+    println!("Recomputing metrics from {} stored sets", all_sets.len());
 
-    // Calculate metrics
     let e1rm_calc = E1RMCalculator::new();
     let volume_calc = VolumeCalculator::new();
     let pr_tracker = PRTracker::new();
+    let load_calc = TrainingLoadCalculator::new();
+
+    let mut derived = DerivedMetrics {
+        e1rm: e1rm_calc.calculate_historical_e1rms(&all_sets)?,
+        volume: volume_calc.calculate_weekly_volumes(&all_sets)?,
+        group_volume: Vec::new(),
+        daily_load: load_calc.calculate_daily_loads(&all_sets)?,
+        weekly_load: load_calc.calculate_weekly_loads(&all_sets)?,
+        prs: pr_tracker.identify_prs(&all_sets)?,
+    };
 
-    // Process E1RM data
-    let e1rm_data = e1rm_calc.calculate_historical_e1rms(&sessions)?;
-    cache.store_e1rm_data(&e1rm_data)?;
-
-    // Process volume data
-    let volume_data = volume_calc.calculate_weekly_volumes(&sessions)?;
-    cache.store_volume_data(&volume_data)?;
+    // Muscle-group and movement-pattern volume need the catalog; without it the table is
+    // cleared rather than left holding an earlier run's rows
+    if exercise_db.exists() {
+        let catalog = ExerciseCatalog::open(exercise_db)?;
+        derived.group_volume = volume_calc.calculate_weekly_group_volumes(
+            &all_sets,
+            &catalog,
+            VolumeGrouping::MuscleGroup,
+        )?;
+        derived
+            .group_volume
+            .extend(volume_calc.calculate_weekly_group_volumes(
+                &all_sets,
+                &catalog,
+                VolumeGrouping::MovementPattern,
+            )?);
+    } else {
+        println!(
+            "Exercise catalog not found at {} - clearing muscle-group volume",
+            exercise_db.display()
+        );
+    }
 
-    // Process PR data
-    let pr_data = pr_tracker.identify_prs(&sessions)?;
-    cache.store_pr_data(&pr_data)?;
+    cache.store_derived(&derived)?;
 
     println!("Metrics processing completed and cached");
     Ok(())
@@ -105,13 +148,24 @@ fn show_cache_status(cache: &MetricsCache) -> Result<(), Box<dyn std::error::Err
 
     println!("Cache Status:");
     println!("  Location: {}", cache.cache_dir().display());
+    println!("  Schema version: {}", status.schema_version);
+    println!("  Set entries: {}", status.set_entries);
     println!("  E1RM entries: {}", status.e1rm_entries);
     println!("  Volume entries: {}", status.volume_entries);
+    println!("  Group volume entries: {}", status.group_volume_entries);
+    println!("  Daily load entries: {}", status.daily_load_entries);
+    println!("  Weekly load entries: {}", status.weekly_load_entries);
     println!("  PR entries: {}", status.pr_entries);
     println!(
         "  Last updated: {}",
         status.last_updated.unwrap_or("Never".to_string())
     );
+    for source in &status.sources {
+        println!(
+            "  Source {}: {} rows, ingested {}",
+            source.source, source.rows, source.ingested_at
+        );
+    }
 
     Ok(())
 }
@@ -1,8 +1,10 @@
 use crate::csv_parser::SessionRecord;
+use crate::exercise_catalog::ExerciseCatalog;
 use chrono::{Datelike, Duration, NaiveDate};
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use thiserror::Error;
+use weightlifting_core::{rpe_adjustment, TrainingMaxes};
 
 #[derive(Error, Debug)]
 pub enum MetricsError {
@@ -35,15 +37,114 @@ pub struct VolumeDataPoint {
     pub session_count: u32,
 }
 
+/// Grouping used for catalog-based volume aggregation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VolumeGrouping {
+    MuscleGroup,
+    MovementPattern,
+}
+
+/// Weekly volume credited to a muscle group or movement pattern
+///
+/// Sets are fractional: a bench press set credits one set to the pecs and part of a set
+/// to the triceps, depending on the catalog intensities.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupVolumeDataPoint {
+    pub grouping: VolumeGrouping,
+    pub group: String,
+    pub week_start: NaiveDate,
+    pub weighted_sets: f64,
+    pub weighted_reps: f64,
+    pub weighted_tonnage_kg: f64,
+    pub exercises: Vec<String>,
+}
+
 /// Personal record data point
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PRDataPoint {
     pub exercise: String,
-    pub pr_type: String, // "1RM", "5RM", "volume", etc.
+    pub pr_type: PRKind,
     pub date: NaiveDate,
-    pub value: f64, // weight in kg or total volume
+    pub value: f64, // kg for load-based kinds, seconds for holds
     pub reps: Option<u32>,
     pub notes: Option<String>,
+    #[serde(default)]
+    pub source: PRSource,
+}
+
+/// Kind of personal record
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PRKind {
+    /// Heaviest load lifted for at least `reps` reps
+    RepMax { reps: u32 },
+    /// Best estimated 1RM
+    E1RM,
+    /// Best single-session tonnage for an exercise
+    SessionVolume,
+    /// Longest timed set
+    LongestHold,
+}
+
+impl PRKind {
+    /// Parse a device-reported PR tag ("1RM", "5rm", "e1rm", "volume", "time", ...)
+    pub fn parse_device_tag(tag: &str) -> Option<PRKind> {
+        let tag = tag.trim().to_ascii_lowercase();
+        match tag.as_str() {
+            "" => None,
+            "e1rm" | "est_1rm" => Some(PRKind::E1RM),
+            "volume" | "session_volume" | "tonnage" => Some(PRKind::SessionVolume),
+            "time" | "hold" | "longest_hold" | "duration" => Some(PRKind::LongestHold),
+            _ => tag
+                .strip_suffix("rm")
+                .and_then(|n| n.parse::<u32>().ok())
+                .filter(|&reps| reps > 0)
+                .map(|reps| PRKind::RepMax { reps }),
+        }
+    }
+
+    /// Unit of `PRDataPoint::value` for this kind
+    pub fn value_unit(&self) -> &'static str {
+        match self {
+            PRKind::LongestHold => "s",
+            _ => "kg",
+        }
+    }
+}
+
+impl std::fmt::Display for PRKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PRKind::RepMax { reps } => write!(f, "{}RM", reps),
+            PRKind::E1RM => write!(f, "e1RM"),
+            PRKind::SessionVolume => write!(f, "Session Volume"),
+            PRKind::LongestHold => write!(f, "Longest Hold"),
+        }
+    }
+}
+
+/// Where a personal record was detected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PRSource {
+    /// Detected by the indexer only
+    #[default]
+    Computed,
+    /// Reported by the device only (e.g. history the indexer has not seen)
+    Device,
+    /// Detected by the indexer and confirmed by the device
+    Both,
+}
+
+/// Entry in the rep-max table: heaviest load lifted for at least N reps
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepMaxEntry {
+    pub reps: u32,
+    pub weight_kg: f64,
+    pub date: NaiveDate,
+    /// Reps actually performed in the source set (>= `reps`)
+    pub source_reps: u32,
 }
 
 /// Calculator for estimated 1RM values
@@ -78,11 +179,7 @@ impl E1RMCalculator {
             let weight_kg = self.convert_to_kg(record.weight.unwrap_or(0.0), &record.unit)?;
 
             // Use Epley formula as default, with RPE adjustment if available
-            let e1rm = if let Some(rpe) = record.rpe {
-                self.calculate_e1rm_with_rpe(weight_kg, reps, rpe)
-            } else {
-                self.calculate_epley_e1rm(weight_kg, reps)
-            };
+            let e1rm = self.estimate_kg(weight_kg, reps, record.rpe);
 
             e1rm_data.push(E1RMDataPoint {
                 exercise: record.ex_code.clone(),
@@ -102,6 +199,28 @@ impl E1RMCalculator {
         Ok(e1rm_data)
     }
 
+    /// Training maxes for load calculation: the best e1RM per exercise on or after `since`
+    pub fn training_maxes(data: &[E1RMDataPoint], since: Option<NaiveDate>) -> TrainingMaxes {
+        let mut maxes = TrainingMaxes::new();
+        for point in data
+            .iter()
+            .filter(|p| since.is_none_or(|since| p.date >= since))
+        {
+            let best = maxes.entry(point.exercise.clone()).or_insert(point.e1rm_kg);
+            *best = best.max(point.e1rm_kg);
+        }
+        maxes
+    }
+
+    /// Estimate 1RM for a set already converted to kg, using the same formula as
+    /// `calculate_historical_e1rms`
+    pub fn estimate_kg(&self, weight_kg: f64, reps: u32, rpe: Option<f64>) -> f64 {
+        match rpe {
+            Some(rpe) => self.calculate_e1rm_with_rpe(weight_kg, reps, rpe),
+            None => self.calculate_epley_e1rm(weight_kg, reps),
+        }
+    }
+
     /// Epley formula: 1RM = weight * (1 + reps/30)
     fn calculate_epley_e1rm(&self, weight: f64, reps: u32) -> f64 {
         weight * (1.0 + (reps as f64 / 30.0))
@@ -109,19 +228,10 @@ impl E1RMCalculator {
 
     /// Enhanced Epley with RPE adjustment
     fn calculate_e1rm_with_rpe(&self, weight: f64, reps: u32, rpe: f64) -> f64 {
-        // RPE adjustment: higher RPE means closer to true max
-        // This is a simplified model - could be enhanced with lookup tables
-        let rpe_adjustment = match rpe {
-            r if r >= 9.5 => 1.0,
-            r if r >= 9.0 => 0.97,
-            r if r >= 8.5 => 0.94,
-            r if r >= 8.0 => 0.91,
-            r if r >= 7.5 => 0.88,
-            _ => 0.85,
-        };
-
+        // RPE adjustment: higher RPE means closer to true max. Shared with core so
+        // planned loads estimated from RPE targets invert these e1RMs exactly.
         let base_e1rm = self.calculate_epley_e1rm(weight, reps);
-        base_e1rm / rpe_adjustment
+        base_e1rm / rpe_adjustment(rpe)
     }
 
     /// Convert weight to kg based on unit
@@ -219,6 +329,59 @@ impl VolumeCalculator {
         Ok(volume_data)
     }
 
+    /// Calculate weekly volume per muscle group or movement pattern using the exercise catalog
+    pub fn calculate_weekly_group_volumes(
+        &self,
+        sessions: &[SessionRecord],
+        catalog: &ExerciseCatalog,
+        grouping: VolumeGrouping,
+    ) -> Result<Vec<GroupVolumeDataPoint>, MetricsError> {
+        let mut weekly: BTreeMap<(String, NaiveDate), GroupVolumeAggregator> = BTreeMap::new();
+
+        for record in sessions {
+            if !record.is_working_set() {
+                continue;
+            }
+
+            let reps = record.reps.unwrap_or(0) as f64;
+            let tonnage = match (record.reps, record.weight) {
+                (Some(reps), Some(weight)) => {
+                    self.convert_to_kg(weight, &record.unit)? * reps as f64
+                }
+                _ => 0.0,
+            };
+
+            let credits = match grouping {
+                VolumeGrouping::MuscleGroup => catalog.muscle_weights(&record.ex_code),
+                VolumeGrouping::MovementPattern => {
+                    vec![(catalog.movement_pattern(&record.ex_code).to_string(), 1.0)]
+                }
+            };
+
+            let week_start = self.get_week_start(record.date);
+            for (group, weight) in credits {
+                let aggregator = weekly.entry((group, week_start)).or_default();
+                aggregator.weighted_sets += weight;
+                aggregator.weighted_reps += weight * reps;
+                aggregator.weighted_tonnage_kg += weight * tonnage;
+                aggregator.exercises.insert(record.ex_code.clone());
+            }
+        }
+
+        Ok(weekly
+            .into_iter()
+            .map(|((group, week_start), aggregator)| GroupVolumeDataPoint {
+                grouping,
+                group,
+                week_start,
+                weighted_sets: aggregator.weighted_sets,
+                weighted_reps: aggregator.weighted_reps,
+                weighted_tonnage_kg: aggregator.weighted_tonnage_kg,
+                exercises: aggregator.exercises.into_iter().collect(),
+            })
+            .collect())
+    }
+
     /// Get the Monday of the week for a given date
     fn get_week_start(&self, date: NaiveDate) -> NaiveDate {
         let days_from_monday = date.weekday().num_days_from_monday() as i64;
@@ -253,9 +416,18 @@ impl VolumeAggregator {
     }
 }
 
+/// Helper struct for aggregating weighted group volume
+#[derive(Default)]
+struct GroupVolumeAggregator {
+    weighted_sets: f64,
+    weighted_reps: f64,
+    weighted_tonnage_kg: f64,
+    exercises: BTreeSet<String>,
+}
+
 /// Personal record tracker
 pub struct PRTracker {
-    // Could add configuration for PR types to track
+    e1rm_calc: E1RMCalculator,
 }
 
 impl Default for PRTracker {
@@ -266,20 +438,45 @@ impl Default for PRTracker {
 
 impl PRTracker {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            e1rm_calc: E1RMCalculator::new(),
+        }
     }
 
     /// Identify personal records from session data
+    ///
+    /// Sets are replayed in chronological order and a PR is emitted whenever a set beats
+    /// the true N-rep max for its rep count, the best e1RM, the best single-session volume
+    /// or the longest hold for its exercise. Device-reported `pr_types` are reconciled
+    /// against the computed records afterwards.
     pub fn identify_prs(
         &self,
         sessions: &[SessionRecord],
     ) -> Result<Vec<PRDataPoint>, MetricsError> {
+        let ordered = Self::chronological(sessions);
         let mut pr_data = Vec::new();
 
-        // Group by exercise and track max weights for different rep ranges
-        let mut exercise_maxes: HashMap<String, HashMap<u32, (f64, NaiveDate)>> = HashMap::new();
+        // Heaviest load per exact rep count, per exercise
+        let mut exact_maxes: HashMap<String, BTreeMap<u32, f64>> = HashMap::new();
+        let mut best_e1rm: HashMap<String, f64> = HashMap::new();
+        let mut best_hold: HashMap<String, u32> = HashMap::new();
+
+        for record in &ordered {
+            if record.is_working_set() {
+                if let Some(time_sec) = record.time_sec {
+                    let best = best_hold.entry(record.ex_code.clone()).or_insert(0);
+                    if time_sec > *best {
+                        *best = time_sec;
+                        pr_data.push(Self::pr_point(
+                            record,
+                            PRKind::LongestHold,
+                            time_sec as f64,
+                            None,
+                        ));
+                    }
+                }
+            }
 
-        for record in sessions {
             if !record.can_calculate_e1rm() {
                 continue;
             }
@@ -287,38 +484,227 @@ impl PRTracker {
             let reps = record.reps.unwrap();
             let weight_kg = self.convert_to_kg(record.weight.unwrap_or(0.0), &record.unit)?;
 
-            let exercise_entry = exercise_maxes.entry(record.ex_code.clone()).or_default();
+            // True N-rep max: heaviest load for this many reps *or more*
+            let exercise_maxes = exact_maxes.entry(record.ex_code.clone()).or_default();
+            let current_rm = exercise_maxes
+                .range(reps..)
+                .map(|(_, weight)| *weight)
+                .reduce(f64::max);
+
+            if current_rm.is_none_or(|max| weight_kg > max) {
+                pr_data.push(Self::pr_point(
+                    record,
+                    PRKind::RepMax { reps },
+                    weight_kg,
+                    Some(reps),
+                ));
+            }
+
+            let exact = exercise_maxes.entry(reps).or_insert(weight_kg);
+            *exact = exact.max(weight_kg);
+
+            let e1rm = self.e1rm_calc.estimate_kg(weight_kg, reps, record.rpe);
+            if best_e1rm
+                .get(&record.ex_code)
+                .is_none_or(|best| e1rm > *best)
+            {
+                best_e1rm.insert(record.ex_code.clone(), e1rm);
+                pr_data.push(Self::pr_point(record, PRKind::E1RM, e1rm, Some(reps)));
+            }
+        }
+
+        pr_data.extend(self.session_volume_prs(&ordered)?);
+        self.reconcile_device_prs(&ordered, &mut pr_data)?;
+
+        pr_data.sort_by(|a, b| {
+            a.date
+                .cmp(&b.date)
+                .then_with(|| a.exercise.cmp(&b.exercise))
+                .then_with(|| a.pr_type.cmp(&b.pr_type))
+        });
+
+        Ok(pr_data)
+    }
+
+    /// Build the current rep-max table per exercise
+    ///
+    /// Entry N holds the heaviest load lifted for at least N reps, so a 100kg x 5 set also
+    /// counts towards the 1-4RM when nothing heavier was done for fewer reps.
+    pub fn rep_max_table(
+        &self,
+        sessions: &[SessionRecord],
+    ) -> Result<BTreeMap<String, Vec<RepMaxEntry>>, MetricsError> {
+        let mut exact: BTreeMap<String, BTreeMap<u32, (f64, NaiveDate)>> = BTreeMap::new();
+
+        for record in Self::chronological(sessions) {
+            if !record.can_calculate_e1rm() {
+                continue;
+            }
+
+            let reps = record.reps.unwrap();
+            let weight_kg = self.convert_to_kg(record.weight.unwrap_or(0.0), &record.unit)?;
+            let entry = exact
+                .entry(record.ex_code.clone())
+                .or_default()
+                .entry(reps)
+                .or_insert((weight_kg, record.date));
+            if weight_kg > entry.0 {
+                *entry = (weight_kg, record.date);
+            }
+        }
+
+        let mut table = BTreeMap::new();
+        for (exercise, by_reps) in exact {
+            let max_reps = by_reps.keys().next_back().copied().unwrap_or(0);
+            let mut entries = Vec::new();
+            let mut best: Option<(f64, NaiveDate, u32)> = None;
+
+            // Walk from the highest rep count down, carrying the heaviest load seen so far
+            for reps in (1..=max_reps).rev() {
+                if let Some(&(weight, date)) = by_reps.get(&reps) {
+                    if best.is_none_or(|(w, _, _)| weight > w) {
+                        best = Some((weight, date, reps));
+                    }
+                }
+                if let Some((weight_kg, date, source_reps)) = best {
+                    entries.push(RepMaxEntry {
+                        reps,
+                        weight_kg,
+                        date,
+                        source_reps,
+                    });
+                }
+            }
+
+            entries.reverse();
+            table.insert(exercise, entries);
+        }
+
+        Ok(table)
+    }
+
+    /// Best single-session tonnage per exercise
+    fn session_volume_prs(
+        &self,
+        ordered: &[&SessionRecord],
+    ) -> Result<Vec<PRDataPoint>, MetricsError> {
+        // (exercise, session) -> (date, tonnage), kept in first-seen order
+        let mut sessions: Vec<((String, String), NaiveDate, f64)> = Vec::new();
+        let mut index: HashMap<(String, String), usize> = HashMap::new();
 
-            // Check if this is a new PR for this rep count
-            let is_pr = match exercise_entry.get(&reps) {
-                Some((current_max, _)) => weight_kg > *current_max,
-                None => true, // First time doing this rep count
+        for record in ordered {
+            if !record.is_working_set() {
+                continue;
+            }
+            let (Some(reps), Some(weight)) = (record.reps, record.weight) else {
+                continue;
             };
+            let tonnage = self.convert_to_kg(weight, &record.unit)? * reps as f64;
+            let key = (record.ex_code.clone(), record.session_id.clone());
+
+            match index.get(&key) {
+                Some(&i) => sessions[i].2 += tonnage,
+                None => {
+                    index.insert(key.clone(), sessions.len());
+                    sessions.push((key, record.date, tonnage));
+                }
+            }
+        }
 
-            if is_pr {
-                exercise_entry.insert(reps, (weight_kg, record.date));
+        let mut best: HashMap<String, f64> = HashMap::new();
+        let mut prs = Vec::new();
+        for ((exercise, _), date, tonnage) in sessions {
+            if tonnage <= 0.0 {
+                continue;
+            }
+            let current = best.entry(exercise.clone()).or_insert(0.0);
+            if tonnage > *current {
+                *current = tonnage;
+                prs.push(PRDataPoint {
+                    exercise,
+                    pr_type: PRKind::SessionVolume,
+                    date,
+                    value: tonnage,
+                    reps: None,
+                    notes: None,
+                    source: PRSource::Computed,
+                });
+            }
+        }
 
-                // Determine PR type
-                let pr_type = match reps {
-                    1 => "1RM".to_string(),
-                    2..=3 => format!("{}RM", reps),
-                    4..=6 => "Strength".to_string(),
-                    7..=12 => "Volume".to_string(),
-                    _ => "Endurance".to_string(),
+        Ok(prs)
+    }
+
+    /// Merge device-reported `pr_types` into the computed PR list
+    ///
+    /// A computed PR on the same exercise, kind and date is marked as confirmed; a device
+    /// tag with no computed counterpart is kept with `PRSource::Device`.
+    fn reconcile_device_prs(
+        &self,
+        ordered: &[&SessionRecord],
+        pr_data: &mut Vec<PRDataPoint>,
+    ) -> Result<(), MetricsError> {
+        for record in ordered {
+            for tag in record.get_pr_types() {
+                let Some(kind) = PRKind::parse_device_tag(&tag) else {
+                    continue;
                 };
 
-                pr_data.push(PRDataPoint {
-                    exercise: record.ex_code.clone(),
-                    pr_type,
-                    date: record.date,
-                    value: weight_kg,
-                    reps: Some(reps),
-                    notes: record.notes.clone(),
-                });
+                if let Some(existing) = pr_data.iter_mut().find(|pr| {
+                    pr.exercise == record.ex_code && pr.pr_type == kind && pr.date == record.date
+                }) {
+                    existing.source = PRSource::Both;
+                    continue;
+                }
+
+                let weight_kg = match record.weight {
+                    Some(weight) => Some(self.convert_to_kg(weight, &record.unit)?),
+                    None => None,
+                };
+                let value = match kind {
+                    PRKind::RepMax { .. } => weight_kg,
+                    PRKind::E1RM => weight_kg
+                        .zip(record.reps)
+                        .map(|(w, reps)| self.e1rm_calc.estimate_kg(w, reps, record.rpe)),
+                    PRKind::SessionVolume => {
+                        weight_kg.zip(record.reps).map(|(w, reps)| w * reps as f64)
+                    }
+                    PRKind::LongestHold => record.time_sec.map(|t| t as f64),
+                };
+
+                if let Some(value) = value {
+                    let mut point = Self::pr_point(record, kind, value, record.reps);
+                    point.source = PRSource::Device;
+                    pr_data.push(point);
+                }
             }
         }
 
-        Ok(pr_data)
+        Ok(())
+    }
+
+    /// Sort records by date and time without cloning them
+    fn chronological(sessions: &[SessionRecord]) -> Vec<&SessionRecord> {
+        let mut ordered: Vec<&SessionRecord> = sessions.iter().collect();
+        ordered.sort_by_key(|r| (r.date, r.time));
+        ordered
+    }
+
+    fn pr_point(
+        record: &SessionRecord,
+        kind: PRKind,
+        value: f64,
+        reps: Option<u32>,
+    ) -> PRDataPoint {
+        PRDataPoint {
+            exercise: record.ex_code.clone(),
+            pr_type: kind,
+            date: record.date,
+            value,
+            reps,
+            notes: record.notes.clone(),
+            source: PRSource::Computed,
+        }
     }
 
     /// Convert weight to kg (reused from other calculators)
@@ -337,6 +723,10 @@ mod tests {
     use super::*;
     use chrono::NaiveDate;
 
+    fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
     #[test]
     fn test_epley_calculation() {
         let calc = E1RMCalculator::new();
@@ -346,6 +736,40 @@ mod tests {
         assert!((e1rm - 116.67).abs() < 0.1);
     }
 
+    #[test]
+    fn test_training_maxes_round_trip_rpe_estimates() {
+        let calc = E1RMCalculator::new();
+        let record = SessionRecord::builder()
+            .session("s1")
+            .date(ymd(2024, 3, 4))
+            .ex("SQ.BB.BACK")
+            .reps(5)
+            .weight(140.0)
+            .rpe(8.0)
+            .build();
+        let older = SessionRecord::builder()
+            .session("s0")
+            .date(ymd(2024, 1, 8))
+            .ex("SQ.BB.BACK")
+            .reps(1)
+            .weight(190.0)
+            .rpe(10.0)
+            .build();
+        let data = calc.calculate_historical_e1rms(&[older, record]).unwrap();
+
+        let since = NaiveDate::from_ymd_opt(2024, 2, 1);
+        let maxes = E1RMCalculator::training_maxes(&data, since);
+        assert_eq!(maxes.len(), 1);
+        assert_eq!(
+            E1RMCalculator::training_maxes(&data, None)["SQ.BB.BACK"],
+            data[0].e1rm_kg
+        );
+
+        // Planning 5 reps at RPE 8 from that max gives back the logged load
+        let planned = maxes["SQ.BB.BACK"] * weightlifting_core::estimated_pct_1rm(5, Some(8.0));
+        assert!((planned - 140.0).abs() < 1e-9);
+    }
+
     #[test]
     fn test_week_start_calculation() {
         let calc = VolumeCalculator::new();
@@ -359,4 +783,223 @@ mod tests {
         let monday = calc.get_week_start(friday);
         assert_eq!(monday, NaiveDate::from_ymd_opt(2025, 8, 18).unwrap()); // Same Monday
     }
+
+    #[test]
+    fn test_rep_max_pr_uses_true_n_rep_max() {
+        let tracker = PRTracker::new();
+        let sessions = vec![
+            SessionRecord::builder()
+                .session("s1")
+                .date(ymd(2025, 8, 4))
+                .ex("SQ.BB.BACK")
+                .reps(5)
+                .weight(100.0)
+                .build(),
+            // 95x3 is lighter than the 100x5 already done, so it is not a 3RM
+            SessionRecord::builder()
+                .session("s2")
+                .date(ymd(2025, 8, 6))
+                .ex("SQ.BB.BACK")
+                .reps(3)
+                .weight(95.0)
+                .build(),
+            SessionRecord::builder()
+                .session("s3")
+                .date(ymd(2025, 8, 8))
+                .ex("SQ.BB.BACK")
+                .reps(3)
+                .weight(105.0)
+                .build(),
+        ];
+
+        let prs = tracker.identify_prs(&sessions).unwrap();
+        let rep_maxes: Vec<_> = prs
+            .iter()
+            .filter(|pr| matches!(pr.pr_type, PRKind::RepMax { .. }))
+            .map(|pr| (pr.pr_type, pr.value))
+            .collect();
+
+        assert_eq!(
+            rep_maxes,
+            vec![
+                (PRKind::RepMax { reps: 5 }, 100.0),
+                (PRKind::RepMax { reps: 3 }, 105.0)
+            ]
+        );
+
+        let table = tracker.rep_max_table(&sessions).unwrap();
+        let squat = &table["SQ.BB.BACK"];
+        assert_eq!(squat.len(), 5);
+        assert_eq!(squat[0].weight_kg, 105.0); // 1RM implied by 105x3
+        assert_eq!(squat[3].weight_kg, 100.0); // 4RM implied by 100x5
+        assert_eq!(squat[3].source_reps, 5);
+    }
+
+    #[test]
+    fn test_e1rm_volume_and_hold_prs() {
+        let tracker = PRTracker::new();
+        let sessions = vec![
+            SessionRecord::builder()
+                .session("s1")
+                .date(ymd(2025, 8, 4))
+                .ex("BP.BB.FLAT")
+                .reps(5)
+                .weight(80.0)
+                .build(),
+            SessionRecord::builder()
+                .session("s1")
+                .date(ymd(2025, 8, 4))
+                .ex("BP.BB.FLAT")
+                .reps(5)
+                .weight(80.0)
+                .build(),
+            SessionRecord::builder()
+                .session("s2")
+                .date(ymd(2025, 8, 6))
+                .ex("BP.BB.FLAT")
+                .reps(10)
+                .weight(60.0)
+                .build(),
+            SessionRecord::builder()
+                .session("s1")
+                .date(ymd(2025, 8, 4))
+                .ex("CORE.BW.PLNK")
+                .time_sec(45)
+                .bodyweight()
+                .build(),
+            SessionRecord::builder()
+                .session("s2")
+                .date(ymd(2025, 8, 6))
+                .ex("CORE.BW.PLNK")
+                .time_sec(60)
+                .bodyweight()
+                .build(),
+        ];
+
+        let prs = tracker.identify_prs(&sessions).unwrap();
+        let of_kind = |kind: PRKind| -> Vec<f64> {
+            prs.iter()
+                .filter(|pr| pr.pr_type == kind)
+                .map(|pr| pr.value)
+                .collect()
+        };
+
+        // 80x5 -> 93.3, 60x10 -> 80.0
+        assert_eq!(of_kind(PRKind::E1RM).len(), 1);
+        // Session 1: 800kg, session 2: 600kg
+        assert_eq!(of_kind(PRKind::SessionVolume), vec![800.0]);
+        assert_eq!(of_kind(PRKind::LongestHold), vec![45.0, 60.0]);
+    }
+
+    #[test]
+    fn test_device_pr_types_are_reconciled() {
+        let tracker = PRTracker::new();
+        let sessions = vec![
+            SessionRecord::builder()
+                .session("s1")
+                .date(ymd(2025, 8, 4))
+                .ex("DL.BB.CONV")
+                .reps(1)
+                .weight(180.0)
+                .pr_types("1RM;e1rm")
+                .build(),
+            SessionRecord::builder()
+                .session("s2")
+                .date(ymd(2025, 8, 6))
+                .ex("DL.BB.CONV")
+                .reps(1)
+                .weight(170.0)
+                .pr_types("1RM")
+                .build(),
+        ];
+
+        let prs = tracker.identify_prs(&sessions).unwrap();
+
+        let confirmed = prs
+            .iter()
+            .find(|pr| pr.pr_type == PRKind::RepMax { reps: 1 } && pr.value == 180.0)
+            .unwrap();
+        assert_eq!(confirmed.source, PRSource::Both);
+
+        // The device claims a 1RM the indexer cannot see in this history
+        let device_only = prs
+            .iter()
+            .find(|pr| pr.pr_type == PRKind::RepMax { reps: 1 } && pr.value == 170.0)
+            .unwrap();
+        assert_eq!(device_only.source, PRSource::Device);
+    }
+
+    #[test]
+    fn test_parse_device_tag() {
+        assert_eq!(
+            PRKind::parse_device_tag("5RM"),
+            Some(PRKind::RepMax { reps: 5 })
+        );
+        assert_eq!(PRKind::parse_device_tag(" e1RM "), Some(PRKind::E1RM));
+        assert_eq!(
+            PRKind::parse_device_tag("volume"),
+            Some(PRKind::SessionVolume)
+        );
+        assert_eq!(PRKind::parse_device_tag("0RM"), None);
+        assert_eq!(PRKind::parse_device_tag("streak"), None);
+        assert_eq!(PRKind::RepMax { reps: 3 }.to_string(), "3RM");
+    }
+
+    #[test]
+    fn test_weekly_group_volumes() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE exercise (id INTEGER PRIMARY KEY, code TEXT, name TEXT, metadata_json TEXT);
+             CREATE TABLE body_part (id INTEGER PRIMARY KEY, key TEXT, name TEXT);
+             CREATE TABLE exercise_body_part (exercise_id INTEGER, body_part_id INTEGER, role TEXT, intensity INTEGER);
+             INSERT INTO exercise VALUES (1, 'BP.BB.FLAT', 'Bench Press', NULL);
+             INSERT INTO body_part VALUES (9, 'triceps_l', 'Triceps (L)');
+             INSERT INTO body_part VALUES (13, 'pecs', 'Pectorals');
+             INSERT INTO exercise_body_part VALUES (1, 13, 'primary', 80);
+             INSERT INTO exercise_body_part VALUES (1, 9, 'secondary', 40);",
+        )
+        .unwrap();
+        let catalog = ExerciseCatalog::from_connection(&conn).unwrap();
+
+        let sessions = vec![
+            SessionRecord::builder()
+                .session("s1")
+                .date(ymd(2025, 8, 4))
+                .ex("BP.BB.FLAT")
+                .reps(5)
+                .weight(100.0)
+                .build(),
+            SessionRecord::builder()
+                .session("s1")
+                .date(ymd(2025, 8, 4))
+                .ex("BP.BB.FLAT")
+                .reps(5)
+                .weight(100.0)
+                .build(),
+            SessionRecord::builder()
+                .session("s1")
+                .date(ymd(2025, 8, 4))
+                .ex("SQ.BB.BACK")
+                .reps(5)
+                .weight(140.0)
+                .build(),
+        ];
+
+        let calc = VolumeCalculator::new();
+        let muscles = calc
+            .calculate_weekly_group_volumes(&sessions, &catalog, VolumeGrouping::MuscleGroup)
+            .unwrap();
+        let by_group: HashMap<_, _> = muscles.iter().map(|d| (d.group.as_str(), d)).collect();
+
+        assert_eq!(by_group["Pectorals"].weighted_sets, 2.0);
+        assert_eq!(by_group["Triceps"].weighted_sets, 1.0);
+        assert_eq!(by_group["Triceps"].weighted_tonnage_kg, 500.0);
+        assert_eq!(by_group["Unmapped"].weighted_sets, 1.0);
+
+        let patterns = calc
+            .calculate_weekly_group_volumes(&sessions, &catalog, VolumeGrouping::MovementPattern)
+            .unwrap();
+        let groups: Vec<_> = patterns.iter().map(|d| d.group.as_str()).collect();
+        assert_eq!(groups, vec!["Horizontal Push", "Squat"]);
+    }
 }
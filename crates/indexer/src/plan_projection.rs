@@ -0,0 +1,352 @@
+use crate::exercise_catalog::{ExerciseCatalog, UNMAPPED_GROUP};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use weightlifting_core::{DataTransforms, Plan, Segment};
+
+/// Default execution time for a set without a prescribed duration
+const SET_WORK_SEC: f64 = 30.0;
+
+/// Default rest between sets when a segment does not prescribe one
+const DEFAULT_REST_SEC: f64 = 90.0;
+
+/// A single prescribed set, resolved for one week
+#[derive(Debug, Clone)]
+struct PlannedSet {
+    ex: String,
+    rpe: Option<f64>,
+    pct_1rm: Option<f64>,
+    work_sec: f64,
+    rest_sec: f64,
+}
+
+/// Planned weekly sets credited to a muscle group
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedGroupVolume {
+    pub week: u32,
+    pub group: String,
+    pub weighted_sets: f64,
+    pub exercises: Vec<String>,
+}
+
+/// Planned intensity for one exercise in one week
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedIntensity {
+    pub week: u32,
+    pub exercise: String,
+    pub sets: u32,
+    pub mean_rpe: Option<f64>,
+    pub max_rpe: Option<f64>,
+    /// Fraction of 1RM (0.75 = 75%)
+    pub mean_pct_1rm: Option<f64>,
+    pub max_pct_1rm: Option<f64>,
+}
+
+/// Estimated session length for one plan day in one week
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedDayDuration {
+    pub week: u32,
+    pub day: u32,
+    pub day_label: String,
+    pub sets: u32,
+    pub minutes: f64,
+    pub time_cap_min: Option<u32>,
+}
+
+/// Week-by-week projection of a plan's prescribed volume, intensity and duration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanProjection {
+    pub plan_name: String,
+    pub weeks: u32,
+    pub volume: Vec<PlannedGroupVolume>,
+    pub intensity: Vec<PlannedIntensity>,
+    pub duration: Vec<PlannedDayDuration>,
+}
+
+/// Projects a plan across its block without any logged history
+pub struct PlanProjector<'a> {
+    catalog: Option<&'a ExerciseCatalog>,
+    /// Count optional segments and `group.optional` items as prescribed work
+    pub include_optional: bool,
+}
+
+impl<'a> PlanProjector<'a> {
+    /// Projector crediting volume through the default exercise -> body part map
+    pub fn new() -> Self {
+        Self {
+            catalog: None,
+            include_optional: false,
+        }
+    }
+
+    /// Projector crediting fractional volume through the exercise catalog's muscle weights
+    pub fn with_catalog(catalog: &'a ExerciseCatalog) -> Self {
+        Self {
+            catalog: Some(catalog),
+            include_optional: false,
+        }
+    }
+
+    /// Project `plan` over weeks 1..=`weeks` (defaults to `Plan::block_weeks`)
+    pub fn project(
+        &self,
+        plan: &Plan,
+        weeks: Option<u32>,
+    ) -> Result<PlanProjection, serde_json::Error> {
+        let weeks = weeks.unwrap_or_else(|| plan.block_weeks()).max(1);
+        let body_parts = DataTransforms::get_default_body_part_map();
+
+        // (week, group) -> (weighted sets, exercises)
+        let mut volume: BTreeMap<(u32, String), (f64, Vec<String>)> = BTreeMap::new();
+        // (week, exercise) -> sets
+        let mut intensity: BTreeMap<(u32, String), Vec<PlannedSet>> = BTreeMap::new();
+        let mut duration = Vec::new();
+
+        for week in 1..=weeks {
+            for day in plan.schedule_for_week(week)? {
+                let sets: Vec<PlannedSet> = day
+                    .segments
+                    .iter()
+                    .flat_map(|segment| self.planned_sets(segment, week))
+                    .collect();
+
+                duration.push(PlannedDayDuration {
+                    week,
+                    day: day.day,
+                    day_label: day.label.clone(),
+                    sets: sets.len() as u32,
+                    minutes: sets.iter().map(|s| s.work_sec + s.rest_sec).sum::<f64>() / 60.0,
+                    time_cap_min: day.time_cap_min,
+                });
+
+                for set in sets {
+                    let credits = match self.catalog {
+                        Some(catalog) => catalog.muscle_weights(&set.ex),
+                        None => vec![(
+                            body_parts
+                                .get(&set.ex)
+                                .cloned()
+                                .unwrap_or_else(|| UNMAPPED_GROUP.to_string()),
+                            1.0,
+                        )],
+                    };
+                    for (group, weight) in credits {
+                        let entry = volume.entry((week, group)).or_default();
+                        entry.0 += weight;
+                        if !entry.1.contains(&set.ex) {
+                            entry.1.push(set.ex.clone());
+                        }
+                    }
+                    intensity
+                        .entry((week, set.ex.clone()))
+                        .or_default()
+                        .push(set);
+                }
+            }
+        }
+
+        Ok(PlanProjection {
+            plan_name: plan.name.clone(),
+            weeks,
+            volume: volume
+                .into_iter()
+                .map(|((week, group), (weighted_sets, mut exercises))| {
+                    exercises.sort();
+                    PlannedGroupVolume {
+                        week,
+                        group,
+                        weighted_sets,
+                        exercises,
+                    }
+                })
+                .collect(),
+            intensity: intensity
+                .into_iter()
+                .map(|((week, exercise), sets)| {
+                    let rpe: Vec<f64> = sets.iter().filter_map(|s| s.rpe).collect();
+                    let pct: Vec<f64> = sets.iter().filter_map(|s| s.pct_1rm).collect();
+                    PlannedIntensity {
+                        week,
+                        exercise,
+                        sets: sets.len() as u32,
+                        mean_rpe: mean(&rpe),
+                        max_rpe: rpe.iter().copied().reduce(f64::max),
+                        mean_pct_1rm: mean(&pct),
+                        max_pct_1rm: pct.iter().copied().reduce(f64::max),
+                    }
+                })
+                .collect(),
+            duration,
+        })
+    }
+
+    /// Flatten a week-resolved segment into individual sets
+    /// Groups pick their items for the week; everything else comes from `Segment::prescriptions`
+    fn planned_sets(&self, segment: &Segment, week: u32) -> Vec<PlannedSet> {
+        match segment {
+            Segment::GroupSuperset(s) => s
+                .items
+                .iter()
+                .flat_map(|item| self.planned_sets(item, week))
+                .collect(),
+            // Choose groups project their first `pick` options, advancing weekly when the
+            // group rotates weekly; rotate groups advance one item per week.
+            Segment::GroupChoose(s) if !s.from.is_empty() => {
+                let offset = if s.rotation.as_deref() == Some("weekly") {
+                    (week as usize - 1) * s.pick as usize
+                } else {
+                    0
+                };
+                (0..(s.pick as usize).min(s.from.len()))
+                    .flat_map(|i| self.planned_sets(&s.from[(offset + i) % s.from.len()], week))
+                    .collect()
+            }
+            Segment::GroupRotate(s) if !s.items.is_empty() => {
+                self.planned_sets(&s.items[(week as usize - 1) % s.items.len()], week)
+            }
+            Segment::GroupOptional(s) if self.include_optional => s
+                .items
+                .iter()
+                .flat_map(|item| self.planned_sets(item, week))
+                .collect(),
+            Segment::GroupChoose(_) | Segment::GroupRotate(_) | Segment::GroupOptional(_) => {
+                Vec::new()
+            }
+            _ => segment
+                .prescriptions()
+                .into_iter()
+                .filter(|p| self.include_optional || !p.optional)
+                .flat_map(|p| {
+                    p.sets.into_iter().map(move |set| PlannedSet {
+                        ex: p.ex.clone(),
+                        rpe: set.rpe,
+                        pct_1rm: set.pct_1rm,
+                        work_sec: set.work_sec.unwrap_or(SET_WORK_SEC),
+                        rest_sec: set.rest_sec.unwrap_or(DEFAULT_REST_SEC),
+                    })
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Default for PlanProjector<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan() -> Plan {
+        serde_json::from_value(serde_json::json!({
+            "name": "Block",
+            "unit": "kg",
+            "dictionary": {},
+            "groups": {},
+            "schedule": [{
+                "day": 1,
+                "label": "Lower",
+                "time_cap_min": 60,
+                "segments": [
+                    {
+                        "type": "straight", "ex": "SQ.BB.BACK", "sets": 3,
+                        "reps": { "min": 5, "max": 5 }, "rpe": 7.0, "rest_sec": 180,
+                        "per_week": { "2": { "sets": 4, "rpe": 8.0 } }
+                    },
+                    {
+                        "type": "percentage", "ex": "DL.BB",
+                        "prescriptions": [
+                            { "sets": 1, "reps": 5, "pct_1rm": 0.75 },
+                            { "sets": 2, "reps": 3, "pct_1rm": 0.85 }
+                        ]
+                    },
+                    { "type": "comment", "text": "Optional finisher" },
+                    {
+                        "type": "straight", "ex": "CALF.RAISE", "sets": 2,
+                        "reps": { "min": 10, "max": 15 }, "optional": true
+                    },
+                    {
+                        "type": "group.rotate",
+                        "items": [
+                            { "type": "straight", "ex": "LEG.CURL", "sets": 2, "reps": { "min": 10, "max": 12 } },
+                            { "type": "straight", "ex": "LEG.EXT", "sets": 2, "reps": { "min": 10, "max": 12 } }
+                        ]
+                    }
+                ]
+            }]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_projection_applies_overlays() {
+        let projection = PlanProjector::new().project(&plan(), None).unwrap();
+        assert_eq!(projection.weeks, 2);
+
+        let squat = |week: u32| {
+            projection
+                .intensity
+                .iter()
+                .find(|i| i.week == week && i.exercise == "SQ.BB.BACK")
+                .unwrap()
+        };
+        assert_eq!((squat(1).sets, squat(1).max_rpe), (3, Some(7.0)));
+        assert_eq!((squat(2).sets, squat(2).max_rpe), (4, Some(8.0)));
+
+        let deadlift = projection
+            .intensity
+            .iter()
+            .find(|i| i.week == 1 && i.exercise == "DL.BB")
+            .unwrap();
+        assert_eq!(deadlift.sets, 3);
+        assert!((deadlift.mean_pct_1rm.unwrap() - 0.8166).abs() < 0.001);
+        assert_eq!(deadlift.max_pct_1rm, Some(0.85));
+
+        // Optional work is excluded and the rotate group advances each week
+        let exercises = |week: u32| -> Vec<&str> {
+            projection
+                .intensity
+                .iter()
+                .filter(|i| i.week == week)
+                .map(|i| i.exercise.as_str())
+                .collect()
+        };
+        assert_eq!(exercises(1), vec!["DL.BB", "LEG.CURL", "SQ.BB.BACK"]);
+        assert_eq!(exercises(2), vec!["DL.BB", "LEG.EXT", "SQ.BB.BACK"]);
+    }
+
+    #[test]
+    fn test_projected_volume_and_duration() {
+        let projection = PlanProjector::new().project(&plan(), Some(2)).unwrap();
+
+        let legs = projection
+            .volume
+            .iter()
+            .find(|v| v.week == 1 && v.group == "Legs")
+            .unwrap();
+        assert_eq!(legs.weighted_sets, 3.0);
+        assert_eq!(legs.exercises, vec!["SQ.BB.BACK".to_string()]);
+
+        let week1 = &projection.duration[0];
+        assert_eq!(
+            (week1.week, week1.sets, week1.time_cap_min),
+            (1, 8, Some(60))
+        );
+        // 3 squat sets at 30s + 180s rest, 5 other sets at 30s + 90s rest
+        assert!((week1.minutes - 20.5).abs() < f64::EPSILON);
+
+        let with_optional = PlanProjector {
+            include_optional: true,
+            ..PlanProjector::new()
+        }
+        .project(&plan(), Some(1))
+        .unwrap();
+        assert_eq!(with_optional.duration[0].sets, 10);
+    }
+}
@@ -0,0 +1,726 @@
+use crate::csv_parser::SessionRecord;
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use thiserror::Error;
+use weightlifting_core::{
+    estimated_pct_1rm, estimated_rpe, Plan, RepsOrRange, Segment, TrainingMaxes,
+};
+
+/// Load added per progression step when the plan gives no `load_increment_kg`
+pub const DEFAULT_LOAD_INCREMENT_KG: f64 = 2.5;
+
+/// RPE a first session is estimated at from the training max when the plan gives none
+const START_RPE: f64 = 8.0;
+
+const KG_PER_LB: f64 = 0.453592;
+
+#[derive(Error, Debug)]
+pub enum ProgressionError {
+    #[error(
+        "Unknown progression mode: {0}. Available: linear, double_progression, rpe_capped, percentage_cycle"
+    )]
+    UnknownMode(String),
+    #[error("Invalid progression: {0}")]
+    Invalid(String),
+    #[error("Failed to resolve week overlays: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgressionMode {
+    /// Add the increment after every session completed at the target reps
+    Linear,
+    /// Add reps up to the top of the range, then add load and drop back to the bottom
+    DoubleProgression,
+    /// Add load while sessions stay a full point under `cap_rpe`, back off above it
+    RpeCapped,
+    /// Loads are the plan's %1RM of the training max, which goes up after each
+    /// completed pass through the block
+    PercentageCycle,
+}
+
+impl FromStr for ProgressionMode {
+    type Err = ProgressionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "linear" => Ok(ProgressionMode::Linear),
+            "double_progression" | "double" => Ok(ProgressionMode::DoubleProgression),
+            "rpe_capped" | "rpe_cap" => Ok(ProgressionMode::RpeCapped),
+            "percentage_cycle" | "percentage" => Ok(ProgressionMode::PercentageCycle),
+            other => Err(ProgressionError::UnknownMode(other.to_string())),
+        }
+    }
+}
+
+/// A plan's `progression` hints with defaults applied
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProgressionRule {
+    pub mode: ProgressionMode,
+    pub load_increment_kg: f64,
+    /// Sessions logged above this RPE never add load
+    pub cap_rpe: Option<f64>,
+}
+
+impl ProgressionRule {
+    /// `mode` picks the rule; without one, `reps_first: true` selects double progression
+    /// and anything else linear. The increment defaults to 2.5 kg.
+    pub fn from_plan(plan: &Plan) -> Result<Self, ProgressionError> {
+        let hints = plan.progression.as_ref();
+        let mode = match hints.and_then(|p| p.mode.as_deref()) {
+            Some(mode) => mode.parse()?,
+            None if hints.and_then(|p| p.reps_first) == Some(true) => {
+                ProgressionMode::DoubleProgression
+            }
+            None => ProgressionMode::Linear,
+        };
+        let load_increment_kg = hints
+            .and_then(|p| p.load_increment_kg)
+            .unwrap_or(DEFAULT_LOAD_INCREMENT_KG);
+        let cap_rpe = hints.and_then(|p| p.cap_rpe);
+
+        if !load_increment_kg.is_finite() || load_increment_kg <= 0.0 {
+            return Err(ProgressionError::Invalid(format!(
+                "load_increment_kg must be positive, got {}",
+                load_increment_kg
+            )));
+        }
+        if cap_rpe.is_some_and(|cap| !(1.0..=10.0).contains(&cap)) {
+            return Err(ProgressionError::Invalid(format!(
+                "cap_rpe must be between 1 and 10, got {}",
+                cap_rpe.unwrap_or_default()
+            )));
+        }
+        if mode == ProgressionMode::RpeCapped && cap_rpe.is_none() {
+            return Err(ProgressionError::Invalid(
+                "rpe_capped progression needs cap_rpe".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            mode,
+            load_increment_kg,
+            cap_rpe,
+        })
+    }
+}
+
+/// One session of an exercise as the plan prescribes it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Prescription {
+    pub week: u32,
+    pub day: u32,
+    pub sets: u32,
+    pub rep_min: u32,
+    pub rep_max: u32,
+    /// Rep target within the range: its `target`, else the bottom
+    pub reps: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pct_1rm: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rpe: Option<f64>,
+}
+
+/// What a session led to for the next one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgressionDecision {
+    /// Nothing logged yet: the load is estimated from the training max
+    Start,
+    /// Load (or, for percentage cycles, the training max) goes up
+    Increase,
+    /// Same load, one more rep
+    AddReps,
+    Hold,
+    /// Load comes down after a session over the RPE cap
+    Decrease,
+    /// Next session of a percentage cycle
+    Continue,
+}
+
+/// Target for the next session of an exercise
+#[derive(Debug, Clone, Serialize)]
+pub struct ProgressionTarget {
+    pub ex: String,
+    /// Block week and day of the prescription the target follows
+    pub week: u32,
+    pub day: u32,
+    pub sets: u32,
+    pub reps: u32,
+    pub load_kg: Option<f64>,
+    pub training_max_kg: Option<f64>,
+    pub decision: ProgressionDecision,
+    /// Date of the last logged session the target follows from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_session: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SimulatedWeek {
+    /// 1-based week from the first simulated session
+    pub week: u32,
+    pub targets: Vec<ProgressionTarget>,
+}
+
+/// Load in kg, reps and RPE of one logged working set
+type LoggedSet = (f64, u32, Option<f64>);
+
+/// Top working sets of one logged session of an exercise
+#[derive(Debug, Clone, PartialEq)]
+struct Outcome {
+    load_kg: f64,
+    sets: u32,
+    min_reps: u32,
+    rpe: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+struct LiftState {
+    /// Sessions done, as a position in the exercise's prescription sequence
+    position: usize,
+    load_kg: Option<f64>,
+    reps: u32,
+    training_max_kg: Option<f64>,
+    /// Whether every session of the current percentage cycle was completed
+    cycle_complete: bool,
+    decision: ProgressionDecision,
+    last_session: Option<NaiveDate>,
+}
+
+/// Turns a plan's `progression` hints into next-session targets.
+///
+/// Each exercise follows the straight, RPE and percentage segments that prescribe it
+/// across the block (the first per day, heaviest entry of a percentage segment), in
+/// week then day order, wrapping around at the end of the block. Logged sessions are
+/// replayed through the rule in date order, each advancing one prescription.
+pub struct ProgressionEngine<'a> {
+    plan: &'a Plan,
+    rule: ProgressionRule,
+    maxes: &'a TrainingMaxes,
+    sequences: BTreeMap<String, Vec<Prescription>>,
+}
+
+impl<'a> ProgressionEngine<'a> {
+    pub fn new(plan: &'a Plan, maxes: &'a TrainingMaxes) -> Result<Self, ProgressionError> {
+        Self::with_rule(plan, maxes, ProgressionRule::from_plan(plan)?)
+    }
+
+    pub fn with_rule(
+        plan: &'a Plan,
+        maxes: &'a TrainingMaxes,
+        rule: ProgressionRule,
+    ) -> Result<Self, ProgressionError> {
+        let mut sequences: BTreeMap<String, Vec<Prescription>> = BTreeMap::new();
+        for week in 1..=plan.block_weeks() {
+            for day in plan.schedule_for_week(week)? {
+                let mut seen = Vec::new();
+                for segment in &day.segments {
+                    let Some((ex, prescription)) = Self::prescription(segment, week, day.day)
+                    else {
+                        continue;
+                    };
+                    if !seen.contains(&ex) {
+                        sequences.entry(ex.clone()).or_default().push(prescription);
+                        seen.push(ex);
+                    }
+                }
+            }
+        }
+
+        Ok(Self {
+            plan,
+            rule,
+            maxes,
+            sequences,
+        })
+    }
+
+    pub fn rule(&self) -> &ProgressionRule {
+        &self.rule
+    }
+
+    /// Every prescribed session of `ex` across the block
+    pub fn prescriptions(&self, ex: &str) -> &[Prescription] {
+        self.sequences.get(ex).map_or(&[], Vec::as_slice)
+    }
+
+    /// The next target for every progressed exercise after the logged sessions
+    pub fn next_targets(&self, sessions: &[SessionRecord]) -> Vec<ProgressionTarget> {
+        let logged = self.outcomes(sessions);
+        self.sequences
+            .keys()
+            .map(|ex| self.target(ex, &self.replay(ex, logged.get(ex))))
+            .collect()
+    }
+
+    /// Targets week by week for `weeks` weeks after the logged sessions, assuming every
+    /// session is completed as prescribed at the RPE its load would take against the
+    /// current training max (so an RPE cap eventually stalls progress)
+    pub fn simulate(&self, sessions: &[SessionRecord], weeks: u32) -> Vec<SimulatedWeek> {
+        let logged = self.outcomes(sessions);
+        let mut states: Vec<(&String, LiftState)> = self
+            .sequences
+            .keys()
+            .map(|ex| (ex, self.replay(ex, logged.get(ex))))
+            .collect();
+
+        (1..=weeks)
+            .map(|week| {
+                let mut targets = Vec::new();
+                for (ex, state) in states.iter_mut() {
+                    let sequence = &self.sequences[*ex];
+                    let block_week = sequence[state.position % sequence.len()].week;
+                    loop {
+                        let target = self.target(ex, state);
+                        if let Some(load_kg) = target.load_kg {
+                            let outcome = Outcome {
+                                load_kg,
+                                sets: target.sets,
+                                min_reps: target.reps,
+                                rpe: self
+                                    .maxes
+                                    .get(*ex)
+                                    .map(|max| estimated_rpe(load_kg, target.reps, *max)),
+                            };
+                            self.step(ex, state, &outcome);
+                        } else {
+                            state.position += 1;
+                        }
+                        targets.push(target);
+                        if sequence[state.position % sequence.len()].week != block_week
+                            || state.position.is_multiple_of(sequence.len())
+                        {
+                            break;
+                        }
+                    }
+                }
+                targets.sort_by_key(|target| target.day);
+                SimulatedWeek { week, targets }
+            })
+            .collect()
+    }
+
+    fn prescription(segment: &Segment, week: u32, day: u32) -> Option<(String, Prescription)> {
+        let range = |reps: &Option<RepsOrRange>| {
+            reps.as_ref().map(|RepsOrRange::Range(range)| {
+                (range.min, range.max, range.target.unwrap_or(range.min))
+            })
+        };
+        let (ex, sets, (rep_min, rep_max, reps), pct_1rm, rpe) = match segment {
+            Segment::Straight(s) => (
+                &s.base.ex,
+                s.sets.or(s.sets_range.as_ref().map(|r| r.min)).unwrap_or(1),
+                range(&s.reps)?,
+                None,
+                s.rpe.or(s.rir.map(|rir| 10.0 - rir)),
+            ),
+            Segment::Rpe(s) => (&s.base.ex, s.sets, range(&s.reps)?, None, Some(s.rpe)),
+            Segment::Percentage(s) => {
+                let top = s
+                    .prescriptions
+                    .iter()
+                    .max_by(|a, b| a.pct_1rm.total_cmp(&b.pct_1rm))?;
+                (
+                    &s.base.ex,
+                    top.sets,
+                    (top.reps, top.reps, top.reps),
+                    Some(top.pct_1rm),
+                    None,
+                )
+            }
+            _ => return None,
+        };
+        Some((
+            ex.clone(),
+            Prescription {
+                week,
+                day,
+                sets,
+                rep_min,
+                rep_max,
+                reps,
+                pct_1rm,
+                rpe,
+            },
+        ))
+    }
+
+    /// Percentage cycles only apply to exercises the plan prescribes by %1RM; the rest
+    /// progress linearly
+    fn mode(&self, ex: &str) -> ProgressionMode {
+        match self.rule.mode {
+            ProgressionMode::PercentageCycle
+                if self.prescriptions(ex).iter().all(|rx| rx.pct_1rm.is_none()) =>
+            {
+                ProgressionMode::Linear
+            }
+            mode => mode,
+        }
+    }
+
+    fn target_reps(&self, ex: &str, rx: &Prescription, state: &LiftState) -> u32 {
+        match self.mode(ex) {
+            ProgressionMode::DoubleProgression => state.reps.clamp(rx.rep_min, rx.rep_max),
+            _ => rx.reps,
+        }
+    }
+
+    fn target(&self, ex: &str, state: &LiftState) -> ProgressionTarget {
+        let sequence = &self.sequences[ex];
+        let rx = &sequence[state.position % sequence.len()];
+        let reps = self.target_reps(ex, rx, state);
+        let load_kg = match self.mode(ex) {
+            ProgressionMode::PercentageCycle => state
+                .training_max_kg
+                .zip(rx.pct_1rm)
+                .map(|(max, pct)| max * pct),
+            _ => state.load_kg.or_else(|| {
+                state.training_max_kg.map(|max| match rx.pct_1rm {
+                    Some(pct) => max * pct,
+                    None => max * estimated_pct_1rm(reps, Some(rx.rpe.unwrap_or(START_RPE))),
+                })
+            }),
+        };
+
+        ProgressionTarget {
+            ex: ex.to_string(),
+            week: rx.week,
+            day: rx.day,
+            sets: rx.sets,
+            reps,
+            load_kg,
+            training_max_kg: state.training_max_kg,
+            decision: state.decision,
+            last_session: state.last_session,
+        }
+    }
+
+    fn replay(&self, ex: &str, logged: Option<&Vec<(NaiveDate, Outcome)>>) -> LiftState {
+        let mut state = LiftState {
+            position: 0,
+            load_kg: None,
+            reps: self.sequences[ex][0].rep_min,
+            training_max_kg: self.maxes.get(ex).copied(),
+            cycle_complete: true,
+            decision: ProgressionDecision::Start,
+            last_session: None,
+        };
+        for (date, outcome) in logged.into_iter().flatten() {
+            self.step(ex, &mut state, outcome);
+            state.last_session = Some(*date);
+        }
+        state
+    }
+
+    /// Apply one session's outcome and move to the next prescription
+    fn step(&self, ex: &str, state: &mut LiftState, outcome: &Outcome) {
+        let sequence = &self.sequences[ex];
+        let rx = &sequence[state.position % sequence.len()];
+        let target_reps = self.target_reps(ex, rx, state);
+        let completed = outcome.sets >= rx.sets && outcome.min_reps >= target_reps;
+        let over_cap = matches!(
+            (outcome.rpe, self.rule.cap_rpe),
+            (Some(rpe), Some(cap)) if rpe > cap
+        );
+        let increment = self.rule.load_increment_kg;
+
+        state.position += 1;
+        let mut load = outcome.load_kg;
+        state.decision = match self.mode(ex) {
+            ProgressionMode::Linear if completed && !over_cap => {
+                load += increment;
+                ProgressionDecision::Increase
+            }
+            ProgressionMode::Linear => ProgressionDecision::Hold,
+            ProgressionMode::DoubleProgression if !completed || over_cap => {
+                ProgressionDecision::Hold
+            }
+            ProgressionMode::DoubleProgression if outcome.min_reps >= rx.rep_max => {
+                load += increment;
+                state.reps = rx.rep_min;
+                ProgressionDecision::Increase
+            }
+            ProgressionMode::DoubleProgression => {
+                state.reps = (outcome.min_reps + 1).min(rx.rep_max);
+                ProgressionDecision::AddReps
+            }
+            ProgressionMode::RpeCapped if over_cap => {
+                load = (load - increment).max(0.0);
+                ProgressionDecision::Decrease
+            }
+            ProgressionMode::RpeCapped
+                if completed
+                    && outcome
+                        .rpe
+                        .zip(self.rule.cap_rpe)
+                        .is_none_or(|(rpe, cap)| rpe <= cap - 1.0) =>
+            {
+                load += increment;
+                ProgressionDecision::Increase
+            }
+            ProgressionMode::RpeCapped => ProgressionDecision::Hold,
+            ProgressionMode::PercentageCycle => {
+                // Without a training max, take the one the first logged load implies
+                if state.training_max_kg.is_none() {
+                    state.training_max_kg = rx.pct_1rm.map(|pct| outcome.load_kg / pct);
+                }
+                state.cycle_complete &= completed;
+                if !state.position.is_multiple_of(sequence.len()) {
+                    ProgressionDecision::Continue
+                } else if std::mem::replace(&mut state.cycle_complete, true) {
+                    state.training_max_kg = state.training_max_kg.map(|max| max + increment);
+                    ProgressionDecision::Increase
+                } else {
+                    ProgressionDecision::Hold
+                }
+            }
+        };
+        state.load_kg = Some(load);
+    }
+
+    /// Logged working sets of this plan's exercises, one outcome per session in date order
+    fn outcomes(&self, sessions: &[SessionRecord]) -> BTreeMap<String, Vec<(NaiveDate, Outcome)>> {
+        // (ex, date, session_id) -> logged sets
+        let mut grouped: BTreeMap<(&str, NaiveDate, &str), Vec<LoggedSet>> = BTreeMap::new();
+        for record in sessions {
+            if !record.is_working_set()
+                || !self.sequences.contains_key(&record.ex_code)
+                || record
+                    .plan_name
+                    .as_deref()
+                    .is_some_and(|name| name != self.plan.name)
+            {
+                continue;
+            }
+            let (Some(weight), Some(reps)) = (record.weight, record.reps) else {
+                continue;
+            };
+            let kg = match record.unit.as_str() {
+                "kg" => weight,
+                "lb" => weight * KG_PER_LB,
+                _ => continue,
+            };
+            let rpe = record.rpe.or(record.rir.map(|rir| 10.0 - rir as f64));
+            grouped
+                .entry((&record.ex_code, record.date, &record.session_id))
+                .or_default()
+                .push((kg, reps, rpe));
+        }
+
+        let mut outcomes: BTreeMap<String, Vec<(NaiveDate, Outcome)>> = BTreeMap::new();
+        for ((ex, date, _), sets) in grouped {
+            let top = sets.iter().map(|(kg, _, _)| *kg).fold(0.0, f64::max);
+            let top_sets: Vec<_> = sets.iter().filter(|(kg, _, _)| *kg >= top - 0.01).collect();
+            let outcome = Outcome {
+                load_kg: top,
+                sets: top_sets.len() as u32,
+                min_reps: top_sets.iter().map(|(_, reps, _)| *reps).min().unwrap_or(0),
+                rpe: top_sets
+                    .iter()
+                    .filter_map(|(_, _, rpe)| *rpe)
+                    .reduce(f64::max),
+            };
+            outcomes
+                .entry(ex.to_string())
+                .or_default()
+                .push((date, outcome));
+        }
+        outcomes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csv_parser::SessionRecordBuilder;
+
+    fn plan(progression: serde_json::Value, segment: serde_json::Value) -> Plan {
+        serde_json::from_value(serde_json::json!({
+            "name": "Test Plan",
+            "unit": "kg",
+            "dictionary": {},
+            "groups": {},
+            "progression": progression,
+            "schedule": [{ "day": 1, "label": "Lower", "segments": [segment] }]
+        }))
+        .unwrap()
+    }
+
+    /// A set logged against the plan in session S<day> on 2025-08-<day>
+    fn logged(day: u32) -> SessionRecordBuilder {
+        SessionRecord::builder()
+            .session(&format!("S{}", day))
+            .date(NaiveDate::from_ymd_opt(2025, 8, day).unwrap())
+            .plan("Test Plan")
+            .day("Lower")
+    }
+
+    #[test]
+    fn test_double_progression_adds_reps_then_load() {
+        let plan = plan(
+            serde_json::json!({ "reps_first": true, "load_increment_kg": 5.0 }),
+            serde_json::json!({
+                "type": "straight", "ex": "SQ.BB.BACK", "sets": 2, "reps": { "min": 6, "max": 8 }
+            }),
+        );
+        let maxes = TrainingMaxes::from([("SQ.BB.BACK".to_string(), 120.0)]);
+        let engine = ProgressionEngine::new(&plan, &maxes).unwrap();
+        assert_eq!(engine.rule().mode, ProgressionMode::DoubleProgression);
+
+        let start = &engine.next_targets(&[])[0];
+        assert_eq!(
+            (start.decision, start.reps),
+            (ProgressionDecision::Start, 6)
+        );
+        assert!((start.load_kg.unwrap() - 120.0 * 0.91 / 1.2).abs() < 1e-9);
+
+        let mut log = vec![
+            logged(4).ex("SQ.BB.BACK").weight(90.0).reps(7).build(),
+            logged(4).ex("SQ.BB.BACK").weight(90.0).reps(7).build(),
+        ];
+        let target = &engine.next_targets(&log)[0];
+        assert_eq!(
+            (target.decision, target.reps),
+            (ProgressionDecision::AddReps, 8)
+        );
+        assert_eq!(target.load_kg, Some(90.0));
+
+        // A short set holds; hitting the top of the range adds load and resets reps
+        log.extend([
+            logged(6).ex("SQ.BB.BACK").weight(90.0).reps(8).build(),
+            logged(6).ex("SQ.BB.BACK").weight(90.0).reps(6).build(),
+        ]);
+        assert_eq!(
+            engine.next_targets(&log)[0].decision,
+            ProgressionDecision::Hold
+        );
+        log.extend([
+            logged(8).ex("SQ.BB.BACK").weight(90.0).reps(8).build(),
+            logged(8).ex("SQ.BB.BACK").weight(90.0).reps(8).build(),
+        ]);
+        let target = &engine.next_targets(&log)[0];
+        assert_eq!(
+            (target.decision, target.reps),
+            (ProgressionDecision::Increase, 6)
+        );
+        assert_eq!(target.load_kg, Some(95.0));
+        assert_eq!(target.last_session, NaiveDate::from_ymd_opt(2025, 8, 8));
+    }
+
+    #[test]
+    fn test_rpe_capped_and_linear_simulation() {
+        let segment = serde_json::json!({
+            "type": "straight", "ex": "BP.BB.FLAT", "sets": 1, "reps": { "min": 5, "max": 5 }
+        });
+        let maxes = TrainingMaxes::from([("BP.BB.FLAT".to_string(), 100.0)]);
+
+        let capped = plan(
+            serde_json::json!({ "mode": "rpe_capped", "cap_rpe": 9.0 }),
+            segment.clone(),
+        );
+        let engine = ProgressionEngine::new(&capped, &maxes).unwrap();
+        let log = [logged(4)
+            .ex("BP.BB.FLAT")
+            .weight(80.0)
+            .reps(5)
+            .rpe(9.5)
+            .build()];
+        let target = &engine.next_targets(&log)[0];
+        assert_eq!(target.decision, ProgressionDecision::Decrease);
+        assert_eq!(target.load_kg, Some(77.5));
+
+        // Simulated sessions climb until the estimated RPE reaches the cap, then hold
+        let weeks = engine.simulate(&[], 6);
+        let loads: Vec<f64> = weeks
+            .iter()
+            .map(|w| w.targets[0].load_kg.unwrap())
+            .collect();
+        assert_eq!(loads.len(), 6);
+        assert!(loads.windows(2).all(|pair| pair[1] >= pair[0]));
+        assert!(loads[0] < loads[5]);
+        assert_eq!(loads[4], loads[5]);
+
+        let linear = plan(serde_json::json!({ "mode": "linear" }), segment);
+        let engine = ProgressionEngine::new(&linear, &maxes).unwrap();
+        let weeks = engine.simulate(
+            &[logged(4).ex("BP.BB.FLAT").weight(70.0).reps(5).build()],
+            3,
+        );
+        let loads: Vec<f64> = weeks
+            .iter()
+            .map(|w| w.targets[0].load_kg.unwrap())
+            .collect();
+        assert_eq!(loads, [72.5, 75.0, 77.5]);
+
+        assert!(ProgressionRule::from_plan(&plan(
+            serde_json::json!({ "mode": "rpe_capped" }),
+            serde_json::json!({ "type": "comment", "text": "x" })
+        ))
+        .is_err());
+        assert!(matches!(
+            "wave".parse::<ProgressionMode>(),
+            Err(ProgressionError::UnknownMode(_))
+        ));
+    }
+
+    #[test]
+    fn test_percentage_cycle_raises_training_max_per_block() {
+        let plan: Plan = serde_json::from_value(serde_json::json!({
+            "name": "Test Plan",
+            "unit": "kg",
+            "dictionary": {},
+            "groups": {},
+            "progression": { "mode": "percentage_cycle", "load_increment_kg": 5.0 },
+            "phase": { "index": 1, "weeks": [1, 2] },
+            "schedule": [{
+                "day": 1,
+                "label": "Lower",
+                "segments": [{
+                    "type": "percentage",
+                    "ex": "SQ.BB.BACK",
+                    "prescriptions": [{ "sets": 3, "reps": 5, "pct_1rm": 0.7 }],
+                    "per_week": {
+                        "2": { "prescriptions": [{ "sets": 3, "reps": 3, "pct_1rm": 0.8 }] }
+                    }
+                }]
+            }]
+        }))
+        .unwrap();
+        let maxes = TrainingMaxes::from([("SQ.BB.BACK".to_string(), 200.0)]);
+        let engine = ProgressionEngine::new(&plan, &maxes).unwrap();
+        assert_eq!(engine.prescriptions("SQ.BB.BACK").len(), 2);
+
+        let weeks = engine.simulate(&[], 4);
+        let summary: Vec<(u32, f64, f64)> = weeks
+            .iter()
+            .map(|w| {
+                let t = &w.targets[0];
+                (t.reps, t.load_kg.unwrap(), t.training_max_kg.unwrap())
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (5, 140.0, 200.0),
+                (3, 160.0, 200.0),
+                (5, 143.5, 205.0),
+                (3, 164.0, 205.0)
+            ]
+        );
+        assert_eq!(weeks[2].targets[0].decision, ProgressionDecision::Increase);
+
+        // A missed session in the cycle holds the training max
+        let log: Vec<SessionRecord> = [(4, 140.0, 5), (4, 140.0, 5), (4, 140.0, 5)]
+            .into_iter()
+            .chain([(11, 160.0, 3), (11, 160.0, 2), (11, 160.0, 3)])
+            .map(|(day, kg, reps)| logged(day).ex("SQ.BB.BACK").weight(kg).reps(reps).build())
+            .collect();
+        let target = &engine.next_targets(&log)[0];
+        assert_eq!(target.decision, ProgressionDecision::Hold);
+        assert_eq!(target.training_max_kg, Some(200.0));
+        assert_eq!((target.week, target.load_kg), (1, Some(140.0)));
+    }
+}
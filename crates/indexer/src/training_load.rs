@@ -0,0 +1,365 @@
+use crate::csv_parser::SessionRecord;
+use crate::metrics::MetricsError;
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// How session RPE load is scaled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoadBasis {
+    /// Session RPE x session duration in minutes (Foster's sRPE)
+    Duration,
+    /// Session RPE x working sets
+    Sets,
+}
+
+/// Load for a single logged session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionLoadDataPoint {
+    pub session_id: String,
+    pub date: NaiveDate,
+    pub session_rpe: f64,
+    pub duration_min: f64,
+    pub working_sets: u32,
+    pub load: f64,
+}
+
+/// Daily load with rolling workload ratios and fitness-fatigue state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyLoadDataPoint {
+    pub date: NaiveDate,
+    pub load: f64,
+    pub acute_load: f64,   // mean daily load over the acute window
+    pub chronic_load: f64, // mean daily load over the chronic window
+    /// Acute:chronic workload ratio, once a full chronic window is available
+    pub acwr: Option<f64>,
+    pub fitness: f64,
+    pub fatigue: f64,
+    pub performance: f64,
+}
+
+/// Weekly load with Foster's monotony and strain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeeklyLoadDataPoint {
+    pub week_start: NaiveDate, // Monday of the week
+    pub total_load: f64,
+    pub mean_daily_load: f64,
+    /// Mean daily load / standard deviation; None when every day had the same load
+    pub monotony: Option<f64>,
+    /// Weekly load x monotony
+    pub strain: Option<f64>,
+}
+
+/// Calculator for session RPE load, ACWR, monotony/strain and the Banister model
+pub struct TrainingLoadCalculator {
+    pub basis: LoadBasis,
+    pub acute_days: usize,
+    pub chronic_days: usize,
+    pub fitness_tau: f64,
+    pub fatigue_tau: f64,
+    pub fitness_gain: f64,
+    pub fatigue_gain: f64,
+}
+
+impl Default for TrainingLoadCalculator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Assumed execution time of a single set, matching `schemes::estimate_duration`
+const SET_EXECUTION_SEC: i64 = 30;
+
+impl TrainingLoadCalculator {
+    pub fn new() -> Self {
+        Self {
+            basis: LoadBasis::Duration,
+            acute_days: 7,
+            chronic_days: 28,
+            fitness_tau: 42.0,
+            fatigue_tau: 7.0,
+            fitness_gain: 1.0,
+            fatigue_gain: 2.0,
+        }
+    }
+
+    pub fn with_basis(mut self, basis: LoadBasis) -> Self {
+        self.basis = basis;
+        self
+    }
+
+    /// Calculate session RPE load for each logged session
+    pub fn calculate_session_loads(
+        &self,
+        sessions: &[SessionRecord],
+    ) -> Result<Vec<SessionLoadDataPoint>, MetricsError> {
+        let mut grouped: BTreeMap<(NaiveDate, String), Vec<&SessionRecord>> = BTreeMap::new();
+        for record in sessions {
+            if record.is_working_set() {
+                grouped
+                    .entry((record.date, record.session_id.clone()))
+                    .or_default()
+                    .push(record);
+            }
+        }
+
+        let mut loads = Vec::new();
+        for ((date, session_id), records) in grouped {
+            let rpes: Vec<f64> = records.iter().map(|r| Self::set_rpe(r)).collect();
+            let session_rpe = rpes.iter().sum::<f64>() / rpes.len() as f64;
+
+            let first = records
+                .iter()
+                .map(|r| r.time)
+                .min()
+                .unwrap_or(NaiveTime::MIN);
+            let last = records
+                .iter()
+                .map(|r| r.time)
+                .max()
+                .unwrap_or(NaiveTime::MIN);
+            let duration_sec = (last - first).num_seconds().max(0) + SET_EXECUTION_SEC;
+            let duration_min = duration_sec as f64 / 60.0;
+
+            let working_sets = records.len() as u32;
+            let load = match self.basis {
+                LoadBasis::Duration => session_rpe * duration_min,
+                LoadBasis::Sets => session_rpe * working_sets as f64,
+            };
+
+            loads.push(SessionLoadDataPoint {
+                session_id,
+                date,
+                session_rpe,
+                duration_min,
+                working_sets,
+                load,
+            });
+        }
+
+        Ok(loads)
+    }
+
+    /// Calculate the daily load series (zero-filled) with ACWR and fitness-fatigue state
+    pub fn calculate_daily_loads(
+        &self,
+        sessions: &[SessionRecord],
+    ) -> Result<Vec<DailyLoadDataPoint>, MetricsError> {
+        let daily = self.daily_totals(sessions)?;
+        if daily.is_empty() {
+            return Ok(Vec::new());
+        }
+        if self.acute_days == 0 || self.chronic_days == 0 {
+            return Err(MetricsError::CalculationError(
+                "Rolling windows must be at least one day".to_string(),
+            ));
+        }
+
+        let fitness_decay = (-1.0 / self.fitness_tau).exp();
+        let fatigue_decay = (-1.0 / self.fatigue_tau).exp();
+        let mut fitness = 0.0;
+        let mut fatigue = 0.0;
+
+        let loads: Vec<f64> = daily.iter().map(|(_, load)| *load).collect();
+        let mut points = Vec::with_capacity(daily.len());
+
+        for (i, (date, load)) in daily.iter().enumerate() {
+            let acute_load = Self::trailing_mean(&loads, i, self.acute_days);
+            let chronic_load = Self::trailing_mean(&loads, i, self.chronic_days);
+            let acwr = if i + 1 >= self.chronic_days && chronic_load > 0.0 {
+                Some(acute_load / chronic_load)
+            } else {
+                None
+            };
+
+            fitness = fitness * fitness_decay + load;
+            fatigue = fatigue * fatigue_decay + load;
+
+            points.push(DailyLoadDataPoint {
+                date: *date,
+                load: *load,
+                acute_load,
+                chronic_load,
+                acwr,
+                fitness,
+                fatigue,
+                performance: self.fitness_gain * fitness - self.fatigue_gain * fatigue,
+            });
+        }
+
+        Ok(points)
+    }
+
+    /// Calculate weekly load, monotony and strain (Monday-Sunday weeks)
+    pub fn calculate_weekly_loads(
+        &self,
+        sessions: &[SessionRecord],
+    ) -> Result<Vec<WeeklyLoadDataPoint>, MetricsError> {
+        let daily: BTreeMap<NaiveDate, f64> = self.daily_totals(sessions)?.into_iter().collect();
+        let (Some(first), Some(last)) = (daily.keys().next(), daily.keys().next_back()) else {
+            return Ok(Vec::new());
+        };
+
+        let mut weeks = Vec::new();
+        let mut week_start = Self::week_start(*first);
+        while week_start <= *last {
+            let days: Vec<f64> = (0..7)
+                .map(|offset| {
+                    daily
+                        .get(&(week_start + Duration::days(offset)))
+                        .copied()
+                        .unwrap_or(0.0)
+                })
+                .collect();
+
+            let total_load: f64 = days.iter().sum();
+            let mean = total_load / 7.0;
+            let variance = days.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / 7.0;
+            let std_dev = variance.sqrt();
+
+            let monotony = if std_dev > f64::EPSILON {
+                Some(mean / std_dev)
+            } else {
+                None
+            };
+
+            weeks.push(WeeklyLoadDataPoint {
+                week_start,
+                total_load,
+                mean_daily_load: mean,
+                monotony,
+                strain: monotony.map(|m| total_load * m),
+            });
+
+            week_start += Duration::days(7);
+        }
+
+        Ok(weeks)
+    }
+
+    /// Sum session loads per day, filling rest days with zero
+    fn daily_totals(
+        &self,
+        sessions: &[SessionRecord],
+    ) -> Result<Vec<(NaiveDate, f64)>, MetricsError> {
+        let mut by_date: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+        for session in self.calculate_session_loads(sessions)? {
+            *by_date.entry(session.date).or_insert(0.0) += session.load;
+        }
+
+        let (Some(&first), Some(&last)) = (by_date.keys().next(), by_date.keys().next_back())
+        else {
+            return Ok(Vec::new());
+        };
+
+        let mut totals = Vec::new();
+        let mut date = first;
+        while date <= last {
+            totals.push((date, by_date.get(&date).copied().unwrap_or(0.0)));
+            date += Duration::days(1);
+        }
+
+        Ok(totals)
+    }
+
+    /// RPE for a set: logged RPE, else 10 - RIR, else mapped from the 1-5 effort scale
+    fn set_rpe(record: &SessionRecord) -> f64 {
+        record
+            .rpe
+            .or_else(|| record.rir.map(|rir| 10.0 - rir as f64))
+            .unwrap_or(5.0 + record.effort_1to5 as f64)
+    }
+
+    fn trailing_mean(values: &[f64], index: usize, window: usize) -> f64 {
+        let start = (index + 1).saturating_sub(window);
+        values[start..=index].iter().sum::<f64>() / window as f64
+    }
+
+    fn week_start(date: NaiveDate) -> NaiveDate {
+        date - Duration::days(date.weekday().num_days_from_monday() as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_rpe_load() {
+        let sessions = vec![
+            SessionRecord::builder().rpe(7.0).build(),
+            SessionRecord::builder().time(9, 59).rpe(9.0).build(),
+        ];
+
+        let duration = TrainingLoadCalculator::new()
+            .calculate_session_loads(&sessions)
+            .unwrap();
+        assert_eq!(duration.len(), 1);
+        assert_eq!(duration[0].session_rpe, 8.0);
+        assert_eq!(duration[0].duration_min, 59.5);
+        assert_eq!(duration[0].load, 476.0);
+
+        let by_sets = TrainingLoadCalculator::new()
+            .with_basis(LoadBasis::Sets)
+            .calculate_session_loads(&sessions)
+            .unwrap();
+        assert_eq!(by_sets[0].load, 16.0);
+    }
+
+    #[test]
+    fn test_acwr_requires_full_chronic_window() {
+        let start = NaiveDate::from_ymd_opt(2025, 7, 7).unwrap();
+        // Same session every day for five weeks
+        let sessions: Vec<_> = (0..35)
+            .map(|d| {
+                SessionRecord::builder()
+                    .session(&format!("S{}", d))
+                    .date(start + Duration::days(d))
+                    .rpe(8.0)
+                    .build()
+            })
+            .collect();
+
+        let calc = TrainingLoadCalculator::new().with_basis(LoadBasis::Sets);
+        let daily = calc.calculate_daily_loads(&sessions).unwrap();
+
+        assert_eq!(daily.len(), 35);
+        assert!(daily[26].acwr.is_none());
+        let acwr = daily[27].acwr.unwrap();
+        assert!(
+            (acwr - 1.0).abs() < 0.1,
+            "steady training keeps ACWR near 1"
+        );
+
+        // Fitness-fatigue: fatigue reacts faster than fitness
+        assert!(daily[0].fatigue == daily[0].fitness);
+        assert!(daily[34].performance < daily[34].fitness);
+    }
+
+    #[test]
+    fn test_monotony_and_strain() {
+        let monday = NaiveDate::from_ymd_opt(2025, 8, 4).unwrap();
+        let sessions = vec![
+            SessionRecord::builder().date(monday).rpe(8.0).build(),
+            SessionRecord::builder()
+                .session("S2")
+                .date(monday + Duration::days(2))
+                .rpe(8.0)
+                .build(),
+        ];
+
+        let weeks = TrainingLoadCalculator::new()
+            .with_basis(LoadBasis::Sets)
+            .calculate_weekly_loads(&sessions)
+            .unwrap();
+
+        assert_eq!(weeks.len(), 1);
+        assert_eq!(weeks[0].week_start, monday);
+        assert_eq!(weeks[0].total_load, 16.0);
+        // Daily loads [8,0,8,0,0,0,0]: mean 2.29, sd 3.61
+        let monotony = weeks[0].monotony.unwrap();
+        assert!((monotony - 0.6325).abs() < 0.001);
+        assert!((weeks[0].strain.unwrap() - 16.0 * monotony).abs() < 1e-9);
+    }
+}
@@ -46,6 +46,8 @@ pub enum ValidationError {
     E161SchemeZeroVolume,
     #[error("Scheme expansion failed: {0}")]
     E162SchemeExpansionFailed(String),
+    #[error("Invalid scheme template parameter: {0}")]
+    E163SchemeInvalidParams(String),
     #[error("Complex segment missing exercise for pct_1rm mode")]
     E170ComplexMissingExercise,
     #[error("Complex segment missing percentage for pct_1rm mode")]
@@ -93,6 +95,7 @@ impl ValidationError {
             Self::E160SchemeEmptyExpansion => "E160",
             Self::E161SchemeZeroVolume => "E161",
             Self::E162SchemeExpansionFailed(_) => "E162",
+            Self::E163SchemeInvalidParams(_) => "E163",
             Self::E170ComplexMissingExercise => "E170",
             Self::E171ComplexMissingPercentage => "E171",
             Self::E172ComplexMissingLoad => "E172",
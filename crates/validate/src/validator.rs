@@ -101,9 +101,9 @@ impl PlanValidator {
         }
 
         // Validate dictionary references
-        for day in &plan.schedule {
+        for (day_idx, day) in plan.schedule.iter().enumerate() {
             for (segment_idx, segment) in day.segments.iter().enumerate() {
-                let path = format!("/schedule/{}/segments/{}", day.day - 1, segment_idx);
+                let path = format!("/schedule/{}/segments/{}", day_idx, segment_idx);
                 self.validate_segment(segment, &path, plan, errors, warnings);
             }
         }
@@ -193,11 +193,7 @@ impl PlanValidator {
         if let Some(role) = &base.group_role {
             if let Some(group) = &base.alt_group {
                 if let Some(gv) = &plan.group_variants {
-                    if gv
-                        .get(group)
-                        .and_then(|roles| roles.get(role))
-                        .is_none()
-                    {
+                    if gv.get(group).and_then(|roles| roles.get(role)).is_none() {
                         errors.push(ValidationErrorInfo::new(
                             ValidationError::E190SchemaViolation,
                             path,
@@ -489,8 +485,21 @@ impl PlanValidator {
         // Validate the exercise exists
         self.validate_exercise(&scheme.base.ex, path, plan, errors);
 
-        // Try to expand the scheme template to validate it
+        // Check the template parameters, then expand the template to validate it
         if let Some(ref template) = scheme.template {
+            let problems = template.check_params();
+            if !problems.is_empty() {
+                for problem in problems {
+                    errors.push(ValidationErrorInfo::new(
+                        ValidationError::E163SchemeInvalidParams(problem),
+                        path,
+                        Some("template"),
+                        Some("Scheme template parameter is missing or out of range"),
+                    ));
+                }
+                return;
+            }
+
             match template.expand(&scheme.base.ex) {
                 Ok(expanded) => {
                     // Validate the expanded scheme makes sense